@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+use p256::{NistP256, ProjectivePoint, Scalar};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use dre_ip::{
+    compute_totals, verify_election, Ballot, DreipGroup, DreipPoint, DreipPublicKey, DreipScalar,
+    Election, Receipt, SecretsPresent, SmallBallot,
+};
+
+/// Time ballot creation with and without precomputed generator tables,
+/// ballot verification with map-backed and small ballots, and of a whole election,
+/// signature verification with and without batching,
+/// and the multi-scalar multiplication used by verification.
+///
+/// Usage: `cargo run --release --example benchmark [BALLOTS] [CANDIDATES]`.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let num_ballots: usize = args
+        .next()
+        .map_or(200, |n| n.parse().expect("Invalid count"));
+    let num_candidates: usize = args
+        .next()
+        .map_or(16, |n| n.parse().expect("Invalid count"));
+    let candidates = (0..num_candidates)
+        .map(|i| format!("Candidate {}", i))
+        .collect::<Vec<_>>();
+
+    let election = Election::<NistP256>::new(&[b"Benchmark"], rand::thread_rng());
+    println!(
+        "Creating {} ballots with {} candidates each.",
+        num_ballots, num_candidates
+    );
+
+    // Both runs share a seed, so must produce identical ballots.
+    let mut rng = StdRng::seed_from_u64(0);
+    let start = Instant::now();
+    let plain = (0..num_ballots)
+        .map(|i| {
+            Ballot::new(
+                &mut rng,
+                election.g1,
+                election.g2,
+                i.to_string(),
+                candidates[i % num_candidates].clone(),
+                no_candidates(&candidates, i),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<Ballot<_, _, SecretsPresent<_>>>>();
+    let plain_time = start.elapsed();
+    println!("Plain:       {:?}", plain_time);
+
+    let mut rng = StdRng::seed_from_u64(0);
+    let start = Instant::now();
+    let tables = election.precompute();
+    let table_time = start.elapsed();
+    let precomputed = (0..num_ballots)
+        .map(|i| {
+            Ballot::new_precomputed(
+                &mut rng,
+                &tables,
+                i.to_string(),
+                candidates[i % num_candidates].clone(),
+                no_candidates(&candidates, i),
+            )
+            .unwrap()
+        })
+        .collect::<Vec<Ballot<_, _, SecretsPresent<_>>>>();
+    let precomputed_time = start.elapsed();
+    println!(
+        "Precomputed: {:?} (of which {:?} building tables)",
+        precomputed_time, table_time
+    );
+
+    assert_eq!(plain, precomputed);
+    println!(
+        "Identical output, {:.2}x speedup.",
+        plain_time.as_secs_f64() / precomputed_time.as_secs_f64()
+    );
+
+    // Verification does four two-term linear combinations per vote.
+    let start = Instant::now();
+    for (i, ballot) in precomputed.iter().enumerate() {
+        assert!(ballot
+            .verify(election.g1, election.g2, i.to_string(), election.transcript)
+            .is_ok());
+    }
+    let verification_time = start.elapsed();
+    println!("Verification: {:?}", verification_time);
+
+    // Small ballots keep their votes in one vector rather than a map.
+    let small = precomputed
+        .iter()
+        .cloned()
+        .map(SmallBallot::from)
+        .collect::<Vec<_>>();
+    let start = Instant::now();
+    for (i, ballot) in small.iter().enumerate() {
+        assert!(ballot
+            .verify(election.g1, election.g2, i.to_string(), election.transcript)
+            .is_ok());
+    }
+    let small_time = start.elapsed();
+    println!(
+        "Small ballot verification: {:?}, {:.2}x speedup.",
+        small_time,
+        verification_time.as_secs_f64() / small_time.as_secs_f64()
+    );
+
+    // Election verification checks every ballot, then sums the votes against the totals.
+    let ballots = precomputed
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(i, ballot)| (i.to_string(), ballot))
+        .collect::<HashMap<_, _>>();
+    let totals = compute_totals(&ballots);
+    let start = Instant::now();
+    assert_eq!(
+        verify_election(
+            election.g1,
+            election.g2,
+            &ballots,
+            &totals,
+            election.transcript
+        ),
+        Ok(())
+    );
+    let election_time = start.elapsed();
+
+    println!("Election verification: {:?}.", election_time);
+
+    // Receipts are signed, and every signature must be checked too.
+    let receipts = precomputed
+        .into_iter()
+        .enumerate()
+        .map(|(i, ballot)| election.issue_receipt(ballot, i.to_string(), None))
+        .collect::<Vec<_>>();
+    let bytes = receipts
+        .iter()
+        .map(|receipt| Receipt::to_bytes(&receipt.ballot, &receipt.ballot_id, None))
+        .collect::<Vec<_>>();
+    let items = bytes
+        .iter()
+        .zip(&receipts)
+        .map(|(bytes, receipt)| (&bytes[..], &receipt.signature))
+        .collect::<Vec<_>>();
+    let start = Instant::now();
+    for (receipt, signature) in items.iter() {
+        assert!(election.public_key.verify(receipt, signature));
+    }
+    let signature_time = start.elapsed();
+    let start = Instant::now();
+    assert_eq!(election.public_key.batch_verify(&items), Ok(()));
+    let batch_time = start.elapsed();
+    println!(
+        "Signature time: {:?}, batched {:?} ({:.2}x).",
+        signature_time,
+        batch_time,
+        signature_time.as_secs_f64() / batch_time.as_secs_f64()
+    );
+
+    let combinations = (0..4 * num_ballots)
+        .map(|i| {
+            let point = ProjectivePoint::from_hash(&[&i.to_be_bytes()]);
+            let scalars = [Scalar::random(&mut rng), Scalar::random(&mut rng)];
+            ([election.g1, point], scalars)
+        })
+        .collect::<Vec<_>>();
+    let start = Instant::now();
+    let naive = combinations
+        .iter()
+        .map(|(p, s)| p[0] * s[0] + p[1] * s[1])
+        .collect::<Vec<_>>();
+    let naive_time = start.elapsed();
+    let start = Instant::now();
+    let msm = combinations
+        .iter()
+        .map(|(p, s)| NistP256::msm(p, s))
+        .collect::<Vec<_>>();
+    let msm_time = start.elapsed();
+    assert_eq!(naive, msm);
+    println!(
+        "{} linear combinations: naive {:?}, MSM {:?} ({:.2}x).",
+        combinations.len(),
+        naive_time,
+        msm_time,
+        naive_time.as_secs_f64() / msm_time.as_secs_f64()
+    );
+}
+
+/// All candidates except the yes candidate for the given ballot.
+fn no_candidates(candidates: &[String], ballot: usize) -> Vec<String> {
+    let yes = ballot % candidates.len();
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| *i != yes)
+        .map(|(_, c)| c.clone())
+        .collect()
+}
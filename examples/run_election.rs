@@ -1,118 +1,51 @@
-use std::collections::HashMap;
-
 use p256::NistP256;
 use rand::Rng;
 
-use dre_ip::group::Serializable;
-use dre_ip::{Ballot, CandidateTotals, Election};
+use dre_ip::{Election, ElectionSession};
 
 fn main() {
     let mut rng = rand::thread_rng();
     const CANDIDATES: &[&str] = &["Alice", "Bob", "Eve"];
-    const BALLOTS: &[&str] = &["0", "1", "2", "3", "4"];
-    const AUDITS: &[&str] = &["5", "6", "7", "8", "9"];
 
-    // Create a new election.
+    // Create a new election, and cast ten ballots for random candidates.
     let election = Election::<NistP256>::new(&[b"Hello, World!"], &mut rng);
-    let mut audited = HashMap::new();
-    let mut confirmed = HashMap::new();
-    let mut totals = HashMap::with_capacity(CANDIDATES.len());
-    for candidate in CANDIDATES {
-        totals.insert(*candidate, CandidateTotals::default());
-    }
-
-    // Create and confirm ballots.
-    for ballot_id in BALLOTS {
-        // Pick a random candidate to vote for.
-        let candidate_index = rng.gen_range(0..CANDIDATES.len());
-        let yes_candidate = CANDIDATES[candidate_index];
-        let no_candidates = CANDIDATES.iter().enumerate().filter_map(|(i, c)| {
-            if i != candidate_index {
-                Some(*c)
-            } else {
-                None
-            }
-        });
-
-        // Create the ballot.
-        let ballot = Ballot::<_, NistP256, _>::new(
-            &mut rng,
-            election.g1,
-            election.g2,
-            *ballot_id,
-            yes_candidate,
-            no_candidates,
-        )
-        .unwrap();
-
-        // Confirm the ballot, adding the secrets to the totals.
-        let mut totals_mut = totals
-            .iter_mut()
-            .map(|(id, t)| (*id, t))
-            .collect::<HashMap<_, _>>();
-        let ballot = ballot.confirm(Some(&mut totals_mut));
-        confirmed.insert(*ballot_id, ballot);
-    }
-
-    // Now create some audited ballots.
-    for ballot_id in AUDITS {
-        // Pick a random candidate to vote for.
-        let candidate_index = rng.gen_range(0..CANDIDATES.len());
-        let yes_candidate = CANDIDATES[candidate_index];
-        let no_candidates = CANDIDATES.iter().enumerate().filter_map(|(i, c)| {
-            if i != candidate_index {
-                Some(*c)
-            } else {
-                None
-            }
-        });
-
-        // Create the ballot.
-        let ballot = Ballot::<_, NistP256, _>::new(
-            &mut rng,
-            election.g1,
-            election.g2,
-            *ballot_id,
-            yes_candidate,
-            no_candidates,
-        )
-        .unwrap();
-        audited.insert(*ballot_id, ballot);
+    println!("Election fingerprint: {}", election.fingerprint_hex());
+    let (g1, g2, transcript) = (election.g1, election.g2, election.transcript);
+    let mut session = ElectionSession::new(election, CANDIDATES.iter().copied());
+    for (i, id) in ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"]
+        .into_iter()
+        .enumerate()
+    {
+        let candidate = CANDIDATES[rng.gen_range(0..CANDIDATES.len())];
+        let handle = session.cast(&mut rng, id, candidate).unwrap();
+        if i < 5 {
+            // Confirm the ballot, adding its secrets to the totals.
+            session.confirm(handle).unwrap();
+        } else {
+            // The auditing voter checks that their ballot really encodes their choice.
+            let audited = session.audit(handle).unwrap();
+            assert!(audited
+                .verify_audited(g1, g2, id, &candidate, transcript)
+                .is_ok());
+        }
     }
 
-    // Verify the election.
-    assert!(dre_ip::verify_election(election.g1, election.g2, &confirmed, &totals).is_ok());
-    for (id, ballot) in audited.iter() {
-        assert!(ballot.verify(election.g1, election.g2, id).is_ok());
-    }
+    // Publish the confirmed and audited ballots, and verify the election.
+    let results = session.into_results();
+    assert!(results.verify().is_ok());
     println!("Election successfully verified.");
 
-    // Announce the results.
-    println!("Results:");
-    for (candidate, candidate_totals) in totals.iter() {
-        println!(
-            "{}: {} votes",
-            candidate,
-            scalar_to_u64(&candidate_totals.tally).unwrap()
-        );
+    // Write the results for the `verify` example, if given a path.
+    if let Some(path) = std::env::args().nth(1) {
+        let json = serde_json::to_string_pretty(&results).unwrap();
+        std::fs::write(&path, json).expect("Failed to write results");
+        println!("Results written to {}.", path);
     }
-}
 
-fn scalar_to_u64<S: Serializable>(scalar: &S) -> Option<u64> {
-    const SIZE: usize = std::mem::size_of::<u64>();
-
-    let bytes: Vec<u8> = scalar
-        .to_bytes()
-        .into_iter()
-        .skip_while(|b| b == &0)
-        .collect();
-    if bytes.len() > SIZE {
-        return None;
+    // Announce the results, in order of candidate.
+    println!("Results:");
+    let counts = dre_ip::results(&results.totals).expect("Tally does not fit in a u64");
+    for candidate in CANDIDATES {
+        println!("{}: {} votes", candidate, counts[candidate]);
     }
-
-    let mut u64_bytes = [0; SIZE];
-    let start = SIZE - bytes.len();
-    u64_bytes[start..SIZE].clone_from_slice(&bytes[..(SIZE - start)]);
-
-    Some(u64::from_be_bytes(u64_bytes))
 }
@@ -0,0 +1,115 @@
+use dre_ip::dynamic::AnyElectionResults;
+use dre_ip::{BallotError, Limits, VerificationError};
+
+/// Verify serialized election results on any enabled group, read from the `group` field.
+///
+/// Usage: `cargo run --example verify --features serde_json[,p384_impl,...] [--strict]
+/// [--max-input-bytes N] [--max-ballots N] [--max-candidates N] [--max-id-bytes N] RESULTS.json`.
+///
+/// With `--strict`, the results are loaded with `AnyElectionResults::from_json_strict`,
+/// rejecting unknown fields and repeated keys, such as a ballot id given twice.
+/// The results are always loaded within `Limits`, the defaults unless overridden by the
+/// `--max-*` options, so that a hostile dump cannot exhaust memory. With the `parallel`
+/// feature, the confirmed ballots are verified in parallel.
+fn main() {
+    let usage = || -> ! {
+        eprintln!(
+            "Usage: verify [--strict] [--max-input-bytes N] [--max-ballots N] \
+             [--max-candidates N] [--max-id-bytes N] RESULTS.json"
+        );
+        std::process::exit(2);
+    };
+    let mut strict = false;
+    let mut limits = Limits::default();
+    let mut paths = Vec::new();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .and_then(|n| n.parse::<usize>().ok())
+                .unwrap_or_else(|| usage())
+        };
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "--max-input-bytes" => limits.max_input_bytes = value() as u64,
+            "--max-ballots" => limits.max_ballots = value(),
+            "--max-candidates" => limits.max_candidates = value(),
+            "--max-id-bytes" => limits.max_id_bytes = value(),
+            _ => paths.push(arg),
+        }
+    }
+    let [path] = &paths[..] else {
+        usage();
+    };
+    let file = std::fs::File::open(path).expect("Failed to read results");
+    let loaded = if strict {
+        AnyElectionResults::from_json_limited(file, &limits)
+    } else {
+        dre_ip::read_bounded(file, limits.max_input_bytes).and_then(|json| {
+            let results: AnyElectionResults<String, String> = serde_json::from_slice(&json)?;
+            results.check_limits(&limits)?;
+            Ok(results)
+        })
+    };
+    let results: AnyElectionResults<String, String> = match loaded {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Invalid results: {}", e);
+            std::process::exit(1);
+        }
+    };
+    println!("Group: {}", results.group().name());
+    // Verify the confirmed ballots on all cores when built with the `parallel` feature.
+    #[cfg(feature = "parallel")]
+    let verified = results.verify_par();
+    #[cfg(not(feature = "parallel"))]
+    let verified = results.verify();
+    match verified {
+        Ok(()) => {
+            println!("Election successfully verified.");
+            // Print the counts as a table in order of candidate.
+            let counts = match results.tallies() {
+                Ok(counts) => counts,
+                Err(e) => {
+                    println!("Invalid tally: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let mut counts = counts.into_iter().collect::<Vec<_>>();
+            counts.sort();
+            println!("Ballots counted: {}", results.total_ballots());
+            let width = counts.iter().map(|(c, _)| c.len()).max().unwrap_or(0);
+            for (candidate, count) in counts {
+                println!("{:<width$}  {:>8} votes", candidate, count);
+            }
+        }
+        // Bad generators or keys are reported before any ballot is checked.
+        Err(VerificationError::InvalidParameters(e)) => {
+            println!("Verification failed: invalid election parameters: {:?}", e);
+            std::process::exit(1);
+        }
+        // Say whether a failed vote's proof or its revealed secrets are wrong.
+        Err(
+            VerificationError::Ballot(BallotError::Vote(e))
+            | VerificationError::Audited(BallotError::Vote(e)),
+        ) => {
+            println!("Verification failed: {}", e);
+            std::process::exit(1);
+        }
+        // Say which equation of a failed ballot proof broke.
+        Err(
+            VerificationError::Ballot(BallotError::BallotProof { ballot_id, reason })
+            | VerificationError::Audited(BallotError::BallotProof { ballot_id, reason }),
+        ) => {
+            println!(
+                "Verification failed: ballot proof of ballot id {:?}: {}",
+                ballot_id, reason
+            );
+            std::process::exit(1);
+        }
+        Err(e) => {
+            println!("Verification failed: {:?}", e);
+            std::process::exit(1);
+        }
+    }
+}
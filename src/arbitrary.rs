@@ -0,0 +1,245 @@
+//! Generating elections from unstructured bytes, for fuzzing and property testing.
+//!
+//! Generated values are always valid: `Election` is created by `Election::from_seed`, and
+//! `ElectionResults` by running an `ElectionSession`, casting each ballot with a ChaCha20
+//! stream seeded from the input, so the same bytes always give the same election. Invalid
+//! results are made from valid ones with `corrupt`, one class of corruption at a time.
+
+use ::arbitrary::{Arbitrary, Unstructured};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::hash::Hash;
+
+use crate::ballots::{Ballot, BallotCreationError, Vote};
+use crate::election::{Election, ElectionResults};
+use crate::group::{DreipGroup, DreipScalar};
+use crate::ids::IdBytes;
+use crate::pwf::VoteProof;
+use crate::session::ElectionSession;
+
+/// The most candidates in a generated election.
+const MAX_CANDIDATES: usize = 4;
+
+/// The most ballots cast in a generated election.
+const MAX_BALLOTS: usize = 6;
+
+impl<'a, G: DreipGroup> Arbitrary<'a> for Election<G> {
+    fn arbitrary(u: &mut Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        let unique_bytes: Vec<u8> = u.arbitrary()?;
+        let seed: [u8; 32] = u.arbitrary()?;
+        let election = Self::from_seed(&[b"DREIP_ARBITRARY", &unique_bytes], &seed);
+        if u.arbitrary()? {
+            Ok(election.bound(u.arbitrary()?))
+        } else {
+            Ok(election)
+        }
+    }
+}
+
+impl<'a, B, C, G> Arbitrary<'a> for ElectionResults<B, C, G>
+where
+    B: Arbitrary<'a> + IdBytes + Eq + Hash + Clone,
+    C: Arbitrary<'a> + IdBytes + Clone + Eq + Hash + Ord,
+    G: DreipGroup,
+    G::Scalar: Eq,
+{
+    /// Run an election with up to four candidates and six ballots, each either confirmed or
+    /// audited. Ballots whose ID was already cast are skipped. The first ballot is always
+    /// confirmed, since verification finds the candidates from the confirmed ballots.
+    fn arbitrary(u: &mut Unstructured<'a>) -> ::arbitrary::Result<Self> {
+        let election = Election::arbitrary(u)?;
+        let mut candidates = Vec::new();
+        for _ in 0..u.int_in_range(1..=MAX_CANDIDATES)? {
+            let candidate = C::arbitrary(u)?;
+            if !candidates.contains(&candidate) {
+                candidates.push(candidate);
+            }
+        }
+        let mut rng = ChaCha20Rng::from_seed(u.arbitrary()?);
+
+        let mut session = ElectionSession::new(election, candidates.clone());
+        for i in 0..u.int_in_range(1..=MAX_BALLOTS)? {
+            let ballot_id = B::arbitrary(u)?;
+            let candidate = u.choose(&candidates)?.clone();
+            let handle = match session.cast(&mut rng, ballot_id, candidate) {
+                Ok(handle) => handle,
+                Err(BallotCreationError::DuplicateBallot) => continue,
+                Err(_) => unreachable!("Candidates are distinct and the yes candidate is one"),
+            };
+            if i > 0 && u.arbitrary()? {
+                if session.audit(handle).is_err() {
+                    unreachable!("Ballot cast in the same session");
+                }
+            } else if session.confirm(handle).is_err() {
+                unreachable!("Ballot cast in the same session");
+            }
+        }
+        Ok(session.into_results())
+    }
+}
+
+/// A class of corruption, which any `ElectionResults` verification must detect.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Corruption<B> {
+    /// Add one to a response scalar of a vote proof, as by `flip_proof_scalar`.
+    ProofScalar,
+    /// Swap the `Z` and `R` values of a vote, as by `swap_zr`.
+    SwappedZR,
+    /// Move a ballot to the given ballot ID, which must not already be in use.
+    BallotId(B),
+}
+
+/// Corrupt one ballot of the results: the first confirmed ballot, or the first audited
+/// ballot if none are confirmed.
+///
+/// Returns whether there was a ballot to corrupt; if not, or the ballot ID of
+/// `Corruption::BallotId` is already in use, the results are unchanged.
+pub fn corrupt<B, C, G>(results: &mut ElectionResults<B, C, G>, corruption: Corruption<B>) -> bool
+where
+    B: Eq + Hash + Clone,
+    C: Eq + Hash + Ord,
+    G: DreipGroup,
+{
+    if let Corruption::BallotId(ballot_id) = &corruption {
+        let in_use = results.confirmed.contains_key(ballot_id)
+            || results.audited.contains_key(ballot_id)
+            || results.cancelled.contains_key(ballot_id)
+            || results.replaced.contains_key(ballot_id);
+        if in_use {
+            return false;
+        }
+    }
+
+    if let Some(ballot_id) = results.confirmed.keys().next().cloned() {
+        let ballot = results
+            .confirmed
+            .remove(&ballot_id)
+            .expect("Key was present");
+        let (ballot_id, ballot) = apply(ballot_id, ballot, corruption);
+        results.confirmed.insert(ballot_id, ballot);
+        true
+    } else if let Some(ballot_id) = results.audited.keys().next().cloned() {
+        let ballot = results.audited.remove(&ballot_id).expect("Key was present");
+        let (ballot_id, ballot) = apply(ballot_id, ballot, corruption);
+        results.audited.insert(ballot_id, ballot);
+        true
+    } else {
+        false
+    }
+}
+
+/// Apply the corruption to a ballot with the given ID.
+fn apply<B, C, G, S>(
+    ballot_id: B,
+    ballot: Ballot<C, G, S>,
+    corruption: Corruption<B>,
+) -> (B, Ballot<C, G, S>)
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    match corruption {
+        Corruption::ProofScalar => (ballot_id, flip_proof_scalar(ballot)),
+        Corruption::SwappedZR => (ballot_id, swap_zr(ballot)),
+        Corruption::BallotId(new_id) => (new_id, ballot),
+    }
+}
+
+/// Corrupt the ballot by adding one to the first response scalar, `r1`, of the proof of its
+/// first vote. A ballot with no votes is returned unchanged.
+pub fn flip_proof_scalar<C, G, S>(ballot: Ballot<C, G, S>) -> Ballot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    map_first_vote(ballot, |vote| {
+        let mut pwf = clone_proof(vote.pwf());
+        pwf.r1 = pwf.r1 + G::Scalar::one();
+        let (r_point, z_point) = (vote.R().clone(), vote.Z().clone());
+        Vote::from_parts(vote.secrets, r_point, z_point, pwf)
+    })
+}
+
+/// Corrupt the ballot by swapping the `Z` and `R` values of its first vote. A ballot with
+/// no votes is returned unchanged.
+pub fn swap_zr<C, G, S>(ballot: Ballot<C, G, S>) -> Ballot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    map_first_vote(ballot, |vote| {
+        let (r_point, z_point) = (vote.R().clone(), vote.Z().clone());
+        let pwf = clone_proof(vote.pwf());
+        Vote::from_parts(vote.secrets, z_point, r_point, pwf)
+    })
+}
+
+/// Clone the proof, which derives `Clone` only for groups that are themselves `Clone`.
+fn clone_proof<G: DreipGroup>(pwf: &VoteProof<G>) -> VoteProof<G> {
+    VoteProof {
+        c1: pwf.c1.clone(),
+        c2: pwf.c2.clone(),
+        r1: pwf.r1.clone(),
+        r2: pwf.r2.clone(),
+    }
+}
+
+/// Replace the first vote of the ballot with the result of `f`.
+fn map_first_vote<C, G, S>(
+    ballot: Ballot<C, G, S>,
+    f: impl FnOnce(Vote<G, S>) -> Vote<G, S>,
+) -> Ballot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    let (mut votes, pwf) = ballot.into_parts();
+    if let Some((candidate, vote)) = votes.pop_first() {
+        votes.insert(candidate, f(vote));
+    }
+    Ballot::from_parts(votes, pwf)
+}
+
+#[cfg(all(test, feature = "p256_impl"))]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    use crate::group::p256::NistP256;
+
+    type Results = ElectionResults<u32, u32, NistP256>;
+
+    /// Generate results from the bytes, as a fuzzer would.
+    fn generate(bytes: &[u8]) -> Results {
+        Results::arbitrary(&mut Unstructured::new(bytes)).unwrap()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_generated_elections_verify(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+            let results = generate(&bytes);
+            prop_assert_eq!(results.verify(), Ok(()));
+        }
+
+        #[test]
+        fn test_corruptions_fail(bytes in prop::collection::vec(any::<u8>(), 0..256)) {
+            let results = generate(&bytes);
+            let unused_id = (0..)
+                .find(|id| !results.confirmed.contains_key(id) && !results.audited.contains_key(id))
+                .unwrap();
+            let corruptions = [
+                Corruption::ProofScalar,
+                Corruption::SwappedZR,
+                Corruption::BallotId(unused_id),
+            ];
+            for corruption in corruptions {
+                let mut corrupted = results.clone();
+                prop_assert!(corrupt(&mut corrupted, corruption.clone()));
+                prop_assert!(corrupted.verify().is_err(), "{:?} verified", corruption);
+            }
+        }
+    }
+}
@@ -1,17 +1,75 @@
 use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter};
 use std::hash::Hash;
 
-use crate::election::CandidateTotals;
-use crate::group::{DreipGroup, DreipPoint, DreipScalar, Serializable};
-use crate::pwf::{BallotProof, VoteProof};
+use crate::election::{to_hex, CandidateTotals, ParameterError};
+use crate::group::{
+    encoded_size, scalar_from_u64, DreipGroup, DreipPoint, DreipScalar, ReceiptSigner,
+    Serializable, SignError,
+};
+use crate::ids::{FromIdBytes, IdBytes};
+use crate::precompute::{ElectionPrecomputed, Generator};
+use crate::pwf::{
+    BallotProof, BallotProofFailure, ToBytes, TranscriptVersion, VoteProof, VoteStatement,
+};
+use crate::receipt;
 
 /// An error due to a vote failing verification.
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct VoteError<B, C> {
     pub ballot_id: B,
     pub candidate_id: C,
+    /// Which part of the vote failed to verify.
+    pub reason: VoteFailure,
+}
+
+impl<B: Debug, C: Debug> Display for VoteError<B, C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "vote for candidate id {:?} on ballot id {:?}: {}",
+            self.candidate_id, self.ballot_id, self.reason
+        )
+    }
+}
+
+impl<B: Debug, C: Debug> Error for VoteError<B, C> {}
+
+impl<B: Clone, C> VoteError<&B, C> {
+    /// Clone the borrowed ballot id into an owned error, as `Option::cloned` does. Verifiers
+    /// borrow ids while checking, and clone them only once something has failed.
+    pub fn cloned(self) -> VoteError<B, C> {
+        VoteError {
+            ballot_id: self.ballot_id.clone(),
+            candidate_id: self.candidate_id,
+            reason: self.reason,
+        }
+    }
+}
+
+/// Why a vote failed to verify.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum VoteFailure {
+    /// The revealed secrets `r` and `v` do not give the vote's `R` and `Z`.
+    SecretsMismatch,
+    /// The proof of well-formedness failed to verify.
+    ProofInvalid,
+    /// The vote's `Z` or `R` is the identity, which no honestly created vote has.
+    IdentityPoint,
+}
+
+impl Display for VoteFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SecretsMismatch => write!(f, "secrets do not match R and Z"),
+            Self::ProofInvalid => write!(f, "invalid proof of well-formedness"),
+            Self::IdentityPoint => write!(f, "Z or R is the identity point"),
+        }
+    }
 }
 
 /// An error due to a ballot failing verification.
@@ -20,7 +78,57 @@ pub enum BallotError<B, C> {
     /// An individual vote failed to verify.
     Vote(VoteError<B, C>),
     /// The overall ballot proof failed to verify.
-    BallotProof { ballot_id: B },
+    BallotProof {
+        ballot_id: B,
+        reason: BallotProofFailure,
+    },
+    /// The ballot has no votes, which no valid ballot can have.
+    Empty { ballot_id: B },
+}
+
+impl<B: Clone, C> BallotError<&B, C> {
+    /// Clone the borrowed ballot id into an owned error, as `VoteError::cloned` does.
+    pub fn cloned(self) -> BallotError<B, C> {
+        match self {
+            Self::Vote(e) => BallotError::Vote(e.cloned()),
+            Self::BallotProof { ballot_id, reason } => BallotError::BallotProof {
+                ballot_id: ballot_id.clone(),
+                reason,
+            },
+            Self::Empty { ballot_id } => BallotError::Empty {
+                ballot_id: ballot_id.clone(),
+            },
+        }
+    }
+}
+
+/// Why a ballot's votes failed to verify, borrowing the candidate id from the ballot, so
+/// that no id is cloned unless verification fails.
+pub(crate) enum VotesFailure<'a, C> {
+    /// The ballot has no votes.
+    Empty,
+    /// The vote for the candidate failed to verify.
+    Vote {
+        candidate_id: &'a C,
+        reason: VoteFailure,
+    },
+}
+
+impl<C: Clone> VotesFailure<'_, C> {
+    /// The error for the ballot with the given id.
+    pub(crate) fn into_error<B>(self, ballot_id: B) -> BallotError<B, C> {
+        match self {
+            Self::Empty => BallotError::Empty { ballot_id },
+            Self::Vote {
+                candidate_id,
+                reason,
+            } => BallotError::Vote(VoteError {
+                ballot_id,
+                candidate_id: candidate_id.clone(),
+                reason,
+            }),
+        }
+    }
 }
 
 /// An error due to an election failing verification.
@@ -33,12 +141,359 @@ pub enum VerificationError<B, C> {
     /// The set of candidates does not match between the ballots
     /// and the proposed tallies.
     WrongCandidates,
+    /// The election generators are invalid, so no ballot work was done.
+    InvalidParameters(ParameterError),
+    /// The ballot id is used by more than one of the confirmed, audited, cancelled, and
+    /// replaced ballots, or by more than one shard of `verify_election_sharded`.
+    DuplicateBallot { ballot_id: B },
+    /// The replaced ballot does not lead to a confirmed ballot, or shares its replacement
+    /// with another replaced ballot.
+    Replacement { ballot_id: B },
+    /// An audited ballot failed to verify, or its secrets do not match its votes.
+    Audited(BallotError<B, C>),
+    /// No weight was given for the ballot to `verify_election_weighted`.
+    MissingWeight { ballot_id: B },
+    /// The ballot's candidates break the `VerificationPolicy`.
+    Policy {
+        ballot_id: B,
+        reason: PolicyViolation<C>,
+    },
+    /// The ballot does not have a vote for exactly the candidates of the totals, although
+    /// the tallies add up. The candidates it is missing and those it has extra are each in
+    /// order of id.
+    InconsistentRoster {
+        ballot_id: B,
+        missing: Vec<C>,
+        extra: Vec<C>,
+    },
+    /// This many ballots fed to an `IncrementalVerifier` failed to verify, each reported as
+    /// it was fed.
+    FailedBallots { count: usize },
+}
+
+impl<B: Clone, C> VerificationError<&B, C> {
+    /// Clone the borrowed ballot id into an owned error, as `BallotError::cloned` does.
+    pub fn cloned(self) -> VerificationError<B, C> {
+        match self {
+            Self::Ballot(e) => VerificationError::Ballot(e.cloned()),
+            Self::Tally { candidate_id } => VerificationError::Tally { candidate_id },
+            Self::WrongCandidates => VerificationError::WrongCandidates,
+            Self::InvalidParameters(e) => VerificationError::InvalidParameters(e),
+            Self::DuplicateBallot { ballot_id } => VerificationError::DuplicateBallot {
+                ballot_id: ballot_id.clone(),
+            },
+            Self::Replacement { ballot_id } => VerificationError::Replacement {
+                ballot_id: ballot_id.clone(),
+            },
+            Self::Audited(e) => VerificationError::Audited(e.cloned()),
+            Self::MissingWeight { ballot_id } => VerificationError::MissingWeight {
+                ballot_id: ballot_id.clone(),
+            },
+            Self::Policy { ballot_id, reason } => VerificationError::Policy {
+                ballot_id: ballot_id.clone(),
+                reason,
+            },
+            Self::InconsistentRoster {
+                ballot_id,
+                missing,
+                extra,
+            } => VerificationError::InconsistentRoster {
+                ballot_id: ballot_id.clone(),
+                missing,
+                extra,
+            },
+            Self::FailedBallots { count } => VerificationError::FailedBallots { count },
+        }
+    }
+}
+
+/// How a ballot's candidates break a `VerificationPolicy`.
+#[derive(Debug, Eq, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum PolicyViolation<C> {
+    /// The ballot has fewer candidates than the policy's minimum.
+    TooFewCandidates { count: usize, min: usize },
+    /// The ballot has more candidates than the policy's maximum.
+    TooManyCandidates { count: usize, max: usize },
+    /// The ballot has no vote for a candidate the policy requires.
+    MissingCandidate(C),
+}
+
+impl<C: Debug> Display for PolicyViolation<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewCandidates { count, min } => {
+                write!(f, "{} candidates, fewer than the minimum {}", count, min)
+            }
+            Self::TooManyCandidates { count, max } => {
+                write!(f, "{} candidates, more than the maximum {}", count, max)
+            }
+            Self::MissingCandidate(candidate) => {
+                write!(f, "no vote for required candidate id {:?}", candidate)
+            }
+        }
+    }
+}
+
+/// An error due to a ballot being impossible to create.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BallotCreationError<C> {
+    /// The candidate ID was given more than once, as a yes or as a no candidate.
+    DuplicateCandidate(C),
+    /// The candidate ID was given as both a yes and a no candidate.
+    YesAndNoCandidate(C),
+    /// Two different candidate IDs have the same bytes, which would give their votes the
+    /// same proof transcripts; they are given in order.
+    AmbiguousCandidateEncoding(C, C),
+    /// No candidates were given.
+    NoCandidates,
+    /// The number of yes candidates given to `Ballot::new_multi` was not `k`.
+    WrongYesCount { expected: usize, actual: usize },
+    /// The yes candidate given to `Election::create_ballots` is not in the candidate list.
+    UnknownCandidate(C),
+    /// The ballot ID was given more than once to `Election::create_ballots`.
+    DuplicateBallot,
+    /// The weight given to `Ballot::new_weighted` was zero.
+    ZeroWeight,
+    /// The question ID was given more than once to `MultiBallot::new`.
+    DuplicateQuestion,
+    /// The election given to `Election::create_ballot` has no roster of candidates of the
+    /// yes candidate's type.
+    NoRoster,
+}
+
+impl<C: Debug> Display for BallotCreationError<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DuplicateCandidate(candidate) => {
+                write!(f, "duplicate candidate id {:?}", candidate)
+            }
+            Self::YesAndNoCandidate(candidate) => {
+                write!(
+                    f,
+                    "candidate id {:?} is both a yes and a no candidate",
+                    candidate
+                )
+            }
+            Self::AmbiguousCandidateEncoding(first, second) => write!(
+                f,
+                "candidate ids {:?} and {:?} have the same bytes",
+                first, second
+            ),
+            Self::NoCandidates => write!(f, "no candidates"),
+            Self::WrongYesCount { expected, actual } => {
+                write!(f, "expected {} yes candidates, got {}", expected, actual)
+            }
+            Self::UnknownCandidate(candidate) => {
+                write!(f, "unknown candidate id {:?}", candidate)
+            }
+            Self::DuplicateBallot => write!(f, "duplicate ballot id"),
+            Self::ZeroWeight => write!(f, "zero ballot weight"),
+            Self::DuplicateQuestion => write!(f, "duplicate question id"),
+            Self::NoRoster => write!(f, "no roster of candidates"),
+        }
+    }
+}
+
+impl<C: Debug> Error for BallotCreationError<C> {}
+
+/// An error due to a ballot being impossible to assemble from existing votes.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum BallotAssemblyError<C> {
+    /// No votes were given.
+    NoVotes,
+    /// The candidate's vote does not verify for the ballot and candidate ids, as when it
+    /// was created for a different ballot.
+    InvalidVote { candidate: C, reason: VoteFailure },
+    /// Two different candidate IDs have the same bytes; they are given in order.
+    AmbiguousCandidateEncoding(C, C),
+    /// The votes did not include exactly one yes vote.
+    WrongYesCount { expected: usize, actual: usize },
+}
+
+impl<C: Debug> Display for BallotAssemblyError<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoVotes => write!(f, "no votes"),
+            Self::InvalidVote { candidate, reason } => {
+                write!(
+                    f,
+                    "vote for candidate id {:?} is invalid: {}",
+                    candidate, reason
+                )
+            }
+            Self::AmbiguousCandidateEncoding(first, second) => write!(
+                f,
+                "candidate ids {:?} and {:?} have the same bytes",
+                first, second
+            ),
+            Self::WrongYesCount { expected, actual } => {
+                write!(f, "expected {} yes votes, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl<C: Debug> Error for BallotAssemblyError<C> {}
+
+/// An error due to a ballot being impossible to confirm.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ConfirmError<C> {
+    /// The totals have no entry for the candidate.
+    MissingCandidate(C),
+    /// The ballot id was already confirmed or audited in this session.
+    DuplicateBallot,
+}
+
+impl<C: Debug> Display for ConfirmError<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCandidate(candidate) => {
+                write!(f, "no totals for candidate id {:?}", candidate)
+            }
+            Self::DuplicateBallot => write!(f, "ballot id already confirmed or audited"),
+        }
+    }
+}
+
+impl<C: Debug> Error for ConfirmError<C> {}
+
+/// An error due to an audited ballot not encoding the claimed choice.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum AuditError<C> {
+    /// The vote for the candidate failed to verify, or its secret is not 0 or 1.
+    Vote(C),
+    /// The overall ballot proof failed to verify.
+    BallotProof,
+    /// No candidate has a yes vote.
+    NoChoice,
+    /// More than one candidate has a yes vote; they are listed in order.
+    MultipleChoices(Vec<C>),
+    /// The ballot encodes a vote for a different candidate.
+    WrongChoice { expected: C, actual: C },
+}
+
+impl<C: Debug> Display for AuditError<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Vote(candidate) => write!(f, "invalid vote for candidate id {:?}", candidate),
+            Self::BallotProof => write!(f, "invalid ballot proof"),
+            Self::NoChoice => write!(f, "no yes votes"),
+            Self::MultipleChoices(candidates) => {
+                write!(f, "yes votes for candidate ids {:?}", candidates)
+            }
+            Self::WrongChoice { expected, actual } => write!(
+                f,
+                "expected a vote for candidate id {:?}, got {:?}",
+                expected, actual
+            ),
+        }
+    }
+}
+
+impl<C: Debug> Error for AuditError<C> {}
+
+/// An error due to bytes not being a vote or ballot's encoding; see `Ballot::decode`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The first byte is not `CANONICAL_MAGIC`.
+    Magic(u8),
+    /// The version is not `CANONICAL_VERSION`.
+    Version(u8),
+    /// The input, or a field within it, ended early.
+    Truncated,
+    /// The input, or a field within it, continued after the encoding.
+    TrailingBytes,
+    /// The named field is not the canonical encoding of a value, such as a point not in
+    /// the group or a scalar not reduced modulo the group order.
+    InvalidField(&'static str),
+    /// The votes are not in strictly increasing order of their candidate ids' bytes.
+    UnsortedVotes,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Magic(magic) => write!(f, "unknown magic byte {:#04x}", magic),
+            Self::Version(version) => write!(f, "unsupported version {}", version),
+            Self::Truncated => write!(f, "truncated input"),
+            Self::TrailingBytes => write!(f, "trailing bytes"),
+            Self::InvalidField(name) => write!(f, "invalid {}", name),
+            Self::UnsortedVotes => write!(f, "votes not sorted by candidate id"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// The first byte of every canonical encoding; see `Ballot::canonical_bytes`.
+pub const CANONICAL_MAGIC: u8 = 0xD7;
+
+/// The version of the canonical encoding, following `CANONICAL_MAGIC`.
+pub const CANONICAL_VERSION: u8 = 1;
+
+/// The framing of every field in the canonical encoding: an 8-byte big-endian length.
+const CANONICAL_FIELDS: TranscriptVersion = TranscriptVersion::V1;
+
+/// Reads the fields of a canonical encoding in turn.
+struct CanonicalReader<'a>(&'a [u8]);
+
+impl<'a> CanonicalReader<'a> {
+    /// Start reading after `CANONICAL_MAGIC` and `CANONICAL_VERSION`.
+    fn with_header(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        match bytes {
+            [CANONICAL_MAGIC, CANONICAL_VERSION, rest @ ..] => Ok(Self(rest)),
+            [CANONICAL_MAGIC, version, ..] => Err(DecodeError::Version(*version)),
+            [magic, ..] if *magic != CANONICAL_MAGIC => Err(DecodeError::Magic(*magic)),
+            _ => Err(DecodeError::Truncated),
+        }
+    }
+
+    /// The contents of the next field.
+    fn field(&mut self) -> Result<&'a [u8], DecodeError> {
+        if self.0.len() < 8 {
+            return Err(DecodeError::Truncated);
+        }
+        let (len, rest) = self.0.split_at(8);
+        let len = u64::from_be_bytes(len.try_into().unwrap());
+        if len > rest.len() as u64 {
+            return Err(DecodeError::Truncated);
+        }
+        let (field, rest) = rest.split_at(len as usize);
+        self.0 = rest;
+        Ok(field)
+    }
+
+    /// Decode the next field as a point or scalar.
+    fn element<T: Serializable>(&mut self, name: &'static str) -> Result<T, DecodeError> {
+        T::from_bytes(self.field()?).ok_or(DecodeError::InvalidField(name))
+    }
+
+    /// Check that every field has been read.
+    fn finish(self) -> Result<(), DecodeError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::TrailingBytes)
+        }
+    }
 }
 
 pub trait VoteSecrets<G: DreipGroup> {
     /// Verify the secrets against the actual `R` and `Z` values.
     #[allow(non_snake_case)]
     fn verify(&self, g1: G::Point, g2: G::Point, R: G::Point, Z: G::Point) -> Option<()>;
+
+    /// Append the secrets as length-prefixed fields of the canonical encoding: none, or
+    /// `r` then `v`.
+    fn write_canonical(&self, out: &mut Vec<u8>);
+
+    /// Decode the secrets from the fields written by `write_canonical`, or `None` if they
+    /// are not exactly such fields.
+    fn read_canonical(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized;
 }
 
 /// Vote secrets are the `r` and `v` values.
@@ -49,32 +504,92 @@ pub struct SecretsPresent<G: DreipGroup> {
     #[serde(with = "crate::group::serde_bytestring")]
     pub r: G::Scalar,
 
-    /// The secret vote value: 1 for yes or 0 for no.
+    /// The secret vote value: 1 (or the ballot's weight) for yes, or 0 for no.
     #[serde(with = "crate::group::serde_bytestring")]
     pub v: G::Scalar,
 }
 
+#[cfg(feature = "zeroize")]
+impl<G: DreipGroup> zeroize::Zeroize for SecretsPresent<G> {
+    fn zeroize(&mut self) {
+        self.r.zeroize();
+        self.v.zeroize();
+    }
+}
+
+/// Secrets are wiped when dropped, including when a vote or ballot is confirmed.
+#[cfg(feature = "zeroize")]
+impl<G: DreipGroup> Drop for SecretsPresent<G> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<G: DreipGroup> zeroize::ZeroizeOnDrop for SecretsPresent<G> {}
+
+impl<G: DreipGroup> SecretsPresent<G> {
+    /// Whether these secrets are a yes: `v` is exactly 1.
+    ///
+    /// Any other `v` is not a yes, including values such as 2 from a malformed dump, which
+    /// no valid vote proof accepts, and the `v` of a weighted yes vote, which is its weight.
+    pub fn is_yes(&self) -> bool {
+        self.v == G::Scalar::one()
+    }
+
+    /// The public values `(R, Z)` that these secrets give: `R = g2*r` and `Z = g1*(r + v)`.
+    #[allow(non_snake_case)]
+    pub fn derive_public(&self, g1: G::Point, g2: G::Point) -> (G::Point, G::Point) {
+        let R = g2 * self.r.clone();
+        let Z = g1 * (self.r.clone() + self.v.clone());
+        (R, Z)
+    }
+}
+
 impl<G: DreipGroup> VoteSecrets<G> for SecretsPresent<G> {
     #[allow(non_snake_case)]
     fn verify(&self, g1: G::Point, g2: G::Point, R: G::Point, Z: G::Point) -> Option<()> {
         // Check that R and Z are correctly calculated from r and v.
-        let correct_Z = g1 * (self.r + self.v);
-        let correct_R = g2 * self.r;
-        if correct_Z == Z && correct_R == R {
+        let (correct_R, correct_Z) = self.derive_public(g1, g2);
+        // Compare in constant time, without short-circuiting, as r and v are secret.
+        if correct_Z.ct_eq(&Z) & correct_R.ct_eq(&R) {
             Some(())
         } else {
             None
         }
     }
+
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        CANONICAL_FIELDS.write_field(out, &self.r.to_bytes());
+        CANONICAL_FIELDS.write_field(out, &self.v.to_bytes());
+    }
+
+    fn read_canonical(bytes: &[u8]) -> Option<Self> {
+        let mut fields = CanonicalReader(bytes);
+        let r = fields.element("r").ok()?;
+        let v = fields.element("v").ok()?;
+        fields.finish().ok()?;
+        Some(Self { r, v })
+    }
 }
 
 impl<'a, G: DreipGroup> From<&'a SecretsPresent<G>> for Vec<u8> {
     fn from(secrets: &'a SecretsPresent<G>) -> Self {
-        let mut bytes = Vec::new();
-        bytes.extend(secrets.r.to_bytes());
-        bytes.extend(secrets.v.to_bytes());
+        // The secrets are concatenated, whatever the transcript version.
+        ToBytes::to_bytes(secrets, TranscriptVersion::V0)
+    }
+}
 
-        bytes
+/// The secrets are concatenated, whatever the transcript version, and form a single field of
+/// the vote's encoding.
+impl<G: DreipGroup> ToBytes for SecretsPresent<G> {
+    fn write_bytes(&self, out: &mut Vec<u8>, _transcript: TranscriptVersion) {
+        out.extend(self.r.to_bytes());
+        out.extend(self.v.to_bytes());
+    }
+
+    fn byte_len(&self, _transcript: TranscriptVersion) -> usize {
+        encoded_size(&self.r) + encoded_size(&self.v)
     }
 }
 
@@ -91,6 +606,12 @@ impl<G: DreipGroup> VoteSecrets<G> for NoSecrets {
         // Nothing to verify.
         Some(())
     }
+
+    fn write_canonical(&self, _out: &mut Vec<u8>) {}
+
+    fn read_canonical(bytes: &[u8]) -> Option<Self> {
+        bytes.is_empty().then_some(NoSecrets(()))
+    }
 }
 
 impl<'a> From<&'a NoSecrets> for Vec<u8> {
@@ -99,6 +620,136 @@ impl<'a> From<&'a NoSecrets> for Vec<u8> {
     }
 }
 
+impl ToBytes for NoSecrets {
+    fn write_bytes(&self, _out: &mut Vec<u8>, _transcript: TranscriptVersion) {}
+
+    fn byte_len(&self, _transcript: TranscriptVersion) -> usize {
+        0
+    }
+}
+
+/// Vote secrets which may or may not be present, decided at runtime, so that confirmed and
+/// audited ballots can be held in one collection, such as when loading a bulletin board.
+///
+/// This serializes as `SecretsPresent` or `NoSecrets` would: with the `r` and `v` fields,
+/// or without them. Deserialization fails if only one of them is present.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Secrets<G: DreipGroup> {
+    /// The secrets are present, as in an audited ballot.
+    Present(SecretsPresent<G>),
+    /// The secrets are absent, as in a confirmed ballot.
+    Absent,
+}
+
+impl<G: DreipGroup> Secrets<G> {
+    /// The secrets, if present.
+    pub fn present(&self) -> Option<&SecretsPresent<G>> {
+        match self {
+            Self::Present(secrets) => Some(secrets),
+            Self::Absent => None,
+        }
+    }
+
+    /// Convert into the secrets, if present.
+    pub fn into_present(self) -> Option<SecretsPresent<G>> {
+        match self {
+            Self::Present(secrets) => Some(secrets),
+            Self::Absent => None,
+        }
+    }
+}
+
+impl<G: DreipGroup> VoteSecrets<G> for Secrets<G> {
+    #[allow(non_snake_case)]
+    fn verify(&self, g1: G::Point, g2: G::Point, R: G::Point, Z: G::Point) -> Option<()> {
+        match self {
+            Self::Present(secrets) => secrets.verify(g1, g2, R, Z),
+            Self::Absent => Some(()),
+        }
+    }
+
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        if let Self::Present(secrets) = self {
+            secrets.write_canonical(out);
+        }
+    }
+
+    fn read_canonical(bytes: &[u8]) -> Option<Self> {
+        if bytes.is_empty() {
+            Some(Self::Absent)
+        } else {
+            SecretsPresent::read_canonical(bytes).map(Self::Present)
+        }
+    }
+}
+
+impl<'a, G: DreipGroup> From<&'a Secrets<G>> for Vec<u8> {
+    fn from(secrets: &'a Secrets<G>) -> Self {
+        secrets.present().map(Vec::from).unwrap_or_default()
+    }
+}
+
+/// Encoded as the secrets if present, and as nothing otherwise.
+impl<G: DreipGroup> ToBytes for Secrets<G> {
+    fn write_bytes(&self, out: &mut Vec<u8>, transcript: TranscriptVersion) {
+        if let Self::Present(secrets) = self {
+            secrets.write_bytes(out, transcript);
+        }
+    }
+
+    fn byte_len(&self, transcript: TranscriptVersion) -> usize {
+        self.present()
+            .map_or(0, |secrets| secrets.byte_len(transcript))
+    }
+}
+
+impl<G: DreipGroup> From<SecretsPresent<G>> for Secrets<G> {
+    fn from(secrets: SecretsPresent<G>) -> Self {
+        Self::Present(secrets)
+    }
+}
+
+impl<G: DreipGroup> From<NoSecrets> for Secrets<G> {
+    fn from(_: NoSecrets) -> Self {
+        Self::Absent
+    }
+}
+
+impl<G: DreipGroup> Serialize for Secrets<G> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Present(secrets) => secrets.serialize(serializer),
+            Self::Absent => NoSecrets(()).serialize(serializer),
+        }
+    }
+}
+
+impl<'de, G: DreipGroup> Deserialize<'de> for Secrets<G> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// A scalar which may be missing.
+        #[derive(Deserialize)]
+        #[serde(bound = "")]
+        struct Scalar<G: DreipGroup>(#[serde(with = "crate::group::serde_bytestring")] G::Scalar);
+
+        #[derive(Deserialize)]
+        #[serde(bound = "")]
+        struct MaybeSecrets<G: DreipGroup> {
+            #[serde(default)]
+            r: Option<Scalar<G>>,
+            #[serde(default)]
+            v: Option<Scalar<G>>,
+        }
+
+        let secrets = MaybeSecrets::<G>::deserialize(deserializer)?;
+        match (secrets.r, secrets.v) {
+            (Some(Scalar(r)), Some(Scalar(v))) => Ok(Self::Present(SecretsPresent { r, v })),
+            (None, None) => Ok(Self::Absent),
+            (Some(_), None) => Err(serde::de::Error::missing_field("v")),
+            (None, Some(_)) => Err(serde::de::Error::missing_field("r")),
+        }
+    }
+}
+
 /// A single vote, representing a yes/no value for a single candidate.
 #[allow(non_snake_case)]
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
@@ -110,14 +761,64 @@ pub struct Vote<G: DreipGroup, S> {
 
     /// The public R value (g2^r).
     #[serde(with = "crate::group::serde_bytestring")]
-    pub R: G::Point,
+    R: G::Point,
 
     /// The public Z value (g1^(r+v)).
     #[serde(with = "crate::group::serde_bytestring")]
-    pub Z: G::Point,
+    Z: G::Point,
+
+    /// The proof of well-formedness that guarantees `R` and `Z` were calculated correctly.
+    pwf: VoteProof<G>,
+}
+
+#[allow(non_snake_case)]
+impl<G: DreipGroup, S> Vote<G, S> {
+    /// Assemble a vote from its parts, such as when loading it from storage. The vote is
+    /// not checked; see `verify`.
+    pub fn from_parts(secrets: S, R: G::Point, Z: G::Point, pwf: VoteProof<G>) -> Self {
+        Self { secrets, R, Z, pwf }
+    }
+
+    /// The public R value (g2^r).
+    pub fn R(&self) -> &G::Point {
+        &self.R
+    }
+
+    /// The public Z value (g1^(r+v)).
+    pub fn Z(&self) -> &G::Point {
+        &self.Z
+    }
 
     /// The proof of well-formedness that guarantees `R` and `Z` were calculated correctly.
-    pub pwf: VoteProof<G>,
+    pub fn pwf(&self) -> &VoteProof<G> {
+        &self.pwf
+    }
+
+    /// The public values `Z` and `R` of this vote, which its proof is about.
+    pub fn statement(&self) -> VoteStatement<G> {
+        VoteStatement {
+            Z: self.Z.clone(),
+            R: self.R.clone(),
+        }
+    }
+
+    /// Mutable access to `R`, for testing that tampering is detected.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn R_mut(&mut self) -> &mut G::Point {
+        &mut self.R
+    }
+
+    /// Mutable access to `Z`, for testing that tampering is detected.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn Z_mut(&mut self) -> &mut G::Point {
+        &mut self.Z
+    }
+
+    /// Mutable access to the proof, for testing that tampering is detected.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn pwf_mut(&mut self) -> &mut VoteProof<G> {
+        &mut self.pwf
+    }
 }
 
 impl<G, S> Vote<G, S>
@@ -125,35 +826,78 @@ where
     G: DreipGroup,
     S: VoteSecrets<G>,
 {
-    /// Verify this vote.
+    /// Verify this vote, using the given transcript version for the proof.
     pub fn verify<B, C>(
         &self,
         g1: G::Point,
         g2: G::Point,
         ballot_id: B,
         candidate_id: C,
+        transcript: TranscriptVersion,
+    ) -> Result<(), VoteError<B, C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        self.verify_weighted(g1, g2, ballot_id, candidate_id, 1, transcript)
+    }
+
+    /// Verify this vote, as `verify` does, but expecting it to be 0 or `weight`, as for
+    /// votes in ballots created with `Ballot::new_weighted`.
+    pub fn verify_weighted<B, C>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        candidate_id: C,
+        weight: u64,
+        transcript: TranscriptVersion,
     ) -> Result<(), VoteError<B, C>>
     where
-        B: AsRef<[u8]>,
-        C: AsRef<[u8]>,
+        B: IdBytes,
+        C: IdBytes,
     {
+        // Reject degenerate commitments before anything else, as no valid vote has them.
+        if self.Z == G::Point::identity() || self.R == G::Point::identity() {
+            return Err(VoteError {
+                ballot_id,
+                candidate_id,
+                reason: VoteFailure::IdentityPoint,
+            });
+        }
+
         // Verify the secrets (if present).
-        if self.secrets.verify(g1, g2, self.R, self.Z).is_none() {
+        if self
+            .secrets
+            .verify(g1.clone(), g2.clone(), self.R.clone(), self.Z.clone())
+            .is_none()
+        {
             return Err(VoteError {
                 ballot_id,
                 candidate_id,
+                reason: VoteFailure::SecretsMismatch,
             });
         }
 
         // Verify the PWF.
         if self
             .pwf
-            .verify(g1, g2, self.Z, self.R, &ballot_id, &candidate_id)
-            .is_none()
+            .verify_weighted(
+                g1,
+                g2,
+                self.Z.clone(),
+                self.R.clone(),
+                &ballot_id,
+                &candidate_id,
+                weight,
+                transcript,
+            )
+            .is_err()
         {
             return Err(VoteError {
                 ballot_id,
                 candidate_id,
+                reason: VoteFailure::ProofInvalid,
             });
         }
 
@@ -161,47 +905,211 @@ where
     }
 }
 
-impl<G: DreipGroup, S> Vote<G, S>
-where
-    for<'a> &'a S: Into<Vec<u8>>,
-{
-    /// Convert to bytes for signing.
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        bytes.extend::<Vec<u8>>((&self.secrets).into());
-        bytes.extend(self.R.to_bytes());
-        bytes.extend(self.Z.to_bytes());
-        bytes.extend(self.pwf.to_bytes());
+impl<G: DreipGroup, S: ToBytes> Vote<G, S> {
+    /// Convert to bytes for signing, using the given transcript version.
+    ///
+    /// This is the legacy encoding, kept for verifying existing signatures;
+    /// new signatures use `canonical_bytes`.
+    pub fn to_bytes(&self, transcript: TranscriptVersion) -> Vec<u8> {
+        ToBytes::to_bytes(self, transcript)
+    }
+}
+
+impl<G: DreipGroup, S: VoteSecrets<G>> Vote<G, S> {
+    /// The canonical encoding of this vote: `CANONICAL_MAGIC` and `CANONICAL_VERSION`, then
+    /// the body written by `write_canonical`.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = vec![CANONICAL_MAGIC, CANONICAL_VERSION];
+        self.write_canonical(&mut out);
+        out
+    }
+
+    /// Append the body of the canonical encoding: the secrets, `R`, `Z`, and the proof,
+    /// as length-prefixed fields. The secrets field holds `r` and `v` as length-prefixed
+    /// fields, or is empty without secrets; the proof field holds `c1`, `c2`, `r1`, and `r2`.
+    fn write_canonical(&self, out: &mut Vec<u8>) {
+        let mut secrets = Vec::new();
+        self.secrets.write_canonical(&mut secrets);
+        CANONICAL_FIELDS.write_field(out, &secrets);
+        CANONICAL_FIELDS.write_field(out, &self.R.to_bytes());
+        CANONICAL_FIELDS.write_field(out, &self.Z.to_bytes());
+        CANONICAL_FIELDS.write_value(out, &self.pwf);
+    }
 
-        bytes
+    /// Encode this vote for storage, as `canonical_bytes`; `decode` reverses it.
+    pub fn encode(&self) -> Vec<u8> {
+        self.canonical_bytes()
+    }
+
+    /// Decode a vote from its `encode` bytes.
+    ///
+    /// Every point and scalar must be canonically encoded, and there must be no bytes left
+    /// over. The vote is not otherwise checked; see `verify`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut fields = CanonicalReader::with_header(bytes)?;
+        let vote = Self::read_canonical(&mut fields)?;
+        fields.finish()?;
+        Ok(vote)
+    }
+
+    /// Read the fields written by `write_canonical`.
+    #[allow(non_snake_case)]
+    fn read_canonical(fields: &mut CanonicalReader<'_>) -> Result<Self, DecodeError> {
+        let secrets =
+            S::read_canonical(fields.field()?).ok_or(DecodeError::InvalidField("secrets"))?;
+        let R = fields.element("R")?;
+        let Z = fields.element("Z")?;
+        let mut proof = CanonicalReader(fields.field()?);
+        let pwf = VoteProof {
+            c1: proof.element("c1")?,
+            c2: proof.element("c2")?,
+            r1: proof.element("r1")?,
+            r2: proof.element("r2")?,
+        };
+        proof.finish()?;
+        Ok(Self { secrets, R, Z, pwf })
+    }
+}
+
+impl<G: DreipGroup, S: ToBytes> ToBytes for Vote<G, S> {
+    fn write_bytes(&self, out: &mut Vec<u8>, transcript: TranscriptVersion) {
+        transcript.write_value(out, &self.secrets);
+        transcript.write_field(out, &self.R.to_bytes());
+        transcript.write_field(out, &self.Z.to_bytes());
+        transcript.write_value(out, &self.pwf);
+    }
+
+    fn byte_len(&self, transcript: TranscriptVersion) -> usize {
+        let total = self.secrets.byte_len(transcript)
+            + encoded_size(&self.R)
+            + encoded_size(&self.Z)
+            + self.pwf.byte_len(transcript);
+        transcript.encoded_len(total, 4)
     }
 }
 
 impl<G: DreipGroup> Vote<G, SecretsPresent<G>> {
     /// Create a new vote.
-    #[allow(non_snake_case)]
     pub fn new(
-        mut rng: impl RngCore + CryptoRng,
+        rng: impl RngCore + CryptoRng,
         g1: G::Point,
         g2: G::Point,
-        ballot_id: impl AsRef<[u8]>,
-        candidate: impl AsRef<[u8]>,
+        ballot_id: impl IdBytes,
+        candidate: impl IdBytes,
         yes: bool,
     ) -> Self {
-        // Choose secret random r.
-        let r = G::Scalar::random(&mut rng);
-        // Select secret vote v.
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_with_transcript(rng, g1, g2, ballot_id, candidate, yes, transcript)
+    }
+
+    /// Create a new vote as `new` does, with a proof using the given transcript version.
+    /// For an election bound to its id, this is `election.transcript`; see `Election::bound`.
+    pub fn new_with_transcript(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: impl IdBytes,
+        candidate: impl IdBytes,
+        yes: bool,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            ballot_id,
+            candidate,
+            yes,
+            transcript,
+        )
+    }
+
+    /// Create a new vote, using precomputed tables for the generators.
+    /// The result is identical to that of `new` given the same random number stream.
+    pub fn new_precomputed(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        ballot_id: impl IdBytes,
+        candidate: impl IdBytes,
+        yes: bool,
+    ) -> Self {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_precomputed_with_transcript(rng, tables, ballot_id, candidate, yes, transcript)
+    }
+
+    /// Create a new vote as `new_precomputed` does, with a proof using the given transcript
+    /// version.
+    pub fn new_precomputed_with_transcript(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        ballot_id: impl IdBytes,
+        candidate: impl IdBytes,
+        yes: bool,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Table(&tables.g1),
+            &Generator::Table(&tables.g2),
+            ballot_id,
+            candidate,
+            yes,
+            transcript,
+        )
+    }
+
+    /// Create a new vote with the given generators and transcript version.
+    fn create(
+        rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        ballot_id: impl IdBytes,
+        candidate: impl IdBytes,
+        yes: bool,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create_weighted(rng, g1, g2, ballot_id, candidate, yes, 1, transcript)
+    }
+
+    /// Create a new vote with the given generators, which is `weight` for yes.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    fn create_weighted(
+        mut rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        ballot_id: impl IdBytes,
+        candidate: impl IdBytes,
+        yes: bool,
+        weight: u64,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        // Choose secret random r.
+        let r = G::Scalar::random(&mut rng);
+        // Select secret vote v.
         let v = if yes {
-            G::Scalar::one()
+            scalar_from_u64(weight)
         } else {
             G::Scalar::zero()
         };
         // Calculate public random R.
-        let R = g2 * r;
+        let R = g2.mul(r.clone());
         // Calculate public vote Z.
-        let Z = g1 * (r + v);
+        let Z = g1.mul(r.clone() + v.clone());
         // Create PWF.
-        let pwf = VoteProof::new(rng, g1, g2, yes, r, Z, R, ballot_id, candidate);
+        let pwf = VoteProof::create_weighted(
+            rng,
+            g1,
+            g2,
+            yes,
+            weight,
+            r.clone(),
+            Z.clone(),
+            R.clone(),
+            ballot_id,
+            candidate,
+            transcript,
+        );
 
         Self {
             secrets: SecretsPresent { r, v },
@@ -211,6 +1119,12 @@ impl<G: DreipGroup> Vote<G, SecretsPresent<G>> {
         }
     }
 
+    /// Whether this vote is a yes, as `SecretsPresent::is_yes` defines. This reads the
+    /// secrets without verifying them against `R` and `Z`.
+    pub fn voted_yes(&self) -> bool {
+        self.secrets.is_yes()
+    }
+
     /// Confirm this vote, discarding `r` and `v`.
     pub fn confirm(self) -> Vote<G, NoSecrets> {
         Vote {
@@ -222,7 +1136,59 @@ impl<G: DreipGroup> Vote<G, SecretsPresent<G>> {
     }
 }
 
-/// A single ballot, representing a yes for exactly one candidate across a set of candidates.
+impl<G: DreipGroup> From<Vote<G, SecretsPresent<G>>> for Vote<G, Secrets<G>> {
+    fn from(vote: Vote<G, SecretsPresent<G>>) -> Self {
+        Vote {
+            secrets: vote.secrets.into(),
+            R: vote.R,
+            Z: vote.Z,
+            pwf: vote.pwf,
+        }
+    }
+}
+
+impl<G: DreipGroup> From<Vote<G, NoSecrets>> for Vote<G, Secrets<G>> {
+    fn from(vote: Vote<G, NoSecrets>) -> Self {
+        Vote {
+            secrets: vote.secrets.into(),
+            R: vote.R,
+            Z: vote.Z,
+            pwf: vote.pwf,
+        }
+    }
+}
+
+impl<G: DreipGroup> Vote<G, Secrets<G>> {
+    /// Convert into a vote with its secrets, or give it back if they are absent.
+    pub fn into_present(self) -> Result<Vote<G, SecretsPresent<G>>, Self> {
+        match self.secrets {
+            Secrets::Present(secrets) => Ok(Vote {
+                secrets,
+                R: self.R,
+                Z: self.Z,
+                pwf: self.pwf,
+            }),
+            Secrets::Absent => Err(self),
+        }
+    }
+
+    /// Convert into a vote without secrets, or give it back if they are present.
+    pub fn into_absent(self) -> Result<Vote<G, NoSecrets>, Self> {
+        match self.secrets {
+            Secrets::Absent => Ok(Vote {
+                secrets: NoSecrets(()),
+                R: self.R,
+                Z: self.Z,
+                pwf: self.pwf,
+            }),
+            Secrets::Present(_) => Err(self),
+        }
+    }
+}
+
+/// A single ballot, representing a yes for exactly one candidate across a set of candidates,
+/// or for exactly `k` candidates if created with `new_multi`. A referendum ballot, created with
+/// `new_referendum`, instead represents a yes or no for a single candidate.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(bound(
     serialize = "C: Serialize, S: Serialize",
@@ -230,159 +1196,1632 @@ impl<G: DreipGroup> Vote<G, SecretsPresent<G>> {
 ))]
 pub struct Ballot<C, G, S>
 where
-    C: Hash + Eq,
+    C: Ord,
+    G: DreipGroup,
+{
+    /// Map from candidate IDs to individual votes, in order of candidate ID, so that the
+    /// ballot serializes the same way every time.
+    votes: BTreeMap<C, Vote<G, S>>,
+
+    /// The proof of well-formedness that guarantees exactly one, or `k`, of the `votes`
+    /// represent yes.
+    pwf: BallotProof<G>,
+}
+
+impl<C, G, S> Ballot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// Assemble a ballot from its votes and proof, such as when loading it from storage.
+    /// The ballot is not checked; see `verify`.
+    pub fn from_parts(votes: BTreeMap<C, Vote<G, S>>, pwf: BallotProof<G>) -> Self {
+        Self { votes, pwf }
+    }
+
+    /// Map from candidate IDs to individual votes, in order of candidate ID.
+    pub fn votes(&self) -> &BTreeMap<C, Vote<G, S>> {
+        &self.votes
+    }
+
+    /// The proof of well-formedness that guarantees exactly one, or `k`, of the votes
+    /// represent yes.
+    pub fn pwf(&self) -> &BallotProof<G> {
+        &self.pwf
+    }
+
+    /// The candidate IDs of the votes, in order.
+    pub fn candidates(&self) -> impl Iterator<Item = &C> {
+        self.votes.keys()
+    }
+
+    /// The number of votes, one per candidate.
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    /// Whether the ballot has no votes.
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    /// The vote for the given candidate, if there is one.
+    pub fn get_vote(&self, candidate: &C) -> Option<&Vote<G, S>> {
+        self.votes.get(candidate)
+    }
+
+    /// The sums of the `Z` and `R` values of all votes, in that order: the statement that
+    /// the ballot proof is made about.
+    pub fn zr_sums(&self) -> (G::Point, G::Point) {
+        zr_sums(self.votes.values())
+    }
+
+    /// Take the ballot apart into its votes and proof.
+    pub fn into_parts(self) -> (BTreeMap<C, Vote<G, S>>, BallotProof<G>) {
+        (self.votes, self.pwf)
+    }
+
+    /// Mutable access to the votes, for testing that tampering is detected.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn votes_mut(&mut self) -> &mut BTreeMap<C, Vote<G, S>> {
+        &mut self.votes
+    }
+
+    /// Mutable access to the proof, for testing that tampering is detected.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn pwf_mut(&mut self) -> &mut BallotProof<G> {
+        &mut self.pwf
+    }
+}
+
+impl<C, G, S> ToBytes for Ballot<C, G, S>
+where
+    C: IdBytes + Ord,
     G: DreipGroup,
+    S: ToBytes,
 {
-    /// Map from candidate IDs to individual votes.
-    pub votes: HashMap<C, Vote<G, S>>,
+    fn write_bytes(&self, out: &mut Vec<u8>, transcript: TranscriptVersion) {
+        for (candidate, vote) in self.votes.iter() {
+            transcript.write_field(out, &candidate.id_bytes());
+            transcript.write_value(out, vote);
+        }
+        transcript.write_value(out, &self.pwf);
+    }
+
+    fn byte_len(&self, transcript: TranscriptVersion) -> usize {
+        let total = self
+            .votes
+            .iter()
+            .map(|(candidate, vote)| candidate.id_bytes().len() + vote.byte_len(transcript))
+            .sum::<usize>()
+            + self.pwf.byte_len(transcript);
+        transcript.encoded_len(total, 2 * self.votes.len() + 1)
+    }
+}
 
-    /// The proof of well-formedness that guarantees exactly one of the `votes` represents yes.
-    pub pwf: BallotProof<G>,
+impl<C, G, S> Ballot<C, G, S>
+where
+    C: IdBytes + Clone + Ord,
+    G: DreipGroup,
+    S: ToBytes,
+{
+    /// Convert to bytes for signing, using the given transcript version.
+    ///
+    /// This is the legacy encoding, kept only for verifying existing signatures: it does not
+    /// include the ballot id, so ballots with the same votes have the same bytes. New
+    /// signatures use `to_bytes_with_id`.
+    pub fn to_bytes(&self, transcript: TranscriptVersion) -> Vec<u8> {
+        ToBytes::to_bytes(self, transcript)
+    }
 }
 
 impl<C, G, S> Ballot<C, G, S>
 where
-    C: AsRef<[u8]> + Clone + Hash + Eq + Ord,
+    C: IdBytes + Ord,
     G: DreipGroup,
-    for<'a> &'a S: Into<Vec<u8>>,
+    S: VoteSecrets<G>,
 {
-    /// Convert to bytes for signing.
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        // Hashmap order is nondeterministic, ensure we iterate in a consistent order.
+    /// The canonical encoding of this ballot, for signing.
+    ///
+    /// This is `CANONICAL_MAGIC` and `CANONICAL_VERSION`, then length-prefixed fields, each
+    /// with its length as an 8-byte big-endian integer: the number of votes, as an 8-byte
+    /// big-endian integer; for each vote in order of the candidate ids' bytes, the candidate
+    /// id and the vote's body as in `Vote::canonical_bytes`; and the ballot proof, holding
+    /// `a`, `b`, and `r` as length-prefixed fields.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
         let mut votes = self.votes.iter().collect::<Vec<_>>();
-        votes.sort_by_key(|(c, _)| *c);
+        votes.sort_by(|(a, _), (b, _)| a.id_bytes().cmp(&b.id_bytes()));
+
+        let mut out = vec![CANONICAL_MAGIC, CANONICAL_VERSION];
+        CANONICAL_FIELDS.write_field(&mut out, &(votes.len() as u64).to_be_bytes());
+        let mut body = Vec::new();
         for (candidate, vote) in votes {
-            bytes.extend(candidate.as_ref());
-            bytes.extend(vote.to_bytes());
+            CANONICAL_FIELDS.write_field(&mut out, &candidate.id_bytes());
+            body.clear();
+            vote.write_canonical(&mut body);
+            CANONICAL_FIELDS.write_field(&mut out, &body);
+        }
+        CANONICAL_FIELDS.write_value(&mut out, &self.pwf);
+        out
+    }
+
+    /// The encoding of this ballot together with its id, for signing.
+    ///
+    /// This is the bytes signed for a `Receipt` without a `SigningContext`: `CANONICAL_MAGIC`
+    /// and `CANONICAL_VERSION`, then length-prefixed fields holding a label, the ballot id,
+    /// and `canonical_bytes`. Ballots with the same votes but different ids therefore have
+    /// different bytes.
+    pub fn to_bytes_with_id(&self, ballot_id: impl IdBytes) -> Vec<u8> {
+        receipt::signed_bytes(&ballot_id.id_bytes(), &self.canonical_bytes(), None)
+    }
+
+    /// Sign a receipt for this ballot, i.e. its bytes from `to_bytes_with_id`, with the given
+    /// signer.
+    ///
+    /// The signature verifies as that of a `Receipt` for the ballot and id without a
+    /// `SigningContext`.
+    pub fn sign_receipt_with_id(
+        &self,
+        ballot_id: impl IdBytes,
+        signer: &dyn ReceiptSigner<G>,
+    ) -> Result<G::Signature, SignError> {
+        signer.sign(&self.to_bytes_with_id(ballot_id))
+    }
+
+    /// Encode this ballot for storage, as `canonical_bytes`; `decode` reverses it.
+    pub fn encode(&self) -> Vec<u8> {
+        self.canonical_bytes()
+    }
+
+    /// Sign a receipt for this ballot, i.e. its bytes from `canonical_bytes`, with the
+    /// given signer.
+    #[deprecated(note = "use `Ballot::sign_receipt_with_id`, which signs the ballot id too")]
+    pub fn sign_receipt(&self, signer: &dyn ReceiptSigner<G>) -> Result<G::Signature, SignError> {
+        signer.sign(&self.canonical_bytes())
+    }
+}
+
+impl<C, G, S> Ballot<C, G, S>
+where
+    C: FromIdBytes + Ord,
+    G: DreipGroup,
+    S: VoteSecrets<G>,
+{
+    /// Decode a ballot from its `encode` bytes.
+    ///
+    /// The votes must be in strictly increasing order of their candidate ids' bytes, every
+    /// candidate id, point, and scalar must be canonically encoded, and there must be no
+    /// bytes left over. The ballot is not otherwise checked; see `verify`.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut fields = CanonicalReader::with_header(bytes)?;
+        let count = fields.field()?;
+        let count = u64::from_be_bytes(
+            count
+                .try_into()
+                .map_err(|_| DecodeError::InvalidField("vote count"))?,
+        );
+
+        let mut votes = BTreeMap::new();
+        let mut previous: Option<&[u8]> = None;
+        for _ in 0..count {
+            let id = fields.field()?;
+            if previous.is_some_and(|previous| previous >= id) {
+                return Err(DecodeError::UnsortedVotes);
+            }
+            previous = Some(id);
+            let candidate =
+                C::from_id_bytes(id).ok_or(DecodeError::InvalidField("candidate id"))?;
+            let mut body = CanonicalReader(fields.field()?);
+            let vote = Vote::read_canonical(&mut body)?;
+            body.finish()?;
+            votes.insert(candidate, vote);
         }
-        bytes.extend(self.pwf.to_bytes());
 
-        bytes
+        let mut proof = CanonicalReader(fields.field()?);
+        let pwf = BallotProof {
+            a: proof.element("a")?,
+            b: proof.element("b")?,
+            r: proof.element("r")?,
+        };
+        proof.finish()?;
+        fields.finish()?;
+        Ok(Self { votes, pwf })
     }
 }
 
 impl<C, G, S> Ballot<C, G, S>
 where
-    C: Hash + Eq + Clone + AsRef<[u8]>,
+    C: Ord + Clone + IdBytes,
     G: DreipGroup,
     S: VoteSecrets<G>,
 {
-    /// Verify this ballot and all votes within it.
-    #[allow(non_snake_case)]
+    /// Verify this ballot and all votes within it, using the given transcript version
+    /// for the proofs.
+    ///
+    /// Ids are only borrowed while verifying: the ballot id is moved into any error, and a
+    /// candidate id is cloned only when its vote fails, so the ballot id need not be `Clone`.
+    /// Election verifiers likewise clone ids only on failure; they accept maps keyed by
+    /// references, such as `HashMap<&B, _>`, for ids that cannot be cloned.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use dre_ip::ids::IdBytes;
+    /// use dre_ip::{Ballot, BallotError, DreipGroup, Election, NoSecrets};
+    ///
+    /// /// A ballot id that cannot be cloned, such as a handle to a database row.
+    /// #[derive(Debug, PartialEq)]
+    /// struct RowId(Vec<u8>);
+    ///
+    /// impl IdBytes for RowId {
+    ///     fn id_bytes(&self) -> Cow<'_, [u8]> {
+    ///         Cow::Borrowed(&self.0)
+    ///     }
+    /// }
+    ///
+    /// fn check<G: DreipGroup>(
+    ///     election: &Election<G>,
+    ///     ballot: &Ballot<&'static str, G, NoSecrets>,
+    ///     row: RowId,
+    /// ) -> Result<(), BallotError<RowId, &'static str>> {
+    ///     let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    ///     ballot.verify(g1, g2, row, election.transcript)
+    /// }
+    /// ```
     pub fn verify<B>(
         &self,
         g1: G::Point,
         g2: G::Point,
         ballot_id: B,
+        transcript: TranscriptVersion,
     ) -> Result<(), BallotError<B, C>>
     where
-        B: AsRef<[u8]> + Clone,
+        B: IdBytes,
     {
-        // Verify individual vote proofs.
-        for (candidate, vote) in self.votes.iter() {
-            vote.verify(g1, g2, ballot_id.clone(), candidate.clone())
-                .map_err(|e| BallotError::Vote(e))?;
+        self.verify_multi(g1, g2, ballot_id, 1, transcript)
+    }
+
+    /// Verify this ballot and all votes within it, as `verify` does, but expecting exactly
+    /// `k` yes votes, as for ballots created with `new_multi`.
+    pub fn verify_multi<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        k: usize,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        BallotLike::verify_ballot(self, g1, g2, ballot_id, k, transcript)
+    }
+
+    /// Verify this weighted ballot, as created by `new_weighted` with the given `weight`,
+    /// and all votes within it. Each vote's proof shows it is 0 or `weight`, and the ballot
+    /// proof shows that they sum to `weight`.
+    #[allow(non_snake_case)]
+    pub fn verify_weighted<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        weight: u64,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        let (Z_sum, R_sum) =
+            match verify_votes(self, g1.clone(), g2.clone(), &ballot_id, weight, transcript) {
+                Ok(sums) => sums,
+                Err(failure) => return Err(failure.into_error(ballot_id)),
+            };
+
+        // Verify the ballot proof.
+        self.pwf
+            .verify_weighted(g1, g2, Z_sum, R_sum, &ballot_id, weight, transcript)
+            .map_err(|reason| BallotError::BallotProof { ballot_id, reason })
+    }
+
+    /// Verify this single-candidate referendum ballot, as created by `new_referendum`, and
+    /// its vote. The ballot must contain exactly one vote, whose proof shows it is 0 or 1.
+    #[allow(non_snake_case)]
+    pub fn verify_referendum<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        let (_, R_sum) = match verify_votes(self, g1.clone(), g2.clone(), &ballot_id, 1, transcript)
+        {
+            Ok(sums) => sums,
+            Err(failure) => return Err(failure.into_error(ballot_id)),
+        };
+        if self.votes.len() != 1 {
+            return Err(BallotError::BallotProof {
+                ballot_id,
+                reason: BallotProofFailure::WrongVoteCount,
+            });
         }
 
         // Verify the ballot proof.
-        let Z_sum: G::Point = self
-            .votes
-            .values()
-            .map(|vote| vote.Z)
-            .fold(G::Point::identity(), |a, b| a + b);
-        let R_sum: G::Point = self
-            .votes
-            .values()
-            .map(|vote| vote.R)
-            .fold(G::Point::identity(), |a, b| a + b);
         self.pwf
-            .verify(g1, g2, Z_sum, R_sum, &ballot_id)
-            .ok_or(BallotError::BallotProof { ballot_id })
+            .verify_referendum(g1, g2, R_sum, &ballot_id, transcript)
+            .map_err(|reason| BallotError::BallotProof { ballot_id, reason })
+    }
+}
+
+/// A ballot in any representation, such as `Ballot` or the more compact `SmallBallot`,
+/// so that elections can be verified over either.
+pub trait BallotLike {
+    /// The type of candidate IDs.
+    type Candidate;
+    /// The group the ballot was created in.
+    type Group: DreipGroup;
+    /// The secrets of each vote, if any.
+    type Secrets;
+
+    /// The votes, in order of candidate ID.
+    fn ballot_votes(
+        &self,
+    ) -> impl Iterator<Item = (&Self::Candidate, &Vote<Self::Group, Self::Secrets>)>;
+
+    /// The proof of well-formedness of the votes.
+    fn ballot_proof(&self) -> &BallotProof<Self::Group>;
+
+    /// Verify this ballot and all votes within it, expecting exactly `k` yes votes, as
+    /// `Ballot::verify_multi` does.
+    #[allow(non_snake_case)]
+    fn verify_ballot<B>(
+        &self,
+        g1: <Self::Group as DreipGroup>::Point,
+        g2: <Self::Group as DreipGroup>::Point,
+        ballot_id: B,
+        k: usize,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, Self::Candidate>>
+    where
+        Self: Sized,
+        B: IdBytes,
+        Self::Candidate: IdBytes + Clone,
+        Self::Secrets: VoteSecrets<Self::Group>,
+    {
+        let (Z_sum, R_sum) =
+            match verify_votes(self, g1.clone(), g2.clone(), &ballot_id, 1, transcript) {
+                Ok(sums) => sums,
+                Err(failure) => return Err(failure.into_error(ballot_id)),
+            };
+
+        // Verify the ballot proof.
+        self.ballot_proof()
+            .verify_multi(g1, g2, Z_sum, R_sum, &ballot_id, k, transcript)
+            .map_err(|reason| BallotError::BallotProof { ballot_id, reason })
+    }
+}
+
+impl<C, G, S> BallotLike for Ballot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    type Candidate = C;
+    type Group = G;
+    type Secrets = S;
+
+    fn ballot_votes(&self) -> impl Iterator<Item = (&C, &Vote<G, S>)> {
+        self.votes.iter()
+    }
+
+    fn ballot_proof(&self) -> &BallotProof<G> {
+        &self.pwf
+    }
+}
+
+impl<T: BallotLike> BallotLike for &T {
+    type Candidate = T::Candidate;
+    type Group = T::Group;
+    type Secrets = T::Secrets;
+
+    fn ballot_votes(
+        &self,
+    ) -> impl Iterator<Item = (&Self::Candidate, &Vote<Self::Group, Self::Secrets>)> {
+        (**self).ballot_votes()
+    }
+
+    fn ballot_proof(&self) -> &BallotProof<Self::Group> {
+        (**self).ballot_proof()
+    }
+}
+
+/// Verify all votes within a ballot, each being 0 or `weight`, but not the ballot proof,
+/// returning the sums of their `Z` and `R` values for verifying it. This fails if there are
+/// no votes, rather than checking the proof against empty sums.
+///
+/// Ids are only borrowed, so the caller can move or clone its ballot id into the error.
+pub(crate) fn verify_votes<'a, T, B, C, G>(
+    ballot: &'a T,
+    g1: G::Point,
+    g2: G::Point,
+    ballot_id: &B,
+    weight: u64,
+    transcript: TranscriptVersion,
+) -> Result<(G::Point, G::Point), VotesFailure<'a, C>>
+where
+    T: BallotLike<Candidate = C, Group = G>,
+    T::Secrets: VoteSecrets<G>,
+    B: IdBytes,
+    C: IdBytes + 'a,
+    G: DreipGroup + 'a,
+{
+    if ballot.ballot_votes().next().is_none() {
+        return Err(VotesFailure::Empty);
     }
+
+    // Verify individual vote proofs.
+    for (candidate_id, vote) in ballot.ballot_votes() {
+        vote.verify_weighted(
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            candidate_id,
+            weight,
+            transcript,
+        )
+        .map_err(|e| VotesFailure::Vote {
+            candidate_id,
+            reason: e.reason,
+        })?;
+    }
+
+    // Sum the values for the ballot proof.
+    Ok(zr_sums(ballot.ballot_votes().map(|(_, vote)| vote)))
+}
+
+/// The sums of the `Z` and `R` values of the given votes, in that order.
+#[allow(non_snake_case)]
+pub(crate) fn zr_sums<'a, G, S>(votes: impl Iterator<Item = &'a Vote<G, S>>) -> (G::Point, G::Point)
+where
+    G: DreipGroup + 'a,
+    S: 'a,
+{
+    votes.fold(
+        (G::Point::identity(), G::Point::identity()),
+        |(Z_sum, R_sum), vote| (Z_sum + vote.Z.clone(), R_sum + vote.R.clone()),
+    )
 }
 
 impl<C, G> Ballot<C, G, SecretsPresent<G>>
 where
-    C: Hash + Eq + Clone,
+    C: Ord + Clone + IdBytes,
     G: DreipGroup,
-    G::Scalar: Eq,
 {
-    /// Create a new ballot. This will fail if any candidate IDs are duplicates.
-    pub fn new<B>(
-        mut rng: impl RngCore + CryptoRng,
+    /// The candidate this audited ballot votes for: the only one whose secret `v` is 1.
+    ///
+    /// This reads the secrets without verifying them; see `verify_audited`.
+    pub fn claimed_choice(&self) -> Result<C, AuditError<C>> {
+        let mut choices = Vec::new();
+        for (candidate, vote) in self.votes.iter() {
+            if vote.voted_yes() {
+                choices.push(candidate.clone());
+            } else if vote.secrets.v != G::Scalar::zero() {
+                return Err(AuditError::Vote(candidate.clone()));
+            }
+        }
+        match choices.len() {
+            0 => Err(AuditError::NoChoice),
+            1 => Ok(choices.remove(0)),
+            // Already in order of candidate ID, as the votes are.
+            _ => Err(AuditError::MultipleChoices(choices)),
+        }
+    }
+
+    /// Verify this audited ballot, its votes, and their secrets, as `verify` does, and that
+    /// it votes for the `expected` candidate.
+    pub fn verify_audited<B>(
+        &self,
         g1: G::Point,
         g2: G::Point,
         ballot_id: B,
-        yes_candidate: C,
-        no_candidates: impl IntoIterator<Item = C>,
-    ) -> Option<Self>
+        expected: &C,
+        transcript: TranscriptVersion,
+    ) -> Result<(), AuditError<C>>
     where
-        B: AsRef<[u8]>,
-        C: AsRef<[u8]>,
+        B: IdBytes,
     {
-        let no_candidates = no_candidates.into_iter();
-
-        let mut votes = if let (_, Some(len)) = no_candidates.size_hint() {
-            HashMap::with_capacity(len)
-        } else {
-            HashMap::new()
-        };
-
-        // Create yes vote.
-        let yes_vote = Vote::new(&mut rng, g1, g2, &ballot_id, &yes_candidate, true);
-        ensure_none(votes.insert(yes_candidate, yes_vote))?;
-        // Create no votes.
-        for candidate in no_candidates {
-            let no_vote = Vote::new(&mut rng, g1, g2, &ballot_id, &candidate, false);
-            ensure_none(votes.insert(candidate, no_vote))?;
+        self.verify(g1, g2, ballot_id, transcript)
+            .map_err(|e| match e {
+                BallotError::Vote(e) => AuditError::Vote(e.candidate_id),
+                BallotError::BallotProof { .. } => AuditError::BallotProof,
+                BallotError::Empty { .. } => AuditError::NoChoice,
+            })?;
+        let actual = self.claimed_choice()?;
+        if actual != *expected {
+            return Err(AuditError::WrongChoice {
+                expected: expected.clone(),
+                actual,
+            });
         }
-        // Create PWF.
-        let r_sum: G::Scalar = votes
-            .values()
-            .map(|vote| vote.secrets.r)
-            .fold(G::Scalar::zero(), |a, b| a + b);
-        let pwf = BallotProof::new(rng, g1, g2, r_sum, &ballot_id);
-
-        Some(Self { votes, pwf })
+        Ok(())
     }
+}
 
-    /// Confirm this ballot, discarding all `r` and `v` values.
-    /// If `totals` is provided, the candidate totals will be appropriately
-    /// incremented before discarding the values. If provided, `totals` must
-    /// contain an entry for every candidate or a panic will occur.
-    pub fn confirm(
-        self,
-        totals: Option<&mut HashMap<C, &mut CandidateTotals<G>>>,
-    ) -> Ballot<C, G, NoSecrets> {
-        // Increment totals if provided.
-        if let Some(totals) = totals {
-            for (candidate, vote) in self.votes.iter() {
-                let entry = totals.get_mut(candidate).unwrap();
-                entry.tally = entry.tally + vote.secrets.v;
-                entry.r_sum = entry.r_sum + vote.secrets.r;
-            }
-        }
-
-        // Drop the secrets.
-        let votes = self
+impl<C, G> Ballot<C, G, SecretsPresent<G>>
+where
+    C: Ord + Display,
+    G: DreipGroup,
+{
+    /// A summary of this audited ballot to read to the voter, such as
+    /// `Ballot 1234: YES for Alice, NO for Bob, NO for Eve`, followed by a line for each
+    /// candidate with the first `prefix_len` bytes of its vote's `Z` and `R` in hex, for
+    /// checking against the bulletin board.
+    ///
+    /// Candidates are in order of their ids. A secret `v` other than 0 or 1 is shown as is,
+    /// as for a weighted yes vote, or as `INVALID` if it does not fit in a `u64`.
+    pub fn summary(&self, ballot_id: impl Display, prefix_len: usize) -> String {
+        let choices = self
             .votes
-            .into_iter()
-            .map(|(c, v)| (c, v.confirm()))
-            .collect::<HashMap<_, _>>();
+            .iter()
+            .map(|(candidate, vote)| {
+                let choice = match vote.secrets.v.to_u64() {
+                    Some(0) => "NO".to_string(),
+                    Some(1) => "YES".to_string(),
+                    Some(v) => format!("YES ({})", v),
+                    None => "INVALID".to_string(),
+                };
+                format!("{} for {}", choice, candidate)
+            })
+            .collect::<Vec<_>>();
+        let mut summary = format!("Ballot {}: {}", ballot_id, choices.join(", "));
+        write_commitments(&mut summary, &self.votes, prefix_len);
+        summary
+    }
+}
 
-        Ballot {
-            votes,
-            pwf: self.pwf,
+/// Append a line for each candidate with hex prefixes of its vote's `Z` and `R`, as in
+/// `Ballot::summary`.
+pub(crate) fn write_commitments<C: Display, G: DreipGroup, S>(
+    out: &mut String,
+    votes: &BTreeMap<C, Vote<G, S>>,
+    prefix_len: usize,
+) {
+    let prefix = |point: &G::Point| {
+        let bytes = point.to_bytes();
+        to_hex(&bytes[..prefix_len.min(bytes.len())])
+    };
+    for (candidate, vote) in votes.iter() {
+        out.push_str(&format!(
+            "\n{}: Z {}, R {}",
+            candidate,
+            prefix(&vote.Z),
+            prefix(&vote.R)
+        ));
+    }
+}
+
+/// Pair each candidate of a new ballot with whether it is a yes candidate, yes candidates
+/// first, checking that no candidate is given twice and no two have the same bytes.
+fn check_candidates<C>(
+    yes_candidates: impl IntoIterator<Item = C>,
+    no_candidates: impl IntoIterator<Item = C>,
+) -> Result<Vec<(C, bool)>, BallotCreationError<C>>
+where
+    C: IdBytes + Ord + Clone,
+{
+    let candidates = yes_candidates
+        .into_iter()
+        .map(|candidate| (candidate, true))
+        .chain(
+            no_candidates
+                .into_iter()
+                .map(|candidate| (candidate, false)),
+        )
+        .collect::<Vec<_>>();
+    {
+        let mut seen = BTreeMap::new();
+        let mut encodings = BTreeMap::new();
+        for (candidate, yes) in candidates.iter() {
+            if let Some(earlier_yes) = seen.insert(candidate, *yes) {
+                return Err(if earlier_yes == *yes {
+                    BallotCreationError::DuplicateCandidate(candidate.clone())
+                } else {
+                    BallotCreationError::YesAndNoCandidate(candidate.clone())
+                });
+            }
+            if let Some(earlier) = encodings.insert(candidate.id_bytes(), candidate) {
+                return Err(BallotCreationError::AmbiguousCandidateEncoding(
+                    earlier.clone(),
+                    candidate.clone(),
+                ));
+            }
         }
     }
+    Ok(candidates)
 }
 
-/// Invert the given option, returning `Some(())` if it is `None`, and `None` if it is `Some(_)`.
-fn ensure_none<T>(option: Option<T>) -> Option<()> {
-    if option.is_none() {
-        Some(())
-    } else {
-        None
+impl<C, G> Ballot<C, G, SecretsPresent<G>>
+where
+    C: Hash + Ord + Clone,
+    G: DreipGroup,
+    G::Scalar: Eq,
+{
+    /// Create a new ballot. This will fail if any candidate IDs are duplicates, or have the
+    /// same bytes as another.
+    pub fn new<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            ballot_id,
+            [yes_candidate],
+            no_candidates,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new ballot as `new` does, with proofs using the given transcript version.
+    /// For an election bound to its id, this is `election.transcript`; see `Election::bound`.
+    pub fn new_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            ballot_id,
+            [yes_candidate],
+            no_candidates,
+            transcript,
+        )
+    }
+
+    /// Create a new ballot as `new` does, returning `None` on failure.
+    #[deprecated(note = "use `Ballot::new`, which reports why creation failed")]
+    pub fn new_opt<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+    ) -> Option<Self>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        Self::new(rng, g1, g2, ballot_id, yes_candidate, no_candidates).ok()
+    }
+
+    /// Create a new ballot with a yes for each of `yes_candidates`, for elections where
+    /// voters select exactly `k` candidates. This will fail if any candidate IDs are
+    /// duplicates, if there are not exactly `k` yes candidates, or if there are no candidates.
+    ///
+    /// The ballot must be verified with `verify_multi`, giving the same `k`.
+    pub fn new_multi<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidates: impl IntoIterator<Item = C>,
+        no_candidates: impl IntoIterator<Item = C>,
+        k: usize,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_multi_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidates,
+            no_candidates,
+            k,
+            transcript,
+        )
+    }
+
+    /// Create a new ballot as `new_multi` does, with proofs using the given transcript version.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_multi_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidates: impl IntoIterator<Item = C>,
+        no_candidates: impl IntoIterator<Item = C>,
+        k: usize,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let yes_candidates = yes_candidates.into_iter().collect::<Vec<_>>();
+        if yes_candidates.len() != k {
+            return Err(BallotCreationError::WrongYesCount {
+                expected: k,
+                actual: yes_candidates.len(),
+            });
+        }
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            ballot_id,
+            yes_candidates,
+            no_candidates,
+            transcript,
+        )
+    }
+
+    /// Create a new weighted ballot, as for shareholder votes, whose yes vote is `weight`
+    /// rather than 1, so confirming it adds `weight` to the yes candidate's tally.
+    /// This will fail if any candidate IDs are duplicates, or the weight is zero.
+    ///
+    /// Each vote's proof shows it is 0 or `weight`, and the ballot proof shows the votes sum
+    /// to `weight`, so the ballot must be verified with `verify_weighted` and the same weight.
+    pub fn new_weighted<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+        weight: u64,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_weighted_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidate,
+            no_candidates,
+            weight,
+            transcript,
+        )
+    }
+
+    /// Create a new weighted ballot as `new_weighted` does, with proofs using the given
+    /// transcript version.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_weighted_with_transcript<B>(
+        mut rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+        weight: u64,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        if weight == 0 {
+            return Err(BallotCreationError::ZeroWeight);
+        }
+        let (g1, g2) = (Generator::Plain(g1), Generator::Plain(g2));
+
+        // Create the yes vote, then no votes.
+        let votes = check_candidates([yes_candidate], no_candidates)?
+            .into_iter()
+            .map(|(candidate, yes)| {
+                let vote = Vote::create_weighted(
+                    &mut rng, &g1, &g2, &ballot_id, &candidate, yes, weight, transcript,
+                );
+                (candidate, vote)
+            })
+            .collect();
+        Self::with_proof(rng, &g1, &g2, ballot_id, votes, transcript)
+    }
+
+    /// Create a new ballot which formally abstains, with a no for every candidate.
+    /// This will fail if any candidate IDs are duplicates, or there are no candidates.
+    ///
+    /// The ballot looks like any other on the bulletin board, but its proof shows that it
+    /// has no yes votes, so it must be verified with `verify_multi` and `k = 0`.
+    pub fn new_abstention<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        candidates: impl IntoIterator<Item = C>,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_abstention_with_transcript(rng, g1, g2, ballot_id, candidates, transcript)
+    }
+
+    /// Create a new ballot as `new_abstention` does, with proofs using the given transcript
+    /// version.
+    pub fn new_abstention_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        candidates: impl IntoIterator<Item = C>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            ballot_id,
+            [],
+            candidates,
+            transcript,
+        )
+    }
+
+    /// Create a new ballot for a single-candidate referendum, such as one yes/no question,
+    /// voting yes or no for the only candidate.
+    ///
+    /// The ballot proof of such a ballot cannot show the number of yes votes without
+    /// revealing the vote, so the ballot must be verified with `verify_referendum`.
+    pub fn new_referendum<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        candidate: C,
+        yes: bool,
+    ) -> Self
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_referendum_with_transcript(rng, g1, g2, ballot_id, candidate, yes, transcript)
+    }
+
+    /// Create a new ballot as `new_referendum` does, with proofs using the given transcript
+    /// version.
+    pub fn new_referendum_with_transcript<B>(
+        mut rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        candidate: C,
+        yes: bool,
+        transcript: TranscriptVersion,
+    ) -> Self
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let (g1, g2) = (Generator::Plain(g1), Generator::Plain(g2));
+        let vote =
+            Vote::<G, _>::create(&mut rng, &g1, &g2, &ballot_id, &candidate, yes, transcript);
+        let pwf = BallotProof::create_referendum(
+            rng,
+            &g1,
+            &g2,
+            vote.secrets.r.clone(),
+            &ballot_id,
+            transcript,
+        );
+
+        Self {
+            votes: BTreeMap::from([(candidate, vote)]),
+            pwf,
+        }
+    }
+
+    /// Create a new ballot, using precomputed tables for the generators.
+    /// The result is identical to that of `new` given the same random number stream.
+    pub fn new_precomputed<B>(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_precomputed_with_transcript(
+            rng,
+            tables,
+            ballot_id,
+            yes_candidate,
+            no_candidates,
+            transcript,
+        )
+    }
+
+    /// Create a new ballot as `new_precomputed` does, with proofs using the given transcript
+    /// version.
+    pub fn new_precomputed_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        Self::create(
+            rng,
+            &Generator::Table(&tables.g1),
+            &Generator::Table(&tables.g2),
+            ballot_id,
+            [yes_candidate],
+            no_candidates,
+            transcript,
+        )
+    }
+
+    /// Create a new ballot with the given generators and transcript version.
+    fn create<B>(
+        mut rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        ballot_id: B,
+        yes_candidates: impl IntoIterator<Item = C>,
+        no_candidates: impl IntoIterator<Item = C>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        // Create yes votes, then no votes.
+        let votes = check_candidates(yes_candidates, no_candidates)?
+            .into_iter()
+            .map(|(candidate, yes)| {
+                let vote = Vote::create(&mut rng, g1, g2, &ballot_id, &candidate, yes, transcript);
+                (candidate, vote)
+            })
+            .collect();
+        Self::with_proof(rng, g1, g2, ballot_id, votes, transcript)
+    }
+
+    /// Create a new ballot as `new` does, but creating the votes in parallel.
+    ///
+    /// A seed is drawn from `rng` for each vote in turn, yes votes first, and the vote is
+    /// created from a `ChaCha20Rng` with that seed; the ballot proof then uses `rng` itself.
+    /// The result is therefore determined by the random number stream, but differs from
+    /// that of `new` given the same stream.
+    #[cfg(feature = "parallel")]
+    pub fn new_par<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes + Sync,
+        C: IdBytes + Send + Sync,
+        G::Point: Send + Sync,
+        G::Scalar: Send + Sync,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::new_par_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidate,
+            no_candidates,
+            transcript,
+        )
+    }
+
+    /// Create a new ballot as `new_par` does, with proofs using the given transcript version.
+    #[cfg(feature = "parallel")]
+    pub fn new_par_with_transcript<B>(
+        mut rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes + Sync,
+        C: IdBytes + Send + Sync,
+        G::Point: Send + Sync,
+        G::Scalar: Send + Sync,
+    {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+        use rayon::prelude::*;
+
+        let seeded = check_candidates([yes_candidate], no_candidates)?
+            .into_iter()
+            .map(|(candidate, yes)| {
+                let mut seed = [0; 32];
+                rng.fill_bytes(&mut seed);
+                (candidate, yes, seed)
+            })
+            .collect::<Vec<_>>();
+
+        let (g1, g2) = (Generator::Plain(g1), Generator::Plain(g2));
+        let votes = seeded
+            .into_par_iter()
+            .map(|(candidate, yes, seed)| {
+                let rng = ChaCha20Rng::from_seed(seed);
+                let vote = Vote::create(rng, &g1, &g2, &ballot_id, &candidate, yes, transcript);
+                (candidate, vote)
+            })
+            .collect::<BTreeMap<_, _>>();
+        Self::with_proof(rng, &g1, &g2, ballot_id, votes, transcript)
+    }
+
+    /// Assemble a ballot from votes created independently, as by `Vote::new`, such as on a
+    /// voting terminal, by creating the ballot proof from their secrets.
+    ///
+    /// Each vote must verify for `ballot_id` and its candidate id, so votes created for
+    /// another ballot are rejected, and exactly one of them must be a yes vote.
+    pub fn from_votes<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        votes: HashMap<C, Vote<G, SecretsPresent<G>>>,
+    ) -> Result<Self, BallotAssemblyError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        let transcript = TranscriptVersion::CURRENT;
+        Self::from_votes_with_transcript(rng, g1, g2, ballot_id, votes, transcript)
+    }
+
+    /// Assemble a ballot as `from_votes` does, from votes created with the given transcript
+    /// version, as by `Vote::new_with_transcript`, creating the ballot proof with it too.
+    pub fn from_votes_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        votes: HashMap<C, Vote<G, SecretsPresent<G>>>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotAssemblyError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        if votes.is_empty() {
+            return Err(BallotAssemblyError::NoVotes);
+        }
+        let votes = votes.into_iter().collect::<BTreeMap<_, _>>();
+        let mut encodings = BTreeMap::new();
+        for (candidate, vote) in votes.iter() {
+            if let Some(earlier) = encodings.insert(candidate.id_bytes(), candidate) {
+                return Err(BallotAssemblyError::AmbiguousCandidateEncoding(
+                    earlier.clone(),
+                    candidate.clone(),
+                ));
+            }
+            if let Err(e) = vote.verify(g1.clone(), g2.clone(), &ballot_id, candidate, transcript) {
+                return Err(BallotAssemblyError::InvalidVote {
+                    candidate: candidate.clone(),
+                    reason: e.reason,
+                });
+            }
+        }
+        let yes_count = votes.values().filter(|vote| vote.voted_yes()).count();
+        if yes_count != 1 {
+            return Err(BallotAssemblyError::WrongYesCount {
+                expected: 1,
+                actual: yes_count,
+            });
+        }
+        let (g1, g2) = (Generator::Plain(g1), Generator::Plain(g2));
+        Self::with_proof(rng, &g1, &g2, ballot_id, votes, transcript)
+            .map_err(|_| BallotAssemblyError::NoVotes)
+    }
+
+    /// Complete a ballot from its votes by creating the ballot proof.
+    fn with_proof<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        ballot_id: B,
+        votes: BTreeMap<C, Vote<G, SecretsPresent<G>>>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+    {
+        if votes.is_empty() {
+            return Err(BallotCreationError::NoCandidates);
+        }
+        // Create PWF.
+        let pwf =
+            BallotProof::create_for_votes(rng, g1, g2, votes.values(), &ballot_id, transcript);
+
+        Ok(Self { votes, pwf })
+    }
+
+    /// Confirm this ballot, discarding all `r` and `v` values.
+    /// If `totals` is provided, the candidate totals will be appropriately
+    /// incremented before discarding the values.
+    ///
+    /// If provided, `totals` must contain an entry for every candidate. Otherwise, this
+    /// fails naming the first missing candidate, without changing any of the totals; the
+    /// ballot is dropped unconfirmed.
+    pub fn confirm(
+        self,
+        totals: Option<&mut HashMap<C, &mut CandidateTotals<G>>>,
+    ) -> Result<Ballot<C, G, NoSecrets>, ConfirmError<C>> {
+        // Increment totals if provided, once all are known to be present.
+        if let Some(totals) = totals {
+            if let Some(candidate) = self.votes.keys().find(|c| !totals.contains_key(*c)) {
+                return Err(ConfirmError::MissingCandidate(candidate.clone()));
+            }
+            for (candidate, vote) in self.votes.iter() {
+                let entry = totals.get_mut(candidate).expect("Already checked");
+                entry.accumulate(&vote.secrets);
+            }
+        }
+
+        // Drop the secrets.
+        let votes = self
+            .votes
+            .into_iter()
+            .map(|(c, v)| (c, v.confirm()))
+            .collect::<BTreeMap<_, _>>();
+
+        Ok(Ballot {
+            votes,
+            pwf: self.pwf,
+        })
+    }
+
+    /// Confirm this ballot, discarding all `r` and `v` values, and return with it the
+    /// ballot's contribution to each candidate's totals, to be added to them with
+    /// `CandidateTotals::merge`.
+    ///
+    /// Unlike `confirm`, this changes no totals itself, so the caller can apply the update
+    /// however it likes, such as within a database transaction.
+    pub fn confirm_with_delta(self) -> (Ballot<C, G, NoSecrets>, HashMap<C, CandidateTotals<G>>) {
+        let delta = self
+            .votes
+            .iter()
+            .map(|(candidate, vote)| {
+                let secrets = &vote.secrets;
+                let totals = CandidateTotals::from((secrets.v.clone(), secrets.r.clone()));
+                (candidate.clone(), totals)
+            })
+            .collect();
+        let votes = self
+            .votes
+            .into_iter()
+            .map(|(c, v)| (c, v.confirm()))
+            .collect();
+        let ballot = Ballot {
+            votes,
+            pwf: self.pwf,
+        };
+        (ballot, delta)
+    }
+
+    /// Cancel this ballot before it is confirmed, for example because the printer jammed.
+    ///
+    /// Cancelled ballots keep their secrets, so they can be checked like audited ballots,
+    /// but they are not counted towards the totals.
+    pub fn cancel(self) -> CancelledBallot<C, G> {
+        CancelledBallot { ballot: self }
+    }
+}
+
+impl<C, G> From<Ballot<C, G, SecretsPresent<G>>> for Ballot<C, G, Secrets<G>>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    fn from(ballot: Ballot<C, G, SecretsPresent<G>>) -> Self {
+        Ballot {
+            votes: ballot
+                .votes
+                .into_iter()
+                .map(|(c, v)| (c, v.into()))
+                .collect(),
+            pwf: ballot.pwf,
+        }
+    }
+}
+
+impl<C, G> From<Ballot<C, G, NoSecrets>> for Ballot<C, G, Secrets<G>>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    fn from(ballot: Ballot<C, G, NoSecrets>) -> Self {
+        Ballot {
+            votes: ballot
+                .votes
+                .into_iter()
+                .map(|(c, v)| (c, v.into()))
+                .collect(),
+            pwf: ballot.pwf,
+        }
+    }
+}
+
+impl<C, G> Ballot<C, G, Secrets<G>>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// Whether every vote has its secrets, as in an audited ballot.
+    pub fn secrets_present(&self) -> bool {
+        self.votes
+            .values()
+            .all(|vote| matches!(vote.secrets, Secrets::Present(_)))
+    }
+
+    /// Whether no vote has its secrets, as in a confirmed ballot.
+    pub fn secrets_absent(&self) -> bool {
+        self.votes
+            .values()
+            .all(|vote| matches!(vote.secrets, Secrets::Absent))
+    }
+
+    /// Convert into a ballot with its secrets, or give it back unless every vote has them.
+    pub fn into_present(self) -> Result<Ballot<C, G, SecretsPresent<G>>, Self> {
+        if !self.secrets_present() {
+            return Err(self);
+        }
+        let votes = self
+            .votes
+            .into_iter()
+            .map(|(c, v)| (c, v.into_present().ok().expect("Already checked")))
+            .collect();
+        Ok(Ballot {
+            votes,
+            pwf: self.pwf,
+        })
+    }
+
+    /// Convert into a ballot without secrets, or give it back unless no vote has them.
+    pub fn into_absent(self) -> Result<Ballot<C, G, NoSecrets>, Self> {
+        if !self.secrets_absent() {
+            return Err(self);
+        }
+        let votes = self
+            .votes
+            .into_iter()
+            .map(|(c, v)| (c, v.into_absent().ok().expect("Already checked")))
+            .collect();
+        Ok(Ballot {
+            votes,
+            pwf: self.pwf,
+        })
+    }
+}
+
+/// A ballot cancelled before confirmation, with its secrets revealed.
+///
+/// Cancelled ballots are published on the bulletin board alongside the confirmed and audited
+/// ones, and verified like audited ballots, but do not contribute to any `CandidateTotals`.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(bound(serialize = "C: Serialize", deserialize = "C: Deserialize<'de>"))]
+pub struct CancelledBallot<C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The cancelled ballot, with its secrets.
+    pub ballot: Ballot<C, G, SecretsPresent<G>>,
+}
+
+impl<C, G> CancelledBallot<C, G>
+where
+    C: Ord + Clone + IdBytes,
+    G: DreipGroup,
+{
+    /// Verify the secrets and proofs of this ballot, as for an audited ballot.
+    pub fn verify<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        self.ballot.verify(g1, g2, ballot_id, transcript)
+    }
+}
+
+/// A newly created ballot, which must be either audited or confirmed, but not both.
+///
+/// Publishing a ballot's secrets and also counting it would break the protocol, so this
+/// wrapper only allows one of the two, consuming the ballot. Verifiers work with the raw
+/// `Ballot`, or with the `AuditedBallot` and `ConfirmedBallot` this gives.
+///
+/// ```
+/// use dre_ip::{DreipGroup, Election, PendingBallot};
+///
+/// fn cast<G: DreipGroup>(election: &Election<G>, audit: bool)
+/// where
+///     G::Scalar: Eq,
+/// {
+///     let (g1, g2) = (election.g1.clone(), election.g2.clone());
+///     let rng = rand::thread_rng();
+///     let pending =
+///         PendingBallot::<_, G>::new(rng, g1.clone(), g2.clone(), "1", "Alice", ["Bob"]).unwrap();
+///     if audit {
+///         let audited = pending.audit();
+///         let result = audited.verify_audited(g1, g2, "1", &"Alice", election.transcript);
+///         assert!(result.is_ok());
+///     } else {
+///         let confirmed = pending.confirm(None).unwrap();
+///         assert!(confirmed.verify(g1, g2, "1", election.transcript).is_ok());
+///     }
+/// }
+/// ```
+///
+/// A ballot cannot be both audited and confirmed:
+///
+/// ```compile_fail,E0382
+/// use dre_ip::{DreipGroup, PendingBallot};
+///
+/// fn misuse<G: DreipGroup>(pending: PendingBallot<&'static str, G>)
+/// where
+///     G::Scalar: Eq,
+/// {
+///     let audited = pending.audit();
+///     let confirmed = pending.confirm(None);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PendingBallot<C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    ballot: Ballot<C, G, SecretsPresent<G>>,
+}
+
+impl<C, G> PendingBallot<C, G>
+where
+    C: Hash + Ord + Clone,
+    G: DreipGroup,
+    G::Scalar: Eq,
+{
+    /// Create a new ballot, as `Ballot::new` does.
+    pub fn new<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+        C: IdBytes,
+    {
+        Ballot::new(rng, g1, g2, ballot_id, yes_candidate, no_candidates).map(Self::from)
+    }
+
+    /// Audit this ballot, keeping its secrets for publication. It can no longer be confirmed.
+    pub fn audit(self) -> AuditedBallot<C, G> {
+        AuditedBallot {
+            ballot: self.ballot,
+        }
+    }
+
+    /// Confirm this ballot, as `Ballot::confirm` does. It can no longer be audited.
+    pub fn confirm(
+        self,
+        totals: Option<&mut HashMap<C, &mut CandidateTotals<G>>>,
+    ) -> Result<ConfirmedBallot<C, G>, ConfirmError<C>> {
+        self.ballot
+            .confirm(totals)
+            .map(|ballot| ConfirmedBallot { ballot })
+    }
+
+    /// Confirm this ballot, as `Ballot::confirm_with_delta` does. It can no longer be
+    /// audited.
+    pub fn confirm_with_delta(self) -> (ConfirmedBallot<C, G>, HashMap<C, CandidateTotals<G>>) {
+        let (ballot, delta) = self.ballot.confirm_with_delta();
+        (ConfirmedBallot { ballot }, delta)
+    }
+}
+
+impl<C, G> From<Ballot<C, G, SecretsPresent<G>>> for PendingBallot<C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    fn from(ballot: Ballot<C, G, SecretsPresent<G>>) -> Self {
+        Self { ballot }
+    }
+}
+
+/// An audited ballot, with its secrets revealed, as given by `PendingBallot::audit`.
+///
+/// This serializes as the ballot itself.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(
+    transparent,
+    bound(serialize = "C: Serialize", deserialize = "C: Deserialize<'de>")
+)]
+pub struct AuditedBallot<C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The audited ballot, with its secrets.
+    pub ballot: Ballot<C, G, SecretsPresent<G>>,
+}
+
+impl<C, G> AuditedBallot<C, G>
+where
+    C: Ord + Clone + IdBytes,
+    G: DreipGroup,
+{
+    /// A summary of this ballot to read to the voter, as `Ballot::summary` gives.
+    pub fn summary(&self, ballot_id: impl Display, prefix_len: usize) -> String
+    where
+        C: Display,
+    {
+        self.ballot.summary(ballot_id, prefix_len)
+    }
+
+    /// Verify the secrets and proofs of this ballot, as `Ballot::verify` does.
+    pub fn verify<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        self.ballot.verify(g1, g2, ballot_id, transcript)
+    }
+
+    /// Verify this ballot, and that it votes for the `expected` candidate, as
+    /// `Ballot::verify_audited` does.
+    pub fn verify_audited<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        expected: &C,
+        transcript: TranscriptVersion,
+    ) -> Result<(), AuditError<C>>
+    where
+        B: IdBytes,
+    {
+        self.ballot
+            .verify_audited(g1, g2, ballot_id, expected, transcript)
+    }
+}
+
+/// A confirmed ballot, without its secrets, as given by `PendingBallot::confirm`.
+///
+/// This serializes as the ballot itself.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(
+    transparent,
+    bound(serialize = "C: Serialize", deserialize = "C: Deserialize<'de>")
+)]
+pub struct ConfirmedBallot<C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The confirmed ballot.
+    pub ballot: Ballot<C, G, NoSecrets>,
+}
+
+impl<C, G> ConfirmedBallot<C, G>
+where
+    C: Ord + Clone + IdBytes,
+    G: DreipGroup,
+{
+    /// Verify the proofs of this ballot, as `Ballot::verify` does.
+    pub fn verify<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        self.ballot.verify(g1, g2, ballot_id, transcript)
+    }
+}
+
+/// A confirmed ballot that was voided when its voter cast again.
+///
+/// The ballot is published as it was confirmed, without its secrets, so that replacing it
+/// does not reveal the voter's earlier choice; the secrets retained to subtract its votes
+/// from the totals stay with `replace_ballot`. Its proofs are verified as for a confirmed
+/// ballot, but it is not counted.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(bound(
+    serialize = "B: Serialize, C: Serialize",
+    deserialize = "B: Deserialize<'de>, C: Deserialize<'de>"
+))]
+pub struct ReplacedBallot<B, C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The ID of the ballot that replaced this one.
+    pub replaced_by: B,
+
+    /// The replaced ballot, without its secrets.
+    pub ballot: Ballot<C, G, NoSecrets>,
+}
+
+impl<B, C, G> ReplacedBallot<B, C, G>
+where
+    C: Ord + Clone + IdBytes,
+    G: DreipGroup,
+{
+    /// Verify the proofs of this ballot under its original ID, as for a confirmed ballot.
+    pub fn verify<B2>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B2,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B2, C>>
+    where
+        B2: IdBytes + Clone,
+    {
+        self.ballot.verify(g1, g2, ballot_id, transcript)
     }
 }
@@ -0,0 +1,608 @@
+//! Selecting among the built-in groups at runtime.
+//!
+//! Everything else in this crate is generic over `DreipGroup`, so a verifier is normally
+//! compiled for a single group. [`AnyElectionResults`] instead picks the group by the
+//! `group` field in the serialized results, and dispatches to the right monomorphised
+//! code, so one executable can verify elections on any enabled group.
+//!
+//! The generic backends, `ZkPoint`/`ZkScalar` and `ArkGroup`, have no fixed group to name,
+//! so are not included.
+
+use serde::de::value::{MapAccessDeserializer, MapDeserializer, SeqDeserializer};
+use serde::de::{self, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+use std::fmt::{self, Formatter};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::ballots::VerificationError;
+use crate::election::{ElectionResults, Limits, LimitsExceeded, TallyOverflow};
+use crate::group::DreipGroup;
+use crate::ids::IdBytes;
+
+/// Generate `DynamicGroup` and `AnyElectionResults` from a list of built-in groups.
+macro_rules! dynamic_groups {
+    ($($feature:literal, $variant:ident, $group:ty;)*) => {
+        /// A built-in group.
+        #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+        pub enum DynamicGroup {
+            $(
+                #[cfg(feature = $feature)]
+                $variant,
+            )*
+        }
+
+        impl DynamicGroup {
+            /// The name of this group in serialized results, `DreipGroup::NAME`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant => <$group as DreipGroup>::NAME,
+                    )*
+                }
+            }
+        }
+
+        /// The results of an election on any built-in group.
+        ///
+        /// This serializes exactly as the `ElectionResults` it wraps. Deserialization reads
+        /// the `group` field first, then decodes the results on the group it names, so errors
+        /// in the results are reported as they are. Results serialized before that field
+        /// existed are decoded as P-256, the only group of that time.
+        #[derive(Debug, Clone, Serialize)]
+        #[serde(untagged, bound(serialize = "B: Serialize, C: Serialize"))]
+        pub enum AnyElectionResults<B, C>
+        where
+            B: Eq + Hash,
+            C: Eq + Hash + Ord,
+        {
+            $(
+                #[cfg(feature = $feature)]
+                $variant(ElectionResults<B, C, $group>),
+            )*
+        }
+
+        impl<'de, B, C> Deserialize<'de> for AnyElectionResults<B, C>
+        where
+            B: Eq + Hash + Deserialize<'de>,
+            C: Eq + Hash + Ord + Deserialize<'de>,
+        {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let content = Content::deserialize(deserializer)?;
+                let name = content.group_name().map_err(de::Error::custom)?;
+                let results = ContentDeserializer::<D::Error>::new(content);
+                match name.as_deref() {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Some(name) if name == <$group as DreipGroup>::NAME => {
+                            ElectionResults::deserialize(results).map(Self::$variant)
+                        }
+                    )*
+                    Some(name) => Err(de::Error::custom(format_args!(
+                        "unknown or disabled group {name}"
+                    ))),
+                    #[cfg(feature = "p256_impl")]
+                    None => ElectionResults::deserialize(results).map(Self::P256),
+                    #[cfg(not(feature = "p256_impl"))]
+                    None => Err(de::Error::missing_field("group")),
+                }
+            }
+        }
+
+        impl<B, C> AnyElectionResults<B, C>
+        where
+            B: IdBytes + Clone + Eq + Hash,
+            C: IdBytes + Clone + Eq + Hash + Ord,
+        {
+            /// The group these results are on.
+            pub fn group(&self) -> DynamicGroup {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(_) => DynamicGroup::$variant,
+                    )*
+                }
+            }
+
+            /// The tally of each candidate as a vote count, as `ElectionResults::tallies` gives.
+            pub fn tallies(&self) -> Result<HashMap<C, u64>, TallyOverflow<C>> {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(results) => results.tallies(),
+                    )*
+                }
+            }
+
+            /// The number of ballots counted in the totals, as `ElectionResults::total_ballots`
+            /// gives.
+            pub fn total_ballots(&self) -> usize {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(results) => results.total_ballots(),
+                    )*
+                }
+            }
+
+            /// Check the results with `ElectionResults::check_limits` on the right group.
+            pub fn check_limits(&self, limits: &Limits) -> Result<(), LimitsExceeded> {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(results) => results.check_limits(limits),
+                    )*
+                }
+            }
+
+            /// Verify the results with `ElectionResults::verify` on the right group.
+            pub fn verify(&self) -> Result<(), VerificationError<B, C>> {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(results) => results.verify(),
+                    )*
+                }
+            }
+
+            /// Verify the results with `ElectionResults::verify_par` on the right group.
+            #[cfg(feature = "parallel")]
+            pub fn verify_par(&self) -> Result<(), VerificationError<B, C>>
+            where
+                B: Sync,
+                C: Send + Sync,
+            {
+                match self {
+                    $(
+                        #[cfg(feature = $feature)]
+                        Self::$variant(results) => results.verify_par(),
+                    )*
+                }
+            }
+        }
+
+        $(
+            #[cfg(feature = $feature)]
+            impl<B, C> From<ElectionResults<B, C, $group>> for AnyElectionResults<B, C>
+            where
+                B: Eq + Hash,
+                C: Eq + Hash + Ord,
+            {
+                fn from(results: ElectionResults<B, C, $group>) -> Self {
+                    Self::$variant(results)
+                }
+            }
+        )*
+    };
+}
+
+/// A self-describing value read from any deserializer, so that `AnyElectionResults` can
+/// look up the `group` field before choosing the type to decode the rest as.
+enum Content {
+    Bool(bool),
+    U64(u64),
+    I64(i64),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Unit,
+    Some(Box<Content>),
+    Seq(Vec<Content>),
+    Map(Vec<(Content, Content)>),
+}
+
+impl Content {
+    /// The value of the top-level `group` field, if there is one.
+    fn group_name(&self) -> Result<Option<String>, &'static str> {
+        let Content::Map(entries) = self else {
+            return Ok(None);
+        };
+        let group = entries.iter().find_map(|(key, value)| match key {
+            Content::String(key) if key == "group" => Some(value),
+            _ => None,
+        });
+        match group {
+            None => Ok(None),
+            Some(Content::String(name)) => Ok(Some(name.clone())),
+            Some(_) => Err("invalid type for group, expected a string"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Content {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ContentVisitor)
+    }
+}
+
+struct ContentVisitor;
+
+impl<'de> Visitor<'de> for ContentVisitor {
+    type Value = Content;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "any value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Content, E> {
+        Ok(Content::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Content, E> {
+        Ok(Content::I64(v))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Content, E> {
+        Ok(Content::U64(v))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Content, E> {
+        Ok(Content::F64(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Content, E> {
+        Ok(Content::String(v.to_owned()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Content, E> {
+        Ok(Content::String(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Content, E> {
+        Ok(Content::Bytes(v.to_vec()))
+    }
+
+    fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Content, E> {
+        Ok(Content::Bytes(v))
+    }
+
+    fn visit_unit<E>(self) -> Result<Content, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_none<E>(self) -> Result<Content, E> {
+        Ok(Content::Unit)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Content, D::Error> {
+        Ok(Content::Some(Box::new(Content::deserialize(deserializer)?)))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Content, A::Error> {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Content::Seq(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Content, A::Error> {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Content::Map(entries))
+    }
+}
+
+impl<'de, E: de::Error> IntoDeserializer<'de, E> for Content {
+    type Deserializer = ContentDeserializer<E>;
+
+    fn into_deserializer(self) -> ContentDeserializer<E> {
+        ContentDeserializer::new(self)
+    }
+}
+
+/// Implement integer deserialization for `ContentDeserializer`, parsing strings as well as
+/// numbers, since formats such as JSON write integer map keys as strings.
+macro_rules! deserialize_integers {
+    ($($method:ident $ty:ty => $visit:ident;)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+                match self.content {
+                    Content::String(v) => match v.parse::<$ty>() {
+                        Ok(n) => visitor.$visit(n),
+                        Err(_) => visitor.visit_string(v),
+                    },
+                    content => ContentDeserializer::new(content).deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+/// Replays a `Content` to a `Deserialize` implementation.
+struct ContentDeserializer<E> {
+    content: Content,
+    error: PhantomData<E>,
+}
+
+impl<E> ContentDeserializer<E> {
+    fn new(content: Content) -> Self {
+        ContentDeserializer {
+            content,
+            error: PhantomData,
+        }
+    }
+}
+
+impl<'de, E: de::Error> Deserializer<'de> for ContentDeserializer<E> {
+    type Error = E;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Bool(v) => visitor.visit_bool(v),
+            Content::U64(v) => visitor.visit_u64(v),
+            Content::I64(v) => visitor.visit_i64(v),
+            Content::F64(v) => visitor.visit_f64(v),
+            Content::String(v) => visitor.visit_string(v),
+            Content::Bytes(v) => visitor.visit_byte_buf(v),
+            Content::Unit => visitor.visit_unit(),
+            Content::Some(v) => visitor.visit_some(v.into_deserializer()),
+            Content::Seq(v) => visitor.visit_seq(SeqDeserializer::new(v.into_iter())),
+            Content::Map(v) => visitor.visit_map(MapDeserializer::new(v.into_iter())),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        match self.content {
+            Content::Unit => visitor.visit_none(),
+            Content::Some(v) => visitor.visit_some(v.into_deserializer()),
+            content => visitor.visit_some(ContentDeserializer::new(content)),
+        }
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        match self.content {
+            Content::String(v) => v
+                .into_deserializer()
+                .deserialize_enum(name, variants, visitor),
+            Content::Map(v) => visitor.visit_enum(MapAccessDeserializer::new(
+                MapDeserializer::new(v.into_iter()),
+            )),
+            content => ContentDeserializer::new(content).deserialize_any(visitor),
+        }
+    }
+
+    deserialize_integers! {
+        deserialize_i8 i8 => visit_i8;
+        deserialize_i16 i16 => visit_i16;
+        deserialize_i32 i32 => visit_i32;
+        deserialize_i64 i64 => visit_i64;
+        deserialize_i128 i128 => visit_i128;
+        deserialize_u8 u8 => visit_u8;
+        deserialize_u16 u16 => visit_u16;
+        deserialize_u32 u32 => visit_u32;
+        deserialize_u64 u64 => visit_u64;
+        deserialize_u128 u128 => visit_u128;
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool f32 f64 char str string bytes byte_buf
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+#[cfg(any(test, feature = "serde_json"))]
+impl<B, C> AnyElectionResults<B, C>
+where
+    B: Eq + Hash + Serialize + serde::de::DeserializeOwned,
+    C: Eq + Hash + Ord + Serialize + serde::de::DeserializeOwned,
+{
+    /// Load results from JSON on any enabled group, as `ElectionResults::from_json_strict`
+    /// does.
+    pub fn from_json_strict(
+        reader: impl std::io::Read,
+    ) -> Result<Self, crate::strict::StrictError> {
+        crate::strict::from_json_strict(reader)
+    }
+
+    /// Load results from JSON on any enabled group, as `ElectionResults::from_json_limited`
+    /// does.
+    pub fn from_json_limited(
+        reader: impl std::io::Read,
+        limits: &Limits,
+    ) -> Result<Self, crate::strict::StrictError>
+    where
+        B: IdBytes + Clone,
+        C: IdBytes + Clone,
+    {
+        let results: Self =
+            crate::strict::from_json_strict_bounded(reader, limits.max_input_bytes)?;
+        results.check_limits(limits)?;
+        Ok(results)
+    }
+}
+
+dynamic_groups! {
+    "p256_impl", P256, crate::group::p256::NistP256;
+    "p384_impl", P384, crate::group::p384::NistP384;
+    "p521_impl", P521, crate::group::p521::NistP521;
+    "ristretto255", Ristretto255, crate::group::Ristretto255;
+    "bls12_381", Bls12381, crate::group::Bls12381;
+    "modp_impl", ModP, crate::group::ModPGroup;
+    "pasta_impl", Pallas, crate::group::Pallas;
+    "brainpool_impl", BrainpoolP256r1, crate::group::bp256::BrainpoolP256r1;
+}
+
+#[cfg(all(test, feature = "p256_impl", feature = "p384_impl"))]
+mod tests {
+    use super::*;
+
+    use crate::ballots::{Ballot, BallotError, VerificationError, VoteError, VoteFailure};
+    use crate::election::{CandidateTotals, Election, ParameterError};
+    use crate::group::{DreipPoint, Serializable};
+
+    /// Run a small election with one audited ballot, and serialize its results tagged with
+    /// the group.
+    fn results_json<G: DreipGroup>() -> String
+    where
+        AnyElectionResults<String, String>: From<ElectionResults<String, String, G>>,
+    {
+        let mut rng = rand::thread_rng();
+        let election = Election::<G>::new(&[b"Dynamic"], &mut rng);
+        let candidates = ["Alice", "Bob"].map(String::from);
+        let mut totals = candidates
+            .iter()
+            .map(|c| (c.clone(), CandidateTotals::default()))
+            .collect::<HashMap<_, _>>();
+        let mut confirmed = HashMap::new();
+        for (i, yes) in candidates.iter().enumerate() {
+            let no = candidates.iter().filter(|c| *c != yes).cloned();
+            let ballot = Ballot::new(
+                &mut rng,
+                election.g1.clone(),
+                election.g2.clone(),
+                i.to_string(),
+                yes.clone(),
+                no,
+            )
+            .unwrap();
+            let mut totals = totals.iter_mut().map(|(c, t)| (c.clone(), t)).collect();
+            confirmed.insert(i.to_string(), ballot.confirm(Some(&mut totals)).unwrap());
+        }
+        let audited = Ballot::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            "audited",
+            candidates[0].clone(),
+            [candidates[1].clone()],
+        )
+        .unwrap();
+        let mut results = election.results(confirmed, totals);
+        results.audited.insert("audited".to_string(), audited);
+        let results = AnyElectionResults::from(results);
+        serde_json::to_string(&results).unwrap()
+    }
+
+    #[test]
+    fn test_verify_any_group() {
+        for (json, group) in [
+            (
+                results_json::<crate::group::p256::NistP256>(),
+                DynamicGroup::P256,
+            ),
+            (
+                results_json::<crate::group::p384::NistP384>(),
+                DynamicGroup::P384,
+            ),
+        ] {
+            let results: AnyElectionResults<String, String> = serde_json::from_str(&json).unwrap();
+            assert_eq!(results.group(), group);
+            assert!(json.contains(&format!("\"group\":\"{}\"", group.name())));
+            assert_eq!(results.verify(), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_tampered_audited() {
+        let json = results_json::<crate::group::p256::NistP256>();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let vote = &mut value["audited"]["audited"]["votes"]["Bob"];
+        vote["v"] = vote["r"].clone();
+        let results: AnyElectionResults<String, String> = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            results.verify(),
+            Err(VerificationError::Audited(BallotError::Vote(VoteError {
+                ballot_id: "audited".to_string(),
+                candidate_id: "Bob".to_string(),
+                reason: VoteFailure::SecretsMismatch,
+            })))
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        let json = results_json::<crate::group::p384::NistP384>();
+        let results = AnyElectionResults::<String, String>::from_json_strict(json.as_bytes());
+        assert_eq!(results.unwrap().group(), DynamicGroup::P384);
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["extra"] = true.into();
+        let json = value.to_string();
+        assert!(serde_json::from_str::<AnyElectionResults<String, String>>(&json).is_ok());
+        let error =
+            AnyElectionResults::<String, String>::from_json_strict(json.as_bytes()).unwrap_err();
+        assert_eq!(error.to_string(), "unexpected field /extra");
+    }
+
+    #[test]
+    fn test_identity_g2() {
+        type G = crate::group::p256::NistP256;
+        let mut value: serde_json::Value = serde_json::from_str(&results_json::<G>()).unwrap();
+        value["g2"] = <G as DreipGroup>::Point::identity().to_bytestring().into();
+        let results: AnyElectionResults<String, String> = serde_json::from_value(value).unwrap();
+        assert_eq!(
+            results.verify(),
+            Err(VerificationError::InvalidParameters(
+                ParameterError::InvalidG2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_wrong_group() {
+        let json = results_json::<crate::group::p256::NistP256>()
+            .replace(DynamicGroup::P256.name(), DynamicGroup::P384.name());
+        assert!(serde_json::from_str::<AnyElectionResults<String, String>>(&json).is_err());
+        let json = results_json::<crate::group::p256::NistP256>()
+            .replace(DynamicGroup::P256.name(), "DREIP-UNKNOWN");
+        let error = serde_json::from_str::<AnyElectionResults<String, String>>(&json).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("unknown or disabled group DREIP-UNKNOWN"));
+    }
+
+    #[test]
+    fn test_decode_error() {
+        type G = crate::group::p384::NistP384;
+        let mut value: serde_json::Value = serde_json::from_str(&results_json::<G>()).unwrap();
+        value["g1"] = "!".into();
+        let expected = serde_json::from_value::<ElectionResults<String, String, G>>(value.clone())
+            .unwrap_err();
+        let error =
+            serde_json::from_value::<AnyElectionResults<String, String>>(value).unwrap_err();
+        assert_eq!(error.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_integer_ids() {
+        type G = crate::group::p256::NistP256;
+        let mut rng = rand::thread_rng();
+        let election = Election::<G>::new(&[b"Integer ids"], &mut rng);
+        let mut totals =
+            HashMap::from([(1u32, CandidateTotals::default()), (2, Default::default())]);
+        let mut confirmed = HashMap::new();
+        for (ballot_id, yes, no) in [(10u64, 1u32, 2u32), (20, 2, 1)] {
+            let ballot =
+                Ballot::new(&mut rng, election.g1, election.g2, ballot_id, yes, [no]).unwrap();
+            let mut totals = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+            confirmed.insert(ballot_id, ballot.confirm(Some(&mut totals)).unwrap());
+        }
+        let results = AnyElectionResults::from(election.results(confirmed, totals));
+        let json = serde_json::to_string(&results).unwrap();
+        assert!(json.contains("\"10\":"));
+        let loaded: AnyElectionResults<u64, u32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.group(), DynamicGroup::P256);
+        assert_eq!(loaded.verify(), Ok(()));
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+
+    #[test]
+    fn test_legacy_results() {
+        let json = results_json::<crate::group::p256::NistP256>()
+            .replace(&format!("\"group\":\"{}\",", DynamicGroup::P256.name()), "");
+        assert!(!json.contains("group"));
+        let results: AnyElectionResults<String, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(results.group(), DynamicGroup::P256);
+        assert_eq!(results.verify(), Ok(()));
+    }
+}
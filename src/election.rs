@@ -1,15 +1,36 @@
-use rand::{CryptoRng, RngCore};
+use hkdf::Hkdf;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::hash::Hash;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 
-use crate::ballots::{Ballot, VerificationError, VoteSecrets};
-use crate::group::{DreipGroup, DreipPoint, DreipScalar};
+use crate::ballots::{
+    verify_votes, Ballot, BallotCreationError, BallotError, BallotLike, CancelledBallot,
+    ConfirmError, NoSecrets, PolicyViolation, ReplacedBallot, SecretsPresent, VerificationError,
+    VoteSecrets,
+};
+use crate::group::{
+    DreipGroup, DreipPoint, DreipPrivateKey, DreipPublicKey, DreipScalar, GroupName, Serializable,
+};
+use crate::ids::{FromIdBytes, IdBytes};
+use crate::precompute::ElectionPrecomputed;
+use crate::pwf::{BallotProof, TranscriptVersion};
+use crate::receipt::{Receipt, SigningContext};
 
 /// An election using the given group.
 #[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
 #[serde(bound = "")]
 pub struct Election<G: DreipGroup> {
+    /// The name of the group, checked on deserialization. Data serialized before this
+    /// field existed is accepted; see `GroupName::is_legacy`.
+    #[serde(default = "GroupName::legacy")]
+    pub group: GroupName<G>,
+
     /// First generator.
     #[serde(with = "crate::group::serde_bytestring")]
     pub g1: G::Point,
@@ -25,23 +46,666 @@ pub struct Election<G: DreipGroup> {
     /// Verification key.
     #[serde(with = "crate::group::serde_bytestring")]
     pub public_key: G::PublicKey,
+
+    /// The transcript version used for this election's proofs and signatures.
+    /// Elections serialized before this field existed used the legacy transcript.
+    #[serde(default = "legacy_transcript")]
+    pub transcript: TranscriptVersion,
+
+    /// The `IdBytes` of the election's candidates, in order, if given to `with_candidates`,
+    /// for `create_ballot` and `create_totals`. This is not part of the public parameters.
+    /// Elections without a roster serialize without this field, as they did before it
+    /// existed.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "serde_roster"
+    )]
+    pub roster: Option<Vec<Vec<u8>>>,
+}
+
+/// Serialization of a roster as a list of bytestrings, as for other byte fields.
+mod serde_roster {
+    use serde::de::Error;
+    use serde::Deserialize;
+
+    pub fn serialize<S: serde::Serializer>(
+        roster: &Option<Vec<Vec<u8>>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let encoded = roster.as_ref().map(|ids| {
+            ids.iter()
+                .map(|id| base64::encode_config(id, base64::URL_SAFE_NO_PAD))
+                .collect::<Vec<_>>()
+        });
+        serde::Serialize::serialize(&encoded, serializer)
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<Vec<u8>>>, D::Error> {
+        let Some(encoded) = Option::<Vec<String>>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        encoded
+            .iter()
+            .map(|id| {
+                base64::decode_config(id, base64::URL_SAFE_NO_PAD).map_err(|e| {
+                    D::Error::custom(format_args!("invalid bytestring for candidate id: {}", e))
+                })
+            })
+            .collect::<Result<_, _>>()
+            .map(Some)
+    }
+}
+
+/// An error due to a public key not corresponding to the private key it was given with.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct KeyMismatch;
+
+/// An error due to invalid public election parameters.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ParameterError {
+    /// The first generator is not the group's standard generator,
+    /// in a group whose `new_generators` uses it.
+    NonStandardG1,
+    /// The second generator is the identity, or equal to the first.
+    InvalidG2,
+    /// The public key does not survive a round trip through its encoding.
+    InvalidPublicKey,
+}
+
+/// An error due to a ballot not having a vote for exactly the election's candidates.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum CandidateMismatch<B, C> {
+    /// The ballot has no vote for the candidate.
+    Missing { ballot_id: B, candidate: C },
+    /// The ballot has a vote for a candidate not in the election.
+    Extra { ballot_id: B, candidate: C },
+}
+
+/// An error due to partial totals not having the same candidates as the first part given to
+/// `merge_totals`. Parts are numbered from 0, in the order given.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum MergeError<C> {
+    /// The part has no totals for a candidate of the first part.
+    MissingCandidate { part: usize, candidate: C },
+    /// The part has totals for a candidate not in the first part.
+    ExtraCandidate { part: usize, candidate: C },
+}
+
+/// An error due to partial totals not adding up to the totals of the whole election, as
+/// checked by `verify_totals_decomposition`. Parts are numbered from 0, in the order given.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum DecompositionError<C> {
+    /// The part has totals for a candidate not in the whole election.
+    ExtraCandidate { part: usize, candidate: C },
+    /// The parts' totals for the candidate do not add up to the whole election's.
+    Mismatch { candidate: C },
+}
+
+/// An error due to a candidate's tally not fitting in a `u64`, so not being a vote count,
+/// as no honestly computed tally can be.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TallyOverflow<C> {
+    /// The candidate, the least by candidate id if there are several.
+    pub candidate_id: C,
+}
+
+impl<C: fmt::Debug> Display for TallyOverflow<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tally of candidate id {:?} does not fit in a u64",
+            self.candidate_id
+        )
+    }
+}
+
+impl<C: fmt::Debug> Error for TallyOverflow<C> {}
+
+/// The HKDF salt for deriving keys in `Election::from_seed`.
+const SEED_SALT: &[u8] = b"DREIP_ELECTION_SEED";
+
+/// The message signed to check that a private and public key correspond.
+const KEY_CHECK_MESSAGE: &[u8] = b"DREIP_KEY_CHECK";
+
+/// The label hashed with `unique_bytes` by `Election::derive_election_id`.
+const ELECTION_ID_LABEL: &[u8] = b"DREIP_ELECTION_ID";
+
+/// The transcript version of elections serialized without one.
+fn legacy_transcript() -> TranscriptVersion {
+    TranscriptVersion::V0
 }
 
 impl<G: DreipGroup> Election<G> {
-    /// Create a new election with random generators and keys.
+    /// Create a new election with random generators and keys,
+    /// using the current transcript version.
     pub fn new(unique_bytes: &[&[u8]], rng: impl RngCore + CryptoRng) -> Self {
+        let (private_key, public_key) = G::new_keys(rng);
+        Self::with_generators(unique_bytes, private_key, public_key)
+    }
+
+    /// Create a new election as `new` does, with proofs bound to an election id derived
+    /// from `unique_bytes`; see `bound`.
+    pub fn new_bound(unique_bytes: &[&[u8]], rng: impl RngCore + CryptoRng) -> Self {
+        Self::new(unique_bytes, rng).bound(Self::derive_election_id(unique_bytes))
+    }
+
+    /// Bind this election's proofs to the given election id, by switching to transcript
+    /// version `V3`, so that ballots created for it do not verify in any other election,
+    /// even one reusing its `unique_bytes` and so its generators, unless its id is the same.
+    ///
+    /// Ballots for a bound election must be created with its transcript version, as by
+    /// `create_ballots` or `Ballot::new_with_transcript`; `Ballot::new` creates unbound proofs.
+    pub fn bound(mut self, election_id: [u8; 32]) -> Self {
+        self.transcript = TranscriptVersion::bound(election_id);
+        self
+    }
+
+    /// Derive an election id from the same `unique_bytes` as the generators: the SHA-256
+    /// hash of the label `DREIP_ELECTION_ID` and each of `unique_bytes`, length-prefixed.
+    /// An election id given to `bound` may come from anywhere else instead.
+    pub fn derive_election_id(unique_bytes: &[&[u8]]) -> [u8; 32] {
+        let input = TranscriptVersion::V1.encode(&[&[ELECTION_ID_LABEL], unique_bytes].concat());
+        Sha256::digest(input).into()
+    }
+
+    /// The id this election's proofs are bound to, if any.
+    pub fn election_id(&self) -> Option<[u8; 32]> {
+        self.transcript.election_id()
+    }
+
+    /// The transcript version of newly created proofs: this election's if it is bound to
+    /// an id, or `TranscriptVersion::CURRENT` otherwise.
+    fn creation_transcript(&self) -> TranscriptVersion {
+        match self.transcript {
+            TranscriptVersion::V3 { .. } => self.transcript,
+            _ => TranscriptVersion::CURRENT,
+        }
+    }
+
+    /// Create a new election deterministically from the given seed, for reproducible test
+    /// environments and key-escrow ceremonies. The generators are derived as usual, and the
+    /// keys are generated from a ChaCha20 stream keyed with HKDF-SHA256 over the seed and
+    /// `unique_bytes`, so the same seed gives different keys in different elections.
+    ///
+    /// Anyone who knows the seed can recover the private key, so it must be kept as secret.
+    pub fn from_seed(unique_bytes: &[&[u8]], seed: &[u8; 32]) -> Self {
+        let mut key = [0; 32];
+        Hkdf::<Sha256>::new(Some(SEED_SALT), seed)
+            .expand(&TranscriptVersion::V1.encode(unique_bytes), &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        let (private_key, public_key) = G::new_keys(ChaCha20Rng::from_seed(key));
+        Self::with_generators(unique_bytes, private_key, public_key)
+    }
+
+    /// Create a new election with generators derived as usual, but using the given keys,
+    /// for example ones imported from a key-management service.
+    ///
+    /// The keys are checked against each other by signing and verifying a test message.
+    pub fn with_keys(
+        unique_bytes: &[&[u8]],
+        private_key: G::PrivateKey,
+        public_key: G::PublicKey,
+    ) -> Result<Self, KeyMismatch> {
+        let signature = private_key.sign(KEY_CHECK_MESSAGE);
+        if !public_key.verify(KEY_CHECK_MESSAGE, &signature) {
+            return Err(KeyMismatch);
+        }
+        Ok(Self::with_generators(unique_bytes, private_key, public_key))
+    }
+
+    /// The public parameters of an election for which only the public key is known,
+    /// for verifier-side use.
+    pub fn from_public(g1: G::Point, g2: G::Point, public_key: G::PublicKey) -> ElectionParams<G> {
+        ElectionParams { g1, g2, public_key }
+    }
+
+    /// Derive the generators, and assemble an election with the given keys.
+    fn with_generators(
+        unique_bytes: &[&[u8]],
+        private_key: G::PrivateKey,
+        public_key: G::PublicKey,
+    ) -> Self {
         let (g1, g2) = G::new_generators(unique_bytes);
         // Sanity check. This should never fail, but we'd like a big loud warning if it does.
         assert_ne!(g1, G::Point::identity());
         assert_ne!(g2, G::Point::identity());
-        let (private_key, public_key) = G::new_keys(rng);
         Self {
+            group: GroupName::new(),
             g1,
             g2,
             private_key,
             public_key,
+            transcript: TranscriptVersion::CURRENT,
+            roster: None,
+        }
+    }
+
+    /// Create a new election as `new` does, with a roster of the given candidates, so that
+    /// `create_ballot` can fill in the no candidates of each ballot, and reject a yes
+    /// candidate not on the roster, such as a misspelled one.
+    ///
+    /// The roster keeps each candidate's `IdBytes`, so it serializes the same whatever the
+    /// candidates' type, and they are recovered with `FromIdBytes` when ballots are created.
+    pub fn with_candidates<C: IdBytes>(
+        unique_bytes: &[&[u8]],
+        candidates: Vec<C>,
+        rng: impl RngCore + CryptoRng,
+    ) -> Self {
+        let mut election = Self::new(unique_bytes, rng);
+        election.roster = Some(
+            candidates
+                .iter()
+                .map(|candidate| candidate.id_bytes().into_owned())
+                .collect(),
+        );
+        election
+    }
+
+    /// The candidates on this election's roster, in order, or `None` if it has no roster or
+    /// one of its ids is not the bytes of any `C`, as when the roster was created with
+    /// another type of candidate id.
+    pub fn candidates<C: FromIdBytes>(&self) -> Option<Vec<C>> {
+        self.roster
+            .as_ref()?
+            .iter()
+            .map(|id| C::from_id_bytes(id))
+            .collect()
+    }
+
+    /// Create a ballot voting yes for `yes_candidate` and no for every other candidate on
+    /// this election's roster.
+    ///
+    /// This fails with `UnknownCandidate` if the yes candidate is not on the roster, with
+    /// `NoRoster` if there is no roster of candidates of this type, or as `Ballot::new`
+    /// fails. If this election is `bound`, the ballot's proofs are bound to its id.
+    pub fn create_ballot<C>(
+        &self,
+        rng: impl RngCore + CryptoRng,
+        ballot_id: impl IdBytes,
+        yes_candidate: C,
+    ) -> Result<Ballot<C, G, SecretsPresent<G>>, BallotCreationError<C>>
+    where
+        C: FromIdBytes + Clone + Eq + Hash + Ord,
+    {
+        let candidates = self
+            .candidates::<C>()
+            .ok_or(BallotCreationError::NoRoster)?;
+        self.create_ballot_from(rng, &candidates, ballot_id, yes_candidate)
+    }
+
+    /// Zero totals for every candidate on this election's roster, for confirming ballots
+    /// created by `create_ballot`, or `None` as for `candidates`.
+    pub fn create_totals<C>(&self) -> Option<HashMap<C, CandidateTotals<G>>>
+    where
+        C: FromIdBytes + Eq + Hash,
+    {
+        let candidates = self.candidates::<C>()?;
+        Some(
+            candidates
+                .into_iter()
+                .map(|candidate| (candidate, CandidateTotals::default()))
+                .collect(),
+        )
+    }
+
+    /// Build precomputed tables for this election's generators, for faster ballot creation.
+    pub fn precompute(&self) -> ElectionPrecomputed<G> {
+        ElectionPrecomputed::new(self.g1.clone(), self.g2.clone())
+    }
+
+    /// The public parameters of this election.
+    pub fn params(&self) -> ElectionParams<G> {
+        ElectionParams {
+            g1: self.g1.clone(),
+            g2: self.g2.clone(),
+            public_key: self.public_key.clone(),
         }
     }
+
+    /// Encode the public parameters of this election, suitable for signing and publishing.
+    /// The private key is never included. See `ElectionParams` for the format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        encode_params::<G>(&self.g1, &self.g2, &self.public_key)
+    }
+
+    /// Decode public parameters produced by `to_bytes`.
+    pub fn params_from_bytes(bytes: &[u8]) -> Option<ElectionParams<G>> {
+        ElectionParams::from_bytes(bytes)
+    }
+
+    /// A short identifier for this election's public parameters; see `ElectionParams::fingerprint`.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(&self.to_bytes())
+    }
+
+    /// The fingerprint as lowercase hex, for display.
+    pub fn fingerprint_hex(&self) -> String {
+        to_hex(&self.fingerprint())
+    }
+
+    /// Collect the published results of this election, for verification by others.
+    pub fn results<B, C>(
+        &self,
+        confirmed: HashMap<B, Ballot<C, G, NoSecrets>>,
+        totals: HashMap<C, CandidateTotals<G>>,
+    ) -> ElectionResults<B, C, G>
+    where
+        B: Eq + Hash,
+        C: Eq + Hash + Ord,
+    {
+        ElectionResults {
+            group: GroupName::new(),
+            g1: self.g1.clone(),
+            g2: self.g2.clone(),
+            public_key: self.public_key.clone(),
+            transcript: self.transcript,
+            confirmed,
+            totals,
+            audited: HashMap::new(),
+            cancelled: HashMap::new(),
+            replaced: HashMap::new(),
+        }
+    }
+
+    /// Create a ballot for each `(ballot_id, yes_candidate)` request, voting no for every
+    /// other candidate in `candidates`.
+    ///
+    /// The ballots are created in order, as by `Ballot::new` in a loop. This fails at the
+    /// first request whose ballot cannot be created, giving its ballot ID: if the yes
+    /// candidate is not in `candidates`, if the ballot ID was already requested, or as
+    /// `Ballot::new` fails. If this election is `bound`, the ballots' proofs are bound to its id.
+    #[allow(clippy::type_complexity)]
+    pub fn create_ballots<B, C>(
+        &self,
+        mut rng: impl RngCore + CryptoRng,
+        candidates: &[C],
+        requests: impl IntoIterator<Item = (B, C)>,
+    ) -> Result<HashMap<B, Ballot<C, G, SecretsPresent<G>>>, (B, BallotCreationError<C>)>
+    where
+        B: IdBytes + Eq + Hash,
+        C: IdBytes + Clone + Eq + Hash + Ord,
+    {
+        let requests = requests.into_iter();
+        let mut ballots = HashMap::with_capacity(requests.size_hint().0);
+        for (ballot_id, yes_candidate) in requests {
+            if ballots.contains_key(&ballot_id) {
+                return Err((ballot_id, BallotCreationError::DuplicateBallot));
+            }
+            match self.create_ballot_from(&mut rng, candidates, &ballot_id, yes_candidate) {
+                Ok(ballot) => {
+                    ballots.insert(ballot_id, ballot);
+                }
+                Err(e) => return Err((ballot_id, e)),
+            }
+        }
+        Ok(ballots)
+    }
+
+    /// Create a ballot voting yes for `yes_candidate` and no for every other candidate in
+    /// `candidates`, failing if the yes candidate is not among them or as `Ballot::new` fails.
+    pub(crate) fn create_ballot_from<C>(
+        &self,
+        rng: impl RngCore + CryptoRng,
+        candidates: &[C],
+        ballot_id: impl IdBytes,
+        yes_candidate: C,
+    ) -> Result<Ballot<C, G, SecretsPresent<G>>, BallotCreationError<C>>
+    where
+        C: IdBytes + Clone + Eq + Hash + Ord,
+    {
+        let yes_index = match candidates.iter().position(|c| *c == yes_candidate) {
+            Some(index) => index,
+            None => return Err(BallotCreationError::UnknownCandidate(yes_candidate)),
+        };
+        // Skip only the yes candidate itself, so a repeated one is still caught.
+        let no_candidates = candidates
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i != yes_index)
+            .map(|(_, c)| c.clone());
+        Ballot::new_with_transcript(
+            rng,
+            self.g1.clone(),
+            self.g2.clone(),
+            ballot_id,
+            yes_candidate,
+            no_candidates,
+            self.creation_transcript(),
+        )
+    }
+
+    /// Create ballots as `create_ballots` does, but in parallel.
+    ///
+    /// The requests are checked, and a seed drawn from `rng` for each, in order; each ballot
+    /// is then created by `Ballot::new` from a `ChaCha20Rng` with its seed. If more than one
+    /// ballot cannot be created, which of them is reported is unspecified.
+    #[cfg(feature = "parallel")]
+    #[allow(clippy::type_complexity)]
+    pub fn create_ballots_par<B, C>(
+        &self,
+        mut rng: impl RngCore + CryptoRng,
+        candidates: &[C],
+        requests: impl IntoIterator<Item = (B, C)>,
+    ) -> Result<HashMap<B, Ballot<C, G, SecretsPresent<G>>>, (B, BallotCreationError<C>)>
+    where
+        B: IdBytes + Eq + Hash + Send,
+        C: IdBytes + Clone + Eq + Hash + Ord + Send + Sync,
+        G::Point: Send + Sync,
+        G::Scalar: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        let requests = requests.into_iter();
+        let mut ids = HashSet::with_capacity(requests.size_hint().0);
+        let mut seeded = Vec::with_capacity(requests.size_hint().0);
+        for (ballot_id, yes_candidate) in requests {
+            if !ids.insert(ballot_id.id_bytes().into_owned()) {
+                return Err((ballot_id, BallotCreationError::DuplicateBallot));
+            }
+            let yes_index = match candidates.iter().position(|c| *c == yes_candidate) {
+                Some(index) => index,
+                None => {
+                    return Err((
+                        ballot_id,
+                        BallotCreationError::UnknownCandidate(yes_candidate),
+                    ))
+                }
+            };
+            let mut seed = [0; 32];
+            rng.fill_bytes(&mut seed);
+            seeded.push((ballot_id, yes_candidate, yes_index, seed));
+        }
+
+        let (g1, g2) = (&self.g1, &self.g2);
+        let transcript = self.creation_transcript();
+        seeded
+            .into_par_iter()
+            .map(|(ballot_id, yes_candidate, yes_index, seed)| {
+                // Skip only the yes candidate itself, so a repeated one is still caught.
+                let no_candidates = candidates
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != yes_index)
+                    .map(|(_, c)| c.clone());
+                match Ballot::new_with_transcript(
+                    ChaCha20Rng::from_seed(seed),
+                    g1.clone(),
+                    g2.clone(),
+                    &ballot_id,
+                    yes_candidate,
+                    no_candidates,
+                    transcript,
+                ) {
+                    Ok(ballot) => Ok((ballot_id, ballot)),
+                    Err(e) => Err((ballot_id, e)),
+                }
+            })
+            .collect()
+    }
+
+    /// Sign a receipt for the given ballot with this election's private key, covering the
+    /// ballot id and the optional `context` as well as the ballot.
+    pub fn issue_receipt<B, C, S>(
+        &self,
+        ballot: Ballot<C, G, S>,
+        ballot_id: B,
+        context: Option<&SigningContext>,
+    ) -> Receipt<B, C, G, S>
+    where
+        B: IdBytes + Clone,
+        C: IdBytes + Clone + Hash + Eq + Ord,
+        S: VoteSecrets<G>,
+    {
+        let bytes = Receipt::to_bytes(&ballot, &ballot_id, context);
+        Receipt {
+            signature: self.private_key.sign(&bytes),
+            ballot_id,
+            ballot,
+            context: context.cloned(),
+            transcript: self.transcript,
+        }
+    }
+
+    /// Check this election's public parameters, as `ElectionParams::validate_parameters`
+    /// does. An election created here always passes, but one deserialized from a dump
+    /// is not checked until this, or `ElectionResults::verify`, is called.
+    pub fn validate_parameters(&self) -> Result<(), ParameterError> {
+        validate_parameters::<G>(&self.g1, &self.g2, &self.public_key)
+    }
+}
+
+/// The public parameters of an election: everything needed to verify it, without the private key.
+///
+/// The byte encoding is stable, so that independent implementations can reproduce it.
+/// It consists of the canonical encodings of `g1`, `g2`, and `public_key`, in that order,
+/// each prefixed with its length as 8 big-endian bytes. Decoding rejects anything else,
+/// including trailing bytes and identity generators.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ElectionParams<G: DreipGroup> {
+    /// First generator.
+    pub g1: G::Point,
+
+    /// Second generator.
+    pub g2: G::Point,
+
+    /// Verification key.
+    pub public_key: G::PublicKey,
+}
+
+impl<G: DreipGroup> ElectionParams<G> {
+    /// A short identifier for these parameters, so that voters and auditors can check
+    /// they are looking at the same election.
+    ///
+    /// This is the SHA-256 hash of the label `DREIP_ELECTION_FINGERPRINT` followed by
+    /// the byte encoding, so it is as stable as the encoding itself.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        fingerprint(&self.to_bytes())
+    }
+
+    /// The fingerprint as lowercase hex, for display.
+    pub fn fingerprint_hex(&self) -> String {
+        to_hex(&self.fingerprint())
+    }
+
+    /// Check that these parameters could have come from an honest election, so that a
+    /// verifier loading them from a file is not relying on attacker-chosen generators.
+    ///
+    /// `g1` must be the group's standard generator, if `DreipGroup::new_generators` uses it,
+    /// and `g2` must be neither the identity nor `g1`. The public key must re-encode and
+    /// decode to itself.
+    pub fn validate_parameters(&self) -> Result<(), ParameterError> {
+        validate_parameters::<G>(&self.g1, &self.g2, &self.public_key)
+    }
+}
+
+impl<G: DreipGroup> Serializable for ElectionParams<G> {
+    fn to_bytes(&self) -> Vec<u8> {
+        encode_params::<G>(&self.g1, &self.g2, &self.public_key)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let mut rest = bytes;
+        let mut next_field = || {
+            let (len, tail) = rest.split_first_chunk::<8>()?;
+            let len = usize::try_from(u64::from_be_bytes(*len)).ok()?;
+            if tail.len() < len {
+                return None;
+            }
+            let (field, tail) = tail.split_at(len);
+            rest = tail;
+            Some(field)
+        };
+        let g1 = G::Point::from_bytes(next_field()?)?;
+        let g2 = G::Point::from_bytes(next_field()?)?;
+        let public_key = G::PublicKey::from_bytes(next_field()?)?;
+        if !rest.is_empty() || g1 == G::Point::identity() || g2 == G::Point::identity() {
+            return None;
+        }
+        Some(Self { g1, g2, public_key })
+    }
+}
+
+/// The domain separation label for election fingerprints.
+const FINGERPRINT_LABEL: &[u8] = b"DREIP_ELECTION_FINGERPRINT";
+
+/// Hash encoded public election parameters, as documented on `ElectionParams::fingerprint`.
+fn fingerprint(params: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update(FINGERPRINT_LABEL)
+        .chain_update(params)
+        .finalize()
+        .into()
+}
+
+/// Format bytes as lowercase hex.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Check election generators, as documented on `ElectionParams::validate_parameters`.
+pub(crate) fn validate_generators<G: DreipGroup>(
+    g1: &G::Point,
+    g2: &G::Point,
+) -> Result<(), ParameterError> {
+    let standard = G::Point::generator();
+    // Not every group derives g1 this way, so only hold it to the convention if it follows it.
+    if G::new_generators(&[]).0 == standard && *g1 != standard {
+        return Err(ParameterError::NonStandardG1);
+    }
+    if *g2 == G::Point::identity() || g2 == g1 {
+        return Err(ParameterError::InvalidG2);
+    }
+    Ok(())
+}
+
+/// Check public election parameters, as documented on `ElectionParams::validate_parameters`.
+fn validate_parameters<G: DreipGroup>(
+    g1: &G::Point,
+    g2: &G::Point,
+    public_key: &G::PublicKey,
+) -> Result<(), ParameterError> {
+    validate_generators::<G>(g1, g2)?;
+    let bytes = public_key.to_bytes();
+    match G::PublicKey::from_bytes(&bytes) {
+        Some(decoded) if decoded.to_bytes() == bytes => Ok(()),
+        _ => Err(ParameterError::InvalidPublicKey),
+    }
+}
+
+/// Encode public election parameters, as documented on `ElectionParams`.
+fn encode_params<G: DreipGroup>(
+    g1: &G::Point,
+    g2: &G::Point,
+    public_key: &G::PublicKey,
+) -> Vec<u8> {
+    // The V1 transcript encoding is exactly this format, and is itself fixed.
+    TranscriptVersion::V1.encode(&[&g1.to_bytes(), &g2.to_bytes(), &public_key.to_bytes()])
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
@@ -54,6 +718,76 @@ pub struct CandidateTotals<G: DreipGroup> {
     pub r_sum: G::Scalar,
 }
 
+impl<G: DreipGroup> CandidateTotals<G> {
+    /// The tally as a vote count, or `None` if it does not fit in a `u64`.
+    pub fn tally_u64(&self) -> Option<u64> {
+        self.tally.to_u64()
+    }
+
+    /// Add a vote to these totals, given its secrets.
+    pub fn accumulate(&mut self, secrets: &SecretsPresent<G>) {
+        self.tally = self.tally.clone() + secrets.v.clone();
+        self.r_sum = self.r_sum.clone() + secrets.r.clone();
+    }
+
+    /// Remove a vote previously added to these totals, given its retained secrets.
+    pub fn retract(&mut self, secrets: &SecretsPresent<G>) {
+        self.tally = self.tally.clone() - secrets.v.clone();
+        self.r_sum = self.r_sum.clone() - secrets.r.clone();
+    }
+
+    /// Add a vote to these totals, given its secrets.
+    #[deprecated(note = "use `CandidateTotals::accumulate`; `add` is the `Add` operator's")]
+    pub fn add(&mut self, secrets: &SecretsPresent<G>) {
+        self.accumulate(secrets);
+    }
+
+    /// Add totals from a disjoint set of votes to these, as when combining partial totals.
+    pub fn merge(&mut self, other: &Self) {
+        self.tally = self.tally.clone() + other.tally.clone();
+        self.r_sum = self.r_sum.clone() + other.r_sum.clone();
+    }
+
+    /// Remove a vote previously added to these totals, given its retained secrets.
+    #[deprecated(note = "use `CandidateTotals::retract`; `subtract` reads as the `Sub` operator's")]
+    pub fn subtract(&mut self, secrets: &SecretsPresent<G>) {
+        self.retract(secrets);
+    }
+}
+
+/// The totals of two disjoint sets of votes together, as `merge` gives.
+impl<G: DreipGroup> Add for CandidateTotals<G> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<G: DreipGroup> AddAssign for CandidateTotals<G> {
+    fn add_assign(&mut self, other: Self) {
+        self.merge(&other);
+    }
+}
+
+/// The totals of a set of votes without a subset of them, as when removing cancelled votes.
+impl<G: DreipGroup> Sub for CandidateTotals<G> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+impl<G: DreipGroup> SubAssign for CandidateTotals<G> {
+    fn sub_assign(&mut self, other: Self) {
+        self.tally = self.tally.clone() - other.tally;
+        self.r_sum = self.r_sum.clone() - other.r_sum;
+    }
+}
+
 impl<G: DreipGroup> Default for CandidateTotals<G> {
     fn default() -> Self {
         Self {
@@ -69,56 +803,1549 @@ impl<G: DreipGroup> From<(G::Scalar, G::Scalar)> for CandidateTotals<G> {
     }
 }
 
-/// Verify all of the given ballots, and the total tallies.
-/// `ballots` should map ballot IDs to ballots, while `totals` should map
-/// candidate ids to `CandidateTotals`.
-///
-/// Note that this is not sufficient for end-to-end verification; we also need
-/// to verify the integrity of all audited ballots, and check the signatures of
-/// all receipts. These tasks are left to the user of this library.
-pub fn verify_election<G, B, C, S>(
-    g1: G::Point,
-    g2: G::Point,
-    ballots: &HashMap<B, Ballot<C, G, S>>,
-    totals: &HashMap<C, CandidateTotals<G>>,
-) -> Result<(), VerificationError<B, C>>
+/// The published results of an election: its public parameters, the confirmed ballots,
+/// and the totals. This is everything needed to verify the tallies, and never includes
+/// the private key.
+#[derive(Debug, Eq, PartialEq, Clone, Deserialize, Serialize)]
+#[serde(bound(
+    serialize = "B: Serialize, C: Serialize",
+    deserialize = "B: Deserialize<'de>, C: Deserialize<'de>"
+))]
+pub struct ElectionResults<B, C, G>
 where
+    B: Eq + Hash,
+    C: Eq + Hash + Ord,
     G: DreipGroup,
-    B: AsRef<[u8]> + Clone,
-    C: AsRef<[u8]> + Eq + Hash + Clone + Ord,
-    S: VoteSecrets<G>,
 {
-    // Verify individual ballots.
-    for (ballot_id, ballot) in ballots.iter() {
-        ballot
-            .verify(g1, g2, ballot_id.clone())
-            .map_err(|e| VerificationError::Ballot(e))?;
-    }
+    /// The name of the group, checked on deserialization. Data serialized before this
+    /// field existed is accepted; see `GroupName::is_legacy`.
+    #[serde(default = "GroupName::legacy")]
+    pub group: GroupName<G>,
 
-    // Calculate true totals.
-    let mut true_totals = HashMap::with_capacity(totals.len());
-    for ballot in ballots.values() {
-        for (candidate_id, vote) in ballot.votes.iter() {
-            let entry = true_totals
-                .entry(candidate_id)
-                .or_insert((G::Point::identity(), G::Point::identity()));
-            entry.0 = entry.0 + vote.Z;
-            entry.1 = entry.1 + vote.R;
+    /// First generator.
+    #[serde(with = "crate::group::serde_bytestring")]
+    pub g1: G::Point,
+
+    /// Second generator.
+    #[serde(with = "crate::group::serde_bytestring")]
+    pub g2: G::Point,
+
+    /// Verification key.
+    #[serde(with = "crate::group::serde_bytestring")]
+    pub public_key: G::PublicKey,
+
+    /// The transcript version used for this election's proofs and signatures.
+    #[serde(default = "legacy_transcript")]
+    pub transcript: TranscriptVersion,
+
+    /// Map from ballot IDs to confirmed ballots.
+    pub confirmed: HashMap<B, Ballot<C, G, NoSecrets>>,
+
+    /// Map from candidate IDs to their totals.
+    pub totals: HashMap<C, CandidateTotals<G>>,
+
+    /// Map from ballot IDs to audited ballots, with their secrets. Results serialized
+    /// before this field existed have none.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub audited: HashMap<B, Ballot<C, G, SecretsPresent<G>>>,
+
+    /// Map from ballot IDs to cancelled ballots, with their secrets.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cancelled: HashMap<B, CancelledBallot<C, G>>,
+
+    /// Map from the original IDs of replaced ballots to their replacement records.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub replaced: HashMap<B, ReplacedBallot<B, C, G>>,
+}
+
+impl<B, C, G> ElectionResults<B, C, G>
+where
+    B: IdBytes + Clone + Eq + Hash,
+    C: IdBytes + Clone + Eq + Hash + Ord,
+    G: DreipGroup,
+{
+    /// The public parameters of the election.
+    pub fn params(&self) -> ElectionParams<G> {
+        ElectionParams {
+            g1: self.g1.clone(),
+            g2: self.g2.clone(),
+            public_key: self.public_key.clone(),
         }
     }
 
-    // Verify we have the right candidates.
-    if true_totals.len() != totals.len() || !true_totals.keys().all(|k| totals.contains_key(k)) {
-        return Err(VerificationError::WrongCandidates);
+    /// Validate the public parameters, then verify all ballots and totals with
+    /// `verify_full_election`.
+    pub fn verify(&self) -> Result<(), VerificationError<B, C>> {
+        self.params()
+            .validate_parameters()
+            .map_err(VerificationError::InvalidParameters)?;
+        verify_full_election(
+            self.g1.clone(),
+            self.g2.clone(),
+            &self.confirmed,
+            &self.audited,
+            &self.cancelled,
+            &self.replaced,
+            &self.totals,
+            self.transcript,
+        )
     }
-    for (candidate_id, CandidateTotals { tally, r_sum }) in totals.iter() {
-        let true_totals = true_totals.get(candidate_id).expect("Already checked");
-        if g1 * (*tally + *r_sum) != true_totals.0 || g2 * *r_sum != true_totals.1 {
-            return Err(VerificationError::Tally {
+
+    /// Verify the results as `verify` does, giving access to their ranking and outcome,
+    /// which are only available from verified results, so that no unverified counts are
+    /// announced.
+    pub fn verified(&self) -> Result<VerifiedResults<'_, B, C, G>, VerificationError<B, C>> {
+        self.verify()?;
+        Ok(VerifiedResults { results: self })
+    }
+
+    /// Verify the results as `verify` does, with the confirmed ballots verified in parallel
+    /// by `verify_election_par`.
+    #[cfg(feature = "parallel")]
+    pub fn verify_par(&self) -> Result<(), VerificationError<B, C>>
+    where
+        B: Sync,
+        C: Send + Sync,
+        G::Point: Send + Sync,
+        G::Scalar: Sync,
+    {
+        self.params()
+            .validate_parameters()
+            .map_err(VerificationError::InvalidParameters)?;
+        verify_revealed(
+            self.g1.clone(),
+            self.g2.clone(),
+            &self.confirmed,
+            &self.audited,
+            &self.cancelled,
+            &self.replaced,
+            self.transcript,
+        )?;
+        verify_election_par(
+            self.g1.clone(),
+            self.g2.clone(),
+            &self.confirmed,
+            &self.totals,
+            self.transcript,
+        )
+    }
+
+    /// The tally of each candidate as a vote count, as `CandidateTotals::tally_u64` gives.
+    ///
+    /// The tallies are only meaningful once the results have been verified with `verify`.
+    /// This fails naming a candidate whose tally does not fit in a `u64`.
+    pub fn tallies(&self) -> Result<HashMap<C, u64>, TallyOverflow<C>> {
+        let mut overflowed = None;
+        let mut tallies = HashMap::with_capacity(self.totals.len());
+        for (candidate_id, totals) in &self.totals {
+            match totals.tally_u64() {
+                Some(count) => {
+                    tallies.insert(candidate_id.clone(), count);
+                }
+                None => {
+                    if overflowed.is_none_or(|least| candidate_id < least) {
+                        overflowed = Some(candidate_id);
+                    }
+                }
+            }
+        }
+        match overflowed {
+            Some(candidate_id) => Err(TallyOverflow {
                 candidate_id: candidate_id.clone(),
+            }),
+            None => Ok(tallies),
+        }
+    }
+
+    /// The number of ballots counted in the totals: the confirmed ballots.
+    pub fn total_ballots(&self) -> usize {
+        self.confirmed.len()
+    }
+
+    /// Check the results against the given limits, other than `max_input_bytes`, which
+    /// only applies while loading. This is cheap next to verification, so verifiers of
+    /// untrusted results can call it first.
+    pub fn check_limits(&self, limits: &Limits) -> Result<(), LimitsExceeded> {
+        let count =
+            self.confirmed.len() + self.audited.len() + self.cancelled.len() + self.replaced.len();
+        if count > limits.max_ballots {
+            return Err(LimitsExceeded::Ballots {
+                count,
+                max: limits.max_ballots,
             });
         }
+        for (ballot_id, ballot) in &self.confirmed {
+            check_ballot_limits(limits, ballot_id, ballot)?;
+        }
+        for (ballot_id, ballot) in &self.audited {
+            check_ballot_limits(limits, ballot_id, ballot)?;
+        }
+        for (ballot_id, cancelled) in &self.cancelled {
+            check_ballot_limits(limits, ballot_id, &cancelled.ballot)?;
+        }
+        for (ballot_id, replaced) in &self.replaced {
+            check_ballot_limits(limits, ballot_id, &replaced.ballot)?;
+            check_id_limit(limits, &replaced.replaced_by)?;
+        }
+        check_candidate_limits(limits, self.totals.keys())
     }
 
-    Ok(())
+    /// Verify the results as `verify` does, then check that every ballot, whether
+    /// confirmed, audited, cancelled, or replaced, meets the policy.
+    pub fn verify_with_policy(
+        &self,
+        policy: &VerificationPolicy<C>,
+    ) -> Result<(), VerificationError<B, C>> {
+        self.verify()?;
+        let totals = &self.totals;
+        policy.check_all(self.confirmed.iter(), totals)?;
+        policy.check_all(self.audited.iter(), totals)?;
+        policy.check_all(self.cancelled.iter().map(|(id, c)| (id, &c.ballot)), totals)?;
+        policy.check_all(self.replaced.iter().map(|(id, r)| (id, &r.ballot)), totals)
+    }
+}
+
+/// Election results that have passed `ElectionResults::verify`, from which their ranking
+/// and outcome can be taken. These are given only by `ElectionResults::verified`.
+#[derive(Debug, Clone, Copy)]
+pub struct VerifiedResults<'a, B, C, G>
+where
+    B: Eq + Hash,
+    C: Eq + Hash + Ord,
+    G: DreipGroup,
+{
+    results: &'a ElectionResults<B, C, G>,
+}
+
+/// The outcome of a verified election.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum Outcome<C> {
+    /// The candidate has more votes than any other.
+    Winner(C),
+    /// The candidates share the most votes, in order of candidate id bytes.
+    Tie(Vec<C>),
+    /// No ballots were confirmed.
+    NoBallots,
+}
+
+impl<'a, B, C, G> VerifiedResults<'a, B, C, G>
+where
+    B: IdBytes + Clone + Eq + Hash,
+    C: IdBytes + Clone + Eq + Hash + Ord,
+    G: DreipGroup,
+{
+    /// The verified results.
+    pub fn results(&self) -> &'a ElectionResults<B, C, G> {
+        self.results
+    }
+
+    /// The tally of each candidate as a vote count.
+    pub fn tallies(&self) -> HashMap<C, u64> {
+        // A verified tally is the number of yes votes, so at most the number of ballots.
+        self.results
+            .tallies()
+            .unwrap_or_else(|_| unreachable!("Verified tallies count ballots"))
+    }
+
+    /// Every candidate with their vote count, most votes first. Candidates with the same
+    /// count are in order of their id bytes, so the ranking does not depend on map order.
+    pub fn ranking(&self) -> Vec<(C, u64)> {
+        let mut ranking = self.tallies().into_iter().collect::<Vec<_>>();
+        ranking.sort_by(|(a, a_count), (b, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| a.id_bytes().cmp(&b.id_bytes()))
+        });
+        ranking
+    }
+
+    /// The winner, or the candidates tied for the most votes, or `NoBallots` if no ballots
+    /// were confirmed.
+    pub fn outcome(&self) -> Outcome<C> {
+        if self.results.total_ballots() == 0 {
+            return Outcome::NoBallots;
+        }
+        let ranking = self.ranking();
+        let most = ranking.first().map_or(0, |(_, count)| *count);
+        let mut leaders = ranking
+            .into_iter()
+            .take_while(|(_, count)| *count == most)
+            .map(|(candidate, _)| candidate)
+            .collect::<Vec<_>>();
+        if leaders.len() == 1 {
+            Outcome::Winner(leaders.remove(0))
+        } else {
+            Outcome::Tie(leaders)
+        }
+    }
+}
+
+#[cfg(any(test, feature = "serde_json"))]
+impl<B, C, G> ElectionResults<B, C, G>
+where
+    B: Eq + Hash + Serialize + serde::de::DeserializeOwned,
+    C: Eq + Hash + Ord + Serialize + serde::de::DeserializeOwned,
+    G: DreipGroup,
+{
+    /// Load results from JSON with `strict::from_json_strict`, rejecting keys the results
+    /// do not have and keys repeated within an object, such as a ballot id given twice.
+    pub fn from_json_strict(
+        reader: impl std::io::Read,
+    ) -> Result<Self, crate::strict::StrictError> {
+        crate::strict::from_json_strict(reader)
+    }
+
+    /// Load results from JSON as `from_json_strict` does, reading at most
+    /// `limits.max_input_bytes`, then check the other limits with `check_limits`.
+    pub fn from_json_limited(
+        reader: impl std::io::Read,
+        limits: &Limits,
+    ) -> Result<Self, crate::strict::StrictError>
+    where
+        B: IdBytes + Clone,
+        C: IdBytes + Clone,
+    {
+        let results: Self =
+            crate::strict::from_json_strict_bounded(reader, limits.max_input_bytes)?;
+        results.check_limits(limits)?;
+        Ok(results)
+    }
+}
+
+/// Limits on the size of election results, for verifiers loading results from an untrusted
+/// source, so that a hostile dump is rejected before it can exhaust memory or time.
+///
+/// `max_input_bytes` bounds the memory used while loading, as `ElectionResults::from_json_limited`
+/// reads no more than that. The other limits are checked by `ElectionResults::check_limits`
+/// once the results are loaded, before any proof is verified.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Limits {
+    /// The most bytes of input to read.
+    pub max_input_bytes: u64,
+    /// The most ballots, counting confirmed, audited, cancelled, and replaced ballots.
+    pub max_ballots: usize,
+    /// The most candidates on any ballot, or in the totals.
+    pub max_candidates: usize,
+    /// The longest ballot or candidate id, in `IdBytes`.
+    pub max_id_bytes: usize,
+}
+
+impl Default for Limits {
+    /// Limits far beyond any real election: 1 GiB of input, ten million ballots, a thousand
+    /// candidates, and ids of 1 KiB.
+    fn default() -> Self {
+        Self {
+            max_input_bytes: 1 << 30,
+            max_ballots: 10_000_000,
+            max_candidates: 1_000,
+            max_id_bytes: 1 << 10,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits at all, for trusted input.
+    pub fn unlimited() -> Self {
+        Self {
+            max_input_bytes: u64::MAX,
+            max_ballots: usize::MAX,
+            max_candidates: usize::MAX,
+            max_id_bytes: usize::MAX,
+        }
+    }
+}
+
+/// Which of the `Limits` election results exceed.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum LimitsExceeded {
+    /// The input is longer than `max_input_bytes`; no more than that was read.
+    InputBytes { max: u64 },
+    /// There are `count` ballots, more than `max_ballots`.
+    Ballots { count: usize, max: usize },
+    /// A ballot, or the totals, has `count` candidates, more than `max_candidates`.
+    Candidates { count: usize, max: usize },
+    /// An id is `len` bytes long, more than `max_id_bytes`.
+    IdBytes { len: usize, max: usize },
+}
+
+impl Display for LimitsExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InputBytes { max } => write!(f, "input longer than {} bytes", max),
+            Self::Ballots { count, max } => {
+                write!(f, "{} ballots, more than the limit of {}", count, max)
+            }
+            Self::Candidates { count, max } => {
+                write!(f, "{} candidates, more than the limit of {}", count, max)
+            }
+            Self::IdBytes { len, max } => {
+                write!(f, "id of {} bytes, longer than the limit of {}", len, max)
+            }
+        }
+    }
+}
+
+impl Error for LimitsExceeded {}
+
+/// Check a ballot and its id against the limits, as `ElectionResults::check_limits` does.
+fn check_ballot_limits<B, C, G, S>(
+    limits: &Limits,
+    ballot_id: &B,
+    ballot: &Ballot<C, G, S>,
+) -> Result<(), LimitsExceeded>
+where
+    B: IdBytes,
+    C: IdBytes + Ord,
+    G: DreipGroup,
+{
+    check_id_limit(limits, ballot_id)?;
+    check_candidate_limits(limits, ballot.votes().keys())
+}
+
+/// Check the number of candidates, and each of their ids, against the limits.
+fn check_candidate_limits<'a, C: IdBytes + 'a>(
+    limits: &Limits,
+    candidates: impl ExactSizeIterator<Item = &'a C>,
+) -> Result<(), LimitsExceeded> {
+    let count = candidates.len();
+    if count > limits.max_candidates {
+        return Err(LimitsExceeded::Candidates {
+            count,
+            max: limits.max_candidates,
+        });
+    }
+    candidates
+        .into_iter()
+        .try_for_each(|candidate| check_id_limit(limits, candidate))
+}
+
+/// Check the length of an id against the limits.
+fn check_id_limit(limits: &Limits, id: &impl IdBytes) -> Result<(), LimitsExceeded> {
+    let len = id.id_bytes().len();
+    if len > limits.max_id_bytes {
+        return Err(LimitsExceeded::IdBytes {
+            len,
+            max: limits.max_id_bytes,
+        });
+    }
+    Ok(())
+}
+
+/// Requirements on the candidates of every ballot, for `verify_election_with_policy` and
+/// `ElectionResults::verify_with_policy`.
+///
+/// The proofs only show that a ballot is well formed for its own candidates, so a ballot
+/// listing only some of the election's candidates verifies, and is counted for those
+/// candidates. The default policy requires nothing more.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VerificationPolicy<C> {
+    /// The fewest candidates a ballot may have.
+    pub min_candidates: usize,
+    /// The most candidates a ballot may have, if limited.
+    pub max_candidates: Option<usize>,
+    /// The candidates every ballot must have, if any.
+    pub required_candidates: Option<BTreeSet<C>>,
+    /// Whether every ballot must have exactly the candidates of the totals. Only the sums of
+    /// the votes are checked against the totals, so otherwise a ballot missing a candidate,
+    /// or with an extra one, verifies if the totals were computed from the ballots as they
+    /// are.
+    pub consistent_roster: bool,
+}
+
+impl<C> Default for VerificationPolicy<C> {
+    fn default() -> Self {
+        Self {
+            min_candidates: 0,
+            max_candidates: None,
+            required_candidates: None,
+            consistent_roster: false,
+        }
+    }
+}
+
+impl<C: Ord + Clone> VerificationPolicy<C> {
+    /// A policy requiring every ballot to have exactly the given candidates.
+    pub fn roster(candidates: impl IntoIterator<Item = C>) -> Self {
+        let candidates = candidates.into_iter().collect::<BTreeSet<_>>();
+        Self {
+            min_candidates: candidates.len(),
+            max_candidates: Some(candidates.len()),
+            required_candidates: Some(candidates),
+            consistent_roster: false,
+        }
+    }
+
+    /// A policy requiring every ballot to have exactly the candidates of the totals, for
+    /// verifiers that do not know the roster beforehand.
+    pub fn consistent() -> Self {
+        Self {
+            consistent_roster: true,
+            ..Self::default()
+        }
+    }
+
+    /// Check the ballot's candidates against this policy, naming the first violation:
+    /// the count, then the first missing required candidate in order.
+    pub fn check<G, S>(&self, ballot: &Ballot<C, G, S>) -> Result<(), PolicyViolation<C>>
+    where
+        G: DreipGroup,
+    {
+        let count = ballot.len();
+        if count < self.min_candidates {
+            return Err(PolicyViolation::TooFewCandidates {
+                count,
+                min: self.min_candidates,
+            });
+        }
+        if let Some(max) = self.max_candidates.filter(|&max| count > max) {
+            return Err(PolicyViolation::TooManyCandidates { count, max });
+        }
+        let missing = self
+            .required_candidates
+            .iter()
+            .flatten()
+            .find(|candidate| ballot.get_vote(candidate).is_none());
+        match missing {
+            Some(candidate) => Err(PolicyViolation::MissingCandidate(candidate.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Check each ballot against this policy, and its candidates against those of the totals
+    /// if required, naming the first ballot to break it.
+    fn check_all<'a, B, G, S, HC>(
+        &self,
+        ballots: impl IntoIterator<Item = (&'a B, &'a Ballot<C, G, S>)>,
+        totals: &HashMap<C, CandidateTotals<G>, HC>,
+    ) -> Result<(), VerificationError<B, C>>
+    where
+        B: Clone + 'a,
+        C: Eq + Hash + 'a,
+        G: DreipGroup + 'a,
+        S: 'a,
+        HC: BuildHasher,
+    {
+        for (ballot_id, ballot) in ballots {
+            if self.consistent_roster {
+                check_roster(ballot_id, ballot, totals)?;
+            }
+            self.check(ballot)
+                .map_err(|reason| VerificationError::Policy {
+                    ballot_id: ballot_id.clone(),
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Verify all of the given ballots, and the total tallies.
+/// The generators are first checked as by `ElectionParams::validate_parameters`.
+/// `ballots` should map ballot IDs to ballots, while `totals` should map
+/// candidate ids to `CandidateTotals`. Either map may use any hasher, such as a faster one
+/// when the ids are trusted. Proofs are checked with the given transcript version, which
+/// should be the election's.
+///
+/// The ballots may be `Ballot`s, or any other `BallotLike` representation, such as
+/// `SmallBallot`.
+///
+/// Note that this is not sufficient for end-to-end verification; we also need
+/// to verify the integrity of all audited ballots, which `verify_full_election` does,
+/// and check the signatures of all receipts, which is left to the user of this library.
+pub fn verify_election<G, B, C, T, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, T, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    T: BallotLike<Candidate = C, Group = G>,
+    T::Secrets: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    verify_election_multi(g1, g2, ballots, totals, 1, transcript)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does, then
+/// check that every ballot meets the policy, naming the first that does not.
+pub fn verify_election_with_policy<G, B, C, S, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, Ballot<C, G, S>, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+    policy: &VerificationPolicy<C>,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    verify_election(g1, g2, ballots, totals, transcript)?;
+    policy.check_all(ballots.iter(), totals)
+}
+
+/// Check that the ballot has a vote for exactly the candidates of the totals, naming the
+/// candidates it is missing and those it has extra otherwise.
+fn check_roster<B, C, G, S, HC>(
+    ballot_id: &B,
+    ballot: &Ballot<C, G, S>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+) -> Result<(), VerificationError<B, C>>
+where
+    B: Clone,
+    C: Eq + Hash + Ord + Clone,
+    G: DreipGroup,
+    HC: BuildHasher,
+{
+    let extra = ballot
+        .votes()
+        .keys()
+        .filter(|candidate| !totals.contains_key(*candidate))
+        .cloned()
+        .collect::<Vec<_>>();
+    if extra.is_empty() && ballot.len() == totals.len() {
+        return Ok(());
+    }
+    let mut missing = totals
+        .keys()
+        .filter(|candidate| ballot.get_vote(candidate).is_none())
+        .cloned()
+        .collect::<Vec<_>>();
+    missing.sort();
+    Err(VerificationError::InconsistentRoster {
+        ballot_id: ballot_id.clone(),
+        missing,
+        extra,
+    })
+}
+
+/// Verify the whole bulletin board of an election: the confirmed ballots and the totals as
+/// `verify_election` does, the audited and cancelled ballots including their secrets, and
+/// the proofs of the replaced ballots. Only the confirmed ballots count towards the totals.
+///
+/// Each ballot id must appear in only one of `confirmed`, `audited`, `cancelled`, and
+/// `replaced`. Each replaced ballot must lead, through any later replacements, to a
+/// confirmed ballot, and no two may share a replacement.
+/// Receipt signatures are still left to the user of this library.
+#[allow(clippy::too_many_arguments)]
+pub fn verify_full_election<G, B, C, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    confirmed: &HashMap<B, Ballot<C, G, NoSecrets>, HB>,
+    audited: &HashMap<B, Ballot<C, G, SecretsPresent<G>>, HB>,
+    cancelled: &HashMap<B, CancelledBallot<C, G>, HB>,
+    replaced: &HashMap<B, ReplacedBallot<B, C, G>, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone + Eq + Hash,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    verify_revealed(
+        g1.clone(),
+        g2.clone(),
+        confirmed,
+        audited,
+        cancelled,
+        replaced,
+        transcript,
+    )?;
+    verify_election(g1, g2, confirmed, totals, transcript)
+}
+
+/// Check the generators and the ballot ids, and verify the audited, cancelled, and
+/// replaced ballots, leaving only the confirmed ballots and the totals of
+/// `verify_full_election`.
+#[allow(clippy::too_many_arguments)]
+fn verify_revealed<G, B, C, HB>(
+    g1: G::Point,
+    g2: G::Point,
+    confirmed: &HashMap<B, Ballot<C, G, NoSecrets>, HB>,
+    audited: &HashMap<B, Ballot<C, G, SecretsPresent<G>>, HB>,
+    cancelled: &HashMap<B, CancelledBallot<C, G>, HB>,
+    replaced: &HashMap<B, ReplacedBallot<B, C, G>, HB>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone + Eq + Hash,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    HB: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Check that no ballot is both confirmed and revealed, or revealed twice.
+    let mut seen = confirmed.keys().collect::<HashSet<_>>();
+    for ballot_id in audited
+        .keys()
+        .chain(cancelled.keys())
+        .chain(replaced.keys())
+    {
+        if !seen.insert(ballot_id) {
+            return Err(VerificationError::DuplicateBallot {
+                ballot_id: ballot_id.clone(),
+            });
+        }
+    }
+
+    // Check that every replaced ballot leads to exactly one confirmed ballot.
+    let mut replacements = HashSet::new();
+    for (ballot_id, replaced_ballot) in replaced.iter() {
+        let error = || VerificationError::Replacement {
+            ballot_id: ballot_id.clone(),
+        };
+        if !replacements.insert(&replaced_ballot.replaced_by) {
+            return Err(error());
+        }
+        // Follow the chain of re-votes, which is no longer than `replaced` without a cycle.
+        let mut next = &replaced_ballot.replaced_by;
+        let mut steps = 0;
+        while !confirmed.contains_key(next) {
+            match replaced.get(next) {
+                Some(later) if steps < replaced.len() => next = &later.replaced_by,
+                _ => return Err(error()),
+            }
+            steps += 1;
+        }
+    }
+
+    // Verify the revealed ballots.
+    for (ballot_id, ballot) in audited.iter() {
+        ballot
+            .verify(g1.clone(), g2.clone(), ballot_id, transcript)
+            .map_err(|e| VerificationError::Audited(e.cloned()))?;
+    }
+    for (ballot_id, ballot) in cancelled.iter() {
+        ballot
+            .verify(g1.clone(), g2.clone(), ballot_id, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+    }
+    for (ballot_id, ballot) in replaced.iter() {
+        ballot
+            .verify(g1.clone(), g2.clone(), ballot_id, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+    }
+
+    Ok(())
+}
+
+/// The record of a replaced ballot, and its confirmed replacement; see `replace_ballot`.
+pub type Replacement<B, C, G> = (ReplacedBallot<B, C, G>, Ballot<C, G, NoSecrets>);
+
+/// Replace a confirmed ballot with a new one when its voter casts again.
+///
+/// `old` is the confirmed ballot with its secrets, which must have been retained since
+/// confirmation. Its votes are subtracted from `totals`, and `new` is confirmed in its place.
+/// The returned record holds the old ballot without its secrets, as it was confirmed, and
+/// should be kept in `ElectionResults::replaced` under the old ballot's ID, so that
+/// `verify_full_election` can check that it is no longer counted.
+///
+/// `totals` must contain an entry for every candidate of both ballots. Otherwise, this
+/// fails naming the first missing candidate, without changing any of the totals.
+pub fn replace_ballot<G, B, C>(
+    old: Ballot<C, G, SecretsPresent<G>>,
+    new: Ballot<C, G, SecretsPresent<G>>,
+    new_id: B,
+    totals: &mut HashMap<C, &mut CandidateTotals<G>>,
+) -> Result<Replacement<B, C, G>, ConfirmError<C>>
+where
+    G: DreipGroup,
+    C: Eq + Hash + Clone + Ord,
+{
+    // Check both ballots before changing any totals.
+    if let Some(candidate) = old
+        .votes()
+        .keys()
+        .chain(new.votes().keys())
+        .find(|c| !totals.contains_key(*c))
+    {
+        return Err(ConfirmError::MissingCandidate(candidate.clone()));
+    }
+    for (candidate, vote) in old.votes().iter() {
+        let entry = totals.get_mut(candidate).expect("Already checked");
+        entry.retract(&vote.secrets);
+    }
+    let new = new.confirm(Some(totals))?;
+    let old = old
+        .confirm(None)
+        .unwrap_or_else(|_| unreachable!("Confirming without totals cannot fail"));
+    Ok((
+        ReplacedBallot {
+            replaced_by: new_id,
+            ballot: old,
+        },
+        new,
+    ))
+}
+
+/// The totals of each candidate over the given ballots, from their secrets, as confirming
+/// every ballot would give them.
+///
+/// Every candidate of any ballot has an entry. See `compute_totals_checked` to also check
+/// that every ballot has the same candidates.
+pub fn compute_totals<B, C, G, HB>(
+    ballots: &HashMap<B, Ballot<C, G, SecretsPresent<G>>, HB>,
+) -> HashMap<C, CandidateTotals<G>>
+where
+    G: DreipGroup,
+    C: Eq + Hash + Clone + Ord,
+    HB: BuildHasher,
+{
+    let mut totals = HashMap::<C, CandidateTotals<G>>::new();
+    for ballot in ballots.values() {
+        for (candidate, vote) in ballot.votes().iter() {
+            totals
+                .entry(candidate.clone())
+                .or_default()
+                .accumulate(&vote.secrets);
+        }
+    }
+    totals
+}
+
+/// The totals of each of the given candidates over the given ballots, as `compute_totals`
+/// gives them, checking that every ballot has a vote for exactly these candidates.
+///
+/// This fails naming a ballot that does not, and its first candidate in order of id that
+/// is missing or extra. Every candidate has an entry, even if there are no ballots.
+pub fn compute_totals_checked<B, C, G, HB>(
+    candidates: &[C],
+    ballots: &HashMap<B, Ballot<C, G, SecretsPresent<G>>, HB>,
+) -> Result<HashMap<C, CandidateTotals<G>>, CandidateMismatch<B, C>>
+where
+    G: DreipGroup,
+    B: Clone,
+    C: Eq + Hash + Clone + Ord,
+    HB: BuildHasher,
+{
+    let expected = candidates.iter().collect::<BTreeSet<_>>();
+    for (ballot_id, ballot) in ballots.iter() {
+        let actual = ballot.votes().keys().collect::<BTreeSet<_>>();
+        let missing = expected.difference(&actual).map(|c| (*c, true));
+        let extra = actual.difference(&expected).map(|c| (*c, false));
+        if let Some((candidate, is_missing)) = missing.chain(extra).min() {
+            let (ballot_id, candidate) = (ballot_id.clone(), candidate.clone());
+            return Err(if is_missing {
+                CandidateMismatch::Missing {
+                    ballot_id,
+                    candidate,
+                }
+            } else {
+                CandidateMismatch::Extra {
+                    ballot_id,
+                    candidate,
+                }
+            });
+        }
+    }
+
+    let mut totals = compute_totals(ballots);
+    for candidate in candidates.iter() {
+        totals.entry(candidate.clone()).or_default();
+    }
+    Ok(totals)
+}
+
+/// The tally of each candidate as a vote count, as `CandidateTotals::tally_u64` gives, or
+/// `None` if any of them does not fit in a `u64`.
+pub fn results<C, G, HC>(totals: &HashMap<C, CandidateTotals<G>, HC>) -> Option<HashMap<C, u64>>
+where
+    G: DreipGroup,
+    C: Eq + Hash + Clone,
+    HC: BuildHasher,
+{
+    totals
+        .iter()
+        .map(|(candidate, totals)| Some((candidate.clone(), totals.tally_u64()?)))
+        .collect()
+}
+
+/// Sum partial totals from disjoint sets of ballots, such as those of separate polling
+/// stations, into the totals of the whole election.
+///
+/// Every part must have totals for the same candidates. Otherwise, this fails naming the
+/// first part that does not, and its first candidate in order of id that is missing from it
+/// or extra to it, compared to the first part. No parts give empty totals.
+pub fn merge_totals<C, G>(
+    parts: impl IntoIterator<Item = HashMap<C, CandidateTotals<G>>>,
+) -> Result<HashMap<C, CandidateTotals<G>>, MergeError<C>>
+where
+    G: DreipGroup,
+    C: Eq + Hash + Clone + Ord,
+{
+    let mut parts = parts.into_iter();
+    let Some(mut merged) = parts.next() else {
+        return Ok(HashMap::new());
+    };
+    for (part, totals) in (1..).zip(parts) {
+        if let Some(candidate) = merged.keys().filter(|c| !totals.contains_key(*c)).min() {
+            return Err(MergeError::MissingCandidate {
+                part,
+                candidate: candidate.clone(),
+            });
+        }
+        if let Some(candidate) = totals.keys().filter(|c| !merged.contains_key(*c)).min() {
+            return Err(MergeError::ExtraCandidate {
+                part,
+                candidate: candidate.clone(),
+            });
+        }
+        for (candidate, partial) in totals.iter() {
+            merged
+                .get_mut(candidate)
+                .expect("Already checked")
+                .merge(partial);
+        }
+    }
+    Ok(merged)
+}
+
+/// Check that partial totals, such as those verified by `verify_subset` for each polling
+/// station, add up to the totals of the whole election.
+///
+/// A part need not have totals for every candidate, since a candidate it lacks counts as
+/// zero, but it must not have totals for a candidate missing from `full_totals`. Otherwise,
+/// this fails naming the first such part and its first such candidate in order of id, or
+/// else the first candidate in order of id whose totals do not add up.
+pub fn verify_totals_decomposition<C, G, HC, HP>(
+    full_totals: &HashMap<C, CandidateTotals<G>, HC>,
+    parts: &[HashMap<C, CandidateTotals<G>, HP>],
+) -> Result<(), DecompositionError<C>>
+where
+    G: DreipGroup,
+    C: Eq + Hash + Clone + Ord,
+    HC: BuildHasher,
+    HP: BuildHasher,
+{
+    let mut sums = full_totals
+        .keys()
+        .map(|candidate| (candidate, CandidateTotals::<G>::default()))
+        .collect::<BTreeMap<_, _>>();
+    for (part, totals) in parts.iter().enumerate() {
+        if let Some(candidate) = totals.keys().filter(|c| !sums.contains_key(c)).min() {
+            return Err(DecompositionError::ExtraCandidate {
+                part,
+                candidate: candidate.clone(),
+            });
+        }
+        for (candidate, partial) in totals.iter() {
+            sums.get_mut(candidate)
+                .expect("Already checked")
+                .merge(partial);
+        }
+    }
+    for (candidate, sum) in sums {
+        let full = &full_totals[candidate];
+        if sum.tally != full.tally || sum.r_sum != full.r_sum {
+            return Err(DecompositionError::Mismatch {
+                candidate: candidate.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Confirm each of the given ballots, as `Ballot::confirm` does, in parallel.
+///
+/// Each thread adds its ballots' secrets to its own partial totals, and these are merged
+/// into `totals` at the end. `totals` must contain an entry for every candidate of every
+/// ballot. Otherwise, this fails naming a ballot with a missing candidate, without changing
+/// any of the totals; the ballots are dropped unconfirmed.
+#[cfg(feature = "parallel")]
+#[allow(clippy::type_complexity)]
+pub fn confirm_all_par<G, B, C>(
+    ballots: HashMap<B, Ballot<C, G, SecretsPresent<G>>>,
+    totals: &mut HashMap<C, &mut CandidateTotals<G>>,
+) -> Result<HashMap<B, Ballot<C, G, NoSecrets>>, (B, ConfirmError<C>)>
+where
+    G: DreipGroup,
+    B: Eq + Hash + Clone + Send + Sync,
+    C: Eq + Hash + Clone + Ord + Send + Sync,
+    G::Point: Send + Sync,
+    G::Scalar: Send + Sync,
+{
+    use rayon::prelude::*;
+
+    // Check every ballot before changing any totals.
+    let missing = ballots.par_iter().find_map_any(|(ballot_id, ballot)| {
+        let candidate = ballot.votes().keys().find(|c| !totals.contains_key(*c))?;
+        Some((ballot_id.clone(), candidate.clone()))
+    });
+    if let Some((ballot_id, candidate)) = missing {
+        return Err((ballot_id, ConfirmError::MissingCandidate(candidate)));
+    }
+
+    let partials = ballots
+        .par_iter()
+        .fold(
+            HashMap::<&C, CandidateTotals<G>>::new,
+            |mut partial, (_, ballot)| {
+                for (candidate, vote) in ballot.votes().iter() {
+                    partial
+                        .entry(candidate)
+                        .or_default()
+                        .accumulate(&vote.secrets);
+                }
+                partial
+            },
+        )
+        .reduce(HashMap::new, |mut a, b| {
+            for (candidate, partial) in b {
+                a.entry(candidate).or_default().merge(&partial);
+            }
+            a
+        });
+    for (candidate, partial) in partials {
+        totals
+            .get_mut(candidate)
+            .expect("Already checked")
+            .merge(&partial);
+    }
+
+    Ok(ballots
+        .into_par_iter()
+        .map(|(ballot_id, ballot)| {
+            let Ok(ballot) = ballot.confirm(None) else {
+                unreachable!("No totals to miss candidates");
+            };
+            (ballot_id, ballot)
+        })
+        .collect())
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does, but
+/// for elections where every ballot has exactly `k` yes votes, as created with
+/// `Ballot::new_multi`. The tallies are checked in the same way.
+pub fn verify_election_multi<G, B, C, T, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, T, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    k: usize,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    T: BallotLike<Candidate = C, Group = G>,
+    T::Secrets: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    verify_stream(g1, g2, ballots.iter(), totals, k, transcript).map_err(VerificationError::cloned)
+}
+
+/// Verify ballots streamed from any source, such as the rows of a database query, and the
+/// total tallies, as `verify_election` does. The ballots may be owned or borrowed.
+///
+/// Each ballot is verified and then added to running sums of the `Z` and `R` values of each
+/// candidate's votes, which are checked against `totals` once the stream ends. Only those
+/// sums are kept, one pair per candidate, so memory does not grow with the number of
+/// ballots, and each ballot can be dropped as soon as the iterator moves past it. A
+/// candidate found on some ballot but missing from `totals`, or the other way round, is
+/// reported as `WrongCandidates` at the end, as it would be for a map.
+///
+/// A map cannot hold a ballot id twice, but a stream can, and this is not detected, since
+/// that would mean keeping every id: the source must ensure ids are distinct, as a primary
+/// key does.
+pub fn verify_election_iter<G, B, C, T, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: impl IntoIterator<Item = (B, T)>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    T: BallotLike<Candidate = C, Group = G>,
+    T::Secrets: VoteSecrets<G>,
+    HC: BuildHasher,
+{
+    verify_stream(g1, g2, ballots, totals, 1, transcript)
+}
+
+/// Verify streamed ballots, each with `k` yes votes, and the totals in one pass; see
+/// `verify_election_iter`.
+fn verify_stream<G, B, C, T, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: impl IntoIterator<Item = (B, T)>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    k: usize,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    T: BallotLike<Candidate = C, Group = G>,
+    T::Secrets: VoteSecrets<G>,
+    HC: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    let mut sums = VoteSums::new();
+    for (ballot_id, ballot) in ballots {
+        ballot
+            .verify_ballot(g1.clone(), g2.clone(), ballot_id, k, transcript)
+            .map_err(VerificationError::Ballot)?;
+        sums.add(&ballot);
+    }
+
+    sums.check(g1, g2, totals)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does, with
+/// the ballots verified in parallel.
+///
+/// The ballots are verified on all threads, but the error is that of the first failing
+/// ballot in the map's iteration order, exactly as `verify_election` reports it. Each
+/// thread then sums the `Z` and `R` values of its ballots' votes, and the partial sums are
+/// added together to check the totals.
+#[cfg(feature = "parallel")]
+pub fn verify_election_par<G, B, C, T, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, T, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone + Sync,
+    C: IdBytes + Eq + Hash + Clone + Ord + Send + Sync,
+    T: BallotLike<Candidate = C, Group = G> + Sync,
+    T::Secrets: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+    G::Point: Send + Sync,
+{
+    use rayon::prelude::*;
+
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Keep the map's order, so that the first failure is the one found serially.
+    let ballots = ballots.iter().collect::<Vec<_>>();
+    let failure = ballots.par_iter().find_map_first(|(ballot_id, ballot)| {
+        ballot
+            .verify_ballot(g1.clone(), g2.clone(), *ballot_id, 1, transcript)
+            .err()
+    });
+    if let Some(e) = failure {
+        return Err(VerificationError::Ballot(e.cloned()));
+    }
+
+    let sums = ballots
+        .par_iter()
+        .fold(VoteSums::new, |mut sums, (_, ballot)| {
+            sums.add(*ballot);
+            sums
+        })
+        .reduce(VoteSums::new, VoteSums::merge);
+    sums.check(g1, g2, totals)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does, with
+/// the ballots split between several maps, such as those of separate polling stations,
+/// whose totals were combined with `merge_totals`.
+///
+/// A ballot id may appear in only one of the maps.
+pub fn verify_election_sharded<G, B, C, S, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    shards: &[HashMap<B, Ballot<C, G, S>, HB>],
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone + Eq + Hash,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Check that no ballot is counted by more than one shard.
+    let mut seen = HashSet::new();
+    for ballot_id in shards.iter().flat_map(HashMap::keys) {
+        if !seen.insert(ballot_id) {
+            return Err(VerificationError::DuplicateBallot {
+                ballot_id: ballot_id.clone(),
+            });
+        }
+    }
+
+    // Verify individual ballots.
+    for (ballot_id, ballot) in shards.iter().flat_map(HashMap::iter) {
+        ballot
+            .verify(g1.clone(), g2.clone(), ballot_id, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+    }
+
+    verify_totals(g1, g2, shards.iter().flat_map(HashMap::values), totals)
+}
+
+/// Verify a subset of an election's ballots, such as those of one polling station, and the
+/// subtotals claimed for them, with the same ballot and tally checks as `verify_election`.
+///
+/// This verifies the subtotals claim only, not the election: that the parts' subtotals add up
+/// to the whole election's totals is checked by `verify_totals_decomposition`. Unlike
+/// `verify_election`, the subtotals may have candidates that none of the given ballots
+/// votes for, such as the rest of the roster for a station with no ballots, but their
+/// subtotals must then be zero. A candidate of a ballot missing from the subtotals fails
+/// with `WrongCandidates`.
+pub fn verify_subset<G, B, C, T, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, T, HB>,
+    subtotals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    T: BallotLike<Candidate = C, Group = G>,
+    T::Secrets: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    let mut sums = VoteSums::new();
+    for (ballot_id, ballot) in ballots.iter() {
+        ballot
+            .verify_ballot(g1.clone(), g2.clone(), ballot_id, 1, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+        sums.add(ballot);
+    }
+
+    sums.check_within(g1, g2, subtotals)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does, but
+/// for weighted elections, where each ballot was created with `Ballot::new_weighted` and
+/// the weight given for its id in `weights`. The tallies are checked in the same way, as
+/// sums of the weights.
+pub fn verify_election_weighted<G, B, C, S, HB, HC, HW>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, Ballot<C, G, S>, HB>,
+    weights: &HashMap<B, u64, HW>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Eq + Hash + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+    HW: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Verify individual ballots.
+    for (ballot_id, ballot) in ballots.iter() {
+        let weight = *weights
+            .get(ballot_id)
+            .ok_or_else(|| VerificationError::MissingWeight {
+                ballot_id: ballot_id.clone(),
+            })?;
+        ballot
+            .verify_weighted(g1.clone(), g2.clone(), ballot_id, weight, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+    }
+
+    verify_totals(g1, g2, ballots.values(), totals)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does, but
+/// allowing the ballots with the given ids to be abstentions, created with
+/// `Ballot::new_abstention`. Those must have no yes votes, and the rest exactly one.
+///
+/// Abstentions add zero to every tally, so the tallies are checked in the same way.
+pub fn verify_election_with_abstentions<G, B, C, S, HB, HC, HA>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, Ballot<C, G, S>, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    abstentions: &HashSet<B, HA>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone + Eq + Hash,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+    HA: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Verify individual ballots, expecting no yes votes from abstentions.
+    for (ballot_id, ballot) in ballots.iter() {
+        let k = if abstentions.contains(ballot_id) {
+            0
+        } else {
+            1
+        };
+        ballot
+            .verify_multi(g1.clone(), g2.clone(), ballot_id, k, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+    }
+
+    verify_totals(g1, g2, ballots.values(), totals)
+}
+
+/// Verify all of the given single-candidate referendum ballots, created with
+/// `Ballot::new_referendum`, and the total tallies, as `verify_election` does for
+/// ordinary ballots. The tally of the candidate is the number of yes votes.
+pub fn verify_election_referendum<G, B, C, S, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, Ballot<C, G, S>, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Verify individual ballots.
+    for (ballot_id, ballot) in ballots.iter() {
+        ballot
+            .verify_referendum(g1.clone(), g2.clone(), ballot_id, transcript)
+            .map_err(|e| VerificationError::Ballot(e.cloned()))?;
+    }
+
+    verify_totals(g1, g2, ballots.values(), totals)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election` does,
+/// but verifying all ballot proofs together with `BallotProof::batch_verify`.
+///
+/// This accepts and rejects exactly the same elections as `verify_election`, except with
+/// negligible probability over the choice of the batching coefficients from `rng`, which
+/// must be unpredictable to whoever produced the ballots. If several ballots are invalid,
+/// the one reported may differ: all vote proofs are checked before any ballot proof.
+///
+/// The vote proofs themselves are still verified one at a time. A `VoteProof` does not
+/// contain its commitments, only challenges and responses, so each must be recomputed
+/// individually in order to hash it, which rules out combining them.
+pub fn verify_election_batched<G, B, C, S, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, Ballot<C, G, S>, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    transcript: TranscriptVersion,
+    rng: impl RngCore + CryptoRng,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    verify_election_batched_multi(g1, g2, ballots, totals, 1, transcript, rng)
+}
+
+/// Verify all of the given ballots, and the total tallies, as `verify_election_multi` does,
+/// but batching the ballot proofs as `verify_election_batched` does.
+#[allow(non_snake_case)]
+pub fn verify_election_batched_multi<G, B, C, S, HB, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, Ballot<C, G, S>, HB>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+    k: usize,
+    transcript: TranscriptVersion,
+    rng: impl RngCore + CryptoRng,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HC: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(VerificationError::InvalidParameters)?;
+
+    // Verify individual votes, collecting the ballot proofs.
+    let mut ids = Vec::with_capacity(ballots.len());
+    let mut proofs = Vec::with_capacity(ballots.len());
+    for (ballot_id, ballot) in ballots.iter() {
+        let (Z_sum, R_sum) = verify_votes(ballot, g1.clone(), g2.clone(), ballot_id, 1, transcript)
+            .map_err(|failure| VerificationError::Ballot(failure.into_error(ballot_id.clone())))?;
+        ids.push(ballot_id);
+        proofs.push((ballot.pwf(), Z_sum, R_sum, ballot_id.id_bytes()));
+    }
+    let items = proofs
+        .iter()
+        .map(|(pwf, Z_sum, R_sum, id)| (*pwf, Z_sum.clone(), R_sum.clone(), &id[..]));
+
+    // Verify all ballot proofs at once.
+    BallotProof::batch_verify_multi(g1.clone(), g2.clone(), items, k, transcript, rng).map_err(
+        |(i, reason)| {
+            VerificationError::Ballot(BallotError::BallotProof {
+                ballot_id: ids[i].clone(),
+                reason,
+            })
+        },
+    )?;
+
+    verify_totals(g1, g2, ballots.values(), totals)
+}
+
+/// Verify the total tallies against the given ballots, which should already be verified.
+pub(crate) fn verify_totals<'a, G, B, C, T, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: impl IntoIterator<Item = &'a T>,
+    totals: &HashMap<C, CandidateTotals<G>, HC>,
+) -> Result<(), VerificationError<B, C>>
+where
+    G: DreipGroup,
+    C: Eq + Hash + Clone + Ord + 'a,
+    T: BallotLike<Candidate = C, Group = G> + 'a,
+    HC: BuildHasher,
+{
+    let mut sums = VoteSums::new();
+    for ballot in ballots {
+        sums.add(ballot);
+    }
+    sums.check(g1, g2, totals)
+}
+
+/// The sums of the `Z` and `R` values of each candidate's votes, in that order, over the
+/// ballots added so far, for checking against claimed totals.
+#[derive(Debug)]
+pub(crate) struct VoteSums<C, G: DreipGroup> {
+    sums: BTreeMap<C, (G::Point, G::Point)>,
+}
+
+impl<C, G> VoteSums<C, G>
+where
+    C: Eq + Hash + Clone + Ord,
+    G: DreipGroup,
+{
+    /// No ballots, and so no candidates.
+    pub(crate) fn new() -> Self {
+        Self {
+            sums: BTreeMap::new(),
+        }
+    }
+
+    /// Add the votes of the ballot, which should already be verified.
+    pub(crate) fn add<T>(&mut self, ballot: &T)
+    where
+        T: BallotLike<Candidate = C, Group = G>,
+    {
+        for (candidate_id, vote) in ballot.ballot_votes() {
+            // Only clone the candidate id the first time it is seen.
+            let entry = match self.sums.get_mut(candidate_id) {
+                Some(entry) => entry,
+                None => self
+                    .sums
+                    .entry(candidate_id.clone())
+                    .or_insert((G::Point::identity(), G::Point::identity())),
+            };
+            entry.0 = entry.0.clone() + vote.Z().clone();
+            entry.1 = entry.1.clone() + vote.R().clone();
+        }
+    }
+
+    /// Combine the sums of two disjoint sets of ballots.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn merge(mut self, other: Self) -> Self {
+        for (candidate_id, (z_sum, r_sum)) in other.sums {
+            match self.sums.get_mut(&candidate_id) {
+                Some(entry) => {
+                    entry.0 = entry.0.clone() + z_sum;
+                    entry.1 = entry.1.clone() + r_sum;
+                }
+                None => {
+                    self.sums.insert(candidate_id, (z_sum, r_sum));
+                }
+            }
+        }
+        self
+    }
+
+    /// Check the totals against the sums: the candidates must be the same, and each
+    /// candidate's tally and random sum must give its sums.
+    pub(crate) fn check<B, HC>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        totals: &HashMap<C, CandidateTotals<G>, HC>,
+    ) -> Result<(), VerificationError<B, C>>
+    where
+        HC: BuildHasher,
+    {
+        // Verify we have the right candidates.
+        if self.sums.len() != totals.len() {
+            return Err(VerificationError::WrongCandidates);
+        }
+        self.check_within(g1, g2, totals)
+    }
+
+    /// Check the totals against the sums, as `check` does, except that the totals may also
+    /// have candidates with no votes, whose totals must then be zero.
+    pub(crate) fn check_within<B, HC>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        totals: &HashMap<C, CandidateTotals<G>, HC>,
+    ) -> Result<(), VerificationError<B, C>>
+    where
+        HC: BuildHasher,
+    {
+        if !self.sums.keys().all(|k| totals.contains_key(k)) {
+            return Err(VerificationError::WrongCandidates);
+        }
+        let no_votes = (G::Point::identity(), G::Point::identity());
+        for (candidate_id, CandidateTotals { tally, r_sum }) in totals.iter() {
+            let true_totals = self.sums.get(candidate_id).unwrap_or(&no_votes);
+            if g1.clone() * (tally.clone() + r_sum.clone()) != true_totals.0
+                || g2.clone() * r_sum.clone() != true_totals.1
+            {
+                return Err(VerificationError::Tally {
+                    candidate_id: candidate_id.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,6 +1,16 @@
+#[cfg(feature = "bigint")]
+use num_bigint::BigUint;
 use rand::{CryptoRng, RngCore};
-use std::fmt::Debug;
-use std::ops::{Add, Mul, Sub};
+use std::fmt::{self, Debug, Formatter};
+use std::iter::Sum;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+use subtle::ConstantTimeEq;
+
+/// Shared implementation for RustCrypto `elliptic-curve` crates.
+#[cfg(any(feature = "p256_impl", feature = "p384_impl"))]
+#[macro_use]
+mod rustcrypto;
 
 /// Concrete implementation on the NIST P-256 elliptic curve.
 #[cfg(feature = "p256_impl")]
@@ -8,12 +18,103 @@ mod p256_impl;
 #[cfg(feature = "p256_impl")]
 pub use p256;
 
+/// Concrete implementation on the NIST P-384 elliptic curve.
+#[cfg(feature = "p384_impl")]
+mod p384_impl;
+#[cfg(feature = "p384_impl")]
+pub use p384;
+
+/// Concrete implementation on the NIST P-521 elliptic curve.
+#[cfg(feature = "p521_impl")]
+mod p521_impl;
+#[cfg(feature = "p521_impl")]
+pub use p521;
+#[cfg(feature = "p521_impl")]
+pub use p521_impl::{P521SigningKey, P521VerifyingKey};
+
+/// Concrete implementation on the Ristretto255 prime-order group.
+#[cfg(feature = "ristretto255")]
+mod ristretto255_impl;
+#[cfg(feature = "ristretto255")]
+pub use curve25519_dalek;
+#[cfg(feature = "ristretto255")]
+pub use ed25519_dalek;
+#[cfg(feature = "ristretto255")]
+pub use ristretto255_impl::Ristretto255;
+
+/// Concrete implementation on the G1 group of the BLS12-381 pairing-friendly curve.
+#[cfg(feature = "bls12_381")]
+mod bls12_381_impl;
+#[cfg(feature = "bls12_381")]
+pub use bls12_381;
+#[cfg(feature = "bls12_381")]
+pub use bls12_381_impl::{
+    aggregate_verify, Bls12381, BlsSignature, BlsSigningKey, BlsVerifyingKey,
+};
+
+/// Concrete implementation on the RFC 3526 2048-bit multiplicative Schnorr group.
+#[cfg(feature = "modp_impl")]
+mod modp_impl;
+#[cfg(feature = "modp_impl")]
+pub use modp_impl::{
+    ModPElement, ModPGroup, ModPScalar, ModPSignature, ModPSigningKey, ModPVerifyingKey,
+};
+#[cfg(any(feature = "modp_impl", feature = "bigint"))]
+pub use num_bigint;
+
+/// Concrete implementation on the Pallas elliptic curve.
+#[cfg(feature = "pasta_impl")]
+mod pasta_impl;
+#[cfg(feature = "pasta_impl")]
+pub use pasta_curves;
+#[cfg(feature = "pasta_impl")]
+pub use pasta_impl::{Pallas, PallasSignature, PallasSigningKey, PallasVerifyingKey};
+
+/// Concrete implementation on the brainpoolP256r1 elliptic curve.
+/// Note that `bp256` only provides variable-time scalar multiplication.
+#[cfg(feature = "brainpool_impl")]
+mod brainpool_impl;
+#[cfg(feature = "brainpool_impl")]
+pub use bp256;
+#[cfg(feature = "brainpool_impl")]
+pub use brainpool_impl::{BrainpoolSignature, BrainpoolSigningKey, BrainpoolVerifyingKey};
+
+/// Generic points and scalars for groups implementing the zkcrypto `group` and `ff` traits.
+#[cfg(feature = "zkcrypto_impl")]
+mod zkcrypto_impl;
+#[cfg(feature = "zkcrypto_impl")]
+pub use ff;
+#[cfg(feature = "zkcrypto_impl")]
+pub use group;
+#[cfg(feature = "zkcrypto_impl")]
+pub use zkcrypto_impl::{ZkPoint, ZkScalar};
+
+/// Generic implementation on arkworks curves.
+#[cfg(feature = "ark_impl")]
+mod ark_impl;
+#[cfg(feature = "ark_impl")]
+pub use ark_impl::{
+    ArkGroup, ArkP256, ArkP256Config, ArkPoint, ArkScalar, ArkSignature, ArkSigningKey,
+    ArkVerifyingKey,
+};
+#[cfg(feature = "ark_impl")]
+pub use {ark_ec, ark_ff, ark_secp256r1, ark_serialize};
+
 /// An object that can be serialized to/from a binary blob.
 pub trait Serializable {
+    /// The length of every encoding from `to_bytes`, or `None` if it varies or is not
+    /// known at compile time.
+    const BYTE_SIZE: Option<usize> = None;
+
     /// Convert self to a byte sequence.
     fn to_bytes(&self) -> Vec<u8>;
 
     /// Construct self from a byte sequence.
+    ///
+    /// Decoding must be strict: the input must have exactly the expected length and be
+    /// the canonical encoding, i.e. exactly what `to_bytes` produces for the result.
+    /// Anything else, including padded or truncated input, scalars not reduced modulo the
+    /// group order, points not in the group, and alternative point encodings, gives `None`.
     fn from_bytes(bytes: &[u8]) -> Option<Self>
     where
         Self: Sized;
@@ -53,8 +154,15 @@ impl Serializable for Vec<u8> {
 /// Serde (de)serialization to/from bytestrings on types that implement Serializable.
 /// Use by putting the attribute `#[serde(with = "crate::group::serde_bytestring")]`
 /// on your field.
+///
+/// Deserialization errors name the type being decoded and why it was rejected.
+/// Serde does not tell us the field name, so to locate a bad value within a large
+/// dump, wrap the deserializer with `serde_path_to_error`, which reports the full
+/// path to it, such as `90000.votes.Alice.R`.
 pub mod serde_bytestring {
+    use serde::de::Error;
     use serde::Deserialize;
+    use std::any::type_name;
 
     pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -69,23 +177,157 @@ pub mod serde_bytestring {
         T: super::Serializable,
         D: serde::Deserializer<'de>,
     {
-        String::deserialize(deserializer).and_then(|bytestring| {
-            super::Serializable::from_bytestring(&bytestring)
-                .ok_or_else(|| serde::de::Error::custom("Invalid bytestring"))
+        let bytestring = String::deserialize(deserializer)?;
+        let bytes = base64::decode_config(&bytestring, base64::URL_SAFE_NO_PAD).map_err(|e| {
+            D::Error::custom(format_args!(
+                "invalid bytestring for {}: {}",
+                type_name::<T>(),
+                e
+            ))
+        })?;
+        T::from_bytes(&bytes).ok_or_else(|| {
+            D::Error::custom(format_args!(
+                "invalid {} of {} bytes: wrong length or not a canonical encoding",
+                type_name::<T>(),
+                bytes.len()
+            ))
         })
     }
 }
 
+/// A field recording which group some serialized data belongs to, so that data from one
+/// group is rejected by a verifier for another with a clear error, rather than failing
+/// to decode some point deep inside it.
+///
+/// This serializes as `DreipGroup::NAME`. Deserialization fails if the name is different.
+/// Data serialized before group names existed has no such field, which is accepted, but
+/// recorded so that callers can warn about it; see `is_legacy`. All `GroupName`s of the
+/// same group compare equal.
+pub struct GroupName<G: DreipGroup> {
+    legacy: bool,
+    group: PhantomData<G>,
+}
+
+impl<G: DreipGroup> GroupName<G> {
+    /// The name of this group, for newly created data.
+    pub fn new() -> Self {
+        Self {
+            legacy: false,
+            group: PhantomData,
+        }
+    }
+
+    /// The placeholder for data deserialized without a group name.
+    pub fn legacy() -> Self {
+        Self {
+            legacy: true,
+            group: PhantomData,
+        }
+    }
+
+    /// Whether this was deserialized from data without a group name, which predates them.
+    /// Such data cannot be checked to belong to this group.
+    pub fn is_legacy(&self) -> bool {
+        self.legacy
+    }
+}
+
+impl<G: DreipGroup> Default for GroupName<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<G: DreipGroup> Debug for GroupName<G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.legacy {
+            write!(f, "GroupName({}, legacy)", G::NAME)
+        } else {
+            write!(f, "GroupName({})", G::NAME)
+        }
+    }
+}
+
+impl<G: DreipGroup> Clone for GroupName<G> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<G: DreipGroup> Copy for GroupName<G> {}
+
+impl<G: DreipGroup> PartialEq for GroupName<G> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<G: DreipGroup> Eq for GroupName<G> {}
+
+impl<G: DreipGroup> serde::Serialize for GroupName<G> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(G::NAME)
+    }
+}
+
+impl<'de, G: DreipGroup> serde::Deserialize<'de> for GroupName<G> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        if name != G::NAME {
+            return Err(serde::de::Error::custom(format_args!(
+                "dump was created with {}, verifier built for {}",
+                name,
+                G::NAME
+            )));
+        }
+        Ok(Self::new())
+    }
+}
+
 /// A point within a DRE-ip compatible group.
-pub trait DreipPoint {
+///
+/// Multiplication by a scalar is required by `DreipGroup::Point`, since the point type
+/// alone does not determine its scalars.
+pub trait DreipPoint:
+    Sized + Add<Output = Self> + Sub<Output = Self> + Neg<Output = Self> + Sum
+{
     /// The identity point of the group, i.e. the point at infinity, i.e. zero.
     fn identity() -> Self;
+    /// The standard generator of the group, which `DreipGroup::new_generators`
+    /// conventionally uses as `g1`.
+    fn generator() -> Self;
     /// Create a random point deterministically from the given data via hashing.
     fn from_hash(data: &[&[u8]]) -> Self;
+    /// Like `from_hash`, but separated by a tag for the specific use, such as
+    /// `GENERATOR_TAG`. Backends with RFC 9380 hashing append `-` and the tag to their
+    /// domain separation tag, e.g. `CURVE_XMD:SHA-256:DREIP-GEN`; by default, the tag is
+    /// hashed as the first input instead.
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        Self::from_hash(&[&[tag], data].concat())
+    }
+    /// Convert to an integer: the canonical encoding, read as big-endian.
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint
+    where
+        Self: Serializable,
+    {
+        BigUint::from_bytes_be(&self.to_bytes())
+    }
+    /// Compare with another point without branching on secret data.
+    /// By default, this compares the canonical encodings in constant time, so it is
+    /// only as constant-time as the backend's encoding.
+    fn ct_eq(&self, other: &Self) -> bool
+    where
+        Self: Serializable,
+    {
+        self.to_bytes().ct_eq(&other.to_bytes()).into()
+    }
 }
 
 /// A scalar within a DRE-ip compatible group.
-pub trait DreipScalar {
+pub trait DreipScalar:
+    Sized + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Neg<Output = Self> + Sum
+{
     /// The zero scalar; the additive identity.
     fn zero() -> Self;
     /// The one scalar; the multiplicative identity.
@@ -94,6 +336,165 @@ pub trait DreipScalar {
     fn random(rng: impl RngCore + CryptoRng) -> Self;
     /// Create a random scalar deterministically from the given data via hashing.
     fn from_hash(data: &[&[u8]]) -> Self;
+    /// Like `from_hash`, but separated by a tag for the specific use, as for
+    /// `DreipPoint::from_hash_with_tag`.
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        Self::from_hash(&[&[tag], data].concat())
+    }
+    /// The multiplicative inverse, or `None` for zero.
+    fn invert(&self) -> Option<Self>;
+    /// Convert to a `u64`, or `None` if the scalar's value does not fit.
+    /// This is mainly useful for reading tallies.
+    fn to_u64(&self) -> Option<u64>;
+    /// Convert to the integer value of this scalar, in the range `[0, q)`.
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint;
+    /// Convert from an integer, or `None` if it is not less than the group order.
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self>
+    where
+        Self: Sized;
+    /// Compare with another scalar without branching on secret data.
+    /// By default, this compares the canonical encodings in constant time, so it is
+    /// only as constant-time as the backend's encoding.
+    fn ct_eq(&self, other: &Self) -> bool
+    where
+        Self: Serializable,
+    {
+        self.to_bytes().ct_eq(&other.to_bytes()).into()
+    }
+    /// Overwrite this scalar with zero, in a way that is not optimised away, to wipe secrets.
+    ///
+    /// By default, this assigns `zero()` and hides the result from the optimiser, which wipes
+    /// scalars stored inline. The old value is dropped normally, so any heap memory it owns is
+    /// freed but not wiped. Such scalars should override this.
+    #[cfg(feature = "zeroize")]
+    fn zeroize(&mut self)
+    where
+        Self: Sized,
+    {
+        *self = Self::zero();
+        std::hint::black_box(self);
+    }
+}
+
+/// The tag for deriving generators with `DreipPoint::from_hash_with_tag`, which
+/// `DreipGroup::new_generators` uses for `g2`.
+pub const GENERATOR_TAG: &[u8] = b"GEN";
+
+/// Append `-` and the use-specific tag to a backend's domain separation tag.
+#[allow(dead_code)]
+pub(crate) fn tagged_dst(dst: &[u8], tag: &[u8]) -> Vec<u8> {
+    [dst, b"-", tag].concat()
+}
+
+/// The scalar with the given integer value.
+pub(crate) fn scalar_from_u64<S: DreipScalar + Clone>(value: u64) -> S {
+    (0..u64::BITS).rev().fold(S::zero(), |acc, bit| {
+        let acc = acc.clone() + acc;
+        if value >> bit & 1 == 1 {
+            acc + S::one()
+        } else {
+            acc
+        }
+    })
+}
+
+/// The length of the value's encoding, from `Serializable::BYTE_SIZE` where it is known.
+pub(crate) fn encoded_size<T: Serializable>(value: &T) -> usize {
+    T::BYTE_SIZE.unwrap_or_else(|| value.to_bytes().len())
+}
+
+/// The total of the given `Serializable::BYTE_SIZE`s, or `None` if any is unknown.
+pub(crate) const fn total_byte_size(sizes: &[Option<usize>]) -> Option<usize> {
+    let mut total = 0;
+    let mut i = 0;
+    while i < sizes.len() {
+        match sizes[i] {
+            Some(size) => total += size,
+            None => return None,
+        }
+        i += 1;
+    }
+    Some(total)
+}
+
+/// Wipe a secret scalar with `DreipScalar::zeroize` if the `zeroize` feature is enabled.
+#[allow(unused_variables)]
+pub(crate) fn wipe<S: DreipScalar>(scalar: &mut S) {
+    #[cfg(feature = "zeroize")]
+    scalar.zeroize();
+}
+
+/// Encode the integer as exactly `len` big-endian bytes, or `None` if it does not fit.
+#[cfg(all(
+    feature = "bigint",
+    any(
+        feature = "p256_impl",
+        feature = "p384_impl",
+        feature = "p521_impl",
+        feature = "brainpool_impl",
+        feature = "zkcrypto_impl"
+    )
+))]
+fn bigint_to_be_bytes(value: &BigUint, len: usize) -> Option<Vec<u8>> {
+    let bytes = value.to_bytes_be();
+    let padding = len.checked_sub(bytes.len())?;
+    Some([vec![0; padding], bytes].concat())
+}
+
+/// Encode the integer as exactly `len` little-endian bytes, or `None` if it does not fit.
+#[cfg(all(
+    feature = "bigint",
+    any(
+        feature = "ristretto255",
+        feature = "bls12_381",
+        feature = "pasta_impl",
+        feature = "zkcrypto_impl"
+    )
+))]
+fn bigint_to_le_bytes(value: &BigUint, len: usize) -> Option<Vec<u8>> {
+    let mut bytes = value.to_bytes_le();
+    if bytes.len() > len {
+        return None;
+    }
+    bytes.resize(len, 0);
+    Some(bytes)
+}
+
+/// Interpret big-endian bytes as a `u64`, or `None` if the value does not fit.
+#[cfg(any(
+    feature = "p256_impl",
+    feature = "p384_impl",
+    feature = "p521_impl",
+    feature = "brainpool_impl",
+    feature = "zkcrypto_impl"
+))]
+fn u64_from_be_bytes(bytes: &[u8]) -> Option<u64> {
+    let (high, low) = bytes.split_at(bytes.len().saturating_sub(8));
+    if high.iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut u64_bytes = [0; 8];
+    u64_bytes[8 - low.len()..].copy_from_slice(low);
+    Some(u64::from_be_bytes(u64_bytes))
+}
+
+/// Interpret little-endian bytes as a `u64`, or `None` if the value does not fit.
+#[cfg(any(
+    feature = "ristretto255",
+    feature = "bls12_381",
+    feature = "pasta_impl",
+    feature = "zkcrypto_impl"
+))]
+fn u64_from_le_bytes(bytes: &[u8]) -> Option<u64> {
+    let (low, high) = bytes.split_at(bytes.len().min(8));
+    if high.iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut u64_bytes = [0; 8];
+    u64_bytes[..low.len()].copy_from_slice(low);
+    Some(u64::from_le_bytes(u64_bytes))
 }
 
 /// A private key generated from a DRE-ip compatible group.
@@ -103,6 +504,39 @@ pub trait DreipPrivateKey {
 
     /// Sign the given message with this key.
     fn sign(&self, msg: &[u8]) -> Self::Signature;
+
+    /// Encode as a PKCS#8 `PrivateKeyInfo` in DER, for standard key containers.
+    /// Returns `None` if the backend does not support PKCS#8, which is the default.
+    #[cfg(feature = "pem")]
+    fn to_pkcs8_der(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Encode as a PKCS#8 PEM document (`BEGIN PRIVATE KEY`), or `None` as `to_pkcs8_der`.
+    /// The result holds the secret key, and is not wiped when dropped.
+    #[cfg(feature = "pem")]
+    fn to_pkcs8_pem(&self) -> Option<String> {
+        None
+    }
+
+    /// Decode from a PKCS#8 `PrivateKeyInfo` in DER, or `None` if it is invalid,
+    /// for another algorithm or curve, or the backend does not support PKCS#8.
+    #[cfg(feature = "pem")]
+    fn from_pkcs8_der(_der: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Decode from a PKCS#8 PEM document, or `None` as `from_pkcs8_der`.
+    #[cfg(feature = "pem")]
+    fn from_pkcs8_pem(_pem: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
 }
 
 /// A public key generated from a DRE-ip compatible group.
@@ -112,31 +546,144 @@ pub trait DreipPublicKey {
 
     /// Verify the given message and signature with this key. Returns true if valid.
     fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool;
+
+    /// Encode as an X.509 `SubjectPublicKeyInfo` in DER, as read by other tools such as
+    /// OpenSSL. Returns `None` if the backend does not support SPKI, which is the default.
+    #[cfg(feature = "pem")]
+    fn to_public_key_der(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    /// Encode as an SPKI PEM document (`BEGIN PUBLIC KEY`), or `None` as `to_public_key_der`.
+    #[cfg(feature = "pem")]
+    fn to_public_key_pem(&self) -> Option<String> {
+        None
+    }
+
+    /// Decode from an X.509 `SubjectPublicKeyInfo` in DER, or `None` if it is invalid,
+    /// for another algorithm or curve, or the backend does not support SPKI.
+    #[cfg(feature = "pem")]
+    fn from_public_key_der(_der: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Decode from an SPKI PEM document, or `None` as `from_public_key_der`.
+    #[cfg(feature = "pem")]
+    fn from_public_key_pem(_pem: &str) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Verify many messages and signatures with this key, returning `Ok(())` if all are
+    /// valid, or the index of the first invalid one otherwise.
+    ///
+    /// By default, this verifies each signature in turn.
+    fn batch_verify(&self, msgs_and_sigs: &[(&[u8], &Self::Signature)]) -> Result<(), usize> {
+        msgs_and_sigs
+            .iter()
+            .position(|(msg, signature)| !self.verify(msg, signature))
+            .map_or(Ok(()), Err)
+    }
+
+    /// As `batch_verify`, but splitting the signatures into chunks verified in parallel.
+    #[cfg(feature = "parallel")]
+    fn par_batch_verify(&self, msgs_and_sigs: &[(&[u8], &Self::Signature)]) -> Result<(), usize>
+    where
+        Self: Sync,
+        Self::Signature: Sync,
+    {
+        use rayon::prelude::*;
+
+        /// Enough signatures per chunk for `batch_verify` implementations to amortise any setup.
+        const CHUNK_SIZE: usize = 256;
+        msgs_and_sigs
+            .par_chunks(CHUNK_SIZE)
+            .enumerate()
+            .filter_map(|(i, chunk)| self.batch_verify(chunk).err().map(|j| i * CHUNK_SIZE + j))
+            .min()
+            .map_or(Ok(()), Err)
+    }
+}
+
+/// An error from a `ReceiptSigner`, with a description from the signer.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct SignError(pub String);
+
+/// Something that can sign receipts for an election in the given group, such as a
+/// hardware security module or cloud KMS holding the election's private key.
+///
+/// Every `DreipPrivateKey` of the group is a `ReceiptSigner` that never fails, so code
+/// holding the key in-process works unchanged.
+pub trait ReceiptSigner<G: DreipGroup> {
+    /// Sign the given message, or report why the signer could not.
+    fn sign(&self, msg: &[u8]) -> Result<G::Signature, SignError>;
+}
+
+impl<G, K> ReceiptSigner<G> for K
+where
+    G: DreipGroup,
+    K: DreipPrivateKey<Signature = G::Signature>,
+{
+    fn sign(&self, msg: &[u8]) -> Result<G::Signature, SignError> {
+        Ok(DreipPrivateKey::sign(self, msg))
+    }
 }
 
 /// A DRE-ip compatible group (e.g. a DSA-like multiplicative cyclic group,
 /// or an ECDSA-like additive cyclic group).
+///
+/// The arithmetic on points and scalars is part of the traits, so generic code
+/// needs no bounds beyond `G: DreipGroup`:
+///
+/// ```
+/// # #[cfg(feature = "p256_impl")] {
+/// use dre_ip::group::p256::NistP256;
+/// use dre_ip::{Ballot, DreipGroup, DreipPoint, Election, SecretsPresent, Vote};
+///
+/// /// Check that the summed randomness of some votes opens the sum of their `R` values.
+/// #[allow(non_snake_case)]
+/// fn check_openings<'a, G: DreipGroup + 'a>(
+///     g2: G::Point,
+///     votes: impl Iterator<Item = &'a Vote<G, SecretsPresent<G>>> + Clone,
+/// ) -> bool {
+///     let R: G::Point = votes.clone().map(|vote| vote.R().clone()).sum();
+///     let r: G::Scalar = votes.map(|vote| vote.secrets.r.clone()).sum();
+///     R + -(g2 * r) == G::Point::identity()
+/// }
+///
+/// let election = Election::<NistP256>::new(&[b"Example"], rand::thread_rng());
+/// let ballot = Ballot::<_, NistP256, _>::new(
+///     rand::thread_rng(),
+///     election.g1,
+///     election.g2,
+///     "1",
+///     "Alice",
+///     vec!["Bob", "Eve"],
+/// )
+/// .unwrap();
+/// assert!(check_openings(election.g2, ballot.votes().values()));
+/// # }
+/// ```
 pub trait DreipGroup {
+    /// A name identifying this group and its encodings, such as `DREIP-P256`,
+    /// recorded in serialized data by `GroupName`.
+    const NAME: &'static str;
     /// The signature produced by keys from this group.
     type Signature: Serializable;
     /// A point in this group.
     type Point: DreipPoint
         + Serializable
         + Eq
-        + Copy
+        + Clone
         + Debug
-        + Add<Output = Self::Point>
-        + Sub<Output = Self::Point>
         + Mul<Self::Scalar, Output = Self::Point>;
     /// A scalar in this group.
-    type Scalar: DreipScalar
-        + Serializable
-        + Eq
-        + Copy
-        + Debug
-        + Add<Output = Self::Scalar>
-        + Sub<Output = Self::Scalar>
-        + Mul<Output = Self::Scalar>;
+    type Scalar: DreipScalar + Serializable + Eq + Clone + Debug;
     /// A private key in this group.
     type PrivateKey: DreipPrivateKey<Signature = Self::Signature> + Serializable + Clone + Debug;
     /// A public key in this group.
@@ -150,4 +697,20 @@ pub trait DreipGroup {
 
     /// Randomly generate a public/private keypair.
     fn new_keys(rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey);
+
+    /// Compute the multi-scalar multiplication `points[0] * scalars[0] + points[1] * scalars[1] + ...`.
+    /// Panics if the slices have different lengths.
+    ///
+    /// Proof verification uses this for its linear combinations, so groups may override
+    /// the naive default with something faster. It is only used on public values, so
+    /// need not be constant-time.
+    fn msm(points: &[Self::Point], scalars: &[Self::Scalar]) -> Self::Point {
+        assert_eq!(points.len(), scalars.len(), "Mismatched MSM lengths");
+        points
+            .iter()
+            .zip(scalars)
+            .fold(Self::Point::identity(), |acc, (point, scalar)| {
+                acc + point.clone() * scalar.clone()
+            })
+    }
 }
@@ -0,0 +1,612 @@
+use super::*;
+
+use ark_ec::hashing::curve_maps::swu::{SWUConfig, SWUMap};
+use ark_ec::hashing::map_to_curve_hasher::MapToCurve;
+use ark_ec::short_weierstrass::{Affine, Projective, SWCurveConfig};
+use ark_ec::{CurveConfig, CurveGroup, PrimeGroup};
+use ark_ff::{AdditiveGroup, MontFp, PrimeField, UniformRand, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+use std::fmt::{self, Formatter};
+use std::marker::PhantomData;
+
+/// A tag to ensure random oracle uniqueness as per the hash_to_curve spec.
+const DOMAIN_SEPARATION_TAG: &[u8] = b"ARKWORKS_XMD:SHA-256_SSWU_RO_:DREIP";
+
+/// A tag to separate Schnorr signature nonce derivation from all other hashing.
+const NONCE_DOMAIN_SEPARATION_TAG: &[u8] = b"ARKWORKS_XMD:SHA-256:DREIP_SCHNORR_NONCE";
+
+/// A tag to separate Schnorr signature challenges from all other hashing.
+const SIGNATURE_DOMAIN_SEPARATION_TAG: &[u8] = b"ARKWORKS_XMD:SHA-256:DREIP_SCHNORR";
+
+/// Any arkworks curve, supporting arkworks 0.5 (`ark-ec`, `ark-ff`, and `ark-serialize`).
+/// Receipts are signed with Schnorr signatures over the same curve.
+///
+/// Points and scalars use `ark-serialize` compressed canonical encodings.
+/// `DreipGroup` is implemented for short Weierstrass curves over prime fields with
+/// an `SWUConfig`, hashing to the curve with simplified SWU according to the
+/// hash_to_curve spec.
+/// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+pub struct ArkGroup<C: CurveGroup>(PhantomData<C>);
+
+// Implemented by hand, since deriving would require `C` itself to implement these.
+impl<C: CurveGroup> Debug for ArkGroup<C> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("ArkGroup")
+    }
+}
+
+impl<C: CurveGroup> Clone for ArkGroup<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: CurveGroup> Copy for ArkGroup<C> {}
+
+impl<C: CurveGroup> PartialEq for ArkGroup<C> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<C: CurveGroup> Eq for ArkGroup<C> {}
+
+/// The `ark-secp256r1` curve, i.e. NIST P-256, as an `ArkGroup`.
+pub type ArkP256 = ArkGroup<Projective<ArkP256Config>>;
+
+/// The `ark-secp256r1` curve parameters, plus the `Z` value for simplified SWU
+/// from the hash_to_curve spec's `P256_XMD:SHA-256_SSWU_RO_` suite, which
+/// `ark-secp256r1` itself does not provide.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct ArkP256Config;
+
+impl CurveConfig for ArkP256Config {
+    type BaseField = <ark_secp256r1::Config as CurveConfig>::BaseField;
+    type ScalarField = <ark_secp256r1::Config as CurveConfig>::ScalarField;
+
+    const COFACTOR: &'static [u64] = ark_secp256r1::Config::COFACTOR;
+    const COFACTOR_INV: Self::ScalarField = ark_secp256r1::Config::COFACTOR_INV;
+}
+
+impl SWCurveConfig for ArkP256Config {
+    const COEFF_A: Self::BaseField = ark_secp256r1::Config::COEFF_A;
+    const COEFF_B: Self::BaseField = ark_secp256r1::Config::COEFF_B;
+    const GENERATOR: Affine<Self> =
+        Affine::new_unchecked(ark_secp256r1::G_GENERATOR_X, ark_secp256r1::G_GENERATOR_Y);
+}
+
+impl SWUConfig for ArkP256Config {
+    const ZETA: Self::BaseField = MontFp!("-10");
+}
+
+/// A point on an arkworks curve.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ArkPoint<C: CurveGroup>(pub C);
+
+/// A scalar in an arkworks prime field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ArkScalar<F: PrimeField>(pub F);
+
+/// A Schnorr signature `(R, s)` over an arkworks curve.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ArkSignature<C: CurveGroup> {
+    r: C,
+    s: C::ScalarField,
+}
+
+/// A Schnorr signing key: a non-zero scalar `x`.
+#[derive(Clone)]
+pub struct ArkSigningKey<C: CurveGroup>(C::ScalarField);
+
+impl<C: CurveGroup> Debug for ArkSigningKey<C> {
+    /// Only print the corresponding public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArkSigningKey")
+            .field(&self.verifying_key())
+            .finish()
+    }
+}
+
+impl<C: CurveGroup> ArkSigningKey<C> {
+    /// Get the verifying key corresponding to this signing key.
+    pub fn verifying_key(&self) -> ArkVerifyingKey<C> {
+        ArkVerifyingKey(C::generator() * self.0)
+    }
+}
+
+/// A Schnorr verifying key: the non-identity point `x * G`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ArkVerifyingKey<C: CurveGroup>(C);
+
+impl<C: CurveGroup> Add for ArkPoint<C> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<C: CurveGroup> Sub for ArkPoint<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl<C: CurveGroup> Neg for ArkPoint<C> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl<C: CurveGroup> Sum for ArkPoint<C> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|p| p.0).sum())
+    }
+}
+
+impl<C: CurveGroup> Mul<ArkScalar<C::ScalarField>> for ArkPoint<C> {
+    type Output = Self;
+
+    fn mul(self, rhs: ArkScalar<C::ScalarField>) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl<F: PrimeField> Add for ArkScalar<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<F: PrimeField> Sub for ArkScalar<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl<F: PrimeField> Neg for ArkScalar<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl<F: PrimeField> Sum for ArkScalar<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|s| s.0).sum())
+    }
+}
+
+impl<F: PrimeField> Mul for ArkScalar<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+/// Encode with the compressed canonical encoding.
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value
+        .serialize_compressed(&mut bytes)
+        .expect("Writing to a Vec is infallible");
+    bytes
+}
+
+/// Decode from the compressed canonical encoding, which must be exactly `bytes`.
+/// Points not on the curve or outside the prime-order subgroup are rejected, as are
+/// alternative encodings, such as the infinity flag set with a non-zero x coordinate,
+/// which arkworks itself accepts.
+fn deserialize<T: CanonicalSerialize + CanonicalDeserialize>(bytes: &[u8]) -> Option<T> {
+    let value = T::deserialize_compressed(&mut &bytes[..]).ok()?;
+    (serialize(&value) == bytes).then_some(value)
+}
+
+/// Expand the message to `len` uniform bytes with `expand_message_xmd` and SHA256.
+/// This is implemented here as arkworks' own expander pads with the wrong block
+/// size for SHA256, so does not match the hash_to_curve spec.
+fn expand_message_xmd(tag: &[u8], msg: &[u8], len: usize) -> Vec<u8> {
+    let blocks = len.div_ceil(32);
+    assert!(blocks <= 255 && tag.len() <= 255, "Output or tag too long");
+    let tag_prime = [tag, &[tag.len() as u8]].concat();
+    let b_0 = Sha256::new()
+        .chain_update([0; 64])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0])
+        .chain_update(&tag_prime)
+        .finalize();
+    let mut b_i = Sha256::new()
+        .chain_update(b_0)
+        .chain_update([1])
+        .chain_update(&tag_prime)
+        .finalize();
+    let mut bytes = b_i.to_vec();
+    for i in 2..=blocks {
+        let xored: Vec<u8> = b_0.iter().zip(b_i).map(|(a, b)| a ^ b).collect();
+        b_i = Sha256::new()
+            .chain_update(xored)
+            .chain_update([i as u8])
+            .chain_update(&tag_prime)
+            .finalize();
+        bytes.extend(b_i);
+    }
+    bytes.truncate(len);
+    bytes
+}
+
+/// Hash the given data to `N` field elements, according to the hash_to_curve spec.
+fn hash_to_field<F: PrimeField, const N: usize>(tag: &[u8], data: &[&[u8]]) -> [F; N] {
+    // Enough bytes for 128-bit security after reduction.
+    let len = (F::MODULUS_BIT_SIZE as usize + 128).div_ceil(8);
+    let bytes = expand_message_xmd(tag, &data.concat(), N * len);
+    std::array::from_fn(|i| F::from_be_bytes_mod_order(&bytes[i * len..(i + 1) * len]))
+}
+
+/// Hash the given data to a point with simplified SWU, according to the hash_to_curve spec.
+fn hash_to_curve<P>(tag: &[u8], data: &[&[u8]]) -> Projective<P>
+where
+    P: SWUConfig,
+    P::BaseField: PrimeField,
+{
+    let [u0, u1] = hash_to_field::<P::BaseField, 2>(tag, data);
+    let q0 = SWUMap::<P>::map_to_curve(u0).expect("Infallible for valid SWU parameters");
+    let q1 = SWUMap::<P>::map_to_curve(u1).expect("Infallible for valid SWU parameters");
+    P::clear_cofactor(&(q0 + q1).into_affine()).into()
+}
+
+/// Hash the given data to a scalar, according to the hash_to_curve spec.
+fn hash_to_scalar<F: PrimeField>(tag: &[u8], data: &[&[u8]]) -> F {
+    let [scalar] = hash_to_field::<F, 1>(tag, data);
+    scalar
+}
+
+impl<C: CurveGroup> Serializable for ArkSignature<C> {
+    /// Encode as the compressed `R` followed by `s`.
+    fn to_bytes(&self) -> Vec<u8> {
+        serialize(&(self.r, self.s))
+    }
+
+    /// Decode from the compressed `R` followed by `s`.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        deserialize(bytes).map(|(r, s)| Self { r, s })
+    }
+}
+
+impl<C: CurveGroup> Serializable for ArkPoint<C> {
+    fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.0)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        deserialize(bytes).map(Self)
+    }
+}
+
+impl<P> DreipPoint for ArkPoint<Projective<P>>
+where
+    P: SWUConfig,
+    P::BaseField: PrimeField,
+{
+    fn identity() -> Self {
+        Self(Projective::<P>::ZERO)
+    }
+
+    fn generator() -> Self {
+        Self(Projective::<P>::generator())
+    }
+
+    fn from_hash(data: &[&[u8]]) -> Self {
+        Self(hash_to_curve(DOMAIN_SEPARATION_TAG, data))
+    }
+
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        Self(hash_to_curve(&tagged_dst(DOMAIN_SEPARATION_TAG, tag), data))
+    }
+}
+
+impl<F: PrimeField> Serializable for ArkScalar<F> {
+    /// Encode as little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.0)
+    }
+
+    /// Decode from little-endian bytes.
+    /// Values that are not fully reduced modulo the group order are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        deserialize(bytes).map(Self)
+    }
+}
+
+impl<F: PrimeField> DreipScalar for ArkScalar<F> {
+    fn zero() -> Self {
+        Self(F::ZERO)
+    }
+
+    fn one() -> Self {
+        Self(F::ONE)
+    }
+
+    fn random(mut rng: impl RngCore + CryptoRng) -> Self {
+        Self(F::rand(&mut rng))
+    }
+
+    fn from_hash(data: &[&[u8]]) -> Self {
+        Self(hash_to_scalar(DOMAIN_SEPARATION_TAG, data))
+    }
+
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        Self(hash_to_scalar(
+            &tagged_dst(DOMAIN_SEPARATION_TAG, tag),
+            data,
+        ))
+    }
+
+    fn invert(&self) -> Option<Self> {
+        ark_ff::Field::inverse(&self.0).map(Self)
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        let limbs = self.0.into_bigint();
+        let (low, high) = limbs.as_ref().split_first().expect("At least one limb");
+        high.iter().all(|limb| *limb == 0).then_some(*low)
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        self.0.into_bigint().into()
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        F::from_bigint(value.clone().try_into().ok()?).map(Self)
+    }
+}
+
+impl<C: CurveGroup> Serializable for ArkSigningKey<C> {
+    /// Encode as little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.0)
+    }
+
+    /// Decode from little-endian bytes.
+    /// The zero scalar is not a valid key and is rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        deserialize::<C::ScalarField>(bytes)
+            .filter(|x| !x.is_zero())
+            .map(Self)
+    }
+}
+
+impl<C: CurveGroup> DreipPrivateKey for ArkSigningKey<C> {
+    type Signature = ArkSignature<C>;
+
+    /// Sign with Schnorr. The nonce is derived deterministically
+    /// from the key and message, so no randomness is needed here.
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        let public_key = self.verifying_key().to_bytes();
+        let k: C::ScalarField =
+            hash_to_scalar(NONCE_DOMAIN_SEPARATION_TAG, &[&serialize(&self.0), msg]);
+        let r = C::generator() * k;
+        let e: C::ScalarField = hash_to_scalar(
+            SIGNATURE_DOMAIN_SEPARATION_TAG,
+            &[&serialize(&r), &public_key, msg],
+        );
+        ArkSignature {
+            r,
+            s: k + e * self.0,
+        }
+    }
+}
+
+impl<C: CurveGroup> Serializable for ArkVerifyingKey<C> {
+    fn to_bytes(&self) -> Vec<u8> {
+        serialize(&self.0)
+    }
+
+    /// Decode from the compressed encoding.
+    /// The identity and invalid points are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        deserialize::<C>(bytes).filter(|p| !p.is_zero()).map(Self)
+    }
+}
+
+impl<C: CurveGroup> DreipPublicKey for ArkVerifyingKey<C> {
+    type Signature = ArkSignature<C>;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        let e: C::ScalarField = hash_to_scalar(
+            SIGNATURE_DOMAIN_SEPARATION_TAG,
+            &[&serialize(&signature.r), &self.to_bytes(), msg],
+        );
+        C::generator() * signature.s == signature.r + self.0 * e
+    }
+}
+
+impl<P> DreipGroup for ArkGroup<Projective<P>>
+where
+    P: SWUConfig,
+    P::BaseField: PrimeField,
+{
+    /// The same for every curve, as there is no way to name the curve in a constant.
+    const NAME: &'static str = "DREIP-ARK";
+    type Signature = ArkSignature<Projective<P>>;
+    type Point = ArkPoint<Projective<P>>;
+    type Scalar = ArkScalar<P::ScalarField>;
+    type PrivateKey = ArkSigningKey<Projective<P>>;
+    type PublicKey = ArkVerifyingKey<Projective<P>>;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            ArkPoint(Projective::<P>::generator()),
+            ArkPoint::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let secret = loop {
+            let x = P::ScalarField::rand(&mut rng);
+            if !x.is_zero() {
+                break x;
+            }
+        };
+        let private_key = ArkSigningKey(secret);
+        let public_key = private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Point = ArkPoint<Projective<ArkP256Config>>;
+    type Scalar = ArkScalar<ark_secp256r1::Fr>;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = ArkP256::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key = ArkVerifyingKey::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key: ArkSigningKey<_> = Serializable::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = ArkP256::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = ArkPoint(Projective::<ArkP256Config>::rand(&mut rand::thread_rng()));
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 33);
+        let y: Point = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+
+        // Trailing bytes are rejected.
+        let mut extended = serialized.clone();
+        extended.push(0);
+        assert!(<Point as Serializable>::from_bytes(&extended).is_none());
+
+        // arkworks ignores x when the infinity flag is set, but only the zero x is canonical.
+        let mut junk = Serializable::to_bytes(&<Point as DreipPoint>::identity());
+        junk[0] = 1;
+        assert!(Projective::<ArkP256Config>::deserialize_compressed(&junk[..]).is_ok());
+        assert!(<Point as Serializable>::from_bytes(&junk).is_none());
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 32);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = ArkP256::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!g1.0.is_zero());
+            assert!(!g2.0.is_zero());
+        }
+    }
+
+    /// The arkworks hash_to_curve suite must agree with the `p256` crate's for the same tag.
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_matches_p256() {
+        use ark_ff::BigInteger;
+        use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+        let tag = b"CURVE_XMD:SHA-256:DREIP";
+        let data: &[&[u8]] = &[b"Hello, ", b"World!"];
+
+        let ark_point = hash_to_curve::<ArkP256Config>(tag, data).into_affine();
+        let p256_point = <p256::ProjectivePoint as DreipPoint>::from_hash(data);
+        let mut ark_uncompressed = vec![0x04];
+        ark_uncompressed.extend(ark_point.x.into_bigint().to_bytes_be());
+        ark_uncompressed.extend(ark_point.y.into_bigint().to_bytes_be());
+        assert_eq!(
+            ark_uncompressed,
+            p256_point.to_encoded_point(false).as_bytes()
+        );
+
+        let ark_scalar: ark_secp256r1::Fr = hash_to_scalar(tag, data);
+        let p256_scalar = <p256::Scalar as DreipScalar>::from_hash(data);
+        assert_eq!(
+            ark_scalar.into_bigint().to_bytes_be(),
+            Serializable::to_bytes(&p256_scalar)
+        );
+
+        // The same secret gives the same public point on both.
+        let x = 0x1234_5678_u64;
+        let ark_public =
+            (Projective::<ArkP256Config>::generator() * ark_secp256r1::Fr::from(x)).into_affine();
+        let p256_public = p256::ProjectivePoint::GENERATOR * p256::Scalar::from(x);
+        let mut ark_uncompressed = vec![0x04];
+        ark_uncompressed.extend(ark_public.x.into_bigint().to_bytes_be());
+        ark_uncompressed.extend(ark_public.y.into_bigint().to_bytes_be());
+        assert_eq!(
+            ark_uncompressed,
+            p256_public.to_encoded_point(false).as_bytes()
+        );
+    }
+}
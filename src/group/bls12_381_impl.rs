@@ -0,0 +1,440 @@
+use super::*;
+
+use bls12_381::hash_to_curve::{ExpandMsgXmd, HashToCurve, HashToField};
+use bls12_381::{
+    multi_miller_loop, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar,
+};
+use sha2_09::Sha256;
+use std::collections::HashSet;
+use std::fmt::{self, Formatter};
+
+/// A tag to ensure random oracle uniqueness as per the hash_to_curve spec.
+const DOMAIN_SEPARATION_TAG: &[u8] = b"BLS12381G1_XMD:SHA-256_SSWU_RO_DREIP";
+
+/// The ciphersuite tag for BLS signatures, as per the basic scheme of the BLS signature spec.
+/// https://datatracker.ietf.org/doc/draft-irtf-cfrg-bls-signature/
+const SIGNATURE_DOMAIN_SEPARATION_TAG: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// The G1 group of the BLS12-381 pairing-friendly curve.
+/// Receipts are signed with BLS signatures, with public keys in G1 and signatures in G2,
+/// so that many receipts can be checked at once with [`aggregate_verify`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Bls12381;
+
+/// A BLS signature: a point in G2.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BlsSignature(pub G2Affine);
+
+impl BlsSignature {
+    /// Aggregate many signatures into one, for use with [`aggregate_verify`].
+    pub fn aggregate<'a>(signatures: impl IntoIterator<Item = &'a BlsSignature>) -> Self {
+        let sum = signatures
+            .into_iter()
+            .fold(G2Projective::identity(), |acc, sig| acc + sig.0);
+        Self(sum.into())
+    }
+}
+
+/// A BLS signing key: a non-zero scalar.
+#[derive(Clone)]
+pub struct BlsSigningKey(Scalar);
+
+impl Debug for BlsSigningKey {
+    /// Only print the corresponding public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BlsSigningKey")
+            .field(&self.verifying_key())
+            .finish()
+    }
+}
+
+impl BlsSigningKey {
+    /// Get the verifying key corresponding to this signing key.
+    pub fn verifying_key(&self) -> BlsVerifyingKey {
+        BlsVerifyingKey((G1Projective::generator() * self.0).into())
+    }
+}
+
+/// A BLS verifying key: a non-identity point in G1.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BlsVerifyingKey(pub G1Affine);
+
+/// Hash a message to G2 for signing, according to the hash_to_curve spec.
+fn hash_message(msg: &[u8]) -> G2Affine {
+    <G2Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+        msg,
+        SIGNATURE_DOMAIN_SEPARATION_TAG,
+    )
+    .into()
+}
+
+/// Verify an aggregate signature over many messages, each paired with the key that signed it.
+/// This requires a single pairing product rather than one check per message, so is
+/// much faster than verifying each receipt individually.
+///
+/// As per the basic scheme of the BLS signature spec, all messages must be distinct;
+/// verification fails otherwise.
+pub fn aggregate_verify(signed: &[(&BlsVerifyingKey, &[u8])], signature: &BlsSignature) -> bool {
+    if signed.is_empty() {
+        return false;
+    }
+    let mut seen = HashSet::new();
+    if !signed.iter().all(|(_, msg)| seen.insert(*msg)) {
+        return false;
+    }
+    if signed
+        .iter()
+        .any(|(key, _)| bool::from(key.0.is_identity()))
+    {
+        return false;
+    }
+
+    let neg_generator = -G1Affine::generator();
+    let prepared: Vec<(G1Affine, G2Prepared)> = signed
+        .iter()
+        .map(|(key, msg)| (key.0, G2Prepared::from(hash_message(msg))))
+        .chain(std::iter::once((
+            neg_generator,
+            G2Prepared::from(signature.0),
+        )))
+        .collect();
+    let terms: Vec<(&G1Affine, &G2Prepared)> = prepared.iter().map(|(p, q)| (p, q)).collect();
+    multi_miller_loop(&terms).final_exponentiation() == Gt::identity()
+}
+
+impl Serializable for BlsSignature {
+    /// Encode as a 96-byte compressed G2 point.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed().to_vec()
+    }
+
+    /// Decode from a 96-byte compressed G2 point.
+    /// Points outside the prime-order subgroup are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 96] = bytes.try_into().ok()?;
+        Option::from(G2Affine::from_compressed(&bytes)).map(Self)
+    }
+}
+
+impl Serializable for G1Projective {
+    /// Encode as a 48-byte compressed point.
+    fn to_bytes(&self) -> Vec<u8> {
+        G1Affine::from(self).to_compressed().to_vec()
+    }
+
+    /// Decode from a 48-byte compressed point.
+    /// Points outside the prime-order subgroup are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 48] = bytes.try_into().ok()?;
+        Option::<G1Affine>::from(G1Affine::from_compressed(&bytes)).map(G1Projective::from)
+    }
+}
+
+impl DreipPoint for G1Projective {
+    fn identity() -> Self {
+        G1Projective::identity()
+    }
+
+    fn generator() -> Self {
+        G1Projective::generator()
+    }
+
+    /// Create a point using SHA256, according to the hash_to_curve spec.
+    /// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+    fn from_hash(data: &[&[u8]]) -> Self {
+        <G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+            data.concat(),
+            DOMAIN_SEPARATION_TAG,
+        )
+    }
+
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        <G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(
+            data.concat(),
+            &tagged_dst(DOMAIN_SEPARATION_TAG, tag),
+        )
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for Scalar {
+    /// Encode as 32 little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        Scalar::to_bytes(self).to_vec()
+    }
+
+    /// Decode from 32 little-endian bytes.
+    /// Values that are not fully reduced modulo the group order are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Scalar::from_bytes(&bytes).into()
+    }
+}
+
+/// Hash the given data to a scalar, according to the hash_to_field spec.
+fn hash_to_scalar(dst: &[u8], data: &[&[u8]]) -> Scalar {
+    let mut output = [Scalar::zero()];
+    Scalar::hash_to_field::<ExpandMsgXmd<Sha256>>(&data.concat(), dst, &mut output);
+    output[0]
+}
+
+impl DreipScalar for Scalar {
+    fn zero() -> Self {
+        Scalar::zero()
+    }
+
+    fn one() -> Self {
+        Scalar::one()
+    }
+
+    fn random(mut rng: impl RngCore + CryptoRng) -> Self {
+        let mut bytes = [0; 64];
+        rng.fill_bytes(&mut bytes);
+        Scalar::from_bytes_wide(&bytes)
+    }
+
+    /// Create a scalar using SHA256, according to the hash_to_field spec.
+    /// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+    fn from_hash(data: &[&[u8]]) -> Self {
+        hash_to_scalar(DOMAIN_SEPARATION_TAG, data)
+    }
+
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        hash_to_scalar(&tagged_dst(DOMAIN_SEPARATION_TAG, tag), data)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        Scalar::invert(self).into()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64_from_le_bytes(&self.to_bytes())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        BigUint::from_bytes_le(&self.to_bytes())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        Serializable::from_bytes(&bigint_to_le_bytes(value, 32)?)
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for BlsSigningKey {
+    /// Encode as 32 little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    /// Decode from 32 little-endian bytes.
+    /// The zero scalar is not a valid key and is rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Option::<Scalar>::from(Scalar::from_bytes(&bytes))
+            .filter(|s| *s != Scalar::zero())
+            .map(Self)
+    }
+}
+
+impl DreipPrivateKey for BlsSigningKey {
+    type Signature = BlsSignature;
+
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        BlsSignature((hash_message(msg) * self.0).into())
+    }
+}
+
+impl Serializable for BlsVerifyingKey {
+    /// Encode as a 48-byte compressed G1 point.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_compressed().to_vec()
+    }
+
+    /// Decode from a 48-byte compressed G1 point.
+    /// The identity and points outside the prime-order subgroup are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 48] = bytes.try_into().ok()?;
+        Option::<G1Affine>::from(G1Affine::from_compressed(&bytes))
+            .filter(|p| !bool::from(p.is_identity()))
+            .map(Self)
+    }
+}
+
+impl DreipPublicKey for BlsVerifyingKey {
+    type Signature = BlsSignature;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        aggregate_verify(&[(self, msg)], signature)
+    }
+}
+
+impl DreipGroup for Bls12381 {
+    const NAME: &'static str = "DREIP-BLS12-381";
+    type Signature = BlsSignature;
+    type Point = G1Projective;
+    type Scalar = Scalar;
+    type PrivateKey = BlsSigningKey;
+    type PublicKey = BlsVerifyingKey;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            G1Projective::generator(),
+            <G1Projective as DreipPoint>::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let secret = loop {
+            let s = <Scalar as DreipScalar>::random(&mut rng);
+            if s != Scalar::zero() {
+                break s;
+            }
+        };
+        let private_key = BlsSigningKey(secret);
+        let public_key = private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = Bls12381::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key = BlsVerifyingKey::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key = BlsSigningKey::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = Bls12381::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+
+        // The identity is not a valid public key.
+        let identity = G1Affine::identity().to_compressed();
+        assert!(BlsVerifyingKey::from_bytes(&identity).is_none());
+    }
+
+    #[test]
+    fn test_aggregate_verify() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = Bls12381::new_keys(&mut rng);
+        let (other_priv, other_pub) = Bls12381::new_keys(&mut rng);
+
+        let messages: Vec<Vec<u8>> = (0..5)
+            .map(|i| format!("Receipt {}", i).into_bytes())
+            .collect();
+        let mut signed: Vec<(&BlsVerifyingKey, &[u8])> =
+            messages.iter().map(|m| (&pub_key, m.as_slice())).collect();
+        let mut signatures: Vec<BlsSignature> = messages
+            .iter()
+            .map(|m| DreipPrivateKey::sign(&priv_key, m))
+            .collect();
+        let aggregate = BlsSignature::aggregate(&signatures);
+        assert!(aggregate_verify(&signed, &aggregate));
+
+        // A different key can contribute too.
+        let other_msg = b"Another receipt";
+        signed.push((&other_pub, other_msg));
+        signatures.push(DreipPrivateKey::sign(&other_priv, other_msg));
+        let aggregate = BlsSignature::aggregate(&signatures);
+        assert!(aggregate_verify(&signed, &aggregate));
+
+        // Missing a signature.
+        let partial = BlsSignature::aggregate(&signatures[1..]);
+        assert!(!aggregate_verify(&signed, &partial));
+
+        // Wrong message.
+        signed[0].1 = b"Tampered";
+        assert!(!aggregate_verify(&signed, &aggregate));
+
+        // Duplicate messages are rejected.
+        signed[0].1 = &messages[1];
+        assert!(!aggregate_verify(&signed, &aggregate));
+
+        // Nothing to verify.
+        assert!(!aggregate_verify(&[], &aggregate));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = G1Projective::generator() * <Scalar as DreipScalar>::random(rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 48);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 32);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = Bls12381::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!bool::from(g1.is_identity()));
+            assert!(!bool::from(g2.is_identity()));
+        }
+    }
+}
@@ -0,0 +1,323 @@
+use super::*;
+
+use bp256::elliptic_curve::sec1::{FromSec1Point, ToSec1Point};
+use bp256::elliptic_curve::PrimeField;
+use bp256::r1::{BrainpoolP256r1, FieldBytes, ProjectivePoint};
+use bp256::Scalar;
+use ecdsa::signature::{Signer, Verifier};
+use sha2::{Digest, Sha256};
+
+/// A tag to ensure random oracle uniqueness when hashing to the curve.
+const POINT_DOMAIN_SEPARATION_TAG: &[u8] = b"BRAINPOOLP256R1_XMD:SHA-256_TAI:DREIP";
+
+/// A tag to ensure random oracle uniqueness when hashing to scalars.
+const SCALAR_DOMAIN_SEPARATION_TAG: &[u8] = b"BRAINPOOLP256R1_XMD:SHA-256:DREIP";
+
+/// An ECDSA-Brainpool signature over brainpoolP256r1 with SHA256.
+pub type BrainpoolSignature = ecdsa::Signature<BrainpoolP256r1>;
+
+/// An ECDSA-Brainpool signing key.
+pub type BrainpoolSigningKey = ecdsa::SigningKey<BrainpoolP256r1>;
+
+/// An ECDSA-Brainpool verifying key.
+pub type BrainpoolVerifyingKey = ecdsa::VerifyingKey<BrainpoolP256r1>;
+
+/// Hash the tag, a big-endian counter, and the data with SHA256.
+fn hash_with_counter(tag: &[u8], counter: u32, data: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(tag);
+    hasher.update(counter.to_be_bytes());
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+/// Sample 32 random bytes until they encode a canonical scalar.
+/// `bp256` uses a newer `rand_core` than this crate, so we cannot hand it our RNG.
+fn random_scalar_bytes(mut rng: impl RngCore + CryptoRng) -> FieldBytes {
+    loop {
+        let mut bytes = FieldBytes::default();
+        rng.fill_bytes(&mut bytes);
+        if Scalar::from_repr(bytes).is_some().into() {
+            return bytes;
+        }
+    }
+}
+
+impl Serializable for BrainpoolSignature {
+    fn to_bytes(&self) -> Vec<u8> {
+        BrainpoolSignature::to_bytes(self).to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        BrainpoolSignature::from_slice(bytes).ok()
+    }
+}
+
+impl Serializable for ProjectivePoint {
+    /// Encode as SEC1 format.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_sec1_point(true).as_bytes().to_vec()
+    }
+
+    /// Decode from compressed SEC1 format.
+    /// Uncompressed encodings are rejected as non-canonical.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        ProjectivePoint::from_sec1_bytes(bytes)
+            .ok()
+            .filter(|p| Serializable::to_bytes(p) == bytes)
+    }
+}
+
+impl DreipPoint for ProjectivePoint {
+    fn identity() -> Self {
+        ProjectivePoint::IDENTITY
+    }
+
+    fn generator() -> Self {
+        ProjectivePoint::GENERATOR
+    }
+
+    /// Create a point by try-and-increment: hash with SHA256 and a counter to
+    /// a candidate x coordinate until it decompresses to a curve point.
+    /// brainpoolP256r1 has cofactor 1, so every such point is in the group.
+    /// `bp256` does not implement the hash_to_curve spec, and this map is not
+    /// constant-time, which is fine as generators are derived from public data.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        (0..=u32::MAX)
+            .find_map(|counter| {
+                let x = hash_with_counter(POINT_DOMAIN_SEPARATION_TAG, counter, data);
+                let mut compressed = [0x02; 33];
+                compressed[1..].copy_from_slice(&x);
+                ProjectivePoint::from_sec1_bytes(&compressed).ok()
+            })
+            .expect("Roughly half of all candidates are on the curve")
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for Scalar {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_repr().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Scalar::from_repr(FieldBytes::try_from(bytes).ok()?).into()
+    }
+}
+
+impl DreipScalar for Scalar {
+    fn zero() -> Self {
+        Scalar::ZERO
+    }
+
+    fn one() -> Self {
+        Scalar::ONE
+    }
+
+    fn random(rng: impl RngCore + CryptoRng) -> Self {
+        Scalar::from_repr(random_scalar_bytes(rng)).expect("Sampled canonically")
+    }
+
+    /// Create a scalar by hashing with SHA256 and a counter until the
+    /// digest is below the group order, avoiding any modular bias.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        (0..=u32::MAX)
+            .find_map(|counter| {
+                let bytes = hash_with_counter(SCALAR_DOMAIN_SEPARATION_TAG, counter, data);
+                Scalar::from_repr(bytes.into()).into_option()
+            })
+            .expect("Roughly two thirds of all digests are below the order")
+    }
+
+    fn invert(&self) -> Option<Self> {
+        bp256::elliptic_curve::Field::invert(self).into()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64_from_be_bytes(&self.to_repr())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        BigUint::from_bytes_be(&self.to_repr())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        Serializable::from_bytes(&bigint_to_be_bytes(value, FieldBytes::default().len())?)
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for BrainpoolSigningKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        BrainpoolSigningKey::to_bytes(self).to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        // `from_slice` would accept and zero-pad short input.
+        BrainpoolSigningKey::from_bytes(&FieldBytes::try_from(bytes).ok()?).ok()
+    }
+}
+
+impl DreipPrivateKey for BrainpoolSigningKey {
+    type Signature = BrainpoolSignature;
+
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        Signer::sign(self, msg)
+    }
+}
+
+impl Serializable for BrainpoolVerifyingKey {
+    /// Encode as SEC1 format.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_sec1_point(true).as_bytes().to_vec()
+    }
+
+    /// Decode from compressed SEC1 format.
+    /// Uncompressed encodings are rejected as non-canonical.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        BrainpoolVerifyingKey::from_sec1_bytes(bytes)
+            .ok()
+            .filter(|key| Serializable::to_bytes(key) == bytes)
+    }
+}
+
+impl DreipPublicKey for BrainpoolVerifyingKey {
+    type Signature = BrainpoolSignature;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        Verifier::verify(self, msg, signature).is_ok()
+    }
+}
+
+impl DreipGroup for BrainpoolP256r1 {
+    const NAME: &'static str = "DREIP-BRAINPOOLP256R1";
+    type Signature = BrainpoolSignature;
+    type Point = ProjectivePoint;
+    type Scalar = Scalar;
+    type PrivateKey = BrainpoolSigningKey;
+    type PublicKey = BrainpoolVerifyingKey;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            ProjectivePoint::GENERATOR,
+            ProjectivePoint::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let private_key = loop {
+            // Zero is a canonical scalar but not a valid key.
+            if let Ok(key) = BrainpoolSigningKey::from_slice(&random_scalar_bytes(&mut rng)) {
+                break key;
+            }
+        };
+        let public_key = *private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bp256::elliptic_curve::Group;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = BrainpoolP256r1::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key: BrainpoolVerifyingKey =
+            Serializable::from_bytes(&Serializable::to_bytes(&pub_key)).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key: BrainpoolSigningKey =
+            Serializable::from_bytes(&Serializable::to_bytes(&priv_key)).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = BrainpoolP256r1::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = ProjectivePoint::GENERATOR * <Scalar as DreipScalar>::random(rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 33);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+
+        // The uncompressed encoding is valid SEC1 but not canonical.
+        let uncompressed = x.to_sec1_point(false);
+        assert!(<ProjectivePoint as Serializable>::from_bytes(uncompressed.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 32);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = BrainpoolP256r1::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!bool::from(g1.is_identity()));
+            assert!(!bool::from(g2.is_identity()));
+        }
+    }
+}
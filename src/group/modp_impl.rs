@@ -0,0 +1,608 @@
+use super::*;
+
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+use std::fmt::{self, Formatter};
+use std::sync::OnceLock;
+
+/// A tag to ensure random oracle uniqueness.
+const DOMAIN_SEPARATION_TAG: &[u8] = b"MODP2048_XMD:SHA-512:DREIP";
+
+/// A tag to separate Schnorr signature nonce derivation from all other hashing.
+const NONCE_DOMAIN_SEPARATION_TAG: &[u8] = b"MODP2048_XMD:SHA-512:DREIP_SCHNORR_NONCE";
+
+/// A tag to separate Schnorr signature challenges from all other hashing.
+const SIGNATURE_DOMAIN_SEPARATION_TAG: &[u8] = b"MODP2048_XMD:SHA-512:DREIP_SCHNORR";
+
+/// The 2048-bit MODP group prime from RFC 3526, section 3.
+/// https://datatracker.ietf.org/doc/html/rfc3526#section-3
+const P_HEX: &str = "\
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD1\
+    29024E088A67CC74020BBEA63B139B22514A08798E3404DD\
+    EF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245\
+    E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED\
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3D\
+    C2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F\
+    83655D23DCA3AD961C62F356208552BB9ED529077096966D\
+    670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B\
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9\
+    DE2BCBF6955817183995497CEA956AE515D2261898FA0510\
+    15728E5A8AACAA68FFFFFFFFFFFFFFFF";
+
+/// The size in bytes of an encoded group element.
+const ELEMENT_SIZE: usize = 256;
+
+/// The size in bytes of an encoded scalar.
+const SCALAR_SIZE: usize = 256;
+
+/// Extra bytes of hash output used when reducing into the group, to keep the bias negligible.
+const HASH_EXTRA_BYTES: usize = 16;
+
+/// The group parameters: a safe prime `p = 2q + 1`, and a generator `g`
+/// of the order-`q` subgroup of quadratic residues mod `p`.
+struct Params {
+    p: BigUint,
+    q: BigUint,
+    g: BigUint,
+}
+
+fn params() -> &'static Params {
+    static PARAMS: OnceLock<Params> = OnceLock::new();
+    PARAMS.get_or_init(|| {
+        let p = BigUint::parse_bytes(P_HEX.as_bytes(), 16).expect("Valid hex");
+        let q = (&p - 1u32) >> 1;
+        // p = 7 mod 8, so 2 is a quadratic residue and generates the order-q subgroup.
+        let g = BigUint::from(2u32);
+        Params { p, q, g }
+    })
+}
+
+/// The multiplicative subgroup of order `q` in `Z_p*`, where `p = 2q + 1` is the
+/// 2048-bit safe prime from RFC 3526. This is the setting described in the original
+/// DRE-ip paper. Receipts are signed with Schnorr signatures in the same group.
+///
+/// Elements and scalars are encoded as fixed-width 256-byte big-endian integers.
+/// The group operation is written additively to match the `DreipGroup` API:
+/// `a + b` is multiplication mod `p`, and `a * k` is exponentiation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct ModPGroup;
+
+/// An element of the order-`q` subgroup of `Z_p*`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ModPElement(BigUint);
+
+impl ModPElement {
+    /// Construct an element from an integer, checking that it lies in the subgroup.
+    pub fn from_biguint(value: BigUint) -> Option<Self> {
+        let Params { p, q, .. } = params();
+        let zero = BigUint::from(0u32);
+        let one = BigUint::from(1u32);
+        if value == zero || &value >= p || value.modpow(q, p) != one {
+            return None;
+        }
+        Some(Self(value))
+    }
+
+    /// Get the underlying integer, in the range `[1, p)`.
+    pub fn as_biguint(&self) -> &BigUint {
+        &self.0
+    }
+
+    /// The generator of the subgroup.
+    fn generator() -> Self {
+        Self(params().g.clone())
+    }
+}
+
+/// An exponent modulo `q`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ModPScalar(BigUint);
+
+impl ModPScalar {
+    /// Construct a scalar from an integer, which must be fully reduced modulo `q`.
+    pub fn from_biguint(value: BigUint) -> Option<Self> {
+        if value < params().q {
+            Some(Self(value))
+        } else {
+            None
+        }
+    }
+
+    /// Get the underlying integer, in the range `[0, q)`.
+    pub fn as_biguint(&self) -> &BigUint {
+        &self.0
+    }
+}
+
+/// A Schnorr signature `(e, s)`, where `e` is the challenge and `s` the response.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ModPSignature {
+    e: ModPScalar,
+    s: ModPScalar,
+}
+
+/// A Schnorr signing key: a non-zero exponent `x`.
+#[derive(Clone)]
+pub struct ModPSigningKey(ModPScalar);
+
+impl Debug for ModPSigningKey {
+    /// Only print the corresponding public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ModPSigningKey")
+            .field(&self.verifying_key())
+            .finish()
+    }
+}
+
+impl ModPSigningKey {
+    /// Get the verifying key corresponding to this signing key.
+    pub fn verifying_key(&self) -> ModPVerifyingKey {
+        ModPVerifyingKey(ModPElement::generator() * self.0.clone())
+    }
+}
+
+/// A Schnorr verifying key: the element `y = g^x`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ModPVerifyingKey(ModPElement);
+
+/// Hash the given data into `len` bytes with SHA512 in counter mode.
+fn hash_wide(tag: &[u8], data: &[&[u8]], len: usize) -> Vec<u8> {
+    let mut output = Vec::with_capacity(len);
+    let mut counter: u32 = 0;
+    while output.len() < len {
+        let mut hasher = Sha512::new();
+        hasher.update(tag);
+        hasher.update(counter.to_be_bytes());
+        for chunk in data {
+            hasher.update(chunk);
+        }
+        output.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    output.truncate(len);
+    output
+}
+
+/// Hash the given data to a scalar, reducing a wide hash modulo `q`.
+fn hash_to_scalar(tag: &[u8], data: &[&[u8]]) -> ModPScalar {
+    let bytes = hash_wide(tag, data, SCALAR_SIZE + HASH_EXTRA_BYTES);
+    ModPScalar(BigUint::from_bytes_be(&bytes) % &params().q)
+}
+
+/// Encode an integer as exactly `len` big-endian bytes.
+fn to_fixed_bytes(value: &BigUint, len: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut output = vec![0; len - bytes.len()];
+    output.extend(bytes);
+    output
+}
+
+/// Decode an integer from exactly `len` big-endian bytes.
+fn from_fixed_bytes(bytes: &[u8], len: usize) -> Option<BigUint> {
+    if bytes.len() == len {
+        Some(BigUint::from_bytes_be(bytes))
+    } else {
+        None
+    }
+}
+
+impl Add for ModPElement {
+    type Output = Self;
+
+    /// The group operation: multiplication mod `p`.
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0 % &params().p)
+    }
+}
+
+impl Sub for ModPElement {
+    type Output = Self;
+
+    /// The inverse group operation: multiplication by the inverse mod `p`.
+    fn sub(self, rhs: Self) -> Self::Output {
+        let Params { p, q, .. } = params();
+        // rhs has order q, so its inverse is rhs^(q-1).
+        let inverse = rhs.0.modpow(&(q - 1u32), p);
+        Self(self.0 * inverse % p)
+    }
+}
+
+impl Neg for ModPElement {
+    type Output = Self;
+
+    /// The group inverse: the inverse mod `p`.
+    fn neg(self) -> Self::Output {
+        Self::identity() - self
+    }
+}
+
+impl Sum for ModPElement {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::identity(), Add::add)
+    }
+}
+
+impl Mul<ModPScalar> for ModPElement {
+    type Output = Self;
+
+    /// Scalar multiplication: exponentiation mod `p`.
+    fn mul(self, rhs: ModPScalar) -> Self::Output {
+        Self(self.0.modpow(&rhs.0, &params().p))
+    }
+}
+
+impl Add for ModPScalar {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self((self.0 + rhs.0) % &params().q)
+    }
+}
+
+impl Sub for ModPScalar {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let q = &params().q;
+        Self((self.0 + q - rhs.0) % q)
+    }
+}
+
+impl Neg for ModPScalar {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::zero() - self
+    }
+}
+
+impl Sum for ModPScalar {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), Add::add)
+    }
+}
+
+impl Mul for ModPScalar {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0 % &params().q)
+    }
+}
+
+impl Serializable for ModPSignature {
+    /// Encode as `e` followed by `s`, each as 256 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.e.to_bytes();
+        bytes.extend(self.s.to_bytes());
+        bytes
+    }
+
+    /// Decode from `e` followed by `s`, each as 256 big-endian bytes.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if bytes.len() != 2 * SCALAR_SIZE {
+            return None;
+        }
+        let (e, s) = bytes.split_at(SCALAR_SIZE);
+        Some(Self {
+            e: ModPScalar::from_bytes(e)?,
+            s: ModPScalar::from_bytes(s)?,
+        })
+    }
+}
+
+impl Serializable for ModPElement {
+    /// Encode as 256 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        to_fixed_bytes(&self.0, ELEMENT_SIZE)
+    }
+
+    /// Decode from 256 big-endian bytes.
+    /// Values that are not members of the order-`q` subgroup are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        from_fixed_bytes(bytes, ELEMENT_SIZE).and_then(Self::from_biguint)
+    }
+}
+
+impl DreipPoint for ModPElement {
+    fn identity() -> Self {
+        Self(BigUint::from(1u32))
+    }
+
+    fn generator() -> Self {
+        ModPElement::generator()
+    }
+
+    /// Create an element by hashing with SHA512 into `Z_p*` and squaring,
+    /// which lands in the subgroup of quadratic residues. In the negligibly
+    /// likely case that this gives the identity, a counter is appended and
+    /// the hash is retried.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        let Params { p, .. } = params();
+        let two = BigUint::from(2u32);
+        let mut attempt: u8 = 0;
+        loop {
+            let mut input = data.to_vec();
+            let counter = [attempt];
+            if attempt > 0 {
+                input.push(&counter);
+            }
+            let bytes = hash_wide(
+                DOMAIN_SEPARATION_TAG,
+                &input,
+                ELEMENT_SIZE + HASH_EXTRA_BYTES,
+            );
+            let value = (BigUint::from_bytes_be(&bytes) % p).modpow(&two, p);
+            if value > BigUint::from(1u32) {
+                return Self(value);
+            }
+            attempt += 1;
+        }
+    }
+}
+
+impl Serializable for ModPScalar {
+    /// Encode as 256 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        to_fixed_bytes(&self.0, SCALAR_SIZE)
+    }
+
+    /// Decode from 256 big-endian bytes.
+    /// Values that are not fully reduced modulo `q` are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        from_fixed_bytes(bytes, SCALAR_SIZE).and_then(Self::from_biguint)
+    }
+}
+
+impl DreipScalar for ModPScalar {
+    fn zero() -> Self {
+        Self(BigUint::from(0u32))
+    }
+
+    fn one() -> Self {
+        Self(BigUint::from(1u32))
+    }
+
+    fn random(mut rng: impl RngCore + CryptoRng) -> Self {
+        let mut bytes = [0; SCALAR_SIZE + HASH_EXTRA_BYTES];
+        rng.fill_bytes(&mut bytes);
+        Self(BigUint::from_bytes_be(&bytes) % &params().q)
+    }
+
+    /// Create a scalar by hashing with SHA512 and reducing modulo `q`.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        hash_to_scalar(DOMAIN_SEPARATION_TAG, data)
+    }
+
+    /// By Fermat's little theorem, as `q` is prime.
+    fn invert(&self) -> Option<Self> {
+        let q = &params().q;
+        (self.0 != BigUint::from(0u32)).then(|| Self(self.0.modpow(&(q - 2u32), q)))
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64::try_from(&self.0).ok()
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        self.0.clone()
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        Self::from_biguint(value.clone())
+    }
+
+    /// Overwrite the digits with zeros. `assign_from_slice` reuses the existing allocation,
+    /// so this wipes the old digits in place rather than freeing them unwiped.
+    #[cfg(feature = "zeroize")]
+    fn zeroize(&mut self) {
+        let zeros = vec![0; self.0.iter_u32_digits().len()];
+        self.0.assign_from_slice(&zeros);
+        std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl Serializable for ModPSigningKey {
+    /// Encode as 256 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    /// Decode from 256 big-endian bytes.
+    /// The zero scalar is not a valid key and is rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        ModPScalar::from_bytes(bytes)
+            .filter(|x| *x != ModPScalar::zero())
+            .map(Self)
+    }
+}
+
+impl DreipPrivateKey for ModPSigningKey {
+    type Signature = ModPSignature;
+
+    /// Sign with classic Schnorr. The nonce is derived deterministically
+    /// from the key and message, so no randomness is needed here.
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        let public_key = self.verifying_key().to_bytes();
+        let k = hash_to_scalar(NONCE_DOMAIN_SEPARATION_TAG, &[&self.0.to_bytes(), msg]);
+        let r = ModPElement::generator() * k.clone();
+        let e = hash_to_scalar(
+            SIGNATURE_DOMAIN_SEPARATION_TAG,
+            &[&r.to_bytes(), &public_key, msg],
+        );
+        let s = k + e.clone() * self.0.clone();
+        ModPSignature { e, s }
+    }
+}
+
+impl Serializable for ModPVerifyingKey {
+    /// Encode as 256 big-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    /// Decode from 256 big-endian bytes.
+    /// The identity and values outside the subgroup are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        ModPElement::from_bytes(bytes)
+            .filter(|y| *y != ModPElement::identity())
+            .map(Self)
+    }
+}
+
+impl DreipPublicKey for ModPVerifyingKey {
+    type Signature = ModPSignature;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        // r = g^s * y^-e
+        let r =
+            ModPElement::generator() * signature.s.clone() - self.0.clone() * signature.e.clone();
+        let e = hash_to_scalar(
+            SIGNATURE_DOMAIN_SEPARATION_TAG,
+            &[&r.to_bytes(), &self.to_bytes(), msg],
+        );
+        e == signature.e
+    }
+}
+
+impl DreipGroup for ModPGroup {
+    const NAME: &'static str = "DREIP-MODP2048";
+    type Signature = ModPSignature;
+    type Point = ModPElement;
+    type Scalar = ModPScalar;
+    type PrivateKey = ModPSigningKey;
+    type PublicKey = ModPVerifyingKey;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            ModPElement::generator(),
+            ModPElement::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let secret = loop {
+            let x = ModPScalar::random(&mut rng);
+            if x != ModPScalar::zero() {
+                break x;
+            }
+        };
+        let private_key = ModPSigningKey(secret);
+        let public_key = private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = ModPGroup::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key = ModPVerifyingKey::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key = ModPSigningKey::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = ModPGroup::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = ModPElement::generator() * ModPScalar::random(rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 256);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+
+        // p - 1 has order 2, so is not in the subgroup.
+        let minus_one = to_fixed_bytes(&(&params().p - 1u32), ELEMENT_SIZE);
+        assert!(ModPElement::from_bytes(&minus_one).is_none());
+        // Zero and p itself are not in the group at all.
+        assert!(ModPElement::from_bytes(&[0; ELEMENT_SIZE]).is_none());
+        let p = to_fixed_bytes(&params().p, ELEMENT_SIZE);
+        assert!(ModPElement::from_bytes(&p).is_none());
+        // Short encodings are rejected.
+        assert!(ModPElement::from_bytes(&serialized[1..]).is_none());
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = ModPScalar::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 256);
+        let z: ModPScalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+
+        // The group order itself is not a canonical encoding.
+        let q = to_fixed_bytes(&params().q, SCALAR_SIZE);
+        assert!(ModPScalar::from_bytes(&q).is_none());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_scalar_zeroize() {
+        let mut x = ModPScalar::random(rand::thread_rng());
+        x.zeroize();
+        assert_eq!(x, ModPScalar::zero());
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = ModPGroup::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert_ne!(g1, ModPElement::identity());
+            assert_ne!(g2, ModPElement::identity());
+            assert!(ModPElement::from_biguint(g2.as_biguint().clone()).is_some());
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::*;
+
+rustcrypto_group!(
+    p384,
+    NistP384,
+    sha2::Sha384,
+    b"CURVE_XMD:SHA-384:DREIP",
+    "DREIP-P384"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use p384::ecdsa::{SigningKey, VerifyingKey};
+    use p384::elliptic_curve::sec1::ToEncodedPoint;
+    use p384::elliptic_curve::Group;
+    use p384::{NistP384, ProjectivePoint, Scalar};
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = NistP384::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key: VerifyingKey = Serializable::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key: SigningKey = Serializable::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = NistP384::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = ProjectivePoint::random(rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 49);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+
+        // The uncompressed encoding is valid SEC1 but not canonical.
+        let uncompressed = x.to_encoded_point(false);
+        assert!(<ProjectivePoint as Serializable>::from_bytes(uncompressed.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 48);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = NistP384::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!bool::from(g1.is_identity()));
+            assert!(!bool::from(g2.is_identity()));
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use super::*;
+
+use p521::ecdsa::signature::{Signer, Verifier};
+use p521::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p521::elliptic_curve::hash2curve::{ExpandMsgXmd, GroupDigest};
+use p521::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+use p521::elliptic_curve::{Field, PrimeField};
+use p521::{EncodedPoint, FieldBytes, NistP521, ProjectivePoint, Scalar};
+use sha2::Sha512;
+use std::fmt::{self, Formatter};
+
+/// A tag to ensure random oracle uniqueness as per the hash_to_curve spec.
+const DOMAIN_SEPARATION_TAG: &[u8] = b"CURVE_XMD:SHA-512:DREIP";
+
+/// An ECDSA/P-521 signing key.
+///
+/// This wraps `p521::ecdsa::SigningKey`, which does not implement `Debug`.
+#[derive(Clone)]
+pub struct P521SigningKey(pub SigningKey);
+
+impl Debug for P521SigningKey {
+    /// Only print the corresponding public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("P521SigningKey")
+            .field(&self.verifying_key())
+            .finish()
+    }
+}
+
+impl P521SigningKey {
+    /// Get the verifying key corresponding to this signing key.
+    pub fn verifying_key(&self) -> P521VerifyingKey {
+        P521VerifyingKey(VerifyingKey::from(&self.0))
+    }
+}
+
+/// An ECDSA/P-521 verifying key.
+///
+/// This wraps `p521::ecdsa::VerifyingKey`, which does not implement `Debug`.
+#[derive(Clone)]
+pub struct P521VerifyingKey(pub VerifyingKey);
+
+impl Debug for P521VerifyingKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("P521VerifyingKey")
+            .field(&self.0.to_encoded_point(true))
+            .finish()
+    }
+}
+
+impl Serializable for Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        Signature::to_bytes(self).to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Signature::from_slice(bytes).ok()
+    }
+}
+
+impl Serializable for ProjectivePoint {
+    /// Encode as SEC1 format.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    /// Decode from compressed SEC1 format.
+    /// Uncompressed encodings are rejected as non-canonical.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let ep = EncodedPoint::from_bytes(bytes).ok()?;
+        Option::<ProjectivePoint>::from(ProjectivePoint::from_encoded_point(&ep))
+            .filter(|p| Serializable::to_bytes(p) == bytes)
+    }
+}
+
+impl DreipPoint for ProjectivePoint {
+    fn identity() -> Self {
+        ProjectivePoint::IDENTITY
+    }
+
+    fn generator() -> Self {
+        ProjectivePoint::GENERATOR
+    }
+
+    /// Create a point using SHA512, according to the hash_to_curve spec.
+    /// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+    fn from_hash(data: &[&[u8]]) -> Self {
+        NistP521::hash_from_bytes::<ExpandMsgXmd<Sha512>>(data, &[DOMAIN_SEPARATION_TAG])
+            .expect("Infallible")
+    }
+
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        let dst = tagged_dst(DOMAIN_SEPARATION_TAG, tag);
+        NistP521::hash_from_bytes::<ExpandMsgXmd<Sha512>>(data, &[&dst]).expect("Infallible")
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for Scalar {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_repr().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Scalar::from_repr(FieldBytes::from_exact_iter(bytes.iter().cloned())?).into()
+    }
+}
+
+impl DreipScalar for Scalar {
+    fn zero() -> Self {
+        Scalar::ZERO
+    }
+
+    fn one() -> Self {
+        Scalar::ONE
+    }
+
+    fn random(rng: impl RngCore + CryptoRng) -> Self {
+        <Scalar as Field>::random(rng)
+    }
+
+    /// Create a scalar using SHA512, according to the hash_to_curve spec.
+    /// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+    fn from_hash(data: &[&[u8]]) -> Self {
+        NistP521::hash_to_scalar::<ExpandMsgXmd<Sha512>>(data, &[DOMAIN_SEPARATION_TAG])
+            .expect("Infallible")
+    }
+
+    fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+        let dst = tagged_dst(DOMAIN_SEPARATION_TAG, tag);
+        NistP521::hash_to_scalar::<ExpandMsgXmd<Sha512>>(data, &[&dst]).expect("Infallible")
+    }
+
+    fn invert(&self) -> Option<Self> {
+        Field::invert(self).into()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64_from_be_bytes(&self.to_repr())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        BigUint::from_bytes_be(&self.to_repr())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        Serializable::from_bytes(&bigint_to_be_bytes(value, FieldBytes::default().len())?)
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for P521SigningKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        // `from_slice` would accept and zero-pad short input.
+        SigningKey::from_bytes(&FieldBytes::from_exact_iter(bytes.iter().cloned())?)
+            .ok()
+            .map(Self)
+    }
+}
+
+impl DreipPrivateKey for P521SigningKey {
+    type Signature = Signature;
+
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        Signer::sign(&self.0, msg)
+    }
+}
+
+impl Serializable for P521VerifyingKey {
+    /// Encode as SEC1 format.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    /// Decode from compressed SEC1 format.
+    /// Uncompressed encodings are rejected as non-canonical.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        EncodedPoint::from_bytes(bytes)
+            .ok()
+            .and_then(|ep| VerifyingKey::from_encoded_point(&ep).ok())
+            .map(Self)
+            .filter(|key| key.to_bytes() == bytes)
+    }
+}
+
+impl DreipPublicKey for P521VerifyingKey {
+    type Signature = Signature;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        Verifier::verify(&self.0, msg, signature).is_ok()
+    }
+}
+
+impl DreipGroup for NistP521 {
+    const NAME: &'static str = "DREIP-P521";
+    type Signature = Signature;
+    type Point = ProjectivePoint;
+    type Scalar = Scalar;
+    type PrivateKey = P521SigningKey;
+    type PublicKey = P521VerifyingKey;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            ProjectivePoint::GENERATOR,
+            ProjectivePoint::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let private_key = P521SigningKey(SigningKey::random(&mut rng));
+        let public_key = private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use p521::elliptic_curve::sec1::ToEncodedPoint;
+    use p521::elliptic_curve::Group;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = NistP521::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key = P521VerifyingKey::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key = P521SigningKey::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = NistP521::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = ProjectivePoint::random(rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 67);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+
+        // The uncompressed encoding is valid SEC1 but not canonical.
+        let uncompressed = x.to_encoded_point(false);
+        assert!(<ProjectivePoint as Serializable>::from_bytes(uncompressed.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 66);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = NistP521::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!bool::from(g1.is_identity()));
+            assert!(!bool::from(g2.is_identity()));
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use super::*;
+
+use pasta_curves::arithmetic::CurveExt;
+use pasta_curves::group::ff::{Field, FromUniformBytes, PrimeField};
+use pasta_curves::group::{Group, GroupEncoding};
+use pasta_curves::pallas::{Point, Scalar};
+use sha2::{Digest, Sha512};
+use std::fmt::{self, Formatter};
+
+/// The domain prefix for hashing to the curve. The crate appends the curve name and
+/// suite, giving the full tag `DREIP-pallas_XMD:BLAKE2b_SSWU_RO_`.
+const HASH_TO_CURVE_DOMAIN_PREFIX: &str = "DREIP";
+
+/// A tag to ensure random oracle uniqueness when hashing to scalars.
+const DOMAIN_SEPARATION_TAG: &[u8] = b"PALLAS_XMD:SHA-512:DREIP";
+
+/// A tag to separate Schnorr signature nonce derivation from all other hashing.
+const NONCE_DOMAIN_SEPARATION_TAG: &[u8] = b"PALLAS_XMD:SHA-512:DREIP_SCHNORR_NONCE";
+
+/// A tag to separate Schnorr signature challenges from all other hashing.
+const SIGNATURE_DOMAIN_SEPARATION_TAG: &[u8] = b"PALLAS_XMD:SHA-512:DREIP_SCHNORR";
+
+/// The Pallas curve from the Pasta cycle.
+/// Receipts are signed with Schnorr signatures over Pallas.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Pallas;
+
+/// A Schnorr signature `(R, s)` over Pallas.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PallasSignature {
+    r: Point,
+    s: Scalar,
+}
+
+/// A Schnorr signing key: a non-zero scalar `x`.
+#[derive(Clone)]
+pub struct PallasSigningKey(Scalar);
+
+impl Debug for PallasSigningKey {
+    /// Only print the corresponding public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PallasSigningKey")
+            .field(&self.verifying_key())
+            .finish()
+    }
+}
+
+impl PallasSigningKey {
+    /// Get the verifying key corresponding to this signing key.
+    pub fn verifying_key(&self) -> PallasVerifyingKey {
+        PallasVerifyingKey(<Point as DreipPoint>::generator() * self.0)
+    }
+}
+
+/// A Schnorr verifying key: the non-identity point `x * G`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PallasVerifyingKey(Point);
+
+/// Hash the given data to a scalar, reducing 64 bytes of SHA512 output.
+fn hash_to_scalar(tag: &[u8], data: &[&[u8]]) -> Scalar {
+    let mut hasher = Sha512::new();
+    hasher.update(tag);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    Scalar::from_uniform_bytes(&hasher.finalize().into())
+}
+
+impl Serializable for PallasSignature {
+    /// Encode as `R` followed by `s`, each as 32 bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = GroupEncoding::to_bytes(&self.r).to_vec();
+        bytes.extend(self.s.to_repr());
+        bytes
+    }
+
+    /// Decode from `R` followed by `s`, each as 32 bytes.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        if bytes.len() != 64 {
+            return None;
+        }
+        let (r, s) = bytes.split_at(32);
+        Some(Self {
+            r: Serializable::from_bytes(r)?,
+            s: Serializable::from_bytes(s)?,
+        })
+    }
+}
+
+impl Serializable for Point {
+    /// Encode as the 32-byte compressed encoding.
+    fn to_bytes(&self) -> Vec<u8> {
+        GroupEncoding::to_bytes(self).to_vec()
+    }
+
+    /// Decode from the 32-byte compressed encoding.
+    /// Non-canonical encodings are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        <Point as GroupEncoding>::from_bytes(&bytes).into()
+    }
+}
+
+impl DreipPoint for Point {
+    fn identity() -> Self {
+        <Point as Group>::identity()
+    }
+
+    fn generator() -> Self {
+        <Point as Group>::generator()
+    }
+
+    /// Create a point using the crate's hash_to_curve map, which uses BLAKE2b
+    /// and simplified SWU according to the hash_to_curve spec.
+    /// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+    fn from_hash(data: &[&[u8]]) -> Self {
+        Point::hash_to_curve(HASH_TO_CURVE_DOMAIN_PREFIX)(&data.concat())
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for Scalar {
+    /// Encode as 32 little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_repr().to_vec()
+    }
+
+    /// Decode from 32 little-endian bytes.
+    /// Values that are not fully reduced modulo the group order are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Scalar::from_repr(bytes).into()
+    }
+}
+
+impl DreipScalar for Scalar {
+    fn zero() -> Self {
+        Scalar::ZERO
+    }
+
+    fn one() -> Self {
+        Scalar::ONE
+    }
+
+    fn random(rng: impl RngCore + CryptoRng) -> Self {
+        <Scalar as Field>::random(rng)
+    }
+
+    /// Create a scalar by hashing with SHA512 and reducing modulo the group order.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        hash_to_scalar(DOMAIN_SEPARATION_TAG, data)
+    }
+
+    fn invert(&self) -> Option<Self> {
+        Field::invert(self).into()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64_from_le_bytes(&self.to_repr())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        BigUint::from_bytes_le(&self.to_repr())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        Serializable::from_bytes(&bigint_to_le_bytes(value, 32)?)
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for PallasSigningKey {
+    /// Encode as 32 little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_repr().to_vec()
+    }
+
+    /// Decode from 32 little-endian bytes.
+    /// The zero scalar is not a valid key and is rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        <Scalar as Serializable>::from_bytes(bytes)
+            .filter(|x| !bool::from(x.is_zero()))
+            .map(Self)
+    }
+}
+
+impl DreipPrivateKey for PallasSigningKey {
+    type Signature = PallasSignature;
+
+    /// Sign with Schnorr. The nonce is derived deterministically
+    /// from the key and message, so no randomness is needed here.
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        let public_key = self.verifying_key().to_bytes();
+        let k = hash_to_scalar(NONCE_DOMAIN_SEPARATION_TAG, &[&self.0.to_repr(), msg]);
+        let r = <Point as DreipPoint>::generator() * k;
+        let e = hash_to_scalar(
+            SIGNATURE_DOMAIN_SEPARATION_TAG,
+            &[&GroupEncoding::to_bytes(&r), &public_key, msg],
+        );
+        PallasSignature {
+            r,
+            s: k + e * self.0,
+        }
+    }
+}
+
+impl Serializable for PallasVerifyingKey {
+    /// Encode as the 32-byte compressed encoding.
+    fn to_bytes(&self) -> Vec<u8> {
+        GroupEncoding::to_bytes(&self.0).to_vec()
+    }
+
+    /// Decode from the 32-byte compressed encoding.
+    /// The identity and non-canonical encodings are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        <Point as Serializable>::from_bytes(bytes)
+            .filter(|p| !bool::from(p.is_identity()))
+            .map(Self)
+    }
+}
+
+impl DreipPublicKey for PallasVerifyingKey {
+    type Signature = PallasSignature;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        let e = hash_to_scalar(
+            SIGNATURE_DOMAIN_SEPARATION_TAG,
+            &[
+                &GroupEncoding::to_bytes(&signature.r),
+                &self.to_bytes(),
+                msg,
+            ],
+        );
+        <Point as DreipPoint>::generator() * signature.s == signature.r + self.0 * e
+    }
+}
+
+impl DreipGroup for Pallas {
+    const NAME: &'static str = "DREIP-PALLAS";
+    type Signature = PallasSignature;
+    type Point = Point;
+    type Scalar = Scalar;
+    type PrivateKey = PallasSigningKey;
+    type PublicKey = PallasVerifyingKey;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            <Point as DreipPoint>::generator(),
+            <Point as DreipPoint>::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let secret = loop {
+            let x = <Scalar as Field>::random(&mut rng);
+            if !bool::from(x.is_zero()) {
+                break x;
+            }
+        };
+        let private_key = PallasSigningKey(secret);
+        let public_key = private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = Pallas::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key = PallasVerifyingKey::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key = PallasSigningKey::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = Pallas::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = Point::random(rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 32);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 32);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+
+        // The group order itself is not a canonical encoding.
+        let mut order = (Scalar::ZERO - Scalar::ONE).to_repr();
+        order[0] += 1;
+        assert!(<Scalar as Serializable>::from_bytes(&order).is_none());
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = Pallas::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!bool::from(g1.is_identity()));
+            assert!(!bool::from(g2.is_identity()));
+        }
+    }
+}
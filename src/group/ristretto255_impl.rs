@@ -0,0 +1,277 @@
+use super::*;
+
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{CompressedRistretto, RistrettoPoint};
+use curve25519_dalek::scalar::Scalar;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use sha2::{Digest, Sha512};
+
+/// A tag to ensure random oracle uniqueness.
+const DOMAIN_SEPARATION_TAG: &[u8] = b"RISTRETTO255_XMD:SHA-512:DREIP";
+
+/// The Ristretto255 prime-order group, built on Curve25519.
+/// Receipts are signed with Ed25519.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Ristretto255;
+
+/// Hash the given data into 64 uniformly random bytes using SHA512.
+fn hash_wide(data: &[&[u8]]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(DOMAIN_SEPARATION_TAG);
+    for chunk in data {
+        hasher.update(chunk);
+    }
+    hasher.finalize().into()
+}
+
+impl Serializable for Signature {
+    fn to_bytes(&self) -> Vec<u8> {
+        Signature::to_bytes(self).to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Signature::from_slice(bytes).ok()
+    }
+}
+
+impl Serializable for RistrettoPoint {
+    /// Encode as the 32-byte compressed Ristretto encoding.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.compress().to_bytes().to_vec()
+    }
+
+    /// Decode from the 32-byte compressed Ristretto encoding.
+    /// Non-canonical encodings are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        CompressedRistretto::from_slice(bytes).ok()?.decompress()
+    }
+}
+
+impl DreipPoint for RistrettoPoint {
+    fn identity() -> Self {
+        <RistrettoPoint as curve25519_dalek::traits::Identity>::identity()
+    }
+
+    fn generator() -> Self {
+        RISTRETTO_BASEPOINT_POINT
+    }
+
+    /// Create a point by hashing with SHA512 and applying the Elligator map twice.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        RistrettoPoint::from_uniform_bytes(&hash_wide(data))
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for Scalar {
+    /// Encode as 32 little-endian bytes.
+    fn to_bytes(&self) -> Vec<u8> {
+        Scalar::to_bytes(self).to_vec()
+    }
+
+    /// Decode from 32 little-endian bytes.
+    /// Values that are not fully reduced modulo the group order are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Scalar::from_canonical_bytes(bytes).into()
+    }
+}
+
+impl DreipScalar for Scalar {
+    fn zero() -> Self {
+        Scalar::ZERO
+    }
+
+    fn one() -> Self {
+        Scalar::ONE
+    }
+
+    fn random(mut rng: impl RngCore + CryptoRng) -> Self {
+        Scalar::random(&mut rng)
+    }
+
+    /// Create a scalar by hashing with SHA512 and reducing modulo the group order.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        Scalar::from_bytes_mod_order_wide(&hash_wide(data))
+    }
+
+    /// `Scalar::invert` maps zero to zero, so check for it first.
+    fn invert(&self) -> Option<Self> {
+        (*self != Scalar::ZERO).then(|| Scalar::invert(self))
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64_from_le_bytes(self.as_bytes())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        BigUint::from_bytes_le(self.as_bytes())
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        Serializable::from_bytes(&bigint_to_le_bytes(value, 32)?)
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl Serializable for SigningKey {
+    fn to_bytes(&self) -> Vec<u8> {
+        SigningKey::to_bytes(self).to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        Some(SigningKey::from_bytes(&bytes))
+    }
+}
+
+impl DreipPrivateKey for SigningKey {
+    type Signature = Signature;
+
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        Signer::sign(self, msg)
+    }
+}
+
+impl Serializable for VerifyingKey {
+    /// Encode as the 32-byte compressed Edwards encoding.
+    fn to_bytes(&self) -> Vec<u8> {
+        VerifyingKey::to_bytes(self).to_vec()
+    }
+
+    /// Decode from the 32-byte compressed Edwards encoding.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let bytes: [u8; 32] = bytes.try_into().ok()?;
+        VerifyingKey::from_bytes(&bytes).ok()
+    }
+}
+
+impl DreipPublicKey for VerifyingKey {
+    type Signature = Signature;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        self.verify_strict(msg, signature).is_ok()
+    }
+}
+
+impl DreipGroup for Ristretto255 {
+    const NAME: &'static str = "DREIP-RISTRETTO255";
+    type Signature = Signature;
+    type Point = RistrettoPoint;
+    type Scalar = Scalar;
+    type PrivateKey = SigningKey;
+    type PublicKey = VerifyingKey;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        (
+            RISTRETTO_BASEPOINT_POINT,
+            <RistrettoPoint as DreipPoint>::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+        )
+    }
+
+    fn new_keys(mut rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let private_key = SigningKey::generate(&mut rng);
+        let public_key = private_key.verifying_key();
+        (private_key, public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing() {
+        let mut rng = rand::thread_rng();
+        let (priv_key, pub_key) = Ristretto255::new_keys(&mut rng);
+
+        // Sign and verify.
+        let msg = b"This is a message.";
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize and verify.
+        let signature = Serializable::from_bytes(&signature.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Serialize-deserialize the keys and verify.
+        let pub_key: VerifyingKey = Serializable::from_bytes(&pub_key.to_bytes()).unwrap();
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+        let priv_key: SigningKey = Serializable::from_bytes(&priv_key.to_bytes()).unwrap();
+        let signature = DreipPrivateKey::sign(&priv_key, msg);
+        assert!(DreipPublicKey::verify(&pub_key, msg, &signature));
+
+        // Message mismatch.
+        let different_msg = b"This is a different message.";
+        assert!(!DreipPublicKey::verify(&pub_key, different_msg, &signature));
+        let different_sig = DreipPrivateKey::sign(&priv_key, different_msg);
+        assert_ne!(signature, different_sig);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &different_sig));
+
+        // Key mismatch.
+        let (new_priv, new_pub) = Ristretto255::new_keys(&mut rng);
+        assert!(!DreipPublicKey::verify(&new_pub, msg, &signature));
+        let new_sig = DreipPrivateKey::sign(&new_priv, msg);
+        assert!(!DreipPublicKey::verify(&pub_key, msg, &new_sig));
+    }
+
+    #[test]
+    fn test_point_serialization() {
+        let x = RistrettoPoint::random(&mut rand::thread_rng());
+        let serialized = Serializable::to_bytes(&x);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <Scalar as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        let z: Scalar = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+
+        // The group order itself is not a canonical encoding.
+        let mut order = (Scalar::ZERO - Scalar::ONE).to_bytes();
+        order[0] += 1;
+        assert!(<Scalar as Serializable>::from_bytes(&order).is_none());
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let (g1, g2) = Ristretto255::new_generators(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert_ne!(g1, RistrettoPoint::identity());
+            assert_ne!(g2, RistrettoPoint::identity());
+        }
+    }
+}
@@ -0,0 +1,385 @@
+/// Implement `DreipGroup` and its supporting traits for a RustCrypto curve crate
+/// built on `elliptic-curve` 0.13, such as `p256` or `p384`.
+///
+/// The crate must enable its `arithmetic`, `ecdsa`, and `hash2curve` features.
+/// Points and keys use compressed SEC1 encodings, scalars their canonical big-endian
+/// encoding, and signatures the fixed-size `r || s` encoding.
+/// Points and scalars are hashed according to the hash_to_curve spec using `XMD` with
+/// the given hash function and domain separation tag. The last argument is `DreipGroup::NAME`.
+/// With the `pem` feature, keys convert to and from PKCS#8 and SPKI.
+/// New curves should put the curve name in their tag; existing tags are kept as-is,
+/// since changing them would change every generator and therefore every election.
+///
+/// This is a macro rather than a blanket implementation because each curve crate
+/// defines its own concrete point and scalar types, and a blanket implementation over
+/// `elliptic-curve` traits would overlap with the other groups under coherence rules.
+///
+/// https://datatracker.ietf.org/doc/draft-irtf-cfrg-hash-to-curve/
+macro_rules! rustcrypto_group {
+    ($krate:ident, $curve:ident, $hash:ty, $dst:expr, $name:expr) => {
+        const _: () = {
+            use $krate::ecdsa::signature::digest::Digest;
+            use $krate::ecdsa::signature::{PrehashSignature, Signer, Verifier};
+            use $krate::ecdsa::{Signature, SigningKey, VerifyingKey};
+            use $krate::elliptic_curve::generic_array::typenum::Unsigned;
+            use $krate::elliptic_curve::hash2curve::{ExpandMsgXmd, GroupDigest};
+            use $krate::elliptic_curve::ops::{BatchInvert, Reduce};
+            use $krate::elliptic_curve::point::AffineCoordinates;
+            use $krate::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
+            use $krate::elliptic_curve::subtle::ConstantTimeEq;
+            use $krate::elliptic_curve::{Curve, Field, Group as _, PrimeField};
+            use $krate::{$curve, EncodedPoint, FieldBytes, ProjectivePoint, Scalar};
+
+            use crate::precompute::FixedBaseTable;
+
+            /// A tag to ensure random oracle uniqueness as per the hash_to_curve spec.
+            const DOMAIN_SEPARATION_TAG: &[u8] = $dst;
+
+            /// The length of a field element, from which every encoding length follows.
+            const FIELD_SIZE: usize = <<$curve as Curve>::FieldBytesSize as Unsigned>::USIZE;
+
+            impl Serializable for Signature {
+                const BYTE_SIZE: Option<usize> = Some(2 * FIELD_SIZE);
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    Signature::to_bytes(self).to_vec()
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self>
+                where
+                    Self: Sized,
+                {
+                    Signature::from_slice(bytes).ok()
+                }
+            }
+
+            impl Serializable for ProjectivePoint {
+                /// The length of compressed SEC1 points, except the identity, which is one
+                /// byte but never part of a valid ballot.
+                const BYTE_SIZE: Option<usize> = Some(1 + FIELD_SIZE);
+
+                /// Encode as SEC1 format.
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_encoded_point(true).as_bytes().to_vec()
+                }
+
+                /// Decode from compressed SEC1 format.
+                /// Uncompressed encodings are rejected as non-canonical.
+                fn from_bytes(bytes: &[u8]) -> Option<Self>
+                where
+                    Self: Sized,
+                {
+                    let ep = EncodedPoint::from_bytes(bytes).ok()?;
+                    Option::<ProjectivePoint>::from(ProjectivePoint::from_encoded_point(&ep))
+                        .filter(|p| Serializable::to_bytes(p) == bytes)
+                }
+            }
+
+            impl DreipPoint for ProjectivePoint {
+                fn identity() -> Self {
+                    ProjectivePoint::IDENTITY
+                }
+
+                fn generator() -> Self {
+                    ProjectivePoint::GENERATOR
+                }
+
+                fn from_hash(data: &[&[u8]]) -> Self {
+                    $curve::hash_from_bytes::<ExpandMsgXmd<$hash>>(data, &[DOMAIN_SEPARATION_TAG])
+                        .expect("Infallible")
+                }
+
+                fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+                    let dst = tagged_dst(DOMAIN_SEPARATION_TAG, tag);
+                    $curve::hash_from_bytes::<ExpandMsgXmd<$hash>>(data, &[&dst])
+                        .expect("Infallible")
+                }
+
+                fn ct_eq(&self, other: &Self) -> bool {
+                    ConstantTimeEq::ct_eq(self, other).into()
+                }
+            }
+
+            impl Serializable for Scalar {
+                const BYTE_SIZE: Option<usize> = Some(FIELD_SIZE);
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_repr().to_vec()
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self>
+                where
+                    Self: Sized,
+                {
+                    Scalar::from_repr(FieldBytes::from_exact_iter(bytes.iter().cloned())?).into()
+                }
+            }
+
+            impl DreipScalar for Scalar {
+                fn zero() -> Self {
+                    Scalar::ZERO
+                }
+
+                fn one() -> Self {
+                    Scalar::ONE
+                }
+
+                fn random(rng: impl RngCore + CryptoRng) -> Self {
+                    <Scalar as Field>::random(rng)
+                }
+
+                fn from_hash(data: &[&[u8]]) -> Self {
+                    $curve::hash_to_scalar::<ExpandMsgXmd<$hash>>(data, &[DOMAIN_SEPARATION_TAG])
+                        .expect("Infallible")
+                }
+
+                fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+                    let dst = tagged_dst(DOMAIN_SEPARATION_TAG, tag);
+                    $curve::hash_to_scalar::<ExpandMsgXmd<$hash>>(data, &[&dst])
+                        .expect("Infallible")
+                }
+
+                fn invert(&self) -> Option<Self> {
+                    Field::invert(self).into()
+                }
+
+                fn to_u64(&self) -> Option<u64> {
+                    u64_from_be_bytes(&self.to_repr())
+                }
+
+                #[cfg(feature = "bigint")]
+                fn to_bigint(&self) -> BigUint {
+                    BigUint::from_bytes_be(&self.to_repr())
+                }
+
+                #[cfg(feature = "bigint")]
+                fn from_bigint(value: &BigUint) -> Option<Self> {
+                    Serializable::from_bytes(&bigint_to_be_bytes(
+                        value,
+                        FieldBytes::default().len(),
+                    )?)
+                }
+
+                fn ct_eq(&self, other: &Self) -> bool {
+                    ConstantTimeEq::ct_eq(self, other).into()
+                }
+            }
+
+            impl Serializable for SigningKey {
+                const BYTE_SIZE: Option<usize> = Some(FIELD_SIZE);
+
+                fn to_bytes(&self) -> Vec<u8> {
+                    SigningKey::to_bytes(self).to_vec()
+                }
+
+                fn from_bytes(bytes: &[u8]) -> Option<Self>
+                where
+                    Self: Sized,
+                {
+                    // `from_slice` would accept and zero-pad short input.
+                    SigningKey::from_bytes(&FieldBytes::from_exact_iter(bytes.iter().cloned())?)
+                        .ok()
+                }
+            }
+
+            impl DreipPrivateKey for SigningKey {
+                type Signature = Signature;
+
+                fn sign(&self, msg: &[u8]) -> Self::Signature {
+                    Signer::sign(self, msg)
+                }
+
+                #[cfg(feature = "pem")]
+                fn to_pkcs8_der(&self) -> Option<Vec<u8>> {
+                    use $krate::pkcs8::EncodePrivateKey;
+                    EncodePrivateKey::to_pkcs8_der(self)
+                        .ok()
+                        .map(|der| der.as_bytes().to_vec())
+                }
+
+                #[cfg(feature = "pem")]
+                fn to_pkcs8_pem(&self) -> Option<String> {
+                    use $krate::pkcs8::{EncodePrivateKey, LineEnding};
+                    EncodePrivateKey::to_pkcs8_pem(self, LineEnding::LF)
+                        .ok()
+                        .map(|pem| pem.to_string())
+                }
+
+                #[cfg(feature = "pem")]
+                fn from_pkcs8_der(der: &[u8]) -> Option<Self> {
+                    use $krate::pkcs8::DecodePrivateKey;
+                    DecodePrivateKey::from_pkcs8_der(der).ok()
+                }
+
+                #[cfg(feature = "pem")]
+                fn from_pkcs8_pem(pem: &str) -> Option<Self> {
+                    use $krate::pkcs8::DecodePrivateKey;
+                    DecodePrivateKey::from_pkcs8_pem(pem).ok()
+                }
+            }
+
+            impl Serializable for VerifyingKey {
+                const BYTE_SIZE: Option<usize> = Some(1 + FIELD_SIZE);
+
+                /// Encode as SEC1 format.
+                fn to_bytes(&self) -> Vec<u8> {
+                    self.to_encoded_point(true).as_bytes().to_vec()
+                }
+
+                /// Decode from compressed SEC1 format.
+                /// Uncompressed encodings are rejected as non-canonical.
+                fn from_bytes(bytes: &[u8]) -> Option<Self>
+                where
+                    Self: Sized,
+                {
+                    EncodedPoint::from_bytes(bytes)
+                        .ok()
+                        .and_then(|ep| VerifyingKey::from_encoded_point(&ep).ok())
+                        .filter(|key| Serializable::to_bytes(key) == bytes)
+                }
+            }
+
+            impl DreipPublicKey for VerifyingKey {
+                type Signature = Signature;
+
+                fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+                    Verifier::verify(self, msg, signature).is_ok()
+                }
+
+                #[cfg(feature = "pem")]
+                fn to_public_key_der(&self) -> Option<Vec<u8>> {
+                    use $krate::pkcs8::EncodePublicKey;
+                    EncodePublicKey::to_public_key_der(self)
+                        .ok()
+                        .map(|der| der.into_vec())
+                }
+
+                #[cfg(feature = "pem")]
+                fn to_public_key_pem(&self) -> Option<String> {
+                    use $krate::pkcs8::{EncodePublicKey, LineEnding};
+                    EncodePublicKey::to_public_key_pem(self, LineEnding::LF).ok()
+                }
+
+                #[cfg(feature = "pem")]
+                fn from_public_key_der(der: &[u8]) -> Option<Self> {
+                    use $krate::pkcs8::DecodePublicKey;
+                    DecodePublicKey::from_public_key_der(der).ok()
+                }
+
+                #[cfg(feature = "pem")]
+                fn from_public_key_pem(pem: &str) -> Option<Self> {
+                    use $krate::pkcs8::DecodePublicKey;
+                    DecodePublicKey::from_public_key_pem(pem).ok()
+                }
+
+                /// Follows the same steps as ECDSA verification, but multiplies by the
+                /// generator and this key with precomputed tables, and batches the inversions
+                /// of `s`. Accepts exactly the same
+                /// signatures as `verify`. This is variable-time, which is fine for public data.
+                fn batch_verify(
+                    &self,
+                    msgs_and_sigs: &[(&[u8], &Self::Signature)],
+                ) -> Result<(), usize> {
+                    // Building the tables costs about as much as verifying a few signatures.
+                    const MIN_BATCH: usize = 8;
+                    if msgs_and_sigs.len() < MIN_BATCH {
+                        return msgs_and_sigs
+                            .iter()
+                            .position(|(msg, signature)| {
+                                !DreipPublicKey::verify(self, msg, signature)
+                            })
+                            .map_or(Ok(()), Err);
+                    }
+
+                    let generator = FixedBaseTable::<$curve>::new(ProjectivePoint::GENERATOR);
+                    let key = FixedBaseTable::<$curve>::new(self.as_affine().into());
+                    let (rs, ss): (Vec<Scalar>, Vec<Scalar>) = msgs_and_sigs
+                        .iter()
+                        .map(|(_, signature)| {
+                            let (r, s) = signature.split_scalars();
+                            (*r, *s)
+                        })
+                        .unzip();
+                    let s_invs = Option::<Vec<Scalar>>::from(
+                        <Scalar as BatchInvert<[Scalar]>>::batch_invert(&ss[..]),
+                    )
+                    .expect("Signature scalars are non-zero");
+
+                    msgs_and_sigs
+                        .iter()
+                        .zip(rs.iter().zip(s_invs))
+                        .position(|((msg, _), (r, s_inv))| {
+                            // The curve's digest is exactly the field size, so needs no truncation.
+                            let z = <Scalar as Reduce<<$curve as Curve>::Uint>>::reduce_bytes(
+                                &<Signature as PrehashSignature>::Digest::digest(msg),
+                            );
+                            let point = generator.mul(&(z * s_inv)) + key.mul(&(*r * s_inv));
+                            <Scalar as Reduce<<$curve as Curve>::Uint>>::reduce_bytes(
+                                &point.to_affine().x(),
+                            ) != *r
+                        })
+                        .map_or(Ok(()), Err)
+                }
+            }
+
+            impl DreipGroup for $curve {
+                const NAME: &'static str = $name;
+                type Signature = Signature;
+                type Point = ProjectivePoint;
+                type Scalar = Scalar;
+                type PrivateKey = SigningKey;
+                type PublicKey = VerifyingKey;
+
+                fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+                    (
+                        ProjectivePoint::GENERATOR,
+                        ProjectivePoint::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+                    )
+                }
+
+                fn new_keys(
+                    mut rng: impl RngCore + CryptoRng,
+                ) -> (Self::PrivateKey, Self::PublicKey) {
+                    let private_key = SigningKey::random(&mut rng);
+                    let public_key = VerifyingKey::from(&private_key);
+                    (private_key, public_key)
+                }
+
+                /// Straus' generalisation of Shamir's trick: all terms share one chain of
+                /// doublings, adding in a 4-bit window of each scalar at a time.
+                /// This is variable-time.
+                fn msm(points: &[Self::Point], scalars: &[Self::Scalar]) -> Self::Point {
+                    assert_eq!(points.len(), scalars.len(), "Mismatched MSM lengths");
+                    let tables = points
+                        .iter()
+                        .map(|point| {
+                            let mut table = [ProjectivePoint::IDENTITY; 16];
+                            for i in 1..16 {
+                                table[i] = table[i - 1] + point;
+                            }
+                            table
+                        })
+                        .collect::<Vec<_>>();
+                    let reprs = scalars.iter().map(|s| s.to_repr()).collect::<Vec<_>>();
+
+                    // Scalars are big-endian, so start from the most significant window.
+                    let mut acc = ProjectivePoint::IDENTITY;
+                    for byte in 0..FieldBytes::default().len() {
+                        for shift in [4, 0] {
+                            for _ in 0..4 {
+                                acc = acc.double();
+                            }
+                            for (table, repr) in tables.iter().zip(&reprs) {
+                                let digit = (repr[byte] >> shift) & 0x0f;
+                                if digit != 0 {
+                                    acc += table[digit as usize];
+                                }
+                            }
+                        }
+                    }
+                    acc
+                }
+            }
+        };
+    };
+}
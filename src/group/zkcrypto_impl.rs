@@ -0,0 +1,327 @@
+use super::*;
+
+use ff::PrimeField;
+use group::{Group, GroupEncoding};
+use sha2::{Digest, Sha512};
+
+/// A tag to ensure random oracle uniqueness when hashing to points.
+const POINT_DOMAIN_SEPARATION_TAG: &[u8] = b"ZKCRYPTO_XMD:SHA-512_TAI:DREIP";
+
+/// A tag to ensure random oracle uniqueness when hashing to scalars.
+const SCALAR_DOMAIN_SEPARATION_TAG: &[u8] = b"ZKCRYPTO_XMD:SHA-512:DREIP";
+
+/// A point in any prime-order group implementing the zkcrypto `group` traits.
+///
+/// Together with [`ZkScalar`], this provides `DreipPoint`, `DreipScalar`, and
+/// `Serializable` for such groups, so a backend only needs to supply its keys,
+/// signatures, and the `DreipGroup` implementation itself.
+///
+/// `from_hash` uses try-and-increment: SHA512 output is decoded with `GroupEncoding`
+/// until it gives a valid non-identity point. This needs a reasonable fraction of
+/// encodings to be valid points in the group, as for Jubjub's `SubgroupPoint`. It does
+/// not terminate in practice for groups whose encodings are rarely valid, such as
+/// BLS12-381 G1 with its large cofactor; those should use their own hash_to_curve.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ZkPoint<G: Group>(pub G);
+
+/// A scalar in any prime field implementing the zkcrypto `ff` traits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ZkScalar<F: PrimeField>(pub F);
+
+impl<G: Group> ZkPoint<G> {
+    /// The fixed generator of the group.
+    pub fn generator() -> Self {
+        Self(G::generator())
+    }
+}
+
+impl<G: Group> Add for ZkPoint<G> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<G: Group> Sub for ZkPoint<G> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl<G: Group> Neg for ZkPoint<G> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl<G: Group> Sum for ZkPoint<G> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|p| p.0).sum())
+    }
+}
+
+impl<G: Group> Mul<ZkScalar<G::Scalar>> for ZkPoint<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: ZkScalar<G::Scalar>) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl<F: PrimeField> Add for ZkScalar<F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<F: PrimeField> Sub for ZkScalar<F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl<F: PrimeField> Neg for ZkScalar<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0)
+    }
+}
+
+impl<F: PrimeField> Sum for ZkScalar<F> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        Self(iter.map(|s| s.0).sum())
+    }
+}
+
+impl<F: PrimeField> Mul for ZkScalar<F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0 * rhs.0)
+    }
+}
+
+/// Fill `out` with SHA512 output over the tag, a big-endian counter, and the data,
+/// hashing once per 64-byte block.
+fn expand_hash(out: &mut [u8], tag: &[u8], counter: u32, data: &[&[u8]]) {
+    for (block, chunk) in out.chunks_mut(64).enumerate() {
+        let mut hasher = Sha512::new();
+        hasher.update(tag);
+        hasher.update(counter.to_be_bytes());
+        hasher.update((block as u32).to_be_bytes());
+        for item in data {
+            hasher.update(item);
+        }
+        chunk.copy_from_slice(&hasher.finalize()[..chunk.len()]);
+    }
+}
+
+/// Whether the field's `Repr` is little-endian, which `ff` leaves up to the field.
+#[cfg(feature = "bigint")]
+fn repr_is_le<F: PrimeField>() -> bool {
+    F::ONE.to_repr().as_ref()[0] == 1
+}
+
+impl<G: Group + GroupEncoding> Serializable for ZkPoint<G> {
+    /// Encode with the group's `GroupEncoding`.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().as_ref().to_vec()
+    }
+
+    /// Decode with the group's `GroupEncoding`, which rejects invalid points.
+    /// Encodings that do not re-encode to the same bytes are rejected as non-canonical,
+    /// since `GroupEncoding` does not require implementations to check this.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let mut repr = G::Repr::default();
+        if repr.as_ref().len() != bytes.len() {
+            return None;
+        }
+        repr.as_mut().copy_from_slice(bytes);
+        Option::<G>::from(G::from_bytes(&repr))
+            .filter(|p| p.to_bytes().as_ref() == bytes)
+            .map(Self)
+    }
+}
+
+impl<G: Group + GroupEncoding> DreipPoint for ZkPoint<G> {
+    fn identity() -> Self {
+        Self(G::identity())
+    }
+
+    fn generator() -> Self {
+        Self(G::generator())
+    }
+
+    /// Create a point by try-and-increment over SHA512.
+    /// This is not constant-time, which is fine as generators are derived from public data.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        (0..=u32::MAX)
+            .find_map(|counter| {
+                let mut repr = G::Repr::default();
+                expand_hash(repr.as_mut(), POINT_DOMAIN_SEPARATION_TAG, counter, data);
+                Option::<G>::from(G::from_bytes(&repr)).filter(|p| !bool::from(p.is_identity()))
+            })
+            .map(Self)
+            .expect("A fraction of all encodings are valid points")
+    }
+}
+
+impl<F: PrimeField> Serializable for ZkScalar<F> {
+    /// Encode with the field's canonical representation.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_repr().as_ref().to_vec()
+    }
+
+    /// Decode from the field's canonical representation.
+    /// Values that are not fully reduced are rejected.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let mut repr = F::Repr::default();
+        if repr.as_ref().len() != bytes.len() {
+            return None;
+        }
+        repr.as_mut().copy_from_slice(bytes);
+        Option::from(F::from_repr(repr)).map(Self)
+    }
+}
+
+impl<F: PrimeField> DreipScalar for ZkScalar<F> {
+    fn zero() -> Self {
+        Self(F::ZERO)
+    }
+
+    fn one() -> Self {
+        Self(F::ONE)
+    }
+
+    fn random(rng: impl RngCore + CryptoRng) -> Self {
+        Self(F::random(rng))
+    }
+
+    /// Create a scalar by hashing with SHA512 and reducing the 64-byte output
+    /// modulo the field order. The bias is negligible for fields of up to 384 bits.
+    fn from_hash(data: &[&[u8]]) -> Self {
+        let mut bytes = [0; 64];
+        expand_hash(&mut bytes, SCALAR_DOMAIN_SEPARATION_TAG, 0, data);
+        // `ff` has no generic wide reduction, so accumulate the bytes big-endian.
+        let base = F::from(256);
+        Self(
+            bytes
+                .iter()
+                .fold(F::ZERO, |acc, &byte| acc * base + F::from(byte as u64)),
+        )
+    }
+
+    fn invert(&self) -> Option<Self> {
+        Option::from(self.0.invert()).map(Self)
+    }
+
+    /// `ff` leaves the byte order of `Repr` up to the field, so try both
+    /// and keep whichever converts back to this scalar.
+    fn to_u64(&self) -> Option<u64> {
+        let repr = self.0.to_repr();
+        [
+            u64_from_le_bytes(repr.as_ref()),
+            u64_from_be_bytes(repr.as_ref()),
+        ]
+        .into_iter()
+        .flatten()
+        .find(|n| F::from(*n) == self.0)
+    }
+
+    #[cfg(feature = "bigint")]
+    fn to_bigint(&self) -> BigUint {
+        let repr = self.0.to_repr();
+        if repr_is_le::<F>() {
+            BigUint::from_bytes_le(repr.as_ref())
+        } else {
+            BigUint::from_bytes_be(repr.as_ref())
+        }
+    }
+
+    #[cfg(feature = "bigint")]
+    fn from_bigint(value: &BigUint) -> Option<Self> {
+        let len = F::Repr::default().as_ref().len();
+        let bytes = if repr_is_le::<F>() {
+            bigint_to_le_bytes(value, len)?
+        } else {
+            bigint_to_be_bytes(value, len)?
+        };
+        Serializable::from_bytes(&bytes)
+    }
+
+    fn ct_eq(&self, other: &Self) -> bool {
+        self.0.ct_eq(&other.0).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use jubjub::{Fr, SubgroupPoint};
+
+    #[test]
+    fn test_point_serialization() {
+        let x = ZkPoint(SubgroupPoint::random(rand::thread_rng()));
+        let serialized = Serializable::to_bytes(&x);
+        assert_eq!(serialized.len(), 32);
+        let y = Serializable::from_bytes(&serialized).unwrap();
+        assert_eq!(x, y);
+        assert!(<ZkPoint<SubgroupPoint> as Serializable>::from_bytes(&serialized[1..]).is_none());
+    }
+
+    #[test]
+    fn test_scalar_serialization() {
+        let x = <ZkScalar<Fr> as DreipScalar>::random(rand::thread_rng());
+        let y = Serializable::to_bytes(&x);
+        assert_eq!(y.len(), 32);
+        let z = Serializable::from_bytes(&y).unwrap();
+        assert_eq!(x, z);
+
+        // The field order minus one, plus one, is not a canonical encoding.
+        let mut order = (Fr::zero() - Fr::one()).to_bytes();
+        order[0] += 1;
+        assert!(<ZkScalar<Fr> as Serializable>::from_bytes(&order).is_none());
+    }
+
+    #[test]
+    fn test_scalar_hashing() {
+        let x = <ZkScalar<Fr> as DreipScalar>::from_hash(&[b"Hello, World!"]);
+        let y = <ZkScalar<Fr> as DreipScalar>::from_hash(&[b"Hello, World!"]);
+        let z = <ZkScalar<Fr> as DreipScalar>::from_hash(&[b"Hello, World?"]);
+        assert_eq!(x, y);
+        assert_ne!(x, z);
+    }
+
+    #[test]
+    fn test_generators() {
+        let unique_strings = vec![
+            "Hello, World!",
+            "This is a string.",
+            "According to all known laws of aviation, \
+            there is no way that a bee should be able to fly.",
+        ];
+        for unique_str in unique_strings {
+            let g1 = ZkPoint::<SubgroupPoint>::generator();
+            let g2 = <ZkPoint<SubgroupPoint> as DreipPoint>::from_hash(&[unique_str.as_bytes()]);
+            assert_ne!(g1, g2);
+            assert!(!bool::from(g2.0.is_identity()));
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The bytes of a ballot, candidate, or question id, as written into proof challenges,
+/// signed encodings, and derived ids.
+///
+/// The bytes of each implementation are:
+/// - strings (`str`, `String`, and their boxed, shared, and borrowed forms): their UTF-8 bytes;
+/// - byte strings (`[u8]`, `[u8; N]`, `Vec<u8>`, and their boxed and borrowed forms): the
+///   bytes themselves;
+/// - unsigned integers: their big-endian bytes at the type's full width, so `7u32` is
+///   `[0, 0, 0, 7]`, and `usize` is always encoded as a `u64`;
+/// - references: the bytes of the value referred to.
+///
+/// Ids of different types can have the same bytes, such as the string `"\0\0\0\x07"` and
+/// `7u32`, so an election should use one type for each kind of id.
+pub trait IdBytes {
+    /// The bytes of this id.
+    fn id_bytes(&self) -> Cow<'_, [u8]>;
+}
+
+impl<T: IdBytes + ?Sized> IdBytes for &T {
+    fn id_bytes(&self) -> Cow<'_, [u8]> {
+        (**self).id_bytes()
+    }
+}
+
+macro_rules! impl_id_bytes_str {
+    ($($ty:ty),*) => {
+        $(
+            impl IdBytes for $ty {
+                fn id_bytes(&self) -> Cow<'_, [u8]> {
+                    let id: &str = self;
+                    Cow::Borrowed(id.as_bytes())
+                }
+            }
+        )*
+    };
+}
+
+impl_id_bytes_str!(str, String, Box<str>, Rc<str>, Arc<str>, Cow<'_, str>);
+
+macro_rules! impl_id_bytes_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl IdBytes for $ty {
+                fn id_bytes(&self) -> Cow<'_, [u8]> {
+                    let id: &[u8] = self;
+                    Cow::Borrowed(id)
+                }
+            }
+        )*
+    };
+}
+
+impl_id_bytes_bytes!([u8], Vec<u8>, Box<[u8]>, Rc<[u8]>, Arc<[u8]>, Cow<'_, [u8]>);
+
+impl<const N: usize> IdBytes for [u8; N] {
+    fn id_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+macro_rules! impl_id_bytes_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl IdBytes for $ty {
+                fn id_bytes(&self) -> Cow<'_, [u8]> {
+                    Cow::Owned(self.to_be_bytes().to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_id_bytes_unsigned!(u8, u16, u32, u64, u128);
+
+impl IdBytes for usize {
+    fn id_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned((*self as u64).to_be_bytes().to_vec())
+    }
+}
+
+/// An id which can be recovered from its `IdBytes`, as when decoding a ballot.
+///
+/// Decoding must be strict: it gives `None` for any bytes that `id_bytes` does not produce
+/// for some id, such as integers of the wrong width or strings that are not UTF-8.
+pub trait FromIdBytes: IdBytes + Sized {
+    /// The id with the given bytes, if any.
+    fn from_id_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_from_id_bytes_str {
+    ($($ty:ty),*) => {
+        $(
+            impl FromIdBytes for $ty {
+                fn from_id_bytes(bytes: &[u8]) -> Option<Self> {
+                    std::str::from_utf8(bytes).ok().map(Into::into)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_id_bytes_str!(String, Box<str>, Rc<str>, Arc<str>);
+
+macro_rules! impl_from_id_bytes_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl FromIdBytes for $ty {
+                fn from_id_bytes(bytes: &[u8]) -> Option<Self> {
+                    Some(bytes.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_id_bytes_bytes!(Vec<u8>, Box<[u8]>, Rc<[u8]>, Arc<[u8]>);
+
+impl<const N: usize> FromIdBytes for [u8; N] {
+    fn from_id_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok()
+    }
+}
+
+macro_rules! impl_from_id_bytes_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl FromIdBytes for $ty {
+                fn from_id_bytes(bytes: &[u8]) -> Option<Self> {
+                    bytes.try_into().ok().map(<$ty>::from_be_bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_id_bytes_unsigned!(u8, u16, u32, u64, u128);
+
+impl FromIdBytes for usize {
+    fn from_id_bytes(bytes: &[u8]) -> Option<Self> {
+        u64::from_id_bytes(bytes).and_then(|id| id.try_into().ok())
+    }
+}
@@ -0,0 +1,193 @@
+//! Verifying an election one ballot at a time, as ballots arrive.
+//!
+//! `verify_election` needs every ballot up front, and `verify_election_iter` needs them all
+//! from one iterator. [`IncrementalVerifier`] instead takes ballots as they are fed to it,
+//! from a file, the network, or several producers in turn, keeping only running sums of
+//! each candidate's votes, and checks the totals once the last ballot has been fed.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+use crate::ballots::{
+    Ballot, BallotError, BallotLike, SecretsPresent, VerificationError, VoteSecrets,
+};
+use crate::election::{validate_generators, CandidateTotals, ParameterError, VoteSums};
+use crate::group::DreipGroup;
+use crate::ids::IdBytes;
+use crate::pwf::TranscriptVersion;
+
+/// Verifies an election's ballots as they are fed to it, and then its totals.
+///
+/// Confirmed ballots are fed with `feed_ballot`, and audited ballots with
+/// `feed_audited_ballot`; each is verified at once and can be dropped afterwards. Once all
+/// have been fed, `finalize` checks the totals with the same candidate and tally checks as
+/// `verify_election`, and fails if any ballot fed failed. Memory does not grow with the
+/// number of ballots.
+///
+/// Ballot ids are not kept, so a ballot id fed twice is not detected: the source must ensure
+/// ids are distinct, as for `verify_election_iter`.
+///
+/// The verifier is `Send` if the candidate ids and points are, so producers on several
+/// threads can share it behind a `Mutex`:
+///
+/// ```
+/// # #[cfg(feature = "p256_impl")] {
+/// use std::sync::Mutex;
+///
+/// use dre_ip::group::p256::NistP256;
+/// use dre_ip::{Election, ElectionSession, IncrementalVerifier};
+///
+/// let mut rng = rand::thread_rng();
+/// let election = Election::<NistP256>::new(&[b"Incremental"], &mut rng);
+/// let mut session = ElectionSession::new(election, ["Alice", "Bob"]);
+/// for (ballot_id, candidate) in [("1", "Alice"), ("2", "Bob"), ("3", "Alice")] {
+///     let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+///     session.confirm(handle).unwrap();
+/// }
+/// let results = session.into_results();
+///
+/// let verifier = IncrementalVerifier::new(results.g1, results.g2, results.transcript).unwrap();
+/// let verifier = Mutex::new(verifier);
+/// std::thread::scope(|scope| {
+///     for (ballot_id, ballot) in &results.confirmed {
+///         let verifier = &verifier;
+///         scope.spawn(move || verifier.lock().unwrap().feed_ballot(ballot_id, ballot).unwrap());
+///     }
+/// });
+/// let verifier = verifier.into_inner().unwrap();
+/// let report = verifier.finalize::<&str, _>(&results.totals).unwrap();
+/// assert_eq!((report.confirmed, report.candidates), (3, 2));
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct IncrementalVerifier<C, G: DreipGroup> {
+    g1: G::Point,
+    g2: G::Point,
+    transcript: TranscriptVersion,
+    sums: VoteSums<C, G>,
+    confirmed: usize,
+    audited: usize,
+    failed: usize,
+}
+
+/// What an `IncrementalVerifier` verified, once the totals have been checked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct VerificationReport {
+    /// The number of confirmed ballots verified and counted in the totals.
+    pub confirmed: usize,
+    /// The number of audited ballots verified.
+    pub audited: usize,
+    /// The number of candidates whose totals were verified.
+    pub candidates: usize,
+}
+
+impl<C, G> IncrementalVerifier<C, G>
+where
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    G: DreipGroup,
+{
+    /// Start verifying an election with the given generators, which are checked as
+    /// `verify_election` checks them, and transcript version.
+    pub fn new(
+        g1: G::Point,
+        g2: G::Point,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, ParameterError> {
+        validate_generators::<G>(&g1, &g2)?;
+        Ok(Self {
+            g1,
+            g2,
+            transcript,
+            sums: VoteSums::new(),
+            confirmed: 0,
+            audited: 0,
+            failed: 0,
+        })
+    }
+
+    /// Verify a confirmed ballot, as `verify_election` does, and add its votes to the sums
+    /// that the totals are checked against.
+    ///
+    /// A ballot that fails is not added, so the verifier can be fed further ballots to find
+    /// any other failures, but it is recorded, and `finalize` then fails.
+    pub fn feed_ballot<B, T>(&mut self, ballot_id: B, ballot: &T) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+        T: BallotLike<Candidate = C, Group = G>,
+        T::Secrets: VoteSecrets<G>,
+    {
+        let result = ballot.verify_ballot(
+            self.g1.clone(),
+            self.g2.clone(),
+            ballot_id,
+            1,
+            self.transcript,
+        );
+        if let Err(e) = result {
+            self.failed += 1;
+            return Err(e);
+        }
+        self.sums.add(ballot);
+        self.confirmed += 1;
+        Ok(())
+    }
+
+    /// Verify an audited ballot, including that its secrets match its votes, as
+    /// `verify_full_election` does. Audited ballots are not counted in the totals, but one
+    /// that fails is recorded as for `feed_ballot`.
+    pub fn feed_audited_ballot<B>(
+        &mut self,
+        ballot_id: B,
+        ballot: &Ballot<C, G, SecretsPresent<G>>,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        let result = ballot.verify(self.g1.clone(), self.g2.clone(), ballot_id, self.transcript);
+        if let Err(e) = result {
+            self.failed += 1;
+            return Err(e);
+        }
+        self.audited += 1;
+        Ok(())
+    }
+
+    /// The number of confirmed ballots verified so far.
+    pub fn confirmed(&self) -> usize {
+        self.confirmed
+    }
+
+    /// The number of audited ballots verified so far.
+    pub fn audited(&self) -> usize {
+        self.audited
+    }
+
+    /// The number of confirmed and audited ballots that have failed so far.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// Verify the totals against the confirmed ballots fed so far, as `verify_election`
+    /// does: the candidates must be exactly those of the ballots, and each candidate's
+    /// tally and random sum must match their votes.
+    ///
+    /// This fails with `VerificationError::FailedBallots` if any ballot fed failed, since
+    /// the election has then failed whatever the totals.
+    pub fn finalize<B, HC>(
+        self,
+        totals: &HashMap<C, CandidateTotals<G>, HC>,
+    ) -> Result<VerificationReport, VerificationError<B, C>>
+    where
+        HC: BuildHasher,
+    {
+        if self.failed > 0 {
+            return Err(VerificationError::FailedBallots { count: self.failed });
+        }
+        self.sums.check(self.g1, self.g2, totals)?;
+        Ok(VerificationReport {
+            confirmed: self.confirmed,
+            audited: self.audited,
+            candidates: totals.len(),
+        })
+    }
+}
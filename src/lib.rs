@@ -1,186 +1,1558 @@
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
 pub mod ballots;
+#[cfg(any(
+    feature = "p256_impl",
+    feature = "p384_impl",
+    feature = "p521_impl",
+    feature = "ristretto255",
+    feature = "bls12_381",
+    feature = "modp_impl",
+    feature = "pasta_impl",
+    feature = "brainpool_impl"
+))]
+pub mod dynamic;
 pub mod election;
 pub mod group;
+pub mod ids;
+pub mod incremental;
+pub mod precompute;
 pub mod pwf;
+pub mod questions;
+pub mod receipt;
+pub mod session;
+pub mod small;
+#[cfg(any(test, feature = "serde_json"))]
+pub mod strict;
+#[cfg(feature = "test_vectors")]
+pub mod test_vectors;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+#[cfg(feature = "threshold")]
+pub mod threshold;
 
 pub use crate::ballots::{
-    Ballot, BallotError, NoSecrets, SecretsPresent, VerificationError, Vote, VoteError, VoteSecrets,
+    AuditError, AuditedBallot, Ballot, BallotAssemblyError, BallotCreationError, BallotError,
+    BallotLike, CancelledBallot, ConfirmError, ConfirmedBallot, DecodeError, NoSecrets,
+    PendingBallot, PolicyViolation, ReplacedBallot, Secrets, SecretsPresent, VerificationError,
+    Vote, VoteError, VoteFailure, VoteSecrets, CANONICAL_MAGIC, CANONICAL_VERSION,
 };
-pub use crate::election::{verify_election, CandidateTotals, Election};
+pub use crate::election::{
+    compute_totals, compute_totals_checked, merge_totals, replace_ballot, results, verify_election,
+    verify_election_batched, verify_election_batched_multi, verify_election_iter,
+    verify_election_multi, verify_election_referendum, verify_election_sharded,
+    verify_election_weighted, verify_election_with_abstentions, verify_election_with_policy,
+    verify_full_election, verify_subset, verify_totals_decomposition, CandidateMismatch,
+    CandidateTotals, DecompositionError, Election, ElectionParams, ElectionResults, KeyMismatch,
+    Limits, LimitsExceeded, MergeError, Outcome, ParameterError, Replacement, TallyOverflow,
+    VerificationPolicy, VerifiedResults,
+};
+#[cfg(feature = "parallel")]
+pub use crate::election::{confirm_all_par, verify_election_par};
 pub use crate::group::{
-    DreipGroup, DreipPoint, DreipPrivateKey, DreipPublicKey, DreipScalar, Serializable,
+    DreipGroup, DreipPoint, DreipPrivateKey, DreipPublicKey, DreipScalar, GroupName, ReceiptSigner,
+    Serializable, SignError,
+};
+pub use crate::incremental::{IncrementalVerifier, VerificationReport};
+pub use crate::precompute::{ElectionPrecomputed, FixedBaseTable};
+pub use crate::pwf::{
+    BallotProof, BallotProofFailure, BallotProofInstance, ProofError, ToBytes, TranscriptVersion,
+    VoteProof, VoteStatement,
+};
+pub use crate::questions::{
+    question_ballot_id, verify_election_questions, MultiBallot, QuestionVerificationError,
 };
-pub use crate::pwf::{BallotProof, VoteProof};
+pub use crate::receipt::{Receipt, ReceiptError, SigningContext};
+pub use crate::session::{ElectionSession, PendingHandle};
+pub use crate::small::SmallBallot;
+#[cfg(feature = "serde_json")]
+pub use crate::strict::{from_json_strict, from_json_strict_bounded, read_bounded, StrictError};
 
-#[cfg(all(test, feature = "p256_impl"))]
+#[cfg(test)]
 mod tests {
     use super::*;
 
-    use p256::{NistP256, Scalar};
-    use std::collections::HashMap;
+    use std::fmt::Debug;
+
+    use crate::testing::{ballot_proofs, check_group, run_check, Check, ProofValues};
+
+    /// Instantiate the conformance checks in `testing` for a concrete group.
+    macro_rules! group_tests {
+        ($name:ident, $group:ty, $feature:literal) => {
+            #[cfg(feature = $feature)]
+            mod $name {
+                use crate::testing::Check;
+
+                #[test]
+                fn test_vote() {
+                    super::check::<$group>(Check::Vote);
+                }
+
+                #[test]
+                fn test_ballot() {
+                    super::check::<$group>(Check::Ballot);
+                }
+
+                #[test]
+                fn test_inspection() {
+                    super::check::<$group>(Check::Inspection);
+                }
+
+                #[test]
+                fn test_assembly() {
+                    super::check::<$group>(Check::Assembly);
+                }
+
+                #[test]
+                fn test_confirm_delta() {
+                    super::check::<$group>(Check::ConfirmDelta);
+                }
+
+                #[test]
+                fn test_small_ballot() {
+                    super::check::<$group>(Check::SmallBallot);
+                }
+
+                #[test]
+                fn test_compute_totals() {
+                    super::check::<$group>(Check::ComputeTotals);
+                }
+
+                #[test]
+                fn test_sharding() {
+                    super::check::<$group>(Check::Sharding);
+                }
+
+                #[test]
+                fn test_subset() {
+                    super::check::<$group>(Check::Subset);
+                }
+
+                #[test]
+                fn test_totals_arithmetic() {
+                    super::check::<$group>(Check::TotalsArithmetic);
+                }
+
+                #[test]
+                fn test_tally_counts() {
+                    super::check::<$group>(Check::TallyCounts);
+                }
+
+                #[test]
+                fn test_outcome() {
+                    super::check::<$group>(Check::Outcome);
+                }
+
+                #[test]
+                fn test_election() {
+                    super::check::<$group>(Check::Election);
+                }
+
+                #[test]
+                fn test_incremental() {
+                    super::check::<$group>(Check::Incremental);
+                }
+
+                #[test]
+                fn test_multi_select() {
+                    super::check::<$group>(Check::MultiSelect);
+                }
+
+                #[test]
+                fn test_weighted() {
+                    super::check::<$group>(Check::Weighted);
+                }
+
+                #[test]
+                fn test_abstention() {
+                    super::check::<$group>(Check::Abstention);
+                }
+
+                #[test]
+                fn test_referendum() {
+                    super::check::<$group>(Check::Referendum);
+                }
+
+                #[test]
+                fn test_questions() {
+                    super::check::<$group>(Check::Questions);
+                }
+
+                #[test]
+                fn test_cancellation() {
+                    super::check::<$group>(Check::Cancellation);
+                }
+
+                #[test]
+                fn test_revote() {
+                    super::check::<$group>(Check::Revote);
+                }
+
+                #[test]
+                fn test_audit() {
+                    super::check::<$group>(Check::Audit);
+                }
+
+                #[test]
+                fn test_lifecycle() {
+                    super::check::<$group>(Check::Lifecycle);
+                }
+
+                #[test]
+                fn test_create_ballots() {
+                    super::check::<$group>(Check::CreateBallots);
+                }
+
+                #[test]
+                fn test_election_roster() {
+                    super::check::<$group>(Check::ElectionRoster);
+                }
+
+                #[test]
+                fn test_session() {
+                    super::check::<$group>(Check::Session);
+                }
+
+                #[test]
+                fn test_policy() {
+                    super::check::<$group>(Check::Policy);
+                }
+
+                #[test]
+                fn test_roster() {
+                    super::check::<$group>(Check::Roster);
+                }
+
+                #[test]
+                fn test_receipt() {
+                    super::check::<$group>(Check::Receipt);
+                }
+
+                #[test]
+                fn test_round_trip() {
+                    super::check::<$group>(Check::RoundTrip);
+                }
+
+                #[test]
+                fn test_mixed_secrets() {
+                    super::check::<$group>(Check::MixedSecrets);
+                }
+
+                #[test]
+                fn test_hashers() {
+                    super::check::<$group>(Check::Hashers);
+                }
+
+                #[test]
+                fn test_integer_ids() {
+                    super::check::<$group>(Check::IntegerIds);
+                }
+
+                #[test]
+                fn test_params_encoding() {
+                    super::check::<$group>(Check::ParamsEncoding);
+                }
+
+                #[test]
+                fn test_with_keys() {
+                    super::check::<$group>(Check::WithKeys);
+                }
+
+                #[test]
+                fn test_from_seed() {
+                    super::check::<$group>(Check::FromSeed);
+                }
+
+                #[test]
+                fn test_fingerprint() {
+                    super::check::<$group>(Check::Fingerprint);
+                }
+
+                #[test]
+                fn test_parameters() {
+                    super::check::<$group>(Check::Parameters);
+                }
+
+                #[test]
+                fn test_group_name() {
+                    super::check::<$group>(Check::GroupName);
+                }
+
+                #[test]
+                fn test_transcript() {
+                    super::check::<$group>(Check::Transcript);
+                }
+
+                #[test]
+                fn test_election_binding() {
+                    super::check::<$group>(Check::ElectionBinding);
+                }
+
+                #[test]
+                fn test_bound_ballots() {
+                    super::check::<$group>(Check::BoundBallots);
+                }
+
+                #[test]
+                fn test_proof_api() {
+                    super::check::<$group>(Check::ProofApi);
+                }
+
+                #[test]
+                fn test_strict_decoding() {
+                    super::check::<$group>(Check::StrictDecoding);
+                }
+
+                #[test]
+                fn test_identity_points() {
+                    super::check::<$group>(Check::IdentityPoints);
+                }
+
+                #[test]
+                fn test_byte_size() {
+                    super::check::<$group>(Check::ByteSize);
+                }
+
+                #[test]
+                fn test_write_bytes() {
+                    super::check::<$group>(Check::WriteBytes);
+                }
+
+                #[test]
+                fn test_proof_bytes() {
+                    super::check::<$group>(Check::ProofBytes);
+                }
+
+                #[test]
+                fn test_canonical() {
+                    super::check::<$group>(Check::Canonical);
+                }
+
+                #[test]
+                fn test_codec() {
+                    super::check::<$group>(Check::Codec);
+                }
+
+                #[test]
+                fn test_to_u64() {
+                    super::check::<$group>(Check::ToU64);
+                }
+
+                #[test]
+                fn test_invert() {
+                    super::check::<$group>(Check::Invert);
+                }
+
+                #[test]
+                fn test_precomputed() {
+                    super::check::<$group>(Check::Precomputed);
+                }
+
+                #[test]
+                fn test_msm() {
+                    super::check::<$group>(Check::Msm);
+                }
+
+                #[test]
+                fn test_ct_eq() {
+                    super::check::<$group>(Check::CtEq);
+                }
+
+                #[test]
+                fn test_batch_verify() {
+                    super::check::<$group>(Check::BatchVerify);
+                }
+
+                #[test]
+                fn test_signature_batch() {
+                    super::check::<$group>(Check::SignatureBatch);
+                }
+
+                #[cfg(feature = "bigint")]
+                #[test]
+                fn test_bigint() {
+                    super::check::<$group>(Check::Bigint);
+                }
+            }
+        };
+    }
+
+    group_tests!(p256, p256::NistP256, "p256_impl");
+    group_tests!(p384, p384::NistP384, "p384_impl");
+    group_tests!(p521, p521::NistP521, "p521_impl");
+    group_tests!(ristretto255, crate::group::Ristretto255, "ristretto255");
+    group_tests!(bls12_381, crate::group::Bls12381, "bls12_381");
+    group_tests!(modp, crate::group::ModPGroup, "modp_impl");
+    group_tests!(pallas, crate::group::Pallas, "pasta_impl");
+    group_tests!(brainpool, bp256::BrainpoolP256r1, "brainpool_impl");
+    group_tests!(p256_non_copy, super::non_copy::NonCopyP256, "p256_impl");
+    group_tests!(jubjub, super::zkcrypto_jubjub::Jubjub, "zkcrypto_impl");
+    group_tests!(ark_p256, crate::group::ArkP256, "ark_impl");
+
+    /// A group whose points and scalars are deliberately not `Copy`, to check
+    /// that the protocol code does not rely on it.
+    #[cfg(feature = "p256_impl")]
+    mod non_copy {
+        use std::iter::Sum;
+        use std::ops::{Add, Mul, Neg, Sub};
+
+        use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+        use p256::{NistP256, ProjectivePoint, Scalar};
+        use rand::{CryptoRng, RngCore};
+
+        use crate::group::{DreipGroup, DreipPoint, DreipScalar, Serializable};
+
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        pub struct NonCopyP256;
+
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        pub struct Point(Box<ProjectivePoint>);
+
+        #[derive(Debug, Clone, Eq, PartialEq)]
+        pub struct Num(Box<Scalar>);
+
+        impl Add for Point {
+            type Output = Point;
+
+            fn add(self, rhs: Point) -> Point {
+                Point(Box::new(*self.0 + *rhs.0))
+            }
+        }
+
+        impl Sub for Point {
+            type Output = Point;
+
+            fn sub(self, rhs: Point) -> Point {
+                Point(Box::new(*self.0 - *rhs.0))
+            }
+        }
+
+        impl Neg for Point {
+            type Output = Point;
+
+            fn neg(self) -> Point {
+                Point(Box::new(-*self.0))
+            }
+        }
+
+        impl Sum for Point {
+            fn sum<I: Iterator<Item = Point>>(iter: I) -> Point {
+                Point(Box::new(iter.map(|p| *p.0).sum()))
+            }
+        }
+
+        impl Mul<Num> for Point {
+            type Output = Point;
+
+            fn mul(self, rhs: Num) -> Point {
+                Point(Box::new(*self.0 * *rhs.0))
+            }
+        }
+
+        impl Add for Num {
+            type Output = Num;
+
+            fn add(self, rhs: Num) -> Num {
+                Num(Box::new(*self.0 + *rhs.0))
+            }
+        }
+
+        impl Sub for Num {
+            type Output = Num;
+
+            fn sub(self, rhs: Num) -> Num {
+                Num(Box::new(*self.0 - *rhs.0))
+            }
+        }
+
+        impl Neg for Num {
+            type Output = Num;
+
+            fn neg(self) -> Num {
+                Num(Box::new(-*self.0))
+            }
+        }
+
+        impl Sum for Num {
+            fn sum<I: Iterator<Item = Num>>(iter: I) -> Num {
+                Num(Box::new(iter.map(|s| *s.0).sum()))
+            }
+        }
+
+        impl Mul for Num {
+            type Output = Num;
+
+            fn mul(self, rhs: Num) -> Num {
+                Num(Box::new(*self.0 * *rhs.0))
+            }
+        }
+
+        impl Serializable for Point {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.to_bytes()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                ProjectivePoint::from_bytes(bytes).map(|p| Point(Box::new(p)))
+            }
+        }
+
+        impl DreipPoint for Point {
+            fn identity() -> Self {
+                Point(Box::new(<ProjectivePoint as DreipPoint>::identity()))
+            }
+
+            fn generator() -> Self {
+                Point(Box::new(<ProjectivePoint as DreipPoint>::generator()))
+            }
+
+            fn from_hash(data: &[&[u8]]) -> Self {
+                Point(Box::new(ProjectivePoint::from_hash(data)))
+            }
+
+            fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+                Point(Box::new(ProjectivePoint::from_hash_with_tag(tag, data)))
+            }
+        }
+
+        impl Serializable for Num {
+            fn to_bytes(&self) -> Vec<u8> {
+                Serializable::to_bytes(&*self.0)
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                <Scalar as Serializable>::from_bytes(bytes).map(|s| Num(Box::new(s)))
+            }
+        }
+
+        impl DreipScalar for Num {
+            fn zero() -> Self {
+                Num(Box::new(<Scalar as DreipScalar>::zero()))
+            }
+
+            fn one() -> Self {
+                Num(Box::new(<Scalar as DreipScalar>::one()))
+            }
+
+            fn random(rng: impl RngCore + CryptoRng) -> Self {
+                Num(Box::new(<Scalar as DreipScalar>::random(rng)))
+            }
+
+            fn from_hash(data: &[&[u8]]) -> Self {
+                Num(Box::new(<Scalar as DreipScalar>::from_hash(data)))
+            }
+
+            fn from_hash_with_tag(tag: &[u8], data: &[&[u8]]) -> Self {
+                Num(Box::new(<Scalar as DreipScalar>::from_hash_with_tag(
+                    tag, data,
+                )))
+            }
+
+            fn invert(&self) -> Option<Self> {
+                <Scalar as DreipScalar>::invert(&self.0).map(|s| Num(Box::new(s)))
+            }
+
+            fn to_u64(&self) -> Option<u64> {
+                self.0.to_u64()
+            }
+
+            #[cfg(feature = "bigint")]
+            fn to_bigint(&self) -> num_bigint::BigUint {
+                self.0.to_bigint()
+            }
+
+            #[cfg(feature = "bigint")]
+            fn from_bigint(value: &num_bigint::BigUint) -> Option<Self> {
+                <Scalar as DreipScalar>::from_bigint(value).map(|s| Num(Box::new(s)))
+            }
+
+            /// Record the value, then wipe it.
+            #[cfg(feature = "zeroize")]
+            fn zeroize(&mut self) {
+                ZEROIZED.with(|zeroized| zeroized.borrow_mut().push(self.clone()));
+                zeroize::Zeroize::zeroize(&mut *self.0);
+            }
+        }
+
+        #[cfg(feature = "zeroize")]
+        thread_local! {
+            /// The values of all scalars zeroized on this thread, in order.
+            pub static ZEROIZED: std::cell::RefCell<Vec<Num>> = Default::default();
+        }
+
+        impl DreipGroup for NonCopyP256 {
+            const NAME: &'static str = "DREIP-P256-NONCOPY";
+            type Signature = Signature;
+            type Point = Point;
+            type Scalar = Num;
+            type PrivateKey = SigningKey;
+            type PublicKey = VerifyingKey;
+
+            fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+                let (g1, g2) = NistP256::new_generators(unique_bytes);
+                (Point(Box::new(g1)), Point(Box::new(g2)))
+            }
+
+            fn new_keys(rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+                NistP256::new_keys(rng)
+            }
+        }
+    }
+
+    /// `NistP256`, except that key generation ignores its random number generator,
+    /// so every election has the same keys.
+    #[cfg(feature = "p256_impl")]
+    mod fixed_keys {
+        use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+        use p256::{NistP256, ProjectivePoint, Scalar};
+        use rand::{CryptoRng, RngCore, SeedableRng};
+        use rand_chacha::ChaCha20Rng;
+
+        use crate::group::DreipGroup;
+
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        pub struct FixedKeysP256;
+
+        impl DreipGroup for FixedKeysP256 {
+            const NAME: &'static str = "DREIP-P256-FIXEDKEYS";
+            type Signature = Signature;
+            type Point = ProjectivePoint;
+            type Scalar = Scalar;
+            type PrivateKey = SigningKey;
+            type PublicKey = VerifyingKey;
+
+            fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+                NistP256::new_generators(unique_bytes)
+            }
+
+            fn new_keys(_rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+                NistP256::new_keys(ChaCha20Rng::from_seed([0; 32]))
+            }
+        }
+    }
+
+    /// A group built on the zkcrypto adapter as a downstream user would: Jubjub's
+    /// prime-order subgroup, with minimal Schnorr signatures.
+    #[cfg(feature = "zkcrypto_impl")]
+    mod zkcrypto_jubjub {
+        use jubjub::{Fr, SubgroupPoint};
+        use rand::{CryptoRng, RngCore};
+
+        use crate::group::{
+            DreipGroup, DreipPoint, DreipPrivateKey, DreipPublicKey, DreipScalar, Serializable,
+            ZkPoint, ZkScalar, GENERATOR_TAG,
+        };
+
+        type Point = ZkPoint<SubgroupPoint>;
+        type Scalar = ZkScalar<Fr>;
+
+        #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+        pub struct Jubjub;
+
+        #[derive(Debug, Clone)]
+        pub struct SigningKey(Scalar);
+
+        #[derive(Debug, Clone)]
+        pub struct VerifyingKey(Point);
+
+        pub struct Signature(Point, Scalar);
+
+        fn challenge(r: &Point, key: &Point, msg: &[u8]) -> Scalar {
+            Scalar::from_hash(&[&r.to_bytes(), &key.to_bytes(), msg])
+        }
+
+        impl Serializable for Signature {
+            fn to_bytes(&self) -> Vec<u8> {
+                [self.0.to_bytes(), self.1.to_bytes()].concat()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                if bytes.len() != 64 {
+                    return None;
+                }
+                let (r, s) = bytes.split_at(32);
+                Some(Signature(Point::from_bytes(r)?, Scalar::from_bytes(s)?))
+            }
+        }
+
+        impl Serializable for SigningKey {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.to_bytes()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                Scalar::from_bytes(bytes).map(SigningKey)
+            }
+        }
 
-    use crate::group::{DreipPoint, DreipScalar};
+        impl DreipPrivateKey for SigningKey {
+            type Signature = Signature;
 
+            fn sign(&self, msg: &[u8]) -> Signature {
+                let k = Scalar::from_hash(&[b"nonce", &self.0.to_bytes(), msg]);
+                let r = Point::generator() * k;
+                let e = challenge(&r, &(Point::generator() * self.0), msg);
+                Signature(r, k + e * self.0)
+            }
+        }
+
+        impl Serializable for VerifyingKey {
+            fn to_bytes(&self) -> Vec<u8> {
+                self.0.to_bytes()
+            }
+
+            fn from_bytes(bytes: &[u8]) -> Option<Self> {
+                Point::from_bytes(bytes).map(VerifyingKey)
+            }
+        }
+
+        impl DreipPublicKey for VerifyingKey {
+            type Signature = Signature;
+
+            fn verify(&self, msg: &[u8], signature: &Signature) -> bool {
+                let Signature(r, s) = *signature;
+                Point::generator() * s == r + self.0 * challenge(&r, &self.0, msg)
+            }
+        }
+
+        impl DreipGroup for Jubjub {
+            const NAME: &'static str = "DREIP-JUBJUB";
+            type Signature = Signature;
+            type Point = Point;
+            type Scalar = Scalar;
+            type PrivateKey = SigningKey;
+            type PublicKey = VerifyingKey;
+
+            fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+                (
+                    Point::generator(),
+                    Point::from_hash_with_tag(GENERATOR_TAG, unique_bytes),
+                )
+            }
+
+            fn new_keys(rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+                let x = Scalar::random(rng);
+                (SigningKey(x), VerifyingKey(Point::generator() * x))
+            }
+        }
+    }
+    /// Run a single conformance check, failing the test with its message.
+    fn check<G>(check: Check)
+    where
+        G: DreipGroup + Debug + Eq + Clone,
+        G::PublicKey: Sync,
+        G::Signature: Sync,
+    {
+        if let Err(message) = run_check::<G>(check, rand::thread_rng()) {
+            panic!("{:?} check failed: {}", check, message);
+        }
+    }
+
+    fn instances<G: DreipGroup>(proofs: &[ProofValues<G>]) -> Vec<BallotProofInstance<'_, G>> {
+        proofs
+            .iter()
+            .map(|(proof, z_sum, r_sum, ballot_id)| BallotProofInstance {
+                proof,
+                Z_sum: z_sum.clone(),
+                R_sum: r_sum.clone(),
+                ballot_id: ballot_id.as_bytes(),
+            })
+            .collect()
+    }
+
+    /// Creating and confirming votes and ballots wipes their secrets.
+    #[cfg(all(feature = "zeroize", feature = "p256_impl"))]
     #[test]
-    fn test_vote() {
+    fn test_confirm_zeroizes() {
+        use std::collections::HashMap;
+
+        use crate::group::DreipScalar;
+        use non_copy::{NonCopyP256, ZEROIZED};
+
+        let take_zeroized = || ZEROIZED.with(|zeroized| zeroized.take());
         let mut rng = rand::thread_rng();
-        let election = Election::<NistP256>::new(&[b"Test Election"], &mut rng);
-
-        let vote1 =
-            Vote::<NistP256, _>::new(&mut rng, election.g1, election.g2, "1", "Alice", true);
-        assert!(vote1.verify(election.g1, election.g2, "1", "Alice").is_ok());
-
-        let vote2 = Vote::<NistP256, _>::new(&mut rng, election.g1, election.g2, "1", "Bob", false);
-        assert!(vote2.verify(election.g1, election.g2, "1", "Bob").is_ok());
-
-        assert_ne!(vote1.pwf, vote2.pwf);
-        assert!(vote2
-            .pwf
-            .verify(election.g1, election.g2, vote1.Z, vote1.R, "1", "Bob")
-            .is_none());
-        assert!(vote2
-            .pwf
-            .verify(election.g1, election.g2, vote2.Z, vote2.R, "2", "Bob")
-            .is_none());
-        assert!(vote2
-            .pwf
-            .verify(election.g1, election.g2, vote2.Z, vote2.R, "1", "Alice")
-            .is_none());
+        let election = Election::<NonCopyP256>::new(&[b"Zeroize"], &mut rng);
+        let (g1, g2) = (election.g1.clone(), election.g2.clone());
+
+        // Creation wipes the proof's copy of `r` and its nonce, but keeps the secrets.
+        take_zeroized();
+        let vote =
+            Vote::<NonCopyP256, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", true);
+        let (r, v) = (vote.secrets.r.clone(), vote.secrets.v.clone());
+        let zeroized = take_zeroized();
+        assert_eq!(zeroized.len(), 2);
+        assert!(zeroized.contains(&r));
+
+        let _ = vote.confirm();
+        assert_eq!(take_zeroized(), vec![r, v]);
+
+        let ballot =
+            Ballot::<_, NonCopyP256, _>::new(&mut rng, g1, g2, "2", "Alice", vec!["Bob", "Eve"])
+                .unwrap();
+        let secrets = ballot
+            .votes()
+            .values()
+            .flat_map(|vote| [vote.secrets.r.clone(), vote.secrets.v.clone()])
+            .collect::<Vec<_>>();
+        take_zeroized();
+        let mut alice = CandidateTotals::default();
+        let mut bob = CandidateTotals::default();
+        let mut eve = CandidateTotals::default();
+        let mut totals =
+            HashMap::from([("Alice", &mut alice), ("Bob", &mut bob), ("Eve", &mut eve)]);
+        ballot.confirm(Some(&mut totals)).unwrap();
+        let zeroized = take_zeroized();
+        assert_eq!(zeroized.len(), secrets.len());
+        assert!(secrets.iter().all(|secret| zeroized.contains(secret)));
+
+        // Wiped values really are zero afterwards.
+        let mut secrets = SecretsPresent::<NonCopyP256> {
+            r: DreipScalar::one(),
+            v: DreipScalar::one(),
+        };
+        zeroize::Zeroize::zeroize(&mut secrets);
+        assert_eq!(secrets.r, DreipScalar::zero());
+        assert_eq!(secrets.v, DreipScalar::zero());
     }
 
+    /// The conformance suite passes for a real group, and reports each failing check
+    /// for a broken one rather than stopping at the first.
+    #[cfg(feature = "p256_impl")]
     #[test]
-    fn test_ballot() {
-        let mut rng = rand::thread_rng();
-        let election = Election::<NistP256>::new(&[b"Woah some random bytes"], &mut rng);
+    fn test_check_group() {
+        let report = check_group::<::p256::NistP256>(rand::thread_rng());
+        assert!(report.is_ok(), "{:?}", report.failed);
+        assert_eq!(report.passed, Check::ALL);
+
+        let report = check_group::<fixed_keys::FixedKeysP256>(rand::thread_rng());
+        let failed = report
+            .failed
+            .iter()
+            .map(|failure| failure.check)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            failed,
+            vec![
+                Check::WithKeys,
+                Check::FromSeed,
+                Check::Fingerprint,
+                Check::SignatureBatch
+            ]
+        );
+        assert_eq!(report.passed.len() + failed.len(), Check::ALL.len());
+    }
+
+    /// Exactly one of 1000 proofs is corrupted, and batch verification finds it.
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_batch_verify_one_in_1000() {
+        let election = Election::<::p256::NistP256>::new(&[b"Batch"], rand::thread_rng());
+        let (g1, g2) = (election.g1, election.g2);
+        let mut proofs = ballot_proofs::<::p256::NistP256>(rand::thread_rng(), &g1, &g2, 1000);
+        let verify = |proofs: &[_]| {
+            BallotProof::batch_verify(
+                g1,
+                g2,
+                instances(proofs),
+                TranscriptVersion::CURRENT,
+                rand::thread_rng(),
+            )
+        };
+        assert_eq!(verify(&proofs), Ok(()));
+
+        proofs[613].0.r += ::p256::Scalar::ONE;
+        assert_eq!(
+            verify(&proofs),
+            Err((613, BallotProofFailure::ChallengeMismatch))
+        );
+        for (i, (proof, z_sum, r_sum, ballot_id)) in proofs.iter().enumerate() {
+            let result = proof.verify(
+                g1,
+                g2,
+                *z_sum,
+                *r_sum,
+                ballot_id,
+                TranscriptVersion::CURRENT,
+            );
+            assert_eq!(result.is_err(), i == 613);
+        }
+    }
 
-        let mut ballot = Ballot::<_, NistP256, _>::new(
+    /// A stand-in for an HSM, which counts its calls and fails the given one.
+    #[cfg(feature = "p256_impl")]
+    struct MockHsm {
+        key: ::p256::ecdsa::SigningKey,
+        calls: std::cell::Cell<usize>,
+        fail_on: Option<usize>,
+    }
+
+    #[cfg(feature = "p256_impl")]
+    impl ReceiptSigner<::p256::NistP256> for MockHsm {
+        fn sign(&self, msg: &[u8]) -> Result<::p256::ecdsa::Signature, SignError> {
+            let call = self.calls.get();
+            self.calls.set(call + 1);
+            if self.fail_on == Some(call) {
+                return Err(SignError("HSM unavailable".to_string()));
+            }
+            Ok(DreipPrivateKey::sign(&self.key, msg))
+        }
+    }
+
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_external_signer() {
+        let mut rng = rand::thread_rng();
+        let election = Election::<::p256::NistP256>::new(&[b"External signer"], &mut rng);
+        let hsm = MockHsm {
+            key: election.private_key.clone(),
+            calls: Default::default(),
+            fail_on: Some(1),
+        };
+        let ballot = Ballot::new(
             &mut rng,
             election.g1,
             election.g2,
             "1",
             "Alice",
-            vec!["Bob", "Eve"],
+            vec!["Bob"],
         )
+        .unwrap()
+        .confirm(None)
         .unwrap();
-        assert!(ballot.verify(election.g1, election.g2, "1").is_ok());
-        match ballot.verify(election.g1, election.g2, "2") {
-            Err(BallotError::Vote(_)) => {}
-            _ => panic!("Assertion failed!"),
-        }
+        let bytes = ballot.to_bytes_with_id("1");
 
-        // Modify pwf and check it fails.
-        ballot.pwf.r = DreipScalar::random(&mut rng);
+        // The HSM and the in-process key sign interchangeably.
+        let signature = ballot.sign_receipt_with_id("1", &hsm).unwrap();
+        assert!(election.public_key.verify(&bytes, &signature));
+        let signature = ballot
+            .sign_receipt_with_id("1", &election.private_key)
+            .unwrap();
+        assert!(election.public_key.verify(&bytes, &signature));
+
+        // Failures are passed through.
         assert_eq!(
-            ballot.verify(election.g1, election.g2, "1"),
-            Err(BallotError::BallotProof { ballot_id: "1" })
+            ballot.sign_receipt_with_id("1", &hsm),
+            Err(SignError("HSM unavailable".to_string()))
         );
+        assert!(ballot.sign_receipt_with_id("1", &hsm).is_ok());
+        assert_eq!(hsm.calls.get(), 3);
     }
 
+    /// A thousand ballots created in one call verify and tally as if created in a loop.
+    #[cfg(feature = "p256_impl")]
     #[test]
-    fn test_election() {
-        let mut rng = rand::thread_rng();
-        let election = Election::<NistP256>::new(&[b"foobaraboof"], &mut rng);
-        let mut ballots: HashMap<_, Ballot<_, NistP256, _>> = HashMap::new();
+    fn test_create_ballots_1000() {
+        use ::p256::NistP256;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+        use std::collections::HashMap;
 
-        ballots.insert(
-            "1",
-            Ballot::new(
-                &mut rng,
+        let election = Election::<NistP256>::from_seed(&[b"Bulk"], &[7; 32]);
+        let candidates = ["Alice", "Bob", "Eve"];
+        let ids = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+        let requests = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), candidates[i % 7 % 3]))
+            .collect::<Vec<_>>();
+        let bulk = election
+            .create_ballots(ChaCha20Rng::seed_from_u64(1), &candidates, requests.clone())
+            .unwrap();
+        let mut rng = ChaCha20Rng::seed_from_u64(1);
+        let looped = requests
+            .iter()
+            .map(|(id, yes)| {
+                let no = candidates.iter().copied().filter(|c| c != yes);
+                let ballot =
+                    Ballot::new(&mut rng, election.g1, election.g2, *id, *yes, no).unwrap();
+                (*id, ballot)
+            })
+            .collect::<HashMap<_, _>>();
+        assert_eq!(bulk, looped);
+
+        let tally = |ballots: HashMap<_, Ballot<_, NistP256, _>>| {
+            let mut totals = candidates
+                .iter()
+                .map(|c| (*c, CandidateTotals::default()))
+                .collect::<HashMap<_, _>>();
+            let confirmed = ballots
+                .into_iter()
+                .map(|(id, ballot)| {
+                    let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+                    (id, ballot.confirm(Some(&mut refs)).unwrap())
+                })
+                .collect::<HashMap<_, _>>();
+            let rng = ChaCha20Rng::seed_from_u64(2);
+            let result = verify_election_batched(
                 election.g1,
                 election.g2,
-                "1",
-                "Alice",
-                vec!["Bob", "Eve"],
-            )
-            .unwrap(),
+                &confirmed,
+                &totals,
+                election.transcript,
+                rng,
+            );
+            (result, totals)
+        };
+        let (result, totals) = tally(bulk);
+        assert_eq!(result, Ok(()));
+        assert_eq!(tally(looped), (Ok(()), totals.clone()));
+        let counts = candidates.map(|c| totals[c].tally_u64().unwrap());
+        assert_eq!(counts.iter().sum::<u64>(), 1000);
+    }
+
+    /// Ballots created and confirmed in parallel verify, and tally as if done serially.
+    #[cfg(all(feature = "p256_impl", feature = "parallel"))]
+    #[test]
+    fn test_parallel_ballots() {
+        use ::p256::NistP256;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+        use std::collections::HashMap;
+
+        let election = Election::<NistP256>::from_seed(&[b"Parallel"], &[9; 32]);
+        let candidates = ["Alice", "Bob", "Eve", "Mallory"];
+        let ids = (0..200).map(|i| i.to_string()).collect::<Vec<_>>();
+        let requests = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), candidates[i % 5 % 4]))
+            .collect::<Vec<_>>();
+        let ballots = election
+            .create_ballots_par(ChaCha20Rng::seed_from_u64(3), &candidates, requests.clone())
+            .unwrap();
+        assert_eq!(ballots.len(), 200);
+        assert_eq!(
+            election.create_ballots_par(
+                ChaCha20Rng::seed_from_u64(3),
+                &candidates,
+                requests.clone()
+            ),
+            Ok(ballots.clone())
         );
-        ballots.insert(
-            "2",
-            Ballot::new(
-                &mut rng,
+        assert_eq!(
+            election.create_ballots_par(
+                ChaCha20Rng::seed_from_u64(3),
+                &candidates,
+                [("1", "Alice"), ("1", "Bob")]
+            ),
+            Err(("1", BallotCreationError::DuplicateBallot))
+        );
+        assert_eq!(
+            election.create_ballots_par(
+                ChaCha20Rng::seed_from_u64(3),
+                &candidates,
+                [("1", "Trent")]
+            ),
+            Err(("1", BallotCreationError::UnknownCandidate("Trent")))
+        );
+
+        let new_totals = || {
+            candidates
+                .iter()
+                .map(|c| (*c, CandidateTotals::<NistP256>::default()))
+                .collect::<HashMap<_, _>>()
+        };
+        let mut serial = new_totals();
+        for ballot in ballots.values() {
+            let mut refs = serial.iter_mut().map(|(c, t)| (*c, t)).collect();
+            ballot.clone().confirm(Some(&mut refs)).unwrap();
+        }
+        let mut totals = new_totals();
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        let confirmed = confirm_all_par(ballots.clone(), &mut refs).unwrap();
+        assert_eq!(totals, serial);
+        assert_eq!(
+            verify_election(
                 election.g1,
                 election.g2,
-                "2",
-                "Bob",
-                vec!["Alice", "Eve"],
-            )
-            .unwrap(),
+                &confirmed,
+                &totals,
+                election.transcript
+            ),
+            Ok(())
+        );
+        let counts = candidates.map(|c| totals[c].tally_u64().unwrap());
+        assert_eq!(counts, [80, 40, 40, 40]);
+
+        // A missing candidate is reported without changing any of the totals.
+        let mut partial = new_totals();
+        partial.remove("Eve");
+        let mut refs = partial.iter_mut().map(|(c, t)| (*c, t)).collect();
+        let result = confirm_all_par(ballots, &mut refs);
+        assert!(matches!(
+            result,
+            Err((_, ConfirmError::MissingCandidate("Eve")))
+        ));
+        assert!(partial.values().all(|t| *t == CandidateTotals::default()));
+
+        // A single ballot's votes can also be created in parallel.
+        let ballot = Ballot::<_, NistP256, _>::new_par(
+            ChaCha20Rng::seed_from_u64(4),
+            election.g1,
+            election.g2,
+            "par",
+            "Bob",
+            ["Alice", "Eve", "Mallory"],
+        )
+        .unwrap();
+        assert_eq!(ballot.claimed_choice(), Ok("Bob"));
+        assert_eq!(
+            ballot.verify(election.g1, election.g2, "par", election.transcript),
+            Ok(())
+        );
+        let again = Ballot::new_par(
+            ChaCha20Rng::seed_from_u64(4),
+            election.g1,
+            election.g2,
+            "par",
+            "Bob",
+            ["Alice", "Eve", "Mallory"],
         );
-        ballots.insert(
-            "3",
-            Ballot::new(
-                &mut rng,
+        assert_eq!(again, Ok(ballot));
+        assert_eq!(
+            Ballot::<_, NistP256, _>::new_par(
+                ChaCha20Rng::seed_from_u64(4),
                 election.g1,
                 election.g2,
-                "3",
-                "Alice",
-                vec!["Bob", "Eve"],
-            )
-            .unwrap(),
+                "par",
+                "Bob",
+                ["Alice", "Bob"],
+            ),
+            Err(BallotCreationError::YesAndNoCandidate("Bob"))
         );
 
-        let alice_r_sum = ballots
-            .values()
-            .map(|b| b.votes.iter().find(|(c, _)| **c == "Alice").unwrap())
-            .fold(Scalar::zero(), |a, (_, b)| a + b.secrets.r);
-        let bob_r_sum = ballots
-            .values()
-            .map(|b| b.votes.iter().find(|(c, _)| **c == "Bob").unwrap())
-            .fold(Scalar::zero(), |a, (_, b)| a + b.secrets.r);
-        let eve_r_sum = ballots
-            .values()
-            .map(|b| b.votes.iter().find(|(c, _)| **c == "Eve").unwrap())
-            .fold(Scalar::zero(), |a, (_, b)| a + b.secrets.r);
+        // Including for an election bound to its id.
+        let bound = election.clone().bound([1; 32]).transcript;
+        let ballot = Ballot::<_, NistP256, _>::new_par_with_transcript(
+            ChaCha20Rng::seed_from_u64(5),
+            election.g1,
+            election.g2,
+            "par",
+            "Bob",
+            ["Alice", "Eve"],
+            bound,
+        )
+        .unwrap();
+        assert_eq!(
+            ballot.verify(election.g1, election.g2, "par", bound),
+            Ok(())
+        );
+        assert!(ballot
+            .verify(election.g1, election.g2, "par", election.transcript)
+            .is_err());
+    }
 
-        let mut totals = HashMap::new();
-        totals.insert("Alice", (Scalar::from(2), alice_r_sum).into());
-        totals.insert("Bob", (Scalar::from(1), bob_r_sum).into());
-        totals.insert("Eve", (Scalar::from(0), eve_r_sum).into());
+    /// Elections verify in parallel exactly as they do serially, failing at the same ballot.
+    #[cfg(all(feature = "p256_impl", feature = "parallel"))]
+    #[test]
+    fn test_parallel_verification() {
+        use ::p256::NistP256;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha20Rng;
+
+        type Results = ElectionResults<String, &'static str, NistP256>;
+
+        let mut rng = ChaCha20Rng::seed_from_u64(5);
+        let election = Election::<NistP256>::from_seed(&[b"Parallel verification"], &[5; 32]);
+        let candidates = ["Alice", "Bob", "Eve", "Mallory"];
+        let mut session = ElectionSession::new(election, candidates);
+        for i in 0..60 {
+            let handle = session
+                .cast(&mut rng, i.to_string(), candidates[i % 3])
+                .unwrap();
+            session.confirm(handle).unwrap();
+        }
+        let handle = session
+            .cast(&mut rng, "audited".to_string(), "Bob")
+            .unwrap();
+        session.audit(handle).unwrap();
+        let results = session.into_results();
 
-        assert!(verify_election(election.g1, election.g2, &ballots, &totals).is_ok());
+        let verify_both = |results: &Results| {
+            let serial = verify_election(
+                results.g1,
+                results.g2,
+                &results.confirmed,
+                &results.totals,
+                results.transcript,
+            );
+            let parallel = verify_election_par(
+                results.g1,
+                results.g2,
+                &results.confirmed,
+                &results.totals,
+                results.transcript,
+            );
+            assert_eq!(serial, parallel);
+            assert_eq!(results.verify(), results.verify_par());
+            serial
+        };
+        assert_eq!(verify_both(&results), Ok(()));
+        assert_eq!(results.verify_par(), Ok(()));
 
-        // Now change the tally and check it fails.
-        totals.get_mut("Eve").unwrap().tally = Scalar::from(5);
+        // Tampered totals fail the same way.
+        let mut tampered = results.clone();
+        tampered.totals.get_mut("Mallory").unwrap().tally = DreipScalar::one();
         assert_eq!(
-            verify_election(election.g1, election.g2, &ballots, &totals),
+            verify_both(&tampered),
             Err(VerificationError::Tally {
-                candidate_id: "Eve"
+                candidate_id: "Mallory"
             })
         );
+        let mut tampered = results.clone();
+        tampered.totals.remove("Eve");
+        assert_eq!(
+            verify_both(&tampered),
+            Err(VerificationError::WrongCandidates)
+        );
 
-        // Change the random sum and check it fails.
-        totals.get_mut("Eve").unwrap().tally = Scalar::from(0);
-        totals.get_mut("Alice").unwrap().r_sum = Scalar::random(&mut rng);
+        // With many tampered ballots, both report the first in the map's order.
+        let mut tampered = results.clone();
+        for ballot in tampered.confirmed.values_mut().skip(5).step_by(7) {
+            ballot.pwf_mut().r = DreipScalar::random(&mut rng);
+        }
+        let first = tampered.confirmed.keys().nth(5).unwrap().clone();
         assert_eq!(
-            verify_election(election.g1, election.g2, &ballots, &totals),
-            Err(VerificationError::Tally {
-                candidate_id: "Alice"
-            })
+            verify_both(&tampered),
+            Err(VerificationError::Ballot(BallotError::BallotProof {
+                ballot_id: first,
+                reason: BallotProofFailure::ChallengeMismatch,
+            }))
         );
 
-        // Change the candidates and check it fails.
-        totals.get_mut("Alice").unwrap().r_sum = alice_r_sum;
-        totals.remove("Bob").unwrap();
+        // A tampered audited ballot fails before any confirmed ballot is verified.
+        let mut tampered = results.clone();
+        let audited = tampered.audited.get_mut("audited").unwrap();
+        audited.pwf_mut().r = DreipScalar::random(&mut rng);
+        assert!(matches!(
+            tampered.verify_par(),
+            Err(VerificationError::Audited(_))
+        ));
+        assert_eq!(tampered.verify(), tampered.verify_par());
+    }
+
+    /// The encoding of election parameters is stable.
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_params_fixture() {
+        use ::p256::ecdsa::{SigningKey, VerifyingKey};
+
+        // The untagged g2, as derived before generators had their own tag.
+        let g1 = ::p256::ProjectivePoint::GENERATOR;
+        let g2 = <::p256::ProjectivePoint as DreipPoint>::from_hash(&[b"Fixture"]);
+        let private_key = SigningKey::from_slice(&[&[0; 31][..], &[1]].concat()).unwrap();
+        let public_key = VerifyingKey::from(&private_key);
+        let election = Election::<::p256::NistP256> {
+            group: GroupName::new(),
+            g1,
+            g2,
+            private_key,
+            public_key,
+            transcript: TranscriptVersion::CURRENT,
+            roster: None,
+        };
+        let hex = election
+            .to_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        // g1 is the standard generator, as is the public key for private key 1.
         assert_eq!(
-            verify_election(election.g1, election.g2, &ballots, &totals),
-            Err(VerificationError::WrongCandidates)
+            hex,
+            concat!(
+                "0000000000000021",
+                "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+                "0000000000000021",
+                "02f2a80031e4d1b4e51170bdc557c0ec465bac6ee77d03ce93f4a6acfe6135d4b9",
+                "0000000000000021",
+                "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            )
         );
+        assert_eq!(
+            election.fingerprint_hex(),
+            "34ba54c4ff1f72639f35dd2cee71d140dedf03d180fb4fd8b7aac0bda8a9055a"
+        );
+    }
 
-        // Change a vote and check it fails.
-        totals.insert("Bob", (Scalar::from(1), bob_r_sum).into());
-        ballots
-            .get_mut("1")
-            .unwrap()
-            .votes
-            .get_mut("Alice")
-            .unwrap()
-            .R = DreipPoint::identity();
+    /// The canonical encoding of ballots and votes is as specified.
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_canonical_fixture() {
+        use ::p256::{NistP256, ProjectivePoint, Scalar};
+
+        // A ballot with made-up values, as the encoding does not check them.
+        let g = ProjectivePoint::GENERATOR;
+        let vote = Vote::<NistP256, _>::from_parts(
+            SecretsPresent {
+                r: Scalar::from(1u64),
+                v: Scalar::from(0u64),
+            },
+            g,
+            g,
+            VoteProof {
+                c1: Scalar::from(1u64),
+                c2: Scalar::from(2u64),
+                r1: Scalar::from(3u64),
+                r2: Scalar::from(4u64),
+            },
+        );
+        let ballot = Ballot::from_parts(
+            std::collections::BTreeMap::from([("A", vote.clone())]),
+            BallotProof {
+                a: g,
+                b: g,
+                r: Scalar::from(5u64),
+            },
+        );
+
+        let hex = |bytes: Vec<u8>| {
+            bytes
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+        };
+        // Each field is prefixed with its length as 8 big-endian bytes.
+        let field = |value: &str| format!("{:016x}{}", value.len() / 2, value);
+        let point = field("036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296");
+        let scalar = |n: u8| field(&format!("{:064x}", n));
+        let vote_body = |secrets: &str| {
+            [
+                field(secrets),
+                point.clone(),
+                point.clone(),
+                field(&[scalar(1), scalar(2), scalar(3), scalar(4)].concat()),
+            ]
+            .concat()
+        };
+        let ballot_bytes = |secrets: &str| {
+            [
+                "d701".to_string(),
+                field("0000000000000001"),
+                field("41"),
+                field(&vote_body(secrets)),
+                field(&[point.clone(), point.clone(), scalar(5)].concat()),
+            ]
+            .concat()
+        };
+
+        let secrets = [scalar(1), scalar(0)].concat();
         assert_eq!(
-            verify_election(election.g1, election.g2, &ballots, &totals),
-            Err(VerificationError::Ballot(BallotError::Vote(VoteError {
-                ballot_id: "1",
-                candidate_id: "Alice",
-            })))
+            hex(vote.canonical_bytes()),
+            ["d701".to_string(), vote_body(&secrets)].concat()
+        );
+        assert_eq!(hex(ballot.canonical_bytes()), ballot_bytes(&secrets));
+        // Without secrets, their field is empty.
+        let confirmed = ballot.confirm(None).unwrap();
+        assert_eq!(hex(confirmed.canonical_bytes()), ballot_bytes(""));
+    }
+
+    /// Encoded ballots decode to the same ballot in every release.
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_codec_fixture() {
+        use ::p256::{NistP256, ProjectivePoint, Scalar};
+
+        // The ballot of `test_canonical_fixture`, confirmed.
+        let g = ProjectivePoint::GENERATOR;
+        let vote = Vote::<NistP256, _>::from_parts(
+            NoSecrets(()),
+            g,
+            g,
+            VoteProof {
+                c1: Scalar::from(1u64),
+                c2: Scalar::from(2u64),
+                r1: Scalar::from(3u64),
+                r2: Scalar::from(4u64),
+            },
+        );
+        let ballot = Ballot::from_parts(
+            std::collections::BTreeMap::from([("A".to_string(), vote)]),
+            BallotProof {
+                a: g,
+                b: g,
+                r: Scalar::from(5u64),
+            },
+        );
+
+        let hex = concat!(
+            // The header, the vote count, and the candidate id.
+            "d701",
+            "0000000000000008",
+            "0000000000000001",
+            "0000000000000001",
+            "41",
+            // The vote: empty secrets, R, Z, and the proof.
+            "0000000000000102",
+            "0000000000000000",
+            "0000000000000021",
+            "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            "0000000000000021",
+            "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            "00000000000000a0",
+            "0000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "0000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000004",
+            // The ballot proof: a, b, and r.
+            "000000000000007a",
+            "0000000000000021",
+            "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            "0000000000000021",
+            "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+            "0000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000005",
         );
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(Ballot::decode(&bytes).as_ref(), Ok(&ballot));
+        assert_eq!(ballot.encode(), bytes);
+    }
+
+    /// Audited ballots and receipts are summarised for reading aloud.
+    #[cfg(feature = "p256_impl")]
+    #[test]
+    fn test_summary_fixture() {
+        use ::p256::ecdsa::SigningKey;
+        use ::p256::{NistP256, ProjectivePoint, Scalar};
+
+        // A ballot with made-up values, as summaries do not check them.
+        let g = ProjectivePoint::GENERATOR;
+        let vote = |v: u64, z: ProjectivePoint| {
+            Vote::<NistP256, _>::from_parts(
+                SecretsPresent {
+                    r: Scalar::from(1u64),
+                    v: Scalar::from(v),
+                },
+                g,
+                z,
+                VoteProof {
+                    c1: Scalar::from(1u64),
+                    c2: Scalar::from(2u64),
+                    r1: Scalar::from(3u64),
+                    r2: Scalar::from(4u64),
+                },
+            )
+        };
+        let ballot = Ballot::from_parts(
+            std::collections::BTreeMap::from([
+                ("Eve", vote(0, g)),
+                ("Alice", vote(1, g + g)),
+                ("Bob", vote(0, g)),
+            ]),
+            BallotProof {
+                a: g,
+                b: g,
+                r: Scalar::from(5u64),
+            },
+        );
+
+        assert_eq!(
+            ballot.summary(1234, 4),
+            concat!(
+                "Ballot 1234: YES for Alice, NO for Bob, NO for Eve\n",
+                "Alice: Z 037cf27b, R 036b17d1\n",
+                "Bob: Z 036b17d1, R 036b17d1\n",
+                "Eve: Z 036b17d1, R 036b17d1",
+            )
+        );
+        // The prefix is cut to the length of the encoding.
+        assert!(ballot.summary(1234, 100).ends_with(
+            "Eve: Z 036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296, \
+             R 036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"
+        ));
+        let audited = PendingBallot::from(ballot.clone()).audit();
+        assert_eq!(audited.summary(1234, 4), ballot.summary(1234, 4));
+
+        // Receipts show no choice, even with the secrets.
+        let private_key = SigningKey::from_slice(&[&[0; 31][..], &[1]].concat()).unwrap();
+        let confirmed = ballot.clone().confirm(None).unwrap();
+        let receipt = Receipt::sign(
+            &private_key,
+            confirmed,
+            1234u32,
+            None,
+            TranscriptVersion::CURRENT,
+        )
+        .unwrap();
+        assert_eq!(
+            receipt.summary(4),
+            concat!(
+                "Receipt for ballot 1234, signature 8775738e\n",
+                "Alice: Z 037cf27b, R 036b17d1\n",
+                "Bob: Z 036b17d1, R 036b17d1\n",
+                "Eve: Z 036b17d1, R 036b17d1",
+            )
+        );
+        let receipt = Receipt::sign(
+            &private_key,
+            ballot,
+            1234u32,
+            None,
+            TranscriptVersion::CURRENT,
+        )
+        .unwrap();
+        assert!(!receipt.summary(4).contains("YES"));
+    }
+
+    /// The encoding of signing contexts is stable, and changes with every field.
+    #[test]
+    fn test_signing_context_fixture() {
+        let mut context = SigningContext::new("E1", "Q1", "7KQ2");
+        context.extra.insert("station".to_string(), "7".to_string());
+        let hex = context
+            .to_bytes()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        assert_eq!(
+            hex,
+            concat!(
+                "0000000000000015",
+                "44524549505f5349474e494e475f434f4e54455854",
+                "0000000000000002",
+                "4531",
+                "0000000000000002",
+                "5131",
+                "0000000000000004",
+                "374b5132",
+                "0000000000000007",
+                "73746174696f6e",
+                "0000000000000001",
+                "37",
+            )
+        );
+
+        // Changing any field, or moving bytes between fields, changes the encoding.
+        let bytes = context.to_bytes();
+        let mut changed = vec![context.clone(); 6];
+        changed[0].election_id = "E2".to_string();
+        changed[1].question_id = "Q2".to_string();
+        changed[2].confirmation_code = "7KQ3".to_string();
+        changed[3]
+            .extra
+            .insert("station".to_string(), "8".to_string());
+        changed[4]
+            .extra
+            .insert("booth".to_string(), "2".to_string());
+        changed[5].election_id = "E1Q".to_string();
+        changed[5].question_id = "1".to_string();
+        for other in changed.iter() {
+            assert_ne!(other.to_bytes(), bytes);
+        }
+
+        // Extra fields are encoded in order of name, however they were added.
+        let mut first = SigningContext::new("E1", "Q1", "7KQ2");
+        first.extra.insert("a".to_string(), "1".to_string());
+        first.extra.insert("b".to_string(), "2".to_string());
+        let mut second = SigningContext::new("E1", "Q1", "7KQ2");
+        second.extra.insert("b".to_string(), "2".to_string());
+        second.extra.insert("a".to_string(), "1".to_string());
+        assert_eq!(first.to_bytes(), second.to_bytes());
     }
 }
@@ -0,0 +1,122 @@
+use crate::group::{DreipGroup, DreipPoint, DreipScalar, Serializable};
+
+/// The number of bits in each window of a `FixedBaseTable`.
+const WINDOW_BITS: usize = 4;
+
+/// The number of entries in each row of a `FixedBaseTable`.
+const WINDOW_SIZE: usize = 1 << WINDOW_BITS;
+
+/// A precomputed table of multiples of a fixed point, for fast multiplication.
+///
+/// Row `i` holds `base * (j * 16^i)` for each `j` in `0..16`, so multiplying by a scalar
+/// takes one addition per 4-bit digit of the scalar, and no doublings. For a 256-bit
+/// group this is 64 rows of 16 points.
+///
+/// Lookups are indexed by the digits of the scalar, so unlike the backends' own
+/// multiplication, this is not constant-time with respect to cache timing.
+#[derive(Debug, Clone)]
+pub struct FixedBaseTable<G: DreipGroup> {
+    base: G::Point,
+    rows: Vec<Vec<G::Point>>,
+    /// Whether the group's scalars serialize as little-endian.
+    little_endian: bool,
+}
+
+impl<G: DreipGroup> FixedBaseTable<G> {
+    /// Build the table for the given point.
+    pub fn new(base: G::Point) -> Self {
+        // Backends do not agree on byte order, so find out from the encoding of one.
+        let little_endian = G::Scalar::one().to_bytes().first() == Some(&1);
+        let num_rows = 8 * G::Scalar::zero().to_bytes().len() / WINDOW_BITS;
+
+        let mut rows = Vec::with_capacity(num_rows);
+        let mut row_base = base.clone();
+        for _ in 0..num_rows {
+            let mut row = Vec::with_capacity(WINDOW_SIZE);
+            row.push(G::Point::identity());
+            for j in 1..WINDOW_SIZE {
+                row.push(row[j - 1].clone() + row_base.clone());
+            }
+            row_base = row[WINDOW_SIZE - 1].clone() + row_base;
+            rows.push(row);
+        }
+
+        Self {
+            base,
+            rows,
+            little_endian,
+        }
+    }
+
+    /// The point this table multiplies.
+    pub fn base(&self) -> &G::Point {
+        &self.base
+    }
+
+    /// Multiply the base point by the given scalar.
+    pub fn mul(&self, scalar: &G::Scalar) -> G::Point {
+        let mut bytes = scalar.to_bytes();
+        if !self.little_endian {
+            bytes.reverse();
+        }
+        let digits = bytes
+            .into_iter()
+            .flat_map(|byte| [byte & 0x0f, byte >> 4])
+            .map(usize::from);
+        self.rows
+            .iter()
+            .zip(digits)
+            .fold(G::Point::identity(), |acc, (row, digit)| {
+                acc + row[digit].clone()
+            })
+    }
+}
+
+/// Precomputed tables for both generators of an election, for faster ballot creation.
+///
+/// This is opt-in: use the `new_precomputed` constructors of `Ballot`, `Vote`, `VoteProof`,
+/// and `BallotProof` to make use of it. They produce exactly the same output as the plain
+/// constructors given the same random number stream. For a 256-bit group, the tables take
+/// roughly 2048 points of memory.
+#[derive(Debug, Clone)]
+pub struct ElectionPrecomputed<G: DreipGroup> {
+    /// The table for the first generator.
+    pub g1: FixedBaseTable<G>,
+
+    /// The table for the second generator.
+    pub g2: FixedBaseTable<G>,
+}
+
+impl<G: DreipGroup> ElectionPrecomputed<G> {
+    /// Build the tables for the given generators.
+    pub fn new(g1: G::Point, g2: G::Point) -> Self {
+        Self {
+            g1: FixedBaseTable::new(g1),
+            g2: FixedBaseTable::new(g2),
+        }
+    }
+}
+
+/// A generator, with or without a precomputed table.
+pub(crate) enum Generator<'a, G: DreipGroup> {
+    Plain(G::Point),
+    Table(&'a FixedBaseTable<G>),
+}
+
+impl<'a, G: DreipGroup> Generator<'a, G> {
+    /// The generator itself.
+    pub(crate) fn point(&self) -> G::Point {
+        match self {
+            Self::Plain(point) => point.clone(),
+            Self::Table(table) => table.base().clone(),
+        }
+    }
+
+    /// Multiply the generator by the given scalar.
+    pub(crate) fn mul(&self, scalar: G::Scalar) -> G::Point {
+        match self {
+            Self::Plain(point) => point.clone() * scalar,
+            Self::Table(table) => table.mul(&scalar),
+        }
+    }
+}
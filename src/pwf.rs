@@ -1,9 +1,213 @@
 use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
 
-use crate::group::{DreipGroup, DreipScalar, Serializable};
+use crate::ballots::{SecretsPresent, Vote};
+use crate::group::{
+    encoded_size, scalar_from_u64, total_byte_size, wipe, DreipGroup, DreipPoint, DreipScalar,
+    Serializable,
+};
+use crate::ids::IdBytes;
+use crate::precompute::{ElectionPrecomputed, Generator};
+
+/// The version of the byte encoding used for proof challenges and signing.
+///
+/// This is chosen by the verifier, usually from the `Election`, never by the proof itself,
+/// so that proofs cannot be downgraded to an older encoding.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub enum TranscriptVersion {
+    /// The original encoding, which concatenates its inputs directly.
+    /// This is ambiguous for variable-length ids: ballot id `1` with candidate id `2Alice`
+    /// gives the same challenge as ballot id `12` with candidate id `Alice`.
+    /// Only supported for verifying existing elections.
+    V0,
+    /// Each input is prefixed with its length as 8 big-endian bytes, and challenges are
+    /// additionally prefixed with a label for the kind of proof.
+    V1,
+    /// Encoded as `V1`, but challenges are hashed with a domain separation tag for the kind
+    /// of proof, as `DreipScalar::from_hash_with_tag`, instead of being prefixed with a label.
+    /// This keeps challenges separate from generator derivation, as RFC 9380 recommends.
+    V2,
+    /// Encoded and hashed as `V2`, but every challenge also hashes the election id as a
+    /// final input, so a proof from one election does not verify in another, even one
+    /// with the same generators and ids. Signing encodings are the same as for `V2`.
+    V3 {
+        /// The id of the election that proofs are bound to; see `Election::bound`.
+        #[serde(with = "serde_election_id")]
+        election_id: [u8; 32],
+    },
+}
+
+impl TranscriptVersion {
+    /// The version used for all newly created proofs.
+    pub const CURRENT: Self = Self::V2;
+
+    /// The `V3` version binding proofs to the given election id.
+    pub const fn bound(election_id: [u8; 32]) -> Self {
+        Self::V3 { election_id }
+    }
+
+    /// The election id that proofs are bound to, if any.
+    pub const fn election_id(self) -> Option<[u8; 32]> {
+        match self {
+            Self::V0 | Self::V1 | Self::V2 => None,
+            Self::V3 { election_id } => Some(election_id),
+        }
+    }
+
+    /// Encode the given fields into a single byte sequence.
+    pub fn encode(self, fields: &[&[u8]]) -> Vec<u8> {
+        let total = fields.iter().map(|field| field.len()).sum();
+        let mut bytes = Vec::with_capacity(self.encoded_len(total, fields.len()));
+        for field in fields {
+            self.write_field(&mut bytes, field);
+        }
+        bytes
+    }
+
+    /// Append a single field to `out`, as `encode` does.
+    pub fn write_field(self, out: &mut Vec<u8>, field: &[u8]) {
+        self.write_len(out, field.len());
+        out.extend_from_slice(field);
+    }
+
+    /// Append a value's encoding to `out` as a single field, as `encode` would its bytes.
+    pub fn write_value(self, out: &mut Vec<u8>, value: &impl ToBytes) {
+        self.write_len(out, value.byte_len(self));
+        value.write_bytes(out, self);
+    }
+
+    /// Append the prefix of a field with the given length, if this version has one.
+    fn write_len(self, out: &mut Vec<u8>, len: usize) {
+        match self {
+            Self::V0 => {}
+            Self::V1 | Self::V2 | Self::V3 { .. } => out.extend((len as u64).to_be_bytes()),
+        }
+    }
+
+    /// The length of `encode` on the given number of fields with the given total length.
+    pub const fn encoded_len(self, total: usize, fields: usize) -> usize {
+        match self {
+            Self::V0 => total,
+            Self::V1 | Self::V2 | Self::V3 { .. } => total + 8 * fields,
+        }
+    }
+
+    /// The bytes hashed for a challenge: the inputs encoded, with the label first for `V1`,
+    /// and the election id last for `V3`.
+    pub(crate) fn challenge_input(self, label: &[u8], inputs: &[&[u8]]) -> Vec<u8> {
+        match self {
+            Self::V0 | Self::V2 => self.encode(inputs),
+            Self::V1 => self.encode(&[&[label], inputs].concat()),
+            Self::V3 { election_id } => self.encode(&[inputs, &[&election_id]].concat()),
+        }
+    }
+
+    /// Hash the given inputs to a challenge scalar. The label is used by `V1`, and the
+    /// domain separation tag by `V2` and `V3`.
+    pub(crate) fn challenge<S: DreipScalar>(self, label: &[u8], tag: &[u8], inputs: &[&[u8]]) -> S {
+        match self {
+            Self::V0 => S::from_hash(inputs),
+            Self::V1 => S::from_hash(&[&self.challenge_input(label, inputs)]),
+            Self::V2 | Self::V3 { .. } => {
+                S::from_hash_with_tag(tag, &[&self.challenge_input(label, inputs)])
+            }
+        }
+    }
+}
+
+/// Split `bytes` into consecutive fields of the given lengths, if they add up to its length.
+fn split_fields<const N: usize>(bytes: &[u8], lens: [usize; N]) -> Option<[&[u8]; N]> {
+    if lens.iter().sum::<usize>() != bytes.len() {
+        return None;
+    }
+    let mut rest = bytes;
+    Some(lens.map(|len| {
+        let (field, tail) = rest.split_at(len);
+        rest = tail;
+        field
+    }))
+}
+
+/// Serialize an election id as a base64url bytestring, like points and scalars.
+mod serde_election_id {
+    use serde::de::Error;
+    use serde::Deserialize;
+
+    pub fn serialize<S: serde::Serializer>(
+        id: &[u8; 32],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serde::Serialize::serialize(
+            &base64::encode_config(id, base64::URL_SAFE_NO_PAD),
+            serializer,
+        )
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; 32], D::Error> {
+        let bytestring = String::deserialize(deserializer)?;
+        let bytes = base64::decode_config(&bytestring, base64::URL_SAFE_NO_PAD).map_err(|e| {
+            D::Error::custom(format_args!("invalid bytestring for election id: {}", e))
+        })?;
+        bytes.try_into().map_err(|bytes: Vec<u8>| {
+            D::Error::custom(format_args!(
+                "invalid election id of {} bytes, expected 32",
+                bytes.len()
+            ))
+        })
+    }
+}
+
+/// Encoding for signing, written into a caller's buffer to avoid intermediate allocations.
+///
+/// This is implemented for votes, ballots, their proofs, and vote secrets. The inherent
+/// `to_bytes` methods of these types give the same bytes.
+pub trait ToBytes {
+    /// Append the encoding of this value to `out`, using the given transcript version.
+    fn write_bytes(&self, out: &mut Vec<u8>, transcript: TranscriptVersion);
+
+    /// The number of bytes `write_bytes` appends.
+    fn byte_len(&self, transcript: TranscriptVersion) -> usize;
+
+    /// Encode this value into a new buffer of exactly `byte_len` bytes.
+    fn to_bytes(&self, transcript: TranscriptVersion) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.byte_len(transcript));
+        self.write_bytes(&mut out, transcript);
+        out
+    }
+}
+
+/// The challenge label for `VoteProof`s.
+pub(crate) const VOTE_PROOF_LABEL: &[u8] = b"DREIP_VOTE_PROOF";
+
+/// The challenge label for `BallotProof`s.
+pub(crate) const BALLOT_PROOF_LABEL: &[u8] = b"DREIP_BALLOT_PROOF";
+
+/// The domain separation tag for `VoteProof` challenges.
+pub(crate) const VOTE_PROOF_TAG: &[u8] = b"VOTE-PWF";
+
+/// The domain separation tag for `BallotProof` challenges.
+pub(crate) const BALLOT_PROOF_TAG: &[u8] = b"BALLOT-PWF";
+
+/// The public values of a vote that a `VoteProof` is about, named so that they cannot be
+/// given in the wrong order.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct VoteStatement<G: DreipGroup> {
+    /// The public vote, `g1*(r + v)`.
+    pub Z: G::Point,
+    /// The public random value, `g2*r`.
+    pub R: G::Point,
+}
 
 /// Zero-Knowledge Proof of well-formedness that a vote has `v` in `{0, 1}`.
+///
+/// Vote proofs are verified one at a time. A proof carries only its challenges and responses, so
+/// its commitments must be recomputed exactly to rebuild the challenge hash, which leaves nothing
+/// to combine across proofs in a random linear combination.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(bound = "")]
 pub struct VoteProof<G: DreipGroup> {
@@ -22,6 +226,10 @@ pub struct VoteProof<G: DreipGroup> {
 }
 
 impl<G: DreipGroup> VoteProof<G> {
+    /// The total length of the proof's four scalars, if fixed. `to_bytes` adds the
+    /// framing of its transcript version, as given by `TranscriptVersion::encoded_len`.
+    pub const BYTES: Option<usize> = total_byte_size(&[<G::Scalar as Serializable>::BYTE_SIZE; 4]);
+
     /// Create a new proof.
     ///
     /// Based on: https://eprint.iacr.org/2010/452.pdf (Figure 2)
@@ -77,26 +285,221 @@ impl<G: DreipGroup> VoteProof<G> {
     /// The ballot and candidate ids are part of the hash input for the challenge, tying the
     /// proof to the vote. This requires that the combination of the two is globally unique.
     ///
+    /// The challenge is computed with `TranscriptVersion::CURRENT`.
+    ///
     /// This function does not check the validity of the generated proof, so if
     /// the supplied `v`, `r`, `Z`, and `R` values are invalid, an invalid
     /// proof will be generated.
+    #[deprecated(note = "use `VoteProof::for_vote`, which takes `Z` and `R` by name")]
     #[allow(non_snake_case)]
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        mut rng: impl RngCore + CryptoRng,
+        rng: impl RngCore + CryptoRng,
         g1: G::Point,
         g2: G::Point,
         v: bool,
         r: G::Scalar,
         Z: G::Point,
         R: G::Point,
-        ballot_id: impl AsRef<[u8]>,
-        candidate_id: impl AsRef<[u8]>,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            v,
+            r,
+            Z,
+            R,
+            ballot_id,
+            candidate_id,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new proof, as `new` describes, that the vote with the given secrets and
+    /// public values is 0 or 1. Votes are usually created with their proofs by `Vote::new`.
+    ///
+    /// The proof is for a yes vote if `secrets.v` is 1, and a no vote otherwise, so it does
+    /// not verify for other `v` values, nor if `statement` is not what the secrets give.
+    ///
+    /// ```
+    /// use dre_ip::{DreipGroup, DreipScalar, Election, SecretsPresent, VoteProof, VoteStatement};
+    ///
+    /// fn prove<G: DreipGroup>(election: &Election<G>) {
+    ///     let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    ///     let mut rng = rand::thread_rng();
+    ///     let secrets = SecretsPresent::<G> {
+    ///         r: G::Scalar::random(&mut rng),
+    ///         v: G::Scalar::one(),
+    ///     };
+    ///     let (r_point, z_point) = secrets.derive_public(g1.clone(), g2.clone());
+    ///     let statement = VoteStatement { Z: z_point, R: r_point };
+    ///     let (g1_, g2_) = (g1.clone(), g2.clone());
+    ///     let proof = VoteProof::for_vote(rng, g1_, g2_, &secrets, &statement, "1", "Alice");
+    ///     let VoteStatement { Z: z_point, R: r_point } = statement;
+    ///     let result = proof.verify(g1, g2, z_point, r_point, "1", "Alice", election.transcript);
+    ///     assert_eq!(result, Ok(()));
+    /// }
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn for_vote(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        secrets: &SecretsPresent<G>,
+        statement: &VoteStatement<G>,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+    ) -> Self {
+        Self::new_with_transcript(
+            rng,
+            g1,
+            g2,
+            secrets,
+            statement,
+            ballot_id,
+            candidate_id,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new proof, using precomputed tables for the generators.
+    /// The result is identical to that of `new` given the same random number stream.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_precomputed(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        v: bool,
+        r: G::Scalar,
+        Z: G::Point,
+        R: G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+    ) -> Self {
+        Self::new_precomputed_with_transcript(
+            rng,
+            tables,
+            v,
+            r,
+            Z,
+            R,
+            ballot_id,
+            candidate_id,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new proof as `new_precomputed` does, with the given transcript version.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_precomputed_with_transcript(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        v: bool,
+        r: G::Scalar,
+        Z: G::Point,
+        R: G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Table(&tables.g1),
+            &Generator::Table(&tables.g2),
+            v,
+            r,
+            Z,
+            R,
+            ballot_id,
+            candidate_id,
+            transcript,
+        )
+    }
+
+    /// Create a new proof as `for_vote` does, with the given transcript version, such as
+    /// the `V3` version of an election bound to its id. Older versions are only for
+    /// verifying existing elections.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_transcript(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        secrets: &SecretsPresent<G>,
+        statement: &VoteStatement<G>,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            secrets.is_yes(),
+            secrets.r.clone(),
+            statement.Z.clone(),
+            statement.R.clone(),
+            ballot_id,
+            candidate_id,
+            transcript,
+        )
+    }
+
+    /// Create a new proof with the given generators and transcript version.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create(
+        rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        v: bool,
+        r: G::Scalar,
+        Z: G::Point,
+        R: G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create_weighted(
+            rng,
+            g1,
+            g2,
+            v,
+            1,
+            r,
+            Z,
+            R,
+            ballot_id,
+            candidate_id,
+            transcript,
+        )
+    }
+
+    /// Create a new proof that the vote is 0 or `weight`, rather than 0 or 1, where `v`
+    /// says which. The second sub-proof uses `Z - g1*weight` in place of `Z - g1`; with a
+    /// weight of 1, this is the same as `create`.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create_weighted(
+        mut rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        v: bool,
+        weight: u64,
+        mut r: G::Scalar,
+        Z: G::Point,
+        R: G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
     ) -> Self {
         // Generate the input for our genuine proof.
-        let random_scalar = G::Scalar::random(&mut rng);
-        let genuine_a = g1 * random_scalar;
-        let genuine_b = g2 * random_scalar;
+        let mut random_scalar = G::Scalar::random(&mut rng);
+        let genuine_a = g1.mul(random_scalar.clone());
+        let genuine_b = g2.mul(random_scalar.clone());
 
         // Generate our response and sub-challenge for the faked proof.
         let fake_response = G::Scalar::random(&mut rng);
@@ -105,13 +508,18 @@ impl<G: DreipGroup> VoteProof<G> {
         // Our fake_a varies depending on the vote.
         let fake_a = if v {
             // Fake proof for v=0, since v really equals 1.
-            g1 * fake_response + Z * fake_challenge
+            g1.mul(fake_response.clone()) + Z.clone() * fake_challenge.clone()
         } else {
-            // Fake proof for v=1, since v really equals 0.
-            g1 * fake_response + (Z - g1) * fake_challenge
+            // Fake proof for v=weight, since v really equals 0.
+            let g1_weight = if weight == 1 {
+                g1.point()
+            } else {
+                g1.mul(scalar_from_u64(weight))
+            };
+            g1.mul(fake_response.clone()) + (Z.clone() - g1_weight) * fake_challenge.clone()
         };
         // Our fake_b is always the same.
-        let fake_b = g2 * fake_response + R * fake_challenge;
+        let fake_b = g2.mul(fake_response.clone()) + R.clone() * fake_challenge.clone();
 
         // Ensure our `a` and `b` values are always in the right order (proof for v=0 first).
         let (a1, b1, a2, b2) = if v {
@@ -121,22 +529,25 @@ impl<G: DreipGroup> VoteProof<G> {
         };
 
         // Get our non-interactive challenge via hashing.
-        let challenge = G::Scalar::from_hash(&[
-            &g1.to_bytes(),
-            &g2.to_bytes(),
-            &Z.to_bytes(),
-            &R.to_bytes(),
-            &a1.to_bytes(),
-            &b1.to_bytes(),
-            &a2.to_bytes(),
-            &b2.to_bytes(),
-            ballot_id.as_ref(),
-            candidate_id.as_ref(),
-        ]);
+        let challenge = Self::compute_challenge(
+            &g1.point(),
+            &g2.point(),
+            &Z,
+            &R,
+            &a1,
+            &b1,
+            &a2,
+            &b2,
+            ballot_id,
+            candidate_id,
+            transcript,
+        );
         // Split this into sub-challenges.
-        let genuine_challenge = challenge - fake_challenge;
-        // Calculate the genuine response.
-        let genuine_response = random_scalar - r * genuine_challenge;
+        let genuine_challenge = challenge - fake_challenge.clone();
+        // Calculate the genuine response, then wipe the secrets.
+        let genuine_response = random_scalar.clone() - r.clone() * genuine_challenge.clone();
+        wipe(&mut random_scalar);
+        wipe(&mut r);
 
         // Re-order the values so (c1, r1) are always the proof for v=0 and
         // (c2, r2) are always the proof for v=1, regardless of which is fake.
@@ -159,58 +570,265 @@ impl<G: DreipGroup> VoteProof<G> {
         VoteProof { c1, c2, r1, r2 }
     }
 
-    /// Verify the given proof, returning `Some(())` if verification succeeds and `None` otherwise.
+    /// Verify the given proof, with the challenge recomputed with the given transcript
+    /// version.
     #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
     pub fn verify(
         &self,
         g1: G::Point,
         g2: G::Point,
         Z: G::Point,
         R: G::Point,
-        ballot_id: impl AsRef<[u8]>,
-        candidate_id: impl AsRef<[u8]>,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Result<(), ProofError> {
+        self.verify_weighted(g1, g2, Z, R, ballot_id, candidate_id, 1, transcript)
+    }
+
+    /// Verify the given proof as `verify` does, returning `Some(())` if verification
+    /// succeeds and `None` otherwise.
+    #[deprecated(note = "use `VoteProof::verify`, which reports why verification failed")]
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_opt(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        Z: G::Point,
+        R: G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
     ) -> Option<()> {
+        self.verify(g1, g2, Z, R, ballot_id, candidate_id, transcript)
+            .ok()
+    }
+
+    /// Verify the given proof that the vote is 0 or `weight`, as `verify` does for 0 or 1.
+    /// A proof for one weight does not verify with any other.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_weighted(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        Z: G::Point,
+        R: G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        weight: u64,
+        transcript: TranscriptVersion,
+    ) -> Result<(), ProofError> {
         // Reconstruct the `a` and `b` values.
-        let a1 = g1 * self.r1 + Z * self.c1;
-        let b1 = g2 * self.r1 + R * self.c1;
-        let a2 = g1 * self.r2 + (Z - g1) * self.c2;
-        let b2 = g2 * self.r2 + R * self.c2;
+        let a1 = G::msm(
+            &[g1.clone(), Z.clone()],
+            &[self.r1.clone(), self.c1.clone()],
+        );
+        let b1 = G::msm(
+            &[g2.clone(), R.clone()],
+            &[self.r1.clone(), self.c1.clone()],
+        );
+        // Since `a2 = g1*r2 + (Z - g1*weight)*c2`, the `g1` part joins the `g1*r2` term.
+        let a2 = G::msm(
+            &[g1.clone(), Z.clone()],
+            &[
+                self.r2.clone() - scalar_from_u64::<G::Scalar>(weight) * self.c2.clone(),
+                self.c2.clone(),
+            ],
+        );
+        let b2 = G::msm(
+            &[g2.clone(), R.clone()],
+            &[self.r2.clone(), self.c2.clone()],
+        );
 
         // Reconstruct the challenge value.
-        let challenge = G::Scalar::from_hash(&[
-            &g1.to_bytes(),
-            &g2.to_bytes(),
-            &Z.to_bytes(),
-            &R.to_bytes(),
-            &a1.to_bytes(),
-            &b1.to_bytes(),
-            &a2.to_bytes(),
-            &b2.to_bytes(),
-            ballot_id.as_ref(),
-            candidate_id.as_ref(),
-        ]);
+        let challenge = Self::compute_challenge(
+            &g1,
+            &g2,
+            &Z,
+            &R,
+            &a1,
+            &b1,
+            &a2,
+            &b2,
+            ballot_id,
+            candidate_id,
+            transcript,
+        );
 
         // Ensure that the challenge value matches.
-        if self.c1 + self.c2 == challenge {
-            Some(())
+        if (self.c1.clone() + self.c2.clone()).ct_eq(&challenge) {
+            Ok(())
         } else {
-            None
+            Err(ProofError::ChallengeMismatch)
         }
     }
 
+    /// The inputs to the challenge hash, in order, before encoding:
+    /// `g1`, `g2`, `Z`, `R`, `a1`, `b1`, `a2`, `b2`, then the ballot and candidate ids.
+    ///
+    /// Points are in their canonical `Serializable` encoding and ids are their raw bytes.
+    /// For a proof `(c1, c2, r1, r2)`, the verifier reconstructs `a1 = g1*r1 + Z*c1`,
+    /// `b1 = g2*r1 + R*c1`, `a2 = g1*r2 + (Z - g1)*c2`, and `b2 = g2*r2 + R*c2`. For a
+    /// weighted vote, `a2 = g1*r2 + (Z - g1*weight)*c2`; the weight itself is not hashed.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn challenge_input(
+        g1: &G::Point,
+        g2: &G::Point,
+        Z: &G::Point,
+        R: &G::Point,
+        a1: &G::Point,
+        b1: &G::Point,
+        a2: &G::Point,
+        b2: &G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+    ) -> Vec<Vec<u8>> {
+        vec![
+            g1.to_bytes(),
+            g2.to_bytes(),
+            Z.to_bytes(),
+            R.to_bytes(),
+            a1.to_bytes(),
+            b1.to_bytes(),
+            a2.to_bytes(),
+            b2.to_bytes(),
+            ballot_id.id_bytes().into_owned(),
+            candidate_id.id_bytes().into_owned(),
+        ]
+    }
+
+    /// Compute the challenge that `c1 + c2` must equal, by hashing the `challenge_input`
+    /// with the given transcript version:
+    /// - `V0` hashes the inputs concatenated, with `DreipScalar::from_hash`.
+    /// - `V1` prefixes the label `DREIP_VOTE_PROOF`, encodes with `TranscriptVersion::encode`,
+    ///   and hashes with `DreipScalar::from_hash`.
+    /// - `V2` encodes with `TranscriptVersion::encode`, and hashes with
+    ///   `DreipScalar::from_hash_with_tag` and the tag `VOTE-PWF`.
+    /// - `V3` hashes as `V2`, with the election id as a final input.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn compute_challenge(
+        g1: &G::Point,
+        g2: &G::Point,
+        Z: &G::Point,
+        R: &G::Point,
+        a1: &G::Point,
+        b1: &G::Point,
+        a2: &G::Point,
+        b2: &G::Point,
+        ballot_id: impl IdBytes,
+        candidate_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> G::Scalar {
+        let input = Self::challenge_input(g1, g2, Z, R, a1, b1, a2, b2, ballot_id, candidate_id);
+        let fields = input.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        transcript.challenge(VOTE_PROOF_LABEL, VOTE_PROOF_TAG, &fields)
+    }
+
     /// Turn this proof into a byte sequence, suitable for signing.
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        bytes.extend(self.c1.to_bytes());
-        bytes.extend(self.c2.to_bytes());
-        bytes.extend(self.r1.to_bytes());
-        bytes.extend(self.r2.to_bytes());
+    pub fn to_bytes(&self, transcript: TranscriptVersion) -> Vec<u8> {
+        ToBytes::to_bytes(self, transcript)
+    }
 
-        bytes
+    /// Decode a proof from its four scalars concatenated, as `to_bytes` gives them with
+    /// `TranscriptVersion::V0`, for compact storage outside JSON.
+    ///
+    /// Each scalar has the length of the group's scalar encodings, so this fails on any
+    /// other total length, as well as on non-canonical scalars.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let [c1, c2, r1, r2] = split_fields(bytes, [encoded_size(&G::Scalar::zero()); 4])?;
+        Some(Self {
+            c1: G::Scalar::from_bytes(c1)?,
+            c2: G::Scalar::from_bytes(c2)?,
+            r1: G::Scalar::from_bytes(r1)?,
+            r2: G::Scalar::from_bytes(r2)?,
+        })
     }
 }
 
-/// Zero-Knowledge Proof of well-formedness that a ballot has exactly one positive vote.
+impl<G: DreipGroup> ToBytes for VoteProof<G> {
+    fn write_bytes(&self, out: &mut Vec<u8>, transcript: TranscriptVersion) {
+        for scalar in [&self.c1, &self.c2, &self.r1, &self.r2] {
+            transcript.write_field(out, &scalar.to_bytes());
+        }
+    }
+
+    fn byte_len(&self, transcript: TranscriptVersion) -> usize {
+        let total = [&self.c1, &self.c2, &self.r1, &self.r2]
+            .into_iter()
+            .map(encoded_size)
+            .sum();
+        transcript.encoded_len(total, 4)
+    }
+}
+
+/// Why a `VoteProof` failed to verify.
+///
+/// The verifier reconstructs the proof's commitments from its responses, so the only check
+/// is that `c1 + c2` is the challenge they hash to. Changing any value of the proof, or
+/// verifying it against the wrong `Z`, `R`, ids, weight, or transcript version, fails it.
+/// A `BallotProof` says which of its equations failed with a `BallotProofFailure` instead.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum ProofError {
+    /// `c1 + c2` is not the challenge recomputed from the proof and its statement.
+    ChallengeMismatch,
+}
+
+impl Display for ProofError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChallengeMismatch => write!(f, "challenge mismatch"),
+        }
+    }
+}
+
+impl Error for ProofError {}
+
+/// Which check of a `BallotProof` failed.
+///
+/// The challenge hashes `a` and `b`, so changing `a`, `b`, or `r`, or verifying with the
+/// wrong ballot id or transcript version, breaks both equations at once; these cannot be
+/// told apart. A single failing equation means the proof itself is consistent, but the
+/// votes it was verified against are not what it proves.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum BallotProofFailure {
+    /// Only the equation `g1*r = a + X*c` over the sum of the votes' `Z` failed, so the
+    /// votes do not add up to the expected number of yes votes.
+    ZEquation,
+    /// Only the equation `g2*r = b + Y*c` over the sum of the votes' `R` failed. For a
+    /// referendum ballot, which only has this equation, it is reported for any failure.
+    REquation,
+    /// Both equations failed, as when the recomputed challenge is not the one the proof
+    /// was made with, or the proof is for different votes altogether.
+    ChallengeMismatch,
+    /// A referendum ballot does not have exactly one vote.
+    WrongVoteCount,
+    /// The proof's `a` or `b` is the identity, which no honestly created proof has.
+    IdentityPoint,
+}
+
+impl Display for BallotProofFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZEquation => write!(f, "Z-sum equation failed"),
+            Self::REquation => write!(f, "R-sum equation failed"),
+            Self::ChallengeMismatch => write!(f, "both equations failed (challenge mismatch)"),
+            Self::WrongVoteCount => write!(f, "wrong number of votes"),
+            Self::IdentityPoint => write!(f, "a or b is the identity point"),
+        }
+    }
+}
+
+impl Error for BallotProofFailure {}
+
+/// Zero-Knowledge Proof of well-formedness that a ballot has exactly `k` positive votes,
+/// where `k` is one unless the ballot was created with `Ballot::new_multi`.
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(bound = "")]
 pub struct BallotProof<G: DreipGroup> {
@@ -226,6 +844,14 @@ pub struct BallotProof<G: DreipGroup> {
 }
 
 impl<G: DreipGroup> BallotProof<G> {
+    /// The total length of the proof's two points and scalar, if fixed. `to_bytes` adds
+    /// the framing of its transcript version, as given by `TranscriptVersion::encoded_len`.
+    pub const BYTES: Option<usize> = total_byte_size(&[
+        <G::Point as Serializable>::BYTE_SIZE,
+        <G::Point as Serializable>::BYTE_SIZE,
+        <G::Scalar as Serializable>::BYTE_SIZE,
+    ]);
+
     /// Create a new proof.
     ///
     /// Based on: https://eprint.iacr.org/2014/364.pdf (Figure 2)
@@ -248,7 +874,8 @@ impl<G: DreipGroup> BallotProof<G> {
     ///         = g1 * resp
     /// ```
     ///    If the number of yes votes is anything other than 1, then `sum(vote.Z)` will be
-    ///    different and the proof would fail.
+    ///    different and the proof would fail. For a ballot with `k` yes votes, the observer
+    ///    uses `X = sum(vote.Z) - g1*k` instead; the proof itself is the same.
     /// 6. The observer can verify that `g2*resp = b + Y*c`, where `Y = sum(vote.R)` across all
     ///    votes in this ballot; this holds, as:
     /// ```equation
@@ -262,34 +889,259 @@ impl<G: DreipGroup> BallotProof<G> {
     ///
     /// The ballot id is part of the hash input for the challenge, tying the proof to the ballot.
     /// This requires that the ballot id is unique.
+    ///
+    /// The challenge is computed with `TranscriptVersion::CURRENT`.
+    ///
+    /// `r_sum` is trusted to be the sum of the votes' `r` values: if it is not, the proof is
+    /// created without complaint, but the ballot fails verification. Prefer `for_votes`,
+    /// which computes it from the votes themselves.
     pub fn new(
-        mut rng: impl RngCore + CryptoRng,
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        r_sum: G::Scalar,
+        ballot_id: impl IdBytes,
+    ) -> Self {
+        Self::new_with_transcript(rng, g1, g2, r_sum, ballot_id, TranscriptVersion::CURRENT)
+    }
+
+    /// Create a new proof for a ballot of the given votes, as `new` does with the sum of
+    /// their `r` values, so that the proof is always for the votes it is created with.
+    pub fn for_votes<'a>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        votes: impl IntoIterator<Item = &'a Vote<G, SecretsPresent<G>>>,
+        ballot_id: impl IdBytes,
+    ) -> Self
+    where
+        G: 'a,
+    {
+        Self::for_votes_with_transcript(rng, g1, g2, votes, ballot_id, TranscriptVersion::CURRENT)
+    }
+
+    /// Create a new proof as `for_votes` does, with the given transcript version.
+    pub fn for_votes_with_transcript<'a>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        votes: impl IntoIterator<Item = &'a Vote<G, SecretsPresent<G>>>,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self
+    where
+        G: 'a,
+    {
+        Self::create_for_votes(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            votes,
+            ballot_id,
+            transcript,
+        )
+    }
+
+    /// Create a new proof for the given votes with the given generators and transcript version.
+    ///
+    /// In debug builds, this checks that the proof verifies against the votes' `Z` and `R`
+    /// sums, for the total of their `v` values, which is their number of yes votes or, for a
+    /// weighted ballot, its weight.
+    #[allow(non_snake_case)]
+    pub(crate) fn create_for_votes<'a>(
+        rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        votes: impl IntoIterator<Item = &'a Vote<G, SecretsPresent<G>>>,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self
+    where
+        G: 'a,
+    {
+        let votes = votes.into_iter().collect::<Vec<_>>();
+        let r_sum = votes
+            .iter()
+            .map(|vote| vote.secrets.r.clone())
+            .fold(G::Scalar::zero(), |a, b| a + b);
+        let proof = Self::create(rng, g1, g2, r_sum, &ballot_id, transcript);
+
+        #[cfg(debug_assertions)]
+        {
+            let (Z_sum, R_sum, v_sum) = votes.iter().fold(
+                (
+                    G::Point::identity(),
+                    G::Point::identity(),
+                    G::Scalar::zero(),
+                ),
+                |(Z_sum, R_sum, v_sum), vote| {
+                    (
+                        Z_sum + vote.Z().clone(),
+                        R_sum + vote.R().clone(),
+                        v_sum + vote.secrets.v.clone(),
+                    )
+                },
+            );
+            if let Some(total) = v_sum.to_u64() {
+                let result = proof.verify_weighted(
+                    g1.point(),
+                    g2.point(),
+                    Z_sum,
+                    R_sum,
+                    &ballot_id,
+                    total,
+                    transcript,
+                );
+                debug_assert_eq!(result, Ok(()), "ballot proof does not match its votes");
+            }
+        }
+
+        proof
+    }
+
+    /// Create a new proof, using precomputed tables for the generators.
+    /// The result is identical to that of `new` given the same random number stream.
+    pub fn new_precomputed(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        r_sum: G::Scalar,
+        ballot_id: impl IdBytes,
+    ) -> Self {
+        Self::new_precomputed_with_transcript(
+            rng,
+            tables,
+            r_sum,
+            ballot_id,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new proof as `new_precomputed` does, with the given transcript version.
+    pub fn new_precomputed_with_transcript(
+        rng: impl RngCore + CryptoRng,
+        tables: &ElectionPrecomputed<G>,
+        r_sum: G::Scalar,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Table(&tables.g1),
+            &Generator::Table(&tables.g2),
+            r_sum,
+            ballot_id,
+            transcript,
+        )
+    }
+
+    /// Create a new proof with the given transcript version, as for
+    /// `VoteProof::new_with_transcript`.
+    pub fn new_with_transcript(
+        rng: impl RngCore + CryptoRng,
         g1: G::Point,
         g2: G::Point,
         r_sum: G::Scalar,
-        ballot_id: impl AsRef<[u8]>,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        Self::create(
+            rng,
+            &Generator::Plain(g1),
+            &Generator::Plain(g2),
+            r_sum,
+            ballot_id,
+            transcript,
+        )
+    }
+
+    /// Create a new proof with the given generators and transcript version.
+    pub(crate) fn create(
+        mut rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        mut r_sum: G::Scalar,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
     ) -> Self {
         // Generate the input for the challenge.
-        let random_scalar = G::Scalar::random(&mut rng);
-        let a = g1 * random_scalar;
-        let b = g2 * random_scalar;
+        let mut random_scalar = G::Scalar::random(&mut rng);
+        let a = g1.mul(random_scalar.clone());
+        let b = g2.mul(random_scalar.clone());
 
         // Get our non-interactive challenge via hashing.
-        let challenge = G::Scalar::from_hash(&[
-            &g1.to_bytes(),
-            &g2.to_bytes(),
-            &a.to_bytes(),
-            &b.to_bytes(),
-            ballot_id.as_ref(),
-        ]);
+        let challenge =
+            Self::compute_challenge(&g1.point(), &g2.point(), &a, &b, ballot_id, transcript);
 
-        // Calculate the response.
-        let r = random_scalar + challenge * r_sum;
+        // Calculate the response, then wipe the secrets.
+        let r = random_scalar.clone() + challenge * r_sum.clone();
+        wipe(&mut random_scalar);
+        wipe(&mut r_sum);
 
         BallotProof { a, b, r }
     }
 
-    /// Verify the given proof, returning `Some(())` if verification succeeds and `None` otherwise.
+    /// Create a new proof for a single-candidate referendum ballot.
+    ///
+    /// Proving the number of yes votes would reveal the only vote, so this only proves
+    /// knowledge of `r_sum`, as the second equation of `verify`; the vote proof already shows
+    /// that the vote is 0 or 1. The value `a` is a random point rather than `g1 * rand`, so
+    /// the proof does not verify with `verify_multi` for any number of yes votes.
+    pub(crate) fn create_referendum(
+        mut rng: impl RngCore + CryptoRng,
+        g1: &Generator<G>,
+        g2: &Generator<G>,
+        mut r_sum: G::Scalar,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Self {
+        let mut random_scalar = G::Scalar::random(&mut rng);
+        let a = g1.mul(G::Scalar::random(&mut rng));
+        let b = g2.mul(random_scalar.clone());
+
+        let challenge =
+            Self::compute_challenge(&g1.point(), &g2.point(), &a, &b, ballot_id, transcript);
+
+        // Calculate the response, then wipe the secrets.
+        let r = random_scalar.clone() + challenge * r_sum.clone();
+        wipe(&mut random_scalar);
+        wipe(&mut r_sum);
+
+        BallotProof { a, b, r }
+    }
+
+    /// Fail with `IdentityPoint` if `a` or `b` is the identity, before any equation is
+    /// checked against them.
+    fn check_not_identity(&self) -> Result<(), BallotProofFailure> {
+        if self.a == G::Point::identity() || self.b == G::Point::identity() {
+            Err(BallotProofFailure::IdentityPoint)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Verify a proof for a single-candidate referendum ballot, as created by
+    /// `Ballot::new_referendum`, succeeding if `g2*r = b + R_sum*c`.
+    #[allow(non_snake_case)]
+    pub fn verify_referendum(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        R_sum: G::Point,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotProofFailure> {
+        self.check_not_identity()?;
+        let challenge = self.challenge(&g1, &g2, &ballot_id.id_bytes(), transcript);
+        let minus_challenge = G::Scalar::zero() - challenge;
+        if G::msm(&[g2, R_sum], &[self.r.clone(), minus_challenge]).ct_eq(&self.b) {
+            Ok(())
+        } else {
+            Err(BallotProofFailure::REquation)
+        }
+    }
+
+    /// Verify the given proof, returning which equation failed if verification fails.
+    /// The challenge is recomputed with the given transcript version.
     #[allow(non_snake_case)]
     pub fn verify(
         &self,
@@ -297,38 +1149,287 @@ impl<G: DreipGroup> BallotProof<G> {
         g2: G::Point,
         Z_sum: G::Point,
         R_sum: G::Point,
-        ballot_id: impl AsRef<[u8]>,
-    ) -> Option<()> {
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotProofFailure> {
+        self.verify_multi(g1, g2, Z_sum, R_sum, ballot_id, 1, transcript)
+    }
+
+    /// Verify the given proof for a ballot with exactly `k` yes votes, as `verify` does
+    /// for exactly one.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_multi(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        Z_sum: G::Point,
+        R_sum: G::Point,
+        ballot_id: impl IdBytes,
+        k: usize,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotProofFailure> {
+        self.verify_weighted(g1, g2, Z_sum, R_sum, ballot_id, k as u64, transcript)
+    }
+
+    /// Verify the given proof for a weighted ballot, whose votes sum to `weight`, as
+    /// `verify` does for votes summing to 1. The proof itself does not depend on the sum.
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify_weighted(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        Z_sum: G::Point,
+        R_sum: G::Point,
+        ballot_id: impl IdBytes,
+        weight: u64,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotProofFailure> {
+        self.check_not_identity()?;
+
         // Reconstruct the challenge value.
-        let challenge = G::Scalar::from_hash(&[
-            &g1.to_bytes(),
-            &g2.to_bytes(),
-            &self.a.to_bytes(),
-            &self.b.to_bytes(),
-            ballot_id.as_ref(),
-        ]);
-
-        // Verify the first equation.
-        let X = Z_sum - g1;
-        if g1 * self.r != self.a + X * challenge {
-            return None;
+        let challenge = self.challenge(&g1, &g2, &ballot_id.id_bytes(), transcript);
+
+        // Verify the equations, rearranged as `g1*r - X*c = a` and `g2*r - Y*c = b`.
+        // Since `X = Z_sum - g1*weight`, the `g1` part of `X*c` joins the `g1*r` term.
+        let minus_challenge = G::Scalar::zero() - challenge.clone();
+        let g1_scalar = self.r.clone() + scalar_from_u64::<G::Scalar>(weight) * challenge;
+        let a_matches = G::msm(&[g1, Z_sum], &[g1_scalar, minus_challenge.clone()]).ct_eq(&self.a);
+        let b_matches = G::msm(&[g2, R_sum], &[self.r.clone(), minus_challenge]).ct_eq(&self.b);
+        match (a_matches, b_matches) {
+            (true, true) => Ok(()),
+            (false, true) => Err(BallotProofFailure::ZEquation),
+            (true, false) => Err(BallotProofFailure::REquation),
+            (false, false) => Err(BallotProofFailure::ChallengeMismatch),
+        }
+    }
+
+    /// Verify many proofs at once against the same generators, returning `Ok(())` if all of
+    /// them verify, or the index of the first failing item and how it failed otherwise.
+    ///
+    /// Rather than checking the two equations of each proof separately, this checks a single
+    /// random linear combination of all of them with one multi-scalar multiplication:
+    /// ```equation
+    ///         sum(x_i*(g1*r_i - X_i*c_i - a_i) + y_i*(g2*r_i - Y_i*c_i - b_i)) = 0
+    /// ```
+    /// The coefficients `x_i` and `y_i` are freshly drawn from `rng` on every call, so an
+    /// invalid proof passes with probability at most about `1/q` for group order `q`; they
+    /// must not be predictable to whoever produced the proofs. Valid proofs always pass.
+    ///
+    /// If the combination does not hold, the proofs are verified one at a time to find the
+    /// failing item. A single proof is verified directly. This is only faster than calling
+    /// `verify` for each proof if the group's `msm` is faster than its naive default.
+    ///
+    /// Items are `BallotProofInstance`s, or equivalently `(proof, Z_sum, R_sum, ballot_id)` tuples.
+    pub fn batch_verify<'a>(
+        g1: G::Point,
+        g2: G::Point,
+        items: impl IntoIterator<Item = impl Into<BallotProofInstance<'a, G>>>,
+        transcript: TranscriptVersion,
+        rng: impl RngCore + CryptoRng,
+    ) -> Result<(), (usize, BallotProofFailure)>
+    where
+        G: 'a,
+    {
+        Self::batch_verify_multi(g1, g2, items, 1, transcript, rng)
+    }
+
+    /// Verify many proofs at once for ballots with exactly `k` yes votes each, as
+    /// `batch_verify` does for exactly one.
+    pub fn batch_verify_multi<'a>(
+        g1: G::Point,
+        g2: G::Point,
+        items: impl IntoIterator<Item = impl Into<BallotProofInstance<'a, G>>>,
+        k: usize,
+        transcript: TranscriptVersion,
+        mut rng: impl RngCore + CryptoRng,
+    ) -> Result<(), (usize, BallotProofFailure)>
+    where
+        G: 'a,
+    {
+        let items = items.into_iter().map(Into::into).collect::<Vec<_>>();
+        if let [item] = &items[..] {
+            return item
+                .proof
+                .verify_multi(
+                    g1,
+                    g2,
+                    item.Z_sum.clone(),
+                    item.R_sum.clone(),
+                    item.ballot_id,
+                    k,
+                    transcript,
+                )
+                .map_err(|failure| (0, failure));
+        }
+
+        // Degenerate proofs fail as they would alone, before they are combined.
+        for (i, item) in items.iter().enumerate() {
+            item.proof
+                .check_not_identity()
+                .map_err(|failure| (i, failure))?;
+        }
+
+        let k_scalar = scalar_from_u64::<G::Scalar>(k as u64);
+
+        let mut points = Vec::with_capacity(4 * items.len() + 2);
+        let mut scalars = Vec::with_capacity(4 * items.len() + 2);
+        let mut g1_scalar = G::Scalar::zero();
+        let mut g2_scalar = G::Scalar::zero();
+        for item in items.iter() {
+            let challenge = item.proof.challenge(&g1, &g2, item.ballot_id, transcript);
+            let x = G::Scalar::random(&mut rng);
+            let y = G::Scalar::random(&mut rng);
+
+            // Since `X = Z_sum - g1*k`, the `g1` part of `X*c` joins the `g1*r` term.
+            g1_scalar = g1_scalar
+                + x.clone() * (item.proof.r.clone() + k_scalar.clone() * challenge.clone());
+            g2_scalar = g2_scalar + y.clone() * item.proof.r.clone();
+            points.push(item.Z_sum.clone());
+            scalars.push(G::Scalar::zero() - x.clone() * challenge.clone());
+            points.push(item.proof.a.clone());
+            scalars.push(G::Scalar::zero() - x);
+            points.push(item.R_sum.clone());
+            scalars.push(G::Scalar::zero() - y.clone() * challenge);
+            points.push(item.proof.b.clone());
+            scalars.push(G::Scalar::zero() - y);
         }
+        points.push(g1.clone());
+        scalars.push(g1_scalar);
+        points.push(g2.clone());
+        scalars.push(g2_scalar);
 
-        // Verify the second equation.
-        if g2 * self.r != self.b + R_sum * challenge {
-            return None;
+        if G::msm(&points, &scalars) == G::Point::identity() {
+            return Ok(());
         }
 
-        Some(())
+        // At least one proof is invalid, find the first.
+        for (i, item) in items.iter().enumerate() {
+            item.proof
+                .verify_multi(
+                    g1.clone(),
+                    g2.clone(),
+                    item.Z_sum.clone(),
+                    item.R_sum.clone(),
+                    item.ballot_id,
+                    k,
+                    transcript,
+                )
+                .map_err(|failure| (i, failure))?;
+        }
+        Ok(())
+    }
+
+    /// Compute the challenge for this proof.
+    fn challenge(
+        &self,
+        g1: &G::Point,
+        g2: &G::Point,
+        ballot_id: &[u8],
+        transcript: TranscriptVersion,
+    ) -> G::Scalar {
+        Self::compute_challenge(g1, g2, &self.a, &self.b, ballot_id, transcript)
+    }
+
+    /// The inputs to the challenge hash, in order, before encoding: `g1`, `g2`, `a`, `b`,
+    /// then the ballot id.
+    ///
+    /// Points are in their canonical `Serializable` encoding and the id is its raw bytes.
+    pub fn challenge_input(
+        g1: &G::Point,
+        g2: &G::Point,
+        a: &G::Point,
+        b: &G::Point,
+        ballot_id: impl IdBytes,
+    ) -> Vec<Vec<u8>> {
+        vec![
+            g1.to_bytes(),
+            g2.to_bytes(),
+            a.to_bytes(),
+            b.to_bytes(),
+            ballot_id.id_bytes().into_owned(),
+        ]
+    }
+
+    /// Compute the challenge `c` for a proof, by hashing the `challenge_input` with the
+    /// given transcript version, as for `VoteProof::compute_challenge` but with the label
+    /// `DREIP_BALLOT_PROOF` and the tag `BALLOT-PWF`.
+    pub fn compute_challenge(
+        g1: &G::Point,
+        g2: &G::Point,
+        a: &G::Point,
+        b: &G::Point,
+        ballot_id: impl IdBytes,
+        transcript: TranscriptVersion,
+    ) -> G::Scalar {
+        let input = Self::challenge_input(g1, g2, a, b, ballot_id);
+        let fields = input.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        transcript.challenge(BALLOT_PROOF_LABEL, BALLOT_PROOF_TAG, &fields)
     }
 
     /// Turn this proof into a byte sequence, suitable for signing.
-    pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = Vec::new();
-        bytes.extend(self.a.to_bytes());
-        bytes.extend(self.b.to_bytes());
-        bytes.extend(self.r.to_bytes());
+    pub fn to_bytes(&self, transcript: TranscriptVersion) -> Vec<u8> {
+        ToBytes::to_bytes(self, transcript)
+    }
 
-        bytes
+    /// Decode a proof from `a`, `b`, and `r` concatenated, as `to_bytes` gives them with
+    /// `TranscriptVersion::V0`, for compact storage outside JSON.
+    ///
+    /// Points have the length of the generator's encoding and the scalar that of the
+    /// group's scalar encodings, so this fails on any other total length, as well as on
+    /// non-canonical points or scalars.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let point_len = encoded_size(&G::Point::generator());
+        let scalar_len = encoded_size(&G::Scalar::zero());
+        let [a, b, r] = split_fields(bytes, [point_len, point_len, scalar_len])?;
+        Some(Self {
+            a: G::Point::from_bytes(a)?,
+            b: G::Point::from_bytes(b)?,
+            r: G::Scalar::from_bytes(r)?,
+        })
+    }
+}
+
+impl<G: DreipGroup> ToBytes for BallotProof<G> {
+    fn write_bytes(&self, out: &mut Vec<u8>, transcript: TranscriptVersion) {
+        transcript.write_field(out, &self.a.to_bytes());
+        transcript.write_field(out, &self.b.to_bytes());
+        transcript.write_field(out, &self.r.to_bytes());
+    }
+
+    fn byte_len(&self, transcript: TranscriptVersion) -> usize {
+        let total = encoded_size(&self.a) + encoded_size(&self.b) + encoded_size(&self.r);
+        transcript.encoded_len(total, 3)
+    }
+}
+
+/// A `BallotProof` together with the values it is verified against, for `BallotProof::batch_verify`.
+#[allow(non_snake_case)]
+#[derive(Debug, Clone)]
+pub struct BallotProofInstance<'a, G: DreipGroup> {
+    /// The proof.
+    pub proof: &'a BallotProof<G>,
+    /// The sum of the `Z` values of all votes in the ballot.
+    pub Z_sum: G::Point,
+    /// The sum of the `R` values of all votes in the ballot.
+    pub R_sum: G::Point,
+    /// The ballot id.
+    pub ballot_id: &'a [u8],
+}
+
+#[allow(non_snake_case)]
+impl<'a, G: DreipGroup> From<(&'a BallotProof<G>, G::Point, G::Point, &'a [u8])>
+    for BallotProofInstance<'a, G>
+{
+    fn from(
+        (proof, Z_sum, R_sum, ballot_id): (&'a BallotProof<G>, G::Point, G::Point, &'a [u8]),
+    ) -> Self {
+        Self {
+            proof,
+            Z_sum,
+            R_sum,
+            ballot_id,
+        }
     }
 }
@@ -0,0 +1,276 @@
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{BuildHasher, Hash};
+
+use crate::ballots::{
+    Ballot, BallotCreationError, BallotError, ConfirmError, NoSecrets, SecretsPresent,
+    VerificationError, VoteError, VoteSecrets,
+};
+use crate::election::{validate_generators, verify_totals, CandidateTotals, ParameterError};
+use crate::group::DreipGroup;
+use crate::ids::IdBytes;
+use crate::pwf::TranscriptVersion;
+
+/// The first field of every question's ballot id.
+const QUESTION_LABEL: &[u8] = b"DREIP_QUESTION";
+
+/// The id that a question's votes and ballot proof are created and verified with, in place
+/// of the ballot id: the label `DREIP_QUESTION`, the ballot id, and the question id, each
+/// prefixed with its length as 8 big-endian bytes, whatever the transcript version.
+///
+/// As the ballot id is part of every proof's challenge, this binds the proofs to their
+/// question as well as their ballot, and no two questions or ballots share an id.
+pub fn question_ballot_id(ballot_id: impl IdBytes, question_id: impl IdBytes) -> Vec<u8> {
+    TranscriptVersion::V1.encode(&[
+        QUESTION_LABEL,
+        &ballot_id.id_bytes(),
+        &question_id.id_bytes(),
+    ])
+}
+
+/// An error due to an election of multi-question ballots failing verification.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum QuestionVerificationError<Q, B, C> {
+    /// The election generators are invalid, so no ballot work was done.
+    InvalidParameters(ParameterError),
+    /// The ballot does not answer exactly the questions that have totals.
+    WrongQuestions { ballot_id: B },
+    /// A question failed to verify, as its own election would.
+    Question {
+        question_id: Q,
+        error: VerificationError<B, C>,
+    },
+}
+
+/// A ballot answering several questions, each with its own candidates: a yes for exactly
+/// one candidate per question, with a ballot proof for each.
+///
+/// Each question's votes and proof are made with `question_ballot_id`, so they cannot be
+/// moved to another question or ballot.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(bound(
+    serialize = "Q: Serialize, C: Serialize, S: Serialize",
+    deserialize = "Q: Deserialize<'de>, C: Deserialize<'de>, S: Deserialize<'de>"
+))]
+pub struct MultiBallot<Q, C, G, S>
+where
+    Q: Ord,
+    C: Ord,
+    G: DreipGroup,
+{
+    /// Map from question IDs to the ballot for each question, in order of question ID.
+    pub questions: BTreeMap<Q, Ballot<C, G, S>>,
+}
+
+impl<Q, C, G> MultiBallot<Q, C, G, SecretsPresent<G>>
+where
+    Q: Ord + Clone + IdBytes,
+    C: Hash + Ord + Clone + IdBytes,
+    G: DreipGroup,
+    G::Scalar: Eq,
+{
+    /// Create a new ballot from a yes candidate and no candidates for each question.
+    /// This will fail, naming the question, if any question IDs are duplicates, or any
+    /// question's ballot cannot be created as by `Ballot::new`.
+    pub fn new<B, I>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        questions: impl IntoIterator<Item = (Q, C, I)>,
+    ) -> Result<Self, (Q, BallotCreationError<C>)>
+    where
+        B: IdBytes,
+        I: IntoIterator<Item = C>,
+    {
+        Self::new_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            questions,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new ballot as `new` does, with proofs using the given transcript version.
+    pub fn new_with_transcript<B, I>(
+        mut rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        questions: impl IntoIterator<Item = (Q, C, I)>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, (Q, BallotCreationError<C>)>
+    where
+        B: IdBytes,
+        I: IntoIterator<Item = C>,
+    {
+        let mut ballots = BTreeMap::new();
+        for (question_id, yes_candidate, no_candidates) in questions {
+            if ballots.contains_key(&question_id) {
+                return Err((question_id, BallotCreationError::DuplicateQuestion));
+            }
+            let result = Ballot::new_with_transcript(
+                &mut rng,
+                g1.clone(),
+                g2.clone(),
+                question_ballot_id(&ballot_id, &question_id),
+                yes_candidate,
+                no_candidates,
+                transcript,
+            );
+            match result {
+                Ok(ballot) => ballots.insert(question_id, ballot),
+                Err(e) => return Err((question_id, e)),
+            };
+        }
+        Ok(Self { questions: ballots })
+    }
+
+    /// Confirm this ballot, discarding all `r` and `v` values.
+    /// If `totals` is provided, each question's candidate totals will be appropriately
+    /// incremented before discarding the values.
+    ///
+    /// If provided, `totals` must contain an entry for every candidate of every question.
+    /// Otherwise, this fails naming the question and the first missing candidate, without
+    /// changing any of the totals; the ballot is dropped unconfirmed.
+    #[allow(clippy::type_complexity)]
+    pub fn confirm(
+        self,
+        mut totals: Option<&mut HashMap<Q, HashMap<C, &mut CandidateTotals<G>>>>,
+    ) -> Result<MultiBallot<Q, C, G, NoSecrets>, (Q, ConfirmError<C>)>
+    where
+        Q: Hash,
+    {
+        // Check that all totals are present before incrementing any.
+        if let Some(totals) = totals.as_deref() {
+            for (question_id, ballot) in self.questions.iter() {
+                let question_totals = totals.get(question_id);
+                let missing = ballot.votes().keys().find(|candidate| {
+                    !question_totals.is_some_and(|totals| totals.contains_key(*candidate))
+                });
+                if let Some(candidate) = missing {
+                    let error = ConfirmError::MissingCandidate(candidate.clone());
+                    return Err((question_id.clone(), error));
+                }
+            }
+        }
+
+        let mut questions = BTreeMap::new();
+        for (question_id, ballot) in self.questions {
+            let question_totals = totals
+                .as_deref_mut()
+                .map(|totals| totals.get_mut(&question_id).expect("Already checked"));
+            let Ok(ballot) = ballot.confirm(question_totals) else {
+                unreachable!("Already checked");
+            };
+            questions.insert(question_id, ballot);
+        }
+        Ok(MultiBallot { questions })
+    }
+}
+
+impl<Q, C, G, S> MultiBallot<Q, C, G, S>
+where
+    Q: Ord + Clone + IdBytes,
+    C: Ord + Clone + IdBytes,
+    G: DreipGroup,
+    S: VoteSecrets<G>,
+{
+    /// Verify the ballot for each question and all votes within it, as `Ballot::verify`
+    /// does with the question's id, naming the first question to fail.
+    pub fn verify<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        transcript: TranscriptVersion,
+    ) -> Result<(), (Q, BallotError<B, C>)>
+    where
+        B: IdBytes,
+    {
+        for (question_id, ballot) in self.questions.iter() {
+            let id = question_ballot_id(&ballot_id, question_id);
+            if let Err(e) = ballot.verify(g1.clone(), g2.clone(), id, transcript) {
+                // Report the ballot id the caller gave, rather than the question's.
+                let e = match e {
+                    BallotError::Vote(VoteError {
+                        candidate_id,
+                        reason,
+                        ..
+                    }) => BallotError::Vote(VoteError {
+                        ballot_id,
+                        candidate_id,
+                        reason,
+                    }),
+                    BallotError::BallotProof { reason, .. } => {
+                        BallotError::BallotProof { ballot_id, reason }
+                    }
+                    BallotError::Empty { .. } => BallotError::Empty { ballot_id },
+                };
+                return Err((question_id.clone(), e));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Verify all of the given multi-question ballots, and the total tallies of each question,
+/// as `verify_election` does for each question on its own.
+///
+/// Every ballot must answer exactly the questions in `totals`, and each question's totals
+/// must have exactly the candidates of its ballots.
+#[allow(clippy::type_complexity)]
+pub fn verify_election_questions<G, B, Q, C, S, HB, HQ, HC>(
+    g1: G::Point,
+    g2: G::Point,
+    ballots: &HashMap<B, MultiBallot<Q, C, G, S>, HB>,
+    totals: &HashMap<Q, HashMap<C, CandidateTotals<G>, HC>, HQ>,
+    transcript: TranscriptVersion,
+) -> Result<(), QuestionVerificationError<Q, B, C>>
+where
+    G: DreipGroup,
+    B: IdBytes + Clone,
+    Q: IdBytes + Eq + Hash + Clone + Ord,
+    C: IdBytes + Eq + Hash + Clone + Ord,
+    S: VoteSecrets<G>,
+    HB: BuildHasher,
+    HQ: BuildHasher,
+    HC: BuildHasher,
+{
+    validate_generators::<G>(&g1, &g2).map_err(QuestionVerificationError::InvalidParameters)?;
+
+    // Verify individual ballots.
+    for (ballot_id, ballot) in ballots.iter() {
+        if ballot.questions.len() != totals.len()
+            || !ballot.questions.keys().all(|q| totals.contains_key(q))
+        {
+            return Err(QuestionVerificationError::WrongQuestions {
+                ballot_id: ballot_id.clone(),
+            });
+        }
+        ballot
+            .verify(g1.clone(), g2.clone(), ballot_id, transcript)
+            .map_err(|(question_id, e)| QuestionVerificationError::Question {
+                question_id,
+                error: VerificationError::Ballot(e.cloned()),
+            })?;
+    }
+
+    // Verify the totals of each question.
+    for (question_id, question_totals) in totals.iter() {
+        let question_ballots = ballots
+            .values()
+            .map(|ballot| &ballot.questions[question_id]);
+        verify_totals(g1.clone(), g2.clone(), question_ballots, question_totals).map_err(
+            |error| QuestionVerificationError::Question {
+                question_id: question_id.clone(),
+                error,
+            },
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Display, Formatter};
+
+use crate::ballots::{
+    write_commitments, Ballot, BallotError, VoteSecrets, CANONICAL_MAGIC, CANONICAL_VERSION,
+};
+use crate::election::to_hex;
+use crate::group::{DreipGroup, DreipPublicKey, ReceiptSigner, Serializable, SignError};
+use crate::ids::IdBytes;
+use crate::pwf::TranscriptVersion;
+
+/// The first field of every receipt's signed bytes, separating them from other signatures.
+const RECEIPT_LABEL: &[u8] = b"DREIP_RECEIPT";
+
+/// The first field of every signing context's bytes.
+const SIGNING_CONTEXT_LABEL: &[u8] = b"DREIP_SIGNING_CONTEXT";
+
+/// What a receipt is for, bound into its signature alongside the ballot.
+///
+/// The fields are encoded canonically by `to_bytes`, so two implementations that agree on
+/// their values sign identical messages.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Deserialize, Serialize)]
+pub struct SigningContext {
+    /// The ID of the election.
+    pub election_id: String,
+
+    /// The ID of the question on the ballot.
+    pub question_id: String,
+
+    /// The confirmation code shown to the voter.
+    pub confirmation_code: String,
+
+    /// Any further fields, by name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra: BTreeMap<String, String>,
+}
+
+impl SigningContext {
+    /// Create a context with the given IDs and confirmation code, and no extra fields.
+    pub fn new(
+        election_id: impl Into<String>,
+        question_id: impl Into<String>,
+        confirmation_code: impl Into<String>,
+    ) -> Self {
+        Self {
+            election_id: election_id.into(),
+            question_id: question_id.into(),
+            confirmation_code: confirmation_code.into(),
+            extra: BTreeMap::new(),
+        }
+    }
+
+    /// Encode this context: a label, the IDs, the confirmation code, then each extra field's
+    /// name and value in order of name, all as UTF-8 and each prefixed with its length as an
+    /// 8-byte big-endian integer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut fields: Vec<&[u8]> = vec![
+            SIGNING_CONTEXT_LABEL,
+            self.election_id.as_bytes(),
+            self.question_id.as_bytes(),
+            self.confirmation_code.as_bytes(),
+        ];
+        for (name, value) in self.extra.iter() {
+            fields.push(name.as_bytes());
+            fields.push(value.as_bytes());
+        }
+        TranscriptVersion::V1.encode(&fields)
+    }
+}
+
+/// The bytes signed for a receipt, given the bytes of its ballot id and the ballot's
+/// `canonical_bytes`; see `Receipt::to_bytes`.
+pub(crate) fn signed_bytes(
+    ballot_id: &[u8],
+    ballot: &[u8],
+    context: Option<&SigningContext>,
+) -> Vec<u8> {
+    let context = context.map(SigningContext::to_bytes);
+    let mut fields: Vec<&[u8]> = vec![RECEIPT_LABEL, ballot_id, ballot];
+    fields.extend(context.as_deref());
+    [
+        &[CANONICAL_MAGIC, CANONICAL_VERSION][..],
+        &TranscriptVersion::V1.encode(&fields),
+    ]
+    .concat()
+}
+
+/// An error due to a receipt failing verification.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum ReceiptError<B, C> {
+    /// The signature does not match the receipt's contents.
+    Signature,
+    /// The ballot failed to verify.
+    Ballot(BallotError<B, C>),
+}
+
+/// A ballot signed by the election, as handed to the voter.
+///
+/// The signature covers the ballot id, the ballot, and any `SigningContext`, in their
+/// canonical encoding; see `to_bytes`.
+#[derive(Deserialize, Serialize)]
+#[serde(bound(
+    serialize = "B: Serialize, C: Serialize, S: Serialize",
+    deserialize = "B: Deserialize<'de>, C: Deserialize<'de>, S: Deserialize<'de>"
+))]
+pub struct Receipt<B, C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The ballot ID.
+    pub ballot_id: B,
+
+    /// The ballot.
+    pub ballot: Ballot<C, G, S>,
+
+    /// The context covered by the signature, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<SigningContext>,
+
+    /// The transcript version of the election, used for the ballot's proofs.
+    pub transcript: TranscriptVersion,
+
+    /// The election's signature of the receipt.
+    #[serde(with = "crate::group::serde_bytestring")]
+    pub signature: G::Signature,
+}
+
+impl<B, C, G, S> Receipt<B, C, G, S>
+where
+    B: IdBytes + Clone,
+    C: IdBytes + Clone + Ord,
+    G: DreipGroup,
+    S: VoteSecrets<G>,
+{
+    /// The bytes signed for a receipt with the given contents: `CANONICAL_MAGIC` and
+    /// `CANONICAL_VERSION`, then a label, the ballot id, the ballot's `canonical_bytes`, and
+    /// the context's bytes if any, as length-prefixed fields.
+    pub fn to_bytes(
+        ballot: &Ballot<C, G, S>,
+        ballot_id: &B,
+        context: Option<&SigningContext>,
+    ) -> Vec<u8> {
+        signed_bytes(&ballot_id.id_bytes(), &ballot.canonical_bytes(), context)
+    }
+
+    /// Sign a receipt with the given signer, such as an HSM; see `Election::issue_receipt`
+    /// for signing with the election's private key.
+    pub fn sign(
+        signer: &dyn ReceiptSigner<G>,
+        ballot: Ballot<C, G, S>,
+        ballot_id: B,
+        context: Option<&SigningContext>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, SignError> {
+        let bytes = Self::to_bytes(&ballot, &ballot_id, context);
+        Ok(Self {
+            signature: signer.sign(&bytes)?,
+            ballot_id,
+            ballot,
+            context: context.cloned(),
+            transcript,
+        })
+    }
+
+    /// Verify the signature with the election's public key, and the ballot's proofs with its
+    /// generators.
+    pub fn verify(
+        &self,
+        public_key: &G::PublicKey,
+        g1: G::Point,
+        g2: G::Point,
+    ) -> Result<(), ReceiptError<B, C>> {
+        let bytes = Self::to_bytes(&self.ballot, &self.ballot_id, self.context.as_ref());
+        if !public_key.verify(&bytes, &self.signature) {
+            return Err(ReceiptError::Signature);
+        }
+        self.ballot
+            .verify(g1, g2, &self.ballot_id, self.transcript)
+            .map_err(|e| ReceiptError::Ballot(e.cloned()))
+    }
+}
+
+impl<B, C, G, S> Receipt<B, C, G, S>
+where
+    B: Display,
+    C: Ord + Display,
+    G: DreipGroup,
+{
+    /// A summary of this receipt to read to the voter, such as
+    /// `Receipt for ballot 1234, signature 8775738e`, followed by a line for each
+    /// candidate as in `Ballot::summary`. Hex values are cut to their first `prefix_len`
+    /// bytes.
+    ///
+    /// This does not show the voter's choice, even if the ballot has its secrets; for an
+    /// audited ballot, see `Ballot::summary`.
+    pub fn summary(&self, prefix_len: usize) -> String {
+        let signature = self.signature.to_bytes();
+        let mut summary = format!(
+            "Receipt for ballot {}, signature {}",
+            self.ballot_id,
+            to_hex(&signature[..prefix_len.min(signature.len())])
+        );
+        write_commitments(&mut summary, self.ballot.votes(), prefix_len);
+        summary
+    }
+}
+
+impl<B, C, G, S> Debug for Receipt<B, C, G, S>
+where
+    B: Debug,
+    C: Ord,
+    G: DreipGroup,
+    Ballot<C, G, S>: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receipt")
+            .field("ballot_id", &self.ballot_id)
+            .field("ballot", &self.ballot)
+            .field("context", &self.context)
+            .field("transcript", &self.transcript)
+            .field("signature", &self.signature.to_bytestring())
+            .finish()
+    }
+}
+
+impl<B, C, G, S> Clone for Receipt<B, C, G, S>
+where
+    B: Clone,
+    C: Ord,
+    G: DreipGroup,
+    G::Signature: Clone,
+    Ballot<C, G, S>: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            ballot_id: self.ballot_id.clone(),
+            ballot: self.ballot.clone(),
+            context: self.context.clone(),
+            transcript: self.transcript,
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+impl<B, C, G, S> PartialEq for Receipt<B, C, G, S>
+where
+    B: PartialEq,
+    C: Ord,
+    G: DreipGroup,
+    Ballot<C, G, S>: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.ballot_id == other.ballot_id
+            && self.ballot == other.ballot
+            && self.context == other.context
+            && self.transcript == other.transcript
+            && self.signature.to_bytes() == other.signature.to_bytes()
+    }
+}
+
+impl<B, C, G, S> Eq for Receipt<B, C, G, S>
+where
+    B: Eq,
+    C: Ord,
+    G: DreipGroup,
+    Ballot<C, G, S>: Eq,
+{
+}
@@ -0,0 +1,183 @@
+use rand::{CryptoRng, RngCore};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use crate::ballots::{
+    AuditedBallot, BallotCreationError, ConfirmError, ConfirmedBallot, PendingBallot,
+};
+use crate::election::{CandidateTotals, Election, ElectionResults};
+use crate::group::DreipGroup;
+use crate::ids::IdBytes;
+
+/// The bookkeeping of a running election: the candidates, their totals, and the confirmed
+/// and audited ballots, ready to publish as `ElectionResults`.
+///
+/// Ballots are cast with `cast`, giving a `PendingHandle` that must be passed to either
+/// `audit` or `confirm`. Each ballot id can be cast only once, even if its handle is dropped
+/// without being audited or confirmed.
+#[derive(Debug)]
+pub struct ElectionSession<B, C, G>
+where
+    B: Eq + Hash,
+    C: Eq + Hash + Ord,
+    G: DreipGroup,
+{
+    election: Election<G>,
+    candidates: Vec<C>,
+    totals: HashMap<C, CandidateTotals<G>>,
+    confirmed: HashMap<B, ConfirmedBallot<C, G>>,
+    audited: HashMap<B, AuditedBallot<C, G>>,
+    ids: HashSet<B>,
+}
+
+/// A ballot cast in an `ElectionSession`, which must be audited or confirmed.
+#[derive(Debug)]
+pub struct PendingHandle<B, C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    ballot_id: B,
+    ballot: PendingBallot<C, G>,
+}
+
+impl<B, C, G> PendingHandle<B, C, G>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The ID of the cast ballot.
+    pub fn ballot_id(&self) -> &B {
+        &self.ballot_id
+    }
+}
+
+impl<B, C, G> ElectionSession<B, C, G>
+where
+    B: IdBytes + Eq + Hash + Clone,
+    C: IdBytes + Clone + Eq + Hash + Ord,
+    G: DreipGroup,
+{
+    /// Start a session for the given election and candidates, with zero totals.
+    pub fn new(election: Election<G>, candidates: impl IntoIterator<Item = C>) -> Self {
+        let candidates = candidates.into_iter().collect::<Vec<_>>();
+        let totals = candidates
+            .iter()
+            .map(|candidate| (candidate.clone(), CandidateTotals::default()))
+            .collect();
+        Self {
+            election,
+            candidates,
+            totals,
+            confirmed: HashMap::new(),
+            audited: HashMap::new(),
+            ids: HashSet::new(),
+        }
+    }
+
+    /// The election this session runs.
+    pub fn election(&self) -> &Election<G> {
+        &self.election
+    }
+
+    /// Cast a ballot voting yes for `candidate` and no for every other candidate.
+    ///
+    /// This fails if the ballot id was already cast in this session, or the candidate is
+    /// not one of the session's candidates.
+    pub fn cast(
+        &mut self,
+        rng: impl RngCore + CryptoRng,
+        ballot_id: B,
+        candidate: C,
+    ) -> Result<PendingHandle<B, C, G>, BallotCreationError<C>>
+    where
+        G::Scalar: Eq,
+    {
+        if self.ids.contains(&ballot_id) {
+            return Err(BallotCreationError::DuplicateBallot);
+        }
+        let ballot =
+            self.election
+                .create_ballot_from(rng, &self.candidates, &ballot_id, candidate)?;
+        self.ids.insert(ballot_id.clone());
+        Ok(PendingHandle {
+            ballot_id,
+            ballot: PendingBallot::from(ballot),
+        })
+    }
+
+    /// Audit the cast ballot, keeping it with its secrets for publication, and return it
+    /// for the voter to check.
+    ///
+    /// This fails only if the handle was cast in another session, and its ballot id was
+    /// already confirmed or audited in this one.
+    pub fn audit(
+        &mut self,
+        handle: PendingHandle<B, C, G>,
+    ) -> Result<&AuditedBallot<C, G>, ConfirmError<C>>
+    where
+        G::Scalar: Eq,
+    {
+        self.check_unused(&handle.ballot_id)?;
+        Ok(self
+            .audited
+            .entry(handle.ballot_id)
+            .or_insert(handle.ballot.audit()))
+    }
+
+    /// Confirm the cast ballot, adding its votes to the totals, and return it.
+    ///
+    /// This fails only if the handle was cast in another session, with other candidates or
+    /// a ballot id already confirmed or audited in this one.
+    pub fn confirm(
+        &mut self,
+        handle: PendingHandle<B, C, G>,
+    ) -> Result<&ConfirmedBallot<C, G>, ConfirmError<C>>
+    where
+        G::Scalar: Eq,
+    {
+        self.check_unused(&handle.ballot_id)?;
+        let (ballot, delta) = handle.ballot.confirm_with_delta();
+        // Change none of the totals unless all are present.
+        if let Some(candidate) = delta.keys().filter(|c| !self.totals.contains_key(*c)).min() {
+            return Err(ConfirmError::MissingCandidate(candidate.clone()));
+        }
+        for (candidate, contribution) in delta.iter() {
+            let totals = self.totals.get_mut(candidate).expect("Already checked");
+            totals.merge(contribution);
+        }
+        Ok(self.confirmed.entry(handle.ballot_id).or_insert(ballot))
+    }
+
+    /// Fail if the ballot id was already confirmed or audited, as it can be only by a handle
+    /// from another session.
+    fn check_unused(&self, ballot_id: &B) -> Result<(), ConfirmError<C>> {
+        if self.confirmed.contains_key(ballot_id) || self.audited.contains_key(ballot_id) {
+            return Err(ConfirmError::DuplicateBallot);
+        }
+        Ok(())
+    }
+
+    /// The current totals of each candidate.
+    pub fn totals(&self) -> &HashMap<C, CandidateTotals<G>> {
+        &self.totals
+    }
+
+    /// Finish the session, collecting the confirmed and audited ballots and the totals into
+    /// the results to publish.
+    pub fn into_results(self) -> ElectionResults<B, C, G> {
+        let mut results = self.election.results(
+            self.confirmed
+                .into_iter()
+                .map(|(id, ballot)| (id, ballot.ballot))
+                .collect(),
+            self.totals,
+        );
+        results.audited = self
+            .audited
+            .into_iter()
+            .map(|(id, ballot)| (id, ballot.ballot))
+            .collect();
+        results
+    }
+}
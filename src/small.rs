@@ -0,0 +1,345 @@
+//! A compact ballot representation for elections with few candidates.
+//!
+//! `Ballot` keeps its votes in a `BTreeMap`, which allocates a node per handful of
+//! candidates and chases pointers on every lookup. [`SmallBallot`] keeps them in a single
+//! `Vec` sorted by candidate ID instead, so each ballot holds one allocation for its votes
+//! however it is iterated. For rosters of a dozen or so candidates, lookups by binary search
+//! are as fast as the map's.
+//!
+//! Both implement `BallotLike`, so elections verify over either, and both serialize the
+//! same way, so dumps are interchangeable.
+
+use rand::{CryptoRng, RngCore};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::ballots::{
+    zr_sums, Ballot, BallotCreationError, BallotError, BallotLike, ConfirmError, NoSecrets,
+    SecretsPresent, Vote, VoteSecrets,
+};
+use crate::election::CandidateTotals;
+use crate::group::DreipGroup;
+use crate::ids::IdBytes;
+use crate::pwf::{BallotProof, TranscriptVersion};
+
+/// A ballot like `Ballot`, with its votes in a `Vec` sorted by candidate ID rather than a
+/// map. It serializes exactly as the equivalent `Ballot` does.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SmallBallot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The votes, with their candidate IDs, in order of candidate ID with no duplicates.
+    votes: Vec<(C, Vote<G, S>)>,
+
+    /// The proof of well-formedness that guarantees exactly one, or `k`, of the `votes`
+    /// represent yes.
+    pwf: BallotProof<G>,
+}
+
+impl<C, G, S> SmallBallot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    /// The votes, with their candidate IDs, in order of candidate ID.
+    pub fn votes(&self) -> &[(C, Vote<G, S>)] {
+        &self.votes
+    }
+
+    /// The proof of well-formedness that guarantees exactly one, or `k`, of the votes
+    /// represent yes.
+    pub fn pwf(&self) -> &BallotProof<G> {
+        &self.pwf
+    }
+
+    /// The candidate IDs of the votes, in order.
+    pub fn candidates(&self) -> impl Iterator<Item = &C> {
+        self.votes.iter().map(|(candidate, _)| candidate)
+    }
+
+    /// The number of votes, one per candidate.
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    /// Whether the ballot has no votes.
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    /// The vote for the given candidate, if there is one.
+    pub fn get_vote(&self, candidate: &C) -> Option<&Vote<G, S>> {
+        self.votes
+            .binary_search_by(|(c, _)| c.cmp(candidate))
+            .ok()
+            .map(|i| &self.votes[i].1)
+    }
+
+    /// The sums of the `Z` and `R` values of all votes, in that order: the statement that
+    /// the ballot proof is made about.
+    pub fn zr_sums(&self) -> (G::Point, G::Point) {
+        zr_sums(self.votes.iter().map(|(_, vote)| vote))
+    }
+
+    /// Mutable access to the votes, for testing that tampering is detected.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn votes_mut(&mut self) -> &mut [(C, Vote<G, S>)] {
+        &mut self.votes
+    }
+}
+
+impl<C, G, S> SmallBallot<C, G, S>
+where
+    C: Ord + Clone + IdBytes,
+    G: DreipGroup,
+    S: VoteSecrets<G>,
+{
+    /// Verify this ballot and all votes within it, as `Ballot::verify` does.
+    pub fn verify<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        self.verify_ballot(g1, g2, ballot_id, 1, transcript)
+    }
+
+    /// Verify this ballot and all votes within it, expecting exactly `k` yes votes, as
+    /// `Ballot::verify_multi` does.
+    pub fn verify_multi<B>(
+        &self,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        k: usize,
+        transcript: TranscriptVersion,
+    ) -> Result<(), BallotError<B, C>>
+    where
+        B: IdBytes,
+    {
+        self.verify_ballot(g1, g2, ballot_id, k, transcript)
+    }
+}
+
+impl<C, G> SmallBallot<C, G, SecretsPresent<G>>
+where
+    C: Hash + Ord + Clone + IdBytes,
+    G: DreipGroup,
+    G::Scalar: Eq,
+{
+    /// Create a new ballot, as `Ballot::new` does.
+    pub fn new<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+    {
+        Self::new_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidate,
+            no_candidates,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new ballot as `new` does, with proofs using the given transcript version.
+    pub fn new_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidate: C,
+        no_candidates: impl IntoIterator<Item = C>,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+    {
+        Ballot::new_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidate,
+            no_candidates,
+            transcript,
+        )
+        .map(Self::from)
+    }
+
+    /// Create a new ballot with a yes for each of `yes_candidates`, as `Ballot::new_multi`
+    /// does.
+    pub fn new_multi<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidates: impl IntoIterator<Item = C>,
+        no_candidates: impl IntoIterator<Item = C>,
+        k: usize,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+    {
+        Self::new_multi_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidates,
+            no_candidates,
+            k,
+            TranscriptVersion::CURRENT,
+        )
+    }
+
+    /// Create a new ballot as `new_multi` does, with proofs using the given transcript
+    /// version.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_multi_with_transcript<B>(
+        rng: impl RngCore + CryptoRng,
+        g1: G::Point,
+        g2: G::Point,
+        ballot_id: B,
+        yes_candidates: impl IntoIterator<Item = C>,
+        no_candidates: impl IntoIterator<Item = C>,
+        k: usize,
+        transcript: TranscriptVersion,
+    ) -> Result<Self, BallotCreationError<C>>
+    where
+        B: IdBytes,
+    {
+        Ballot::new_multi_with_transcript(
+            rng,
+            g1,
+            g2,
+            ballot_id,
+            yes_candidates,
+            no_candidates,
+            k,
+            transcript,
+        )
+        .map(Self::from)
+    }
+
+    /// Confirm this ballot, discarding all `r` and `v` values, as `Ballot::confirm` does.
+    pub fn confirm(
+        self,
+        totals: Option<&mut HashMap<C, &mut CandidateTotals<G>>>,
+    ) -> Result<SmallBallot<C, G, NoSecrets>, ConfirmError<C>> {
+        // Increment totals if provided, once all are known to be present.
+        if let Some(totals) = totals {
+            if let Some(candidate) = self.candidates().find(|c| !totals.contains_key(*c)) {
+                return Err(ConfirmError::MissingCandidate(candidate.clone()));
+            }
+            for (candidate, vote) in self.votes.iter() {
+                let entry = totals.get_mut(candidate).expect("Already checked");
+                entry.accumulate(&vote.secrets);
+            }
+        }
+
+        // Drop the secrets.
+        let votes = self
+            .votes
+            .into_iter()
+            .map(|(c, v)| (c, v.confirm()))
+            .collect();
+
+        Ok(SmallBallot {
+            votes,
+            pwf: self.pwf,
+        })
+    }
+}
+
+impl<C, G, S> BallotLike for SmallBallot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    type Candidate = C;
+    type Group = G;
+    type Secrets = S;
+
+    fn ballot_votes(&self) -> impl Iterator<Item = (&C, &Vote<G, S>)> {
+        self.votes.iter().map(|(candidate, vote)| (candidate, vote))
+    }
+
+    fn ballot_proof(&self) -> &BallotProof<G> {
+        &self.pwf
+    }
+}
+
+impl<C, G, S> From<Ballot<C, G, S>> for SmallBallot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    fn from(ballot: Ballot<C, G, S>) -> Self {
+        let (votes, pwf) = ballot.into_parts();
+        Self {
+            votes: votes.into_iter().collect(),
+            pwf,
+        }
+    }
+}
+
+impl<C, G, S> From<SmallBallot<C, G, S>> for Ballot<C, G, S>
+where
+    C: Ord,
+    G: DreipGroup,
+{
+    fn from(ballot: SmallBallot<C, G, S>) -> Self {
+        Ballot::from_parts(ballot.votes.into_iter().collect(), ballot.pwf)
+    }
+}
+
+impl<C, G, S> Serialize for SmallBallot<C, G, S>
+where
+    C: Ord + Serialize,
+    G: DreipGroup,
+    S: Serialize,
+{
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        /// The votes, serialized as the map of a `Ballot`.
+        struct Votes<'a, C, G: DreipGroup, S>(&'a [(C, Vote<G, S>)]);
+
+        impl<C: Serialize, G: DreipGroup, S: Serialize> Serialize for Votes<'_, C, G, S> {
+            fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+                serializer.collect_map(self.0.iter().map(|(candidate, vote)| (candidate, vote)))
+            }
+        }
+
+        let mut state = serializer.serialize_struct("Ballot", 2)?;
+        state.serialize_field("votes", &Votes(&self.votes))?;
+        state.serialize_field("pwf", &self.pwf)?;
+        state.end()
+    }
+}
+
+impl<'de, C, G, S> Deserialize<'de> for SmallBallot<C, G, S>
+where
+    C: Ord + Deserialize<'de>,
+    G: DreipGroup,
+    S: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ballot::deserialize(deserializer).map(Self::from)
+    }
+}
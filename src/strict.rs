@@ -0,0 +1,402 @@
+//! Strict and bounded loading of published JSON dumps.
+//!
+//! Deserialization is normally lenient, as serde is: keys a type does not know are ignored,
+//! and when a JSON object repeats a key, the last value wins. In a published dump, either
+//! can hide data an auditor never sees, such as voter-identifying fields, or a ballot
+//! silently replaced by another with the same id. [`from_json_strict`] rejects both.
+//!
+//! A key is unexpected if serializing the loaded value again does not write it. This covers
+//! keys unknown to the type anywhere in the dump, and fields written only when non-empty,
+//! such as `audited`, given explicitly as empty. Dumps serialized by this crate never
+//! contain either.
+//!
+//! A hostile dump can also be large enough to exhaust a verifier's memory before any proof
+//! is checked; [`read_bounded`] refuses to read more than a given number of bytes, and
+//! `ElectionResults::from_json_limited` also applies the rest of its `Limits`.
+
+use serde::de::{self, DeserializeOwned, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::io::Read;
+
+use crate::election::LimitsExceeded;
+
+/// An error due to a dump failing to load strictly.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum StrictError {
+    /// The dump is not valid JSON for the type, or repeats a key within an object, which
+    /// the message names along with its line and column.
+    Json(serde_json::Error),
+    /// The dump has a key that the loaded value does not serialize, at the given
+    /// JSON pointer, such as `/confirmed/1/extra`.
+    UnexpectedField { pointer: String },
+    /// The dump exceeds the limits it was loaded with.
+    LimitsExceeded(LimitsExceeded),
+}
+
+impl Display for StrictError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "{}", e),
+            Self::UnexpectedField { pointer } => write!(f, "unexpected field {}", pointer),
+            Self::LimitsExceeded(e) => write!(f, "limits exceeded: {}", e),
+        }
+    }
+}
+
+impl Error for StrictError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            Self::UnexpectedField { .. } => None,
+            Self::LimitsExceeded(e) => Some(e),
+        }
+    }
+}
+
+impl From<serde_json::Error> for StrictError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<LimitsExceeded> for StrictError {
+    fn from(e: LimitsExceeded) -> Self {
+        Self::LimitsExceeded(e)
+    }
+}
+
+/// Read all of the input, failing without reading further once it is longer than
+/// `max_bytes`.
+pub fn read_bounded(reader: impl Read, max_bytes: u64) -> Result<Vec<u8>, StrictError> {
+    let mut bytes = Vec::new();
+    reader
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)
+        .map_err(serde_json::Error::io)?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(LimitsExceeded::InputBytes { max: max_bytes }.into());
+    }
+    Ok(bytes)
+}
+
+/// Load a value from JSON, failing if any object repeats a key, or if the dump has any
+/// key the value does not serialize again. See the module documentation.
+pub fn from_json_strict<T>(reader: impl Read) -> Result<T, StrictError>
+where
+    T: DeserializeOwned + Serialize,
+{
+    from_json_strict_bounded(reader, u64::MAX)
+}
+
+/// Load a value as `from_json_strict` does, reading at most `max_bytes` as `read_bounded`
+/// does.
+pub fn from_json_strict_bounded<T>(reader: impl Read, max_bytes: u64) -> Result<T, StrictError>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let bytes = read_bounded(reader, max_bytes)?;
+    let mut deserializer = serde_json::Deserializer::from_slice(&bytes);
+    let UniqueKeys(input) = UniqueKeys::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    let value = T::deserialize(&input)?;
+    match unexpected_field(&input, &serde_json::to_value(&value)?) {
+        Some(pointer) => Err(StrictError::UnexpectedField { pointer }),
+        None => Ok(value),
+    }
+}
+
+/// The JSON pointer of the first key in `input` that `output` does not have, if any.
+fn unexpected_field(input: &Value, output: &Value) -> Option<String> {
+    match (input, output) {
+        (Value::Object(input), Value::Object(output)) => input.iter().find_map(|(key, value)| {
+            let pointer = format!("/{}", key.replace('~', "~0").replace('/', "~1"));
+            match output.get(key) {
+                Some(output) => unexpected_field(value, output).map(|rest| pointer + &rest),
+                None => Some(pointer),
+            }
+        }),
+        (Value::Array(input), Value::Array(output)) => input
+            .iter()
+            .zip(output)
+            .enumerate()
+            .find_map(|(i, (input, output))| {
+                unexpected_field(input, output).map(|rest| format!("/{}{}", i, rest))
+            }),
+        _ => None,
+    }
+}
+
+/// A JSON value which, unlike `serde_json::Value`, fails to deserialize if an object
+/// repeats a key, rather than keeping the last value.
+struct UniqueKeys(Value);
+
+impl<'de> Deserialize<'de> for UniqueKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(UniqueKeysVisitor)
+    }
+}
+
+struct UniqueKeysVisitor;
+
+impl<'de> Visitor<'de> for UniqueKeysVisitor {
+    type Value = UniqueKeys;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("any JSON value")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::Bool(v)))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::from(v)))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::from(v)))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::from(v)))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::String(v.to_owned())))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::String(v)))
+    }
+
+    fn visit_unit<E>(self) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::Null))
+    }
+
+    fn visit_none<E>(self) -> Result<UniqueKeys, E> {
+        Ok(UniqueKeys(Value::Null))
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<UniqueKeys, D::Error> {
+        UniqueKeys::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<UniqueKeys, A::Error> {
+        let mut array = Vec::new();
+        while let Some(UniqueKeys(value)) = seq.next_element()? {
+            array.push(value);
+        }
+        Ok(UniqueKeys(Value::Array(array)))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<UniqueKeys, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            if object.contains_key(&key) {
+                return Err(de::Error::custom(format_args!("duplicate key {:?}", key)));
+            }
+            let UniqueKeys(value) = map.next_value()?;
+            object.insert(key, value);
+        }
+        Ok(UniqueKeys(Value::Object(object)))
+    }
+}
+
+#[cfg(all(test, feature = "p256_impl"))]
+mod tests {
+    use super::*;
+
+    use crate::election::{Election, ElectionResults, Limits};
+    use crate::group::p256::NistP256;
+    use crate::session::ElectionSession;
+
+    type Results = ElectionResults<String, String, NistP256>;
+
+    /// Run a small election with two confirmed ballots and one audited, and serialize its
+    /// results.
+    fn results_json() -> String {
+        let mut rng = rand::thread_rng();
+        let election = Election::<NistP256>::new(&[b"Strict"], &mut rng);
+        let mut session = ElectionSession::new(election, ["Alice", "Bob"]);
+        for (ballot_id, candidate) in [("ballot-1", "Alice"), ("ballot-2", "Bob")] {
+            let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+            session.confirm(handle).unwrap();
+        }
+        let handle = session.cast(&mut rng, "ballot-3", "Bob").unwrap();
+        session.audit(handle).unwrap();
+        serde_json::to_string(&session.into_results()).unwrap()
+    }
+
+    fn load(json: &str) -> Result<Results, StrictError> {
+        from_json_strict(json.as_bytes())
+    }
+
+    #[test]
+    fn test_honest_dump() {
+        let json = results_json();
+        let results = load(&json).unwrap();
+        assert_eq!(results, serde_json::from_str(&json).unwrap());
+        assert_eq!(results.verify(), Ok(()));
+        assert_eq!(Results::from_json_strict(json.as_bytes()).unwrap(), results);
+    }
+
+    #[test]
+    fn test_unexpected_fields() {
+        let json = results_json();
+        let mut value: Value = serde_json::from_str(&json).unwrap();
+        let cases = [
+            ("/voter", "Alice Smith".into()),
+            ("/confirmed/ballot-1/voter", "Alice Smith".into()),
+            ("/confirmed/ballot-2/votes/Bob/pwf/note", 1.into()),
+            ("/audited/ballot-3/votes/Alice/x~1y", Value::Null),
+        ];
+        for (pointer, extra) in cases {
+            let mut value = value.clone();
+            let (parent, key) = pointer.rsplit_once('/').unwrap();
+            let key = key.replace("~1", "/");
+            let parent = value.pointer_mut(parent).unwrap().as_object_mut().unwrap();
+            parent.insert(key, extra);
+            let json = value.to_string();
+
+            // The lenient loader ignores the field.
+            assert!(serde_json::from_str::<Results>(&json).is_ok());
+            let error = load(&json).unwrap_err();
+            assert!(
+                matches!(&error, StrictError::UnexpectedField { pointer: p } if p == pointer),
+                "{:?}",
+                error
+            );
+            assert_eq!(error.to_string(), format!("unexpected field {}", pointer));
+        }
+
+        // Known fields omitted when empty are unexpected when given empty.
+        value["cancelled"] = serde_json::json!({});
+        assert_eq!(
+            load(&value.to_string()).unwrap_err().to_string(),
+            "unexpected field /cancelled"
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keys() {
+        // The second confirmed ballot takes the first's id, as does a vote's candidate.
+        let json = results_json();
+        for (from, to) in [("\"ballot-2\"", "\"ballot-1\""), ("\"Bob\"", "\"Alice\"")] {
+            let duplicated = json.replace(from, to);
+            assert!(serde_json::from_str::<Results>(&duplicated).is_ok());
+            let error = load(&duplicated).unwrap_err();
+            assert!(matches!(error, StrictError::Json(_)), "{:?}", error);
+            assert!(
+                error
+                    .to_string()
+                    .starts_with(&format!("duplicate key {} at line 1 column", to)),
+                "{}",
+                error
+            );
+        }
+
+        // The lenient loader keeps only one of the duplicated ballots.
+        let duplicated = json.replace("\"ballot-2\"", "\"ballot-1\"");
+        let lenient: Results = serde_json::from_str(&duplicated).unwrap();
+        assert_eq!(lenient.confirmed.len(), 1);
+    }
+
+    #[test]
+    fn test_limits() {
+        let json = results_json();
+        let load = |limits: Limits| Results::from_json_limited(json.as_bytes(), &limits);
+        assert!(load(Limits::default()).is_ok());
+        assert!(load(Limits::unlimited()).is_ok());
+
+        let cases = [
+            (
+                Limits {
+                    max_input_bytes: json.len() as u64 - 1,
+                    ..Limits::default()
+                },
+                LimitsExceeded::InputBytes {
+                    max: json.len() as u64 - 1,
+                },
+            ),
+            (
+                Limits {
+                    max_ballots: 2,
+                    ..Limits::default()
+                },
+                LimitsExceeded::Ballots { count: 3, max: 2 },
+            ),
+            (
+                Limits {
+                    max_candidates: 1,
+                    ..Limits::default()
+                },
+                LimitsExceeded::Candidates { count: 2, max: 1 },
+            ),
+            (
+                Limits {
+                    max_id_bytes: 5,
+                    ..Limits::default()
+                },
+                LimitsExceeded::IdBytes { len: 8, max: 5 },
+            ),
+        ];
+        for (limits, expected) in cases {
+            match load(limits) {
+                Err(StrictError::LimitsExceeded(e)) => assert_eq!(e, expected),
+                result => panic!("expected {:?}, got {:?}", expected, result),
+            }
+        }
+        let limits = Limits {
+            max_input_bytes: json.len() as u64,
+            max_ballots: 3,
+            max_candidates: 2,
+            max_id_bytes: 8,
+        };
+        assert!(load(limits).is_ok());
+        let error = load(Limits {
+            max_ballots: 2,
+            ..limits
+        })
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "limits exceeded: 3 ballots, more than the limit of 2"
+        );
+    }
+
+    #[test]
+    fn test_endless_input() {
+        // Reading stops just past the limit, however long the input.
+        let endless = std::io::repeat(b' ');
+        match read_bounded(endless, 1 << 20) {
+            Err(StrictError::LimitsExceeded(LimitsExceeded::InputBytes { max })) => {
+                assert_eq!(max, 1 << 20)
+            }
+            result => panic!(
+                "expected the input limit, got {:?}",
+                result.map(|b| b.len())
+            ),
+        }
+        let limits = Limits {
+            max_input_bytes: 1 << 20,
+            ..Limits::default()
+        };
+        let result = Results::from_json_limited(std::io::repeat(b'['), &limits);
+        assert!(matches!(
+            result,
+            Err(StrictError::LimitsExceeded(
+                LimitsExceeded::InputBytes { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_trailing_data() {
+        let json = results_json() + "{}";
+        assert!(matches!(load(&json), Err(StrictError::Json(_))));
+    }
+}
@@ -0,0 +1,484 @@
+//! Test vectors for checking independent implementations of DRE-ip against this one.
+//!
+//! The vectors describe a small election over `NistP256`, derived entirely from fixed seeds,
+//! so regenerating them must reproduce [`P256_VECTORS`] byte for byte. Any change to hashing,
+//! encoding, or serialization shows up as a difference; see [`verify_test_vectors`].
+//!
+//! # Encoding
+//!
+//! In the JSON, points, scalars, keys, and signatures are base64url without padding, as
+//! everywhere else in this crate. Byte strings that are not themselves values are hex.
+//! For `NistP256`, points are compressed SEC1 (33 bytes), scalars are big-endian (32 bytes),
+//! and signatures are ECDSA over SHA-256, as `r || s`. Ballot and candidate ids are UTF-8.
+//!
+//! # Transcript
+//!
+//! The election uses transcript version `V2`. Below, `encode(x_1, ..., x_n)` is the
+//! concatenation of each `x_i`, prefixed with its length as 8 big-endian bytes, and
+//! `hash_T` is `hash_to_scalar` from RFC 9380, using `expand_message_xmd` with SHA-256 and
+//! the domain separation tag `CURVE_XMD:SHA-256:DREIP-T`. The generator `g2` is
+//! `hash_to_curve` of the unique bytes, with the tag `CURVE_XMD:SHA-256:DREIP-GEN`.
+//!
+//! - A vote proof for ballot `B` and candidate `C` has the challenge
+//!   `hash_VOTE-PWF(encode(g1, g2, Z, R, a1, b1, a2, b2, B, C))`, where
+//!   `a1 = g1*r1 + Z*c1`, `b1 = g2*r1 + R*c1`, `a2 = g1*r2 + (Z - g1)*c2`, and
+//!   `b2 = g2*r2 + R*c2`. It is valid if `c1 + c2` equals the challenge.
+//! - A ballot proof for ballot `B` has the challenge
+//!   `c = hash_BALLOT-PWF(encode(g1, g2, a, b, B))`. It is valid if
+//!   `g1*r = a + (Z_sum - g1)*c` and `g2*r = b + R_sum*c`, where `Z_sum` and `R_sum`
+//!   are the sums over all votes in the ballot.
+//! - The receipt of a ballot, which is signed, is
+//!   `encode(C_1, vote_1, ..., C_n, vote_n, encode(a, b, r))` with candidates in byte
+//!   order, where each `vote_i` is `encode(secrets, R, Z, encode(c1, c2, r1, r2))`, and
+//!   `secrets` is `r || v` for audited ballots, or empty for confirmed ones.
+//!
+//! The encoded input to `hash` for every proof is listed under `transcripts`, to help
+//! find where an implementation diverges.
+
+use std::collections::{BTreeMap, HashMap};
+
+use p256::NistP256;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::ballots::{Ballot, NoSecrets, SecretsPresent, VerificationError};
+use crate::election::{verify_election, CandidateTotals, Election};
+use crate::group::{DreipGroup, DreipPrivateKey, DreipPublicKey};
+use crate::pwf::{BallotProof, VoteProof, BALLOT_PROOF_LABEL, VOTE_PROOF_LABEL};
+
+/// The embedded test vectors for `NistP256`, as produced by [`generate_json`].
+pub const P256_VECTORS: &str = include_str!("../test_vectors/p256.json");
+
+/// The unique bytes of the test election.
+const UNIQUE_BYTES: &str = "DRE-ip test vectors";
+
+/// The seed of the test election, passed to `Election::from_seed`.
+const ELECTION_SEED: [u8; 32] = [0; 32];
+
+/// The seed of the random number stream used to create the ballots.
+const BALLOT_SEED: [u8; 32] = [1; 32];
+
+/// The candidates of the test election.
+const CANDIDATES: [&str; 3] = ["Alice", "Bob", "Eve"];
+
+/// The ballots of the test election, as ballot id, yes candidate, and whether to confirm it.
+const BALLOTS: [(&str, &str, bool); 4] = [
+    ("1", "Alice", true),
+    ("2", "Bob", true),
+    ("3", "Alice", true),
+    ("4", "Eve", false),
+];
+
+/// A complete set of test vectors.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TestVectors {
+    /// The unique bytes passed to `Election::from_seed`, as UTF-8.
+    pub unique_bytes: String,
+
+    /// The seed passed to `Election::from_seed`, as hex.
+    pub election_seed: String,
+
+    /// The election, including its private key, which is public here since the seed is.
+    pub election: Election<NistP256>,
+
+    /// The output of `Election::to_bytes`, as hex.
+    pub params: String,
+
+    /// The output of `Election::fingerprint`, as hex.
+    pub fingerprint: String,
+
+    /// The confirmed ballots, by ballot id.
+    pub confirmed: BTreeMap<String, Ballot<String, NistP256, NoSecrets>>,
+
+    /// The audited ballots, with their secrets, by ballot id.
+    pub audited: BTreeMap<String, Ballot<String, NistP256, SecretsPresent<NistP256>>>,
+
+    /// The totals of the confirmed ballots, by candidate id.
+    pub totals: BTreeMap<String, CandidateTotals<NistP256>>,
+
+    /// The hash inputs of every proof, by ballot id.
+    pub transcripts: BTreeMap<String, BallotTranscripts>,
+
+    /// The signed receipt of every ballot, by ballot id.
+    pub receipts: BTreeMap<String, Receipt>,
+}
+
+/// The hash inputs of the proofs of one ballot, as hex.
+#[derive(Debug, Clone, Eq, PartialEq, Deserialize, Serialize)]
+pub struct BallotTranscripts {
+    /// The input to the challenge of each vote proof, by candidate id.
+    pub votes: BTreeMap<String, String>,
+
+    /// The input to the challenge of the ballot proof.
+    pub ballot: String,
+}
+
+/// A ballot receipt and its signature.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Receipt {
+    /// The bytes of the receipt, as hex.
+    pub bytes: String,
+
+    /// The election's signature of the bytes.
+    #[serde(with = "crate::group::serde_bytestring")]
+    pub signature: <NistP256 as DreipGroup>::Signature,
+}
+
+/// An error due to the test vectors not matching this implementation.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum TestVectorError {
+    /// The embedded vectors could not be parsed.
+    Malformed,
+    /// Regenerating the vectors gave a different value for the named field.
+    Mismatch(String),
+    /// The election in the vectors failed verification.
+    Verification(VerificationError<String, String>),
+    /// The named audited ballot failed verification.
+    Audited(String),
+    /// The signature of the named ballot's receipt failed verification.
+    Receipt(String),
+}
+
+/// Generate the test vectors from the fixed seeds.
+pub fn generate() -> TestVectors {
+    let election = Election::<NistP256>::from_seed(&[UNIQUE_BYTES.as_bytes()], &ELECTION_SEED);
+    let mut rng = ChaCha20Rng::from_seed(BALLOT_SEED);
+
+    let mut totals = CANDIDATES
+        .iter()
+        .map(|candidate| (candidate.to_string(), CandidateTotals::default()))
+        .collect::<HashMap<_, _>>();
+    let mut confirmed = BTreeMap::new();
+    let mut audited = BTreeMap::new();
+    let mut transcripts = BTreeMap::new();
+    let mut receipts = BTreeMap::new();
+    for (ballot_id, yes, confirm) in BALLOTS {
+        let no = CANDIDATES
+            .iter()
+            .filter(|candidate| **candidate != yes)
+            .map(|candidate| candidate.to_string());
+        let ballot = Ballot::new(
+            &mut rng,
+            election.g1,
+            election.g2,
+            ballot_id,
+            yes.to_string(),
+            no,
+        )
+        .expect("Candidates are unique");
+        transcripts.insert(
+            ballot_id.to_string(),
+            ballot_transcripts(&election, ballot_id, &ballot),
+        );
+
+        let bytes = if confirm {
+            let mut totals_mut = totals
+                .iter_mut()
+                .map(|(id, t)| (id.clone(), t))
+                .collect::<HashMap<_, _>>();
+            let ballot = ballot.confirm(Some(&mut totals_mut)).unwrap();
+            let bytes = ballot.to_bytes(election.transcript);
+            confirmed.insert(ballot_id.to_string(), ballot);
+            bytes
+        } else {
+            let bytes = ballot.to_bytes(election.transcript);
+            audited.insert(ballot_id.to_string(), ballot);
+            bytes
+        };
+        let signature = election.private_key.sign(&bytes);
+        receipts.insert(
+            ballot_id.to_string(),
+            Receipt {
+                bytes: to_hex(&bytes),
+                signature,
+            },
+        );
+    }
+
+    TestVectors {
+        unique_bytes: UNIQUE_BYTES.to_string(),
+        election_seed: to_hex(&ELECTION_SEED),
+        params: to_hex(&election.to_bytes()),
+        fingerprint: election.fingerprint_hex(),
+        election,
+        confirmed,
+        audited,
+        totals: totals.into_iter().collect(),
+        transcripts,
+        receipts,
+    }
+}
+
+/// Generate the test vectors as JSON, with keys sorted so that the output is reproducible.
+/// This is the exact content of [`P256_VECTORS`].
+pub fn generate_json() -> String {
+    let value = serde_json::to_value(generate()).expect("Test vectors are serializable");
+    serde_json::to_string_pretty(&value).expect("JSON values are serializable") + "\n"
+}
+
+/// Check that this implementation reproduces the embedded test vectors exactly,
+/// and that the election they describe verifies.
+pub fn verify_test_vectors() -> Result<(), TestVectorError> {
+    let embedded: serde_json::Value =
+        serde_json::from_str(P256_VECTORS).map_err(|_| TestVectorError::Malformed)?;
+    let vectors = generate();
+    let generated = serde_json::to_value(&vectors).expect("Test vectors are serializable");
+    let (Some(embedded), Some(generated)) = (embedded.as_object(), generated.as_object()) else {
+        return Err(TestVectorError::Malformed);
+    };
+    for (field, value) in generated {
+        if embedded.get(field) != Some(value) {
+            return Err(TestVectorError::Mismatch(field.clone()));
+        }
+    }
+    if let Some(field) = embedded
+        .keys()
+        .find(|field| !generated.contains_key(*field))
+    {
+        return Err(TestVectorError::Mismatch(field.clone()));
+    }
+
+    let election = &vectors.election;
+    let confirmed = vectors.confirmed.into_iter().collect::<HashMap<_, _>>();
+    let totals = vectors.totals.into_iter().collect::<HashMap<_, _>>();
+    verify_election(
+        election.g1,
+        election.g2,
+        &confirmed,
+        &totals,
+        election.transcript,
+    )
+    .map_err(TestVectorError::Verification)?;
+    for (ballot_id, ballot) in vectors.audited.iter() {
+        ballot
+            .verify(election.g1, election.g2, ballot_id, election.transcript)
+            .map_err(|_| TestVectorError::Audited(ballot_id.clone()))?;
+    }
+    for (ballot_id, receipt) in vectors.receipts.iter() {
+        let bytes = from_hex(&receipt.bytes).ok_or(TestVectorError::Malformed)?;
+        if !election.public_key.verify(&bytes, &receipt.signature) {
+            return Err(TestVectorError::Receipt(ballot_id.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the hash inputs of all proofs in the given ballot.
+#[allow(non_snake_case)]
+fn ballot_transcripts<S>(
+    election: &Election<NistP256>,
+    ballot_id: &str,
+    ballot: &Ballot<String, NistP256, S>,
+) -> BallotTranscripts {
+    let (g1, g2) = (election.g1, election.g2);
+    let votes = ballot
+        .votes()
+        .iter()
+        .map(|(candidate, vote)| {
+            let (Z, R, pwf) = (*vote.Z(), *vote.R(), vote.pwf());
+            let a1 = g1 * pwf.r1 + Z * pwf.c1;
+            let b1 = g2 * pwf.r1 + R * pwf.c1;
+            let a2 = g1 * pwf.r2 + (Z - g1) * pwf.c2;
+            let b2 = g2 * pwf.r2 + R * pwf.c2;
+            let fields = VoteProof::<NistP256>::challenge_input(
+                &g1, &g2, &Z, &R, &a1, &b1, &a2, &b2, ballot_id, candidate,
+            );
+            let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            let input = election
+                .transcript
+                .challenge_input(VOTE_PROOF_LABEL, &fields);
+            (candidate.clone(), to_hex(&input))
+        })
+        .collect();
+    let fields = BallotProof::<NistP256>::challenge_input(
+        &g1,
+        &g2,
+        &ballot.pwf().a,
+        &ballot.pwf().b,
+        ballot_id,
+    );
+    let fields = fields.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let ballot = election
+        .transcript
+        .challenge_input(BALLOT_PROOF_LABEL, &fields);
+    BallotTranscripts {
+        votes,
+        ballot: to_hex(&ballot),
+    }
+}
+
+/// Format bytes as lowercase hex.
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Parse lowercase or uppercase hex.
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ballots::Secrets;
+    use crate::group::DreipScalar;
+    use crate::pwf::VOTE_PROOF_TAG;
+
+    #[test]
+    fn test_embedded_vectors() {
+        assert_eq!(verify_test_vectors(), Ok(()));
+        assert_eq!(generate_json(), P256_VECTORS);
+    }
+
+    #[test]
+    fn test_tallies() {
+        // The published results of the embedded election give its known counts.
+        let vectors: TestVectors = serde_json::from_str(P256_VECTORS).unwrap();
+        let mut results = vectors.election.results(
+            vectors.confirmed.into_iter().collect(),
+            vectors.totals.into_iter().collect(),
+        );
+        results.audited = vectors.audited.into_iter().collect();
+        assert_eq!(results.verify(), Ok(()));
+        assert_eq!(
+            results.tallies(),
+            Ok(HashMap::from([
+                ("Alice".to_string(), 2),
+                ("Bob".to_string(), 1),
+                ("Eve".to_string(), 0),
+            ]))
+        );
+        assert_eq!(results.total_ballots(), 3);
+    }
+
+    #[test]
+    fn test_transcripts() {
+        // The listed hash inputs really do give the proofs' challenges.
+        let vectors = generate();
+        for (ballot_id, ballot) in vectors.audited.iter() {
+            let transcripts = &vectors.transcripts[ballot_id];
+            for (candidate, vote) in ballot.votes().iter() {
+                let input = from_hex(&transcripts.votes[candidate]).unwrap();
+                let challenge = p256::Scalar::from_hash_with_tag(VOTE_PROOF_TAG, &[&input]);
+                assert_eq!(challenge, vote.pwf().c1 + vote.pwf().c2);
+            }
+        }
+    }
+
+    /// Auditors can recompute the challenges of the embedded proofs with the public API.
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_public_challenges() {
+        let vectors: TestVectors = serde_json::from_str(P256_VECTORS).unwrap();
+        let election = &vectors.election;
+        let (g1, g2) = (election.g1, election.g2);
+        for (ballot_id, ballot) in vectors.audited.iter() {
+            for (candidate, vote) in ballot.votes().iter() {
+                let (Z, R, pwf) = (*vote.Z(), *vote.R(), vote.pwf());
+                let challenge = VoteProof::<NistP256>::compute_challenge(
+                    &g1,
+                    &g2,
+                    &Z,
+                    &R,
+                    &(g1 * pwf.r1 + Z * pwf.c1),
+                    &(g2 * pwf.r1 + R * pwf.c1),
+                    &(g1 * pwf.r2 + (Z - g1) * pwf.c2),
+                    &(g2 * pwf.r2 + R * pwf.c2),
+                    ballot_id,
+                    candidate,
+                    election.transcript,
+                );
+                assert_eq!(challenge, pwf.c1 + pwf.c2);
+            }
+
+            let (a, b, r) = (ballot.pwf().a, ballot.pwf().b, ballot.pwf().r);
+            let challenge = BallotProof::<NistP256>::compute_challenge(
+                &g1,
+                &g2,
+                &a,
+                &b,
+                ballot_id,
+                election.transcript,
+            );
+            let Z_sum = ballot
+                .votes()
+                .values()
+                .map(|vote| *vote.Z())
+                .sum::<p256::ProjectivePoint>();
+            let R_sum = ballot
+                .votes()
+                .values()
+                .map(|vote| *vote.R())
+                .sum::<p256::ProjectivePoint>();
+            assert_eq!(g1 * r, a + (Z_sum - g1) * challenge);
+            assert_eq!(g2 * r, b + R_sum * challenge);
+        }
+    }
+
+    /// The confirmed and audited ballots of the vectors load into one map, and verify.
+    #[test]
+    fn test_mixed_ballots() {
+        let vectors: serde_json::Value = serde_json::from_str(P256_VECTORS).unwrap();
+        let mut mixed = vectors["confirmed"].as_object().unwrap().clone();
+        mixed.extend(vectors["audited"].as_object().unwrap().clone());
+        let json = serde_json::to_string(&mixed).unwrap();
+        let ballots: HashMap<String, Ballot<String, NistP256, Secrets<NistP256>>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(ballots.len(), 4);
+
+        let vectors: TestVectors = serde_json::from_str(P256_VECTORS).unwrap();
+        let election = &vectors.election;
+        for (ballot_id, ballot) in ballots.iter() {
+            assert_eq!(
+                ballot.secrets_present(),
+                vectors.audited.contains_key(ballot_id)
+            );
+            assert_eq!(
+                ballot.verify(election.g1, election.g2, ballot_id, election.transcript),
+                Ok(())
+            );
+        }
+        let confirmed = ballots
+            .into_iter()
+            .filter(|(_, ballot)| ballot.secrets_absent())
+            .collect::<HashMap<_, _>>();
+        let totals = vectors.totals.into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(
+            verify_election(
+                election.g1,
+                election.g2,
+                &confirmed,
+                &totals,
+                election.transcript
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let mut vectors: serde_json::Value = serde_json::from_str(P256_VECTORS).unwrap();
+        vectors["fingerprint"] = "00".into();
+        let generated = serde_json::to_value(generate()).unwrap();
+        assert_ne!(vectors, generated);
+        assert_eq!(vectors["params"], generated["params"]);
+    }
+
+    /// Rewrite the embedded vectors after an intentional change to the format, with
+    /// `cargo test --features test_vectors regenerate -- --ignored`.
+    #[test]
+    #[ignore]
+    fn regenerate() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test_vectors/p256.json");
+        std::fs::write(path, generate_json()).unwrap();
+    }
+}
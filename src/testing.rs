@@ -0,0 +1,5915 @@
+//! A conformance suite for `DreipGroup` implementations.
+//!
+//! The protocol relies on more than the trait signatures say: generators must not be the
+//! identity, decoding must be strict, hashing must be deterministic, keys must sign and
+//! verify, and the arithmetic must obey the usual group laws. [`check_group`] runs the
+//! crate's own test battery against any group, so a third-party backend can check that
+//! it meets these assumptions. This module requires the `test-utils` feature.
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::{BuildHasherDefault, Hasher};
+use std::panic::{self, AssertUnwindSafe};
+
+use rand::seq::SliceRandom;
+use rand::{CryptoRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use crate::ballots::{
+    verify_votes, AuditError, AuditedBallot, Ballot, BallotAssemblyError, BallotCreationError,
+    BallotError, ConfirmError, ConfirmedBallot, DecodeError, NoSecrets, PendingBallot,
+    PolicyViolation, Secrets, SecretsPresent, VerificationError, Vote, VoteError, VoteFailure,
+    VoteSecrets, CANONICAL_MAGIC, CANONICAL_VERSION,
+};
+use crate::election::{
+    compute_totals, compute_totals_checked, merge_totals, replace_ballot, results, verify_election,
+    verify_election_batched, verify_election_batched_multi, verify_election_iter,
+    verify_election_multi, verify_election_referendum, verify_election_sharded,
+    verify_election_weighted, verify_election_with_abstentions, verify_election_with_policy,
+    verify_full_election, verify_subset, verify_totals_decomposition, CandidateMismatch,
+    CandidateTotals, DecompositionError, Election, ElectionParams, ElectionResults, KeyMismatch,
+    MergeError, Outcome, ParameterError, TallyOverflow, VerificationPolicy,
+};
+use crate::group::{
+    DreipGroup, DreipPoint, DreipPrivateKey, DreipPublicKey, DreipScalar, Serializable,
+};
+use crate::ids::IdBytes;
+use crate::incremental::{IncrementalVerifier, VerificationReport};
+use crate::precompute::ElectionPrecomputed;
+use crate::pwf::{
+    BallotProof, BallotProofFailure, ProofError, ToBytes, TranscriptVersion, VoteProof,
+    VoteStatement, BALLOT_PROOF_LABEL, BALLOT_PROOF_TAG, VOTE_PROOF_LABEL, VOTE_PROOF_TAG,
+};
+use crate::questions::{
+    question_ballot_id, verify_election_questions, MultiBallot, QuestionVerificationError,
+};
+use crate::receipt::{Receipt, ReceiptError, SigningContext};
+use crate::session::ElectionSession;
+use crate::small::SmallBallot;
+
+/// A single check in the conformance suite.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Check {
+    /// Votes verify, their proofs reject the wrong values and ids, and failures say why.
+    Vote,
+    /// Ballots verify, and reject the wrong id, a tampered proof, or having no votes.
+    Ballot,
+    /// Ballots list their candidates and votes, and sum their votes' `Z` and `R` values.
+    Inspection,
+    /// Ballots assembled from separately created votes verify, and need one yes vote, all
+    /// created for the ballot's id; ballot proofs verify only for their votes' `r` values.
+    Assembly,
+    /// Totals merged from each confirmed ballot's delta equal those updated in place.
+    ConfirmDelta,
+    /// Small ballots match the ballots they convert from, serialize identically, and
+    /// verify in elections as they do.
+    SmallBallot,
+    /// Totals computed from ballots' secrets verify, and can require consistent candidates.
+    ComputeTotals,
+    /// Partial totals of separate shards merge into the whole election's, and the shards
+    /// verify against them as the whole election does.
+    Sharding,
+    /// Subsets of the ballots verify against their own subtotals, which add up to the whole
+    /// election's totals.
+    Subset,
+    /// Candidate totals accumulate and retract votes, and add and subtract as operators.
+    TotalsArithmetic,
+    /// Tallies convert to vote counts exactly when they fit in a `u64`.
+    TallyCounts,
+    /// Verified results rank candidates by votes and name the winner or those tied.
+    Outcome,
+    /// Whole elections verify, sequentially and batched, and report what is wrong.
+    Election,
+    /// Ballots fed one at a time to an `IncrementalVerifier` verify exactly as the whole
+    /// election does.
+    Incremental,
+    /// Ballots selecting `k` candidates verify only for exactly `k` yes votes.
+    MultiSelect,
+    /// Weighted ballots verify only for their own weight, and add it to the tallies.
+    Weighted,
+    /// Abstentions verify only with no yes votes, and add nothing to the tallies.
+    Abstention,
+    /// Single-candidate referendum ballots verify, and are tallied, without revealing votes.
+    Referendum,
+    /// Multi-question ballots verify and are tallied per question, and their votes cannot
+    /// move between questions.
+    Questions,
+    /// Cancelled ballots verify like audited ones, and are not counted.
+    Cancellation,
+    /// Replaced ballots are removed from the totals, and cannot be counted twice.
+    Revote,
+    /// Audited ballots verify only against the candidate they vote for.
+    Audit,
+    /// Pending ballots audit or confirm into wrappers that verify and serialize like the
+    /// raw ballots.
+    Lifecycle,
+    /// Ballots created in bulk match those created one by one, and errors name their ballot.
+    CreateBallots,
+    /// Elections with a roster create ballots for its candidates only, and zero totals for
+    /// them, and keep it through serialization.
+    ElectionRoster,
+    /// Sessions cast, audit, and confirm ballots once each, into results that verify.
+    Session,
+    /// Ballots missing candidates verify, but break a policy requiring them.
+    Policy,
+    /// A ballot without exactly the candidates of the totals breaks a consistent roster
+    /// policy, naming the candidates it is missing and those it has extra.
+    Roster,
+    /// Receipts survive a JSON round trip, and detect tampering with any part.
+    Receipt,
+    /// Elections, ballots, and totals survive a JSON round trip.
+    RoundTrip,
+    /// Confirmed and audited ballots load from one JSON map, and verify.
+    MixedSecrets,
+    /// Elections verify from maps with any hasher, including deserialized ones.
+    Hashers,
+    /// Elections run with integer ballot and candidate ids, encoded as big-endian bytes.
+    IntegerIds,
+    /// Election parameters survive their byte encoding, without the private key.
+    ParamsEncoding,
+    /// Elections can be built from existing keys, which must correspond.
+    WithKeys,
+    /// Seeded elections are deterministic, and differ between seeds.
+    FromSeed,
+    /// Fingerprints survive round trips, and change with any parameter.
+    Fingerprint,
+    /// Parameter validation accepts new elections, and rejects bad generators.
+    Parameters,
+    /// Serialized elections name their group, and are rejected by other groups.
+    GroupName,
+    /// Proofs are bound to their transcript version.
+    Transcript,
+    /// Proofs of a bound election do not verify in another, even with the same generators.
+    ElectionBinding,
+    /// Every kind of ballot and proof can be created for a bound election, and verifies only
+    /// under its transcript.
+    BoundBallots,
+    /// Every public proof constructor and verifier works from the generators alone, as
+    /// given by an election's public parameters.
+    ProofApi,
+    /// Decoding accepts only canonical encodings.
+    StrictDecoding,
+    /// Verification rejects votes and ballot proofs with identity points.
+    IdentityPoints,
+    /// Encodings have the length given by `Serializable::BYTE_SIZE`, where declared.
+    ByteSize,
+    /// Writing into a buffer gives the original encodings, of length `ToBytes::byte_len`.
+    WriteBytes,
+    /// Proofs decode from their unframed encodings, and reject any other length or
+    /// non-canonical values.
+    ProofBytes,
+    /// Canonical encodings are versioned, ordered by candidate bytes, and signed in receipts.
+    Canonical,
+    /// Ballots and votes decode from their canonical encodings, and reject any other bytes.
+    Codec,
+    /// Scalars convert to `u64` exactly when they fit.
+    ToU64,
+    /// Scalars invert, except zero.
+    Invert,
+    /// Precomputed tables give the same results as plain multiplication.
+    Precomputed,
+    /// Multi-scalar multiplication agrees with the naive sum.
+    Msm,
+    /// Constant-time equality agrees with `==`.
+    CtEq,
+    /// Batch verification of ballot proofs agrees with verifying them one by one.
+    BatchVerify,
+    /// Batch verification of signatures agrees with verifying them one by one.
+    SignatureBatch,
+    /// Scalars and points convert to and from big integers.
+    #[cfg(feature = "bigint")]
+    Bigint,
+}
+
+impl Check {
+    /// Every check, in the order `check_group` runs them.
+    pub const ALL: &'static [Check] = &[
+        Check::Vote,
+        Check::Ballot,
+        Check::Inspection,
+        Check::Assembly,
+        Check::ConfirmDelta,
+        Check::SmallBallot,
+        Check::ComputeTotals,
+        Check::Sharding,
+        Check::Subset,
+        Check::TotalsArithmetic,
+        Check::TallyCounts,
+        Check::Outcome,
+        Check::Election,
+        Check::Incremental,
+        Check::MultiSelect,
+        Check::Weighted,
+        Check::Abstention,
+        Check::Referendum,
+        Check::Questions,
+        Check::Cancellation,
+        Check::Revote,
+        Check::Audit,
+        Check::Lifecycle,
+        Check::CreateBallots,
+        Check::ElectionRoster,
+        Check::Session,
+        Check::Policy,
+        Check::Roster,
+        Check::Receipt,
+        Check::RoundTrip,
+        Check::MixedSecrets,
+        Check::Hashers,
+        Check::IntegerIds,
+        Check::ParamsEncoding,
+        Check::WithKeys,
+        Check::FromSeed,
+        Check::Fingerprint,
+        Check::Parameters,
+        Check::GroupName,
+        Check::Transcript,
+        Check::ElectionBinding,
+        Check::BoundBallots,
+        Check::ProofApi,
+        Check::StrictDecoding,
+        Check::IdentityPoints,
+        Check::ByteSize,
+        Check::WriteBytes,
+        Check::ProofBytes,
+        Check::Canonical,
+        Check::Codec,
+        Check::ToU64,
+        Check::Invert,
+        Check::Precomputed,
+        Check::Msm,
+        Check::CtEq,
+        Check::BatchVerify,
+        Check::SignatureBatch,
+        #[cfg(feature = "bigint")]
+        Check::Bigint,
+    ];
+}
+
+/// A check that failed, with the reason.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CheckFailure {
+    /// The check that failed.
+    pub check: Check,
+    /// The panic message of the failed assertion.
+    pub message: String,
+}
+
+/// The results of running the conformance suite against a group.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct GroupReport {
+    /// The checks that passed.
+    pub passed: Vec<Check>,
+    /// The checks that failed.
+    pub failed: Vec<CheckFailure>,
+}
+
+impl GroupReport {
+    /// Whether every check passed.
+    pub fn is_ok(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Run every check against the given group, collecting the results rather than stopping
+/// at the first failure. All randomness is drawn from `rng`.
+///
+/// Failed assertions are caught as panics, so they are still printed by the panic hook.
+pub fn check_group<G>(mut rng: impl RngCore + CryptoRng) -> GroupReport
+where
+    G: DreipGroup + Debug + Eq + Clone,
+    G::PublicKey: Sync,
+    G::Signature: Sync,
+{
+    let mut report = GroupReport::default();
+    for &check in Check::ALL {
+        match run_check::<G>(check, &mut rng) {
+            Ok(()) => report.passed.push(check),
+            Err(message) => report.failed.push(CheckFailure { check, message }),
+        }
+    }
+    report
+}
+
+/// Run a single check against the given group, returning the panic message if it fails.
+pub fn run_check<G>(check: Check, rng: impl RngCore + CryptoRng) -> Result<(), String>
+where
+    G: DreipGroup + Debug + Eq + Clone,
+    G::PublicKey: Sync,
+    G::Signature: Sync,
+{
+    panic::catch_unwind(AssertUnwindSafe(|| match check {
+        Check::Vote => check_vote::<G>(rng),
+        Check::Ballot => check_ballot::<G>(rng),
+        Check::Inspection => check_inspection::<G>(rng),
+        Check::Assembly => check_assembly::<G>(rng),
+        Check::ConfirmDelta => check_confirm_delta::<G>(rng),
+        Check::SmallBallot => check_small_ballot::<G>(rng),
+        Check::ComputeTotals => check_compute_totals::<G>(rng),
+        Check::Sharding => check_sharding::<G>(rng),
+        Check::Subset => check_subset::<G>(rng),
+        Check::TotalsArithmetic => check_totals_arithmetic::<G>(rng),
+        Check::TallyCounts => check_tally_counts::<G>(rng),
+        Check::Outcome => check_outcome::<G>(rng),
+        Check::Election => check_election::<G>(rng),
+        Check::Incremental => check_incremental::<G>(rng),
+        Check::MultiSelect => check_multi_select::<G>(rng),
+        Check::Weighted => check_weighted::<G>(rng),
+        Check::Abstention => check_abstention::<G>(rng),
+        Check::Referendum => check_referendum::<G>(rng),
+        Check::Questions => check_questions::<G>(rng),
+        Check::Cancellation => check_cancellation::<G>(rng),
+        Check::Revote => check_revote::<G>(rng),
+        Check::Audit => check_audit::<G>(rng),
+        Check::Lifecycle => check_lifecycle::<G>(rng),
+        Check::CreateBallots => check_create_ballots::<G>(rng),
+        Check::ElectionRoster => check_election_roster::<G>(rng),
+        Check::Session => check_session::<G>(rng),
+        Check::Policy => check_policy::<G>(rng),
+        Check::Roster => check_roster::<G>(rng),
+        Check::Receipt => check_receipt::<G>(rng),
+        Check::RoundTrip => check_round_trip::<G>(rng),
+        Check::MixedSecrets => check_mixed_secrets::<G>(rng),
+        Check::Hashers => check_hashers::<G>(rng),
+        Check::IntegerIds => check_integer_ids::<G>(rng),
+        Check::ParamsEncoding => check_params_encoding::<G>(rng),
+        Check::WithKeys => check_with_keys::<G>(rng),
+        Check::FromSeed => check_from_seed::<G>(rng),
+        Check::Fingerprint => check_fingerprint::<G>(rng),
+        Check::Parameters => check_parameters::<G>(rng),
+        Check::GroupName => check_group_name::<G>(rng),
+        Check::Transcript => check_transcript::<G>(rng),
+        Check::ElectionBinding => check_election_binding::<G>(rng),
+        Check::BoundBallots => check_bound_ballots::<G>(rng),
+        Check::ProofApi => check_proof_api::<G>(rng),
+        Check::StrictDecoding => check_strict_decoding::<G>(rng),
+        Check::IdentityPoints => check_identity_points::<G>(rng),
+        Check::ByteSize => check_byte_size::<G>(rng),
+        Check::WriteBytes => check_write_bytes::<G>(rng),
+        Check::ProofBytes => check_proof_bytes::<G>(rng),
+        Check::Canonical => check_canonical::<G>(rng),
+        Check::Codec => check_codec::<G>(rng),
+        Check::ToU64 => check_to_u64::<G>(rng),
+        Check::Invert => check_invert::<G>(rng),
+        Check::Precomputed => check_precomputed::<G>(rng),
+        Check::Msm => check_msm::<G>(rng),
+        Check::CtEq => check_ct_eq::<G>(rng),
+        Check::BatchVerify => check_batch_verify::<G>(rng),
+        Check::SignatureBatch => check_signature_batch::<G>(rng),
+        #[cfg(feature = "bigint")]
+        Check::Bigint => check_bigint::<G>(rng),
+    }))
+    .map_err(panic_message)
+}
+
+/// Extract the message from a panic payload.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "Non-string panic".to_string()
+    }
+}
+
+#[allow(non_snake_case)]
+fn check_vote<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Test Election"], &mut rng);
+
+    let vote1 = Vote::<G, _>::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        true,
+    );
+    assert!(vote1
+        .verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            "1",
+            "Alice",
+            election.transcript
+        )
+        .is_ok());
+
+    let vote2 = Vote::<G, _>::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Bob",
+        false,
+    );
+    assert!(vote2
+        .verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            "1",
+            "Bob",
+            election.transcript
+        )
+        .is_ok());
+
+    // The secrets say which vote is a yes, and give back the public values.
+    assert!(vote1.voted_yes() && vote1.secrets.is_yes());
+    assert!(!vote2.voted_yes() && !vote2.secrets.is_yes());
+    for vote in [&vote1, &vote2] {
+        let (g1, g2) = (election.g1.clone(), election.g2.clone());
+        assert_eq!(
+            vote.secrets.derive_public(g1, g2),
+            (vote.R().clone(), vote.Z().clone())
+        );
+    }
+    // A malformed v of 2 is not a yes, though it is non-zero, and gives the public values
+    // for 2, which no longer match the vote.
+    let malformed = SecretsPresent::<G> {
+        r: vote1.secrets.r.clone(),
+        v: G::Scalar::one() + G::Scalar::one(),
+    };
+    assert!(!malformed.is_yes());
+    let (r_point, z_point) = malformed.derive_public(election.g1.clone(), election.g2.clone());
+    assert_eq!(r_point, *vote1.R());
+    assert_eq!(z_point, vote1.Z().clone() + election.g1.clone());
+
+    assert_ne!(vote1.pwf(), vote2.pwf());
+    assert_eq!(
+        vote2.pwf().verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            vote1.Z().clone(),
+            vote1.R().clone(),
+            "1",
+            "Bob",
+            election.transcript
+        ),
+        Err(ProofError::ChallengeMismatch)
+    );
+    assert_eq!(
+        vote2.pwf().verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            vote2.Z().clone(),
+            vote2.R().clone(),
+            "2",
+            "Bob",
+            election.transcript
+        ),
+        Err(ProofError::ChallengeMismatch)
+    );
+    assert_eq!(
+        vote2.pwf().verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            vote2.Z().clone(),
+            vote2.R().clone(),
+            "1",
+            "Alice",
+            election.transcript
+        ),
+        Err(ProofError::ChallengeMismatch)
+    );
+
+    // Changing any value of a proof breaks its challenge.
+    let verify_proof = |proof: &VoteProof<G>| {
+        proof.verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            vote2.Z().clone(),
+            vote2.R().clone(),
+            "1",
+            "Bob",
+            election.transcript,
+        )
+    };
+    assert_eq!(verify_proof(vote2.pwf()), Ok(()));
+    for i in 0..4 {
+        let mut tampered = vote2.pwf().clone();
+        let value = [
+            &mut tampered.c1,
+            &mut tampered.c2,
+            &mut tampered.r1,
+            &mut tampered.r2,
+        ]
+        .into_iter()
+        .nth(i)
+        .unwrap();
+        *value = value.clone() + G::Scalar::one();
+        let result = verify_proof(&tampered);
+        assert_eq!(result, Err(ProofError::ChallengeMismatch));
+        assert_eq!(result.unwrap_err().to_string(), "challenge mismatch");
+        #[allow(deprecated)]
+        let shim = tampered.verify_opt(
+            election.g1.clone(),
+            election.g2.clone(),
+            vote2.Z().clone(),
+            vote2.R().clone(),
+            "1",
+            "Bob",
+            election.transcript,
+        );
+        assert_eq!(shim, None);
+    }
+
+    // Proofs can be made separately from a vote's secrets and statement, with `Z` and `R`
+    // named, and are as valid as those made with the vote, or by the positional form.
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    for vote in [&vote1, &vote2] {
+        let statement = vote.statement();
+        let (Z, R) = (vote.Z().clone(), vote.R().clone());
+        let candidate = if vote.voted_yes() { "Alice" } else { "Bob" };
+        let verify = |proof: &VoteProof<G>, Z: &G::Point, R: &G::Point| {
+            proof.verify(
+                g1.clone(),
+                g2.clone(),
+                Z.clone(),
+                R.clone(),
+                "1",
+                candidate,
+                election.transcript,
+            )
+        };
+        let proof = VoteProof::for_vote(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            &vote.secrets,
+            &statement,
+            "1",
+            candidate,
+        );
+        assert_eq!(verify(&proof, &Z, &R), Ok(()));
+        assert_eq!(verify(vote.pwf(), &Z, &R), Ok(()));
+        #[allow(deprecated)]
+        let positional = VoteProof::new(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            vote.voted_yes(),
+            vote.secrets.r.clone(),
+            Z.clone(),
+            R.clone(),
+            "1",
+            candidate,
+        );
+        assert_eq!(verify(&positional, &Z, &R), Ok(()));
+        // Swapping `Z` and `R` is caught by verification, whichever way it happens.
+        let swapped = VoteStatement {
+            Z: R.clone(),
+            R: Z.clone(),
+        };
+        let proof = VoteProof::for_vote(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            &vote.secrets,
+            &swapped,
+            "1",
+            candidate,
+        );
+        assert_eq!(verify(&proof, &Z, &R), Err(ProofError::ChallengeMismatch));
+        assert_eq!(verify(&proof, &R, &Z), Err(ProofError::ChallengeMismatch));
+    }
+
+    // Failures say whether the proof or the revealed secrets are wrong.
+    let error = vote2
+        .verify(g1.clone(), g2.clone(), "2", "Bob", election.transcript)
+        .unwrap_err();
+    assert_eq!(
+        error,
+        VoteError {
+            ballot_id: "2",
+            candidate_id: "Bob",
+            reason: VoteFailure::ProofInvalid,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "vote for candidate id \"Bob\" on ballot id \"2\": invalid proof of well-formedness"
+    );
+    let mut tampered = vote1;
+    tampered.secrets = malformed;
+    assert_eq!(
+        tampered.verify(g1, g2, "1", "Alice", election.transcript),
+        Err(VoteError {
+            ballot_id: "1",
+            candidate_id: "Alice",
+            reason: VoteFailure::SecretsMismatch,
+        })
+    );
+}
+
+#[allow(non_snake_case)]
+fn check_transcript<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Transcripts"], &mut rng);
+    assert_eq!(election.transcript, TranscriptVersion::CURRENT);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+
+    // The legacy transcript cannot tell ballot "1" for candidate "2Alice"
+    // apart from ballot "12" for candidate "Alice".
+    let r = G::Scalar::random(&mut rng);
+    let R = g2.clone() * r.clone();
+    let Z = g1.clone() * (r.clone() + G::Scalar::one());
+    let legacy = VoteProof::<G>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        &SecretsPresent {
+            r,
+            v: G::Scalar::one(),
+        },
+        &VoteStatement {
+            Z: Z.clone(),
+            R: R.clone(),
+        },
+        "1",
+        "2Alice",
+        TranscriptVersion::V0,
+    );
+    assert_eq!(
+        legacy.verify(
+            g1.clone(),
+            g2.clone(),
+            Z.clone(),
+            R.clone(),
+            "1",
+            "2Alice",
+            TranscriptVersion::V0
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        legacy.verify(
+            g1.clone(),
+            g2.clone(),
+            Z.clone(),
+            R.clone(),
+            "12",
+            "Alice",
+            TranscriptVersion::V0
+        ),
+        Ok(())
+    );
+    // Legacy proofs do not verify under later transcripts.
+    for transcript in [TranscriptVersion::V1, TranscriptVersion::V2] {
+        assert_eq!(
+            legacy.verify(
+                g1.clone(),
+                g2.clone(),
+                Z.clone(),
+                R.clone(),
+                "1",
+                "2Alice",
+                transcript
+            ),
+            Err(ProofError::ChallengeMismatch)
+        );
+    }
+
+    // V1 proofs, labelled rather than tagged, still verify as V1 and only as V1.
+    let r = G::Scalar::random(&mut rng);
+    let R = g2.clone() * r.clone();
+    let Z = g1.clone() * r.clone();
+    let labelled = VoteProof::<G>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        &SecretsPresent {
+            r,
+            v: G::Scalar::zero(),
+        },
+        &VoteStatement {
+            Z: Z.clone(),
+            R: R.clone(),
+        },
+        "1",
+        "Alice",
+        TranscriptVersion::V1,
+    );
+    for (transcript, valid) in [
+        (TranscriptVersion::V0, false),
+        (TranscriptVersion::V1, true),
+        (TranscriptVersion::V2, false),
+    ] {
+        let result = labelled.verify(
+            g1.clone(),
+            g2.clone(),
+            Z.clone(),
+            R.clone(),
+            "1",
+            "Alice",
+            transcript,
+        );
+        assert_eq!(result.is_ok(), valid);
+    }
+
+    // The current transcript separates the ids.
+    let current = TranscriptVersion::CURRENT;
+    let vote = Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "2Alice", true);
+    assert!(vote
+        .verify(g1.clone(), g2.clone(), "1", "2Alice", current)
+        .is_ok());
+    assert!(vote
+        .verify(g1.clone(), g2.clone(), "12", "Alice", current)
+        .is_err());
+    // And its proofs cannot be downgraded to earlier transcripts.
+    for transcript in [TranscriptVersion::V0, TranscriptVersion::V1] {
+        assert!(vote
+            .verify(g1.clone(), g2.clone(), "1", "2Alice", transcript)
+            .is_err());
+    }
+
+    // Ballot proofs are versioned in the same way.
+    let ballot =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "2Alice", vec!["Bob"])
+            .unwrap();
+    assert!(ballot.verify(g1.clone(), g2.clone(), "1", current).is_ok());
+    for transcript in [TranscriptVersion::V0, TranscriptVersion::V1] {
+        assert!(ballot
+            .verify(g1.clone(), g2.clone(), "1", transcript)
+            .is_err());
+    }
+
+    // Signing bytes are length-prefixed from V1 on, and V2 encodes as V1.
+    let vote_bytes = vote.to_bytes(TranscriptVersion::V0);
+    assert_eq!(
+        vote.to_bytes(TranscriptVersion::V1).len(),
+        vote_bytes.len() + 4 * 8 + 4 * 8
+    );
+    assert_ne!(
+        ballot.to_bytes(TranscriptVersion::V0),
+        ballot.to_bytes(TranscriptVersion::V1)
+    );
+    assert_eq!(
+        ballot.to_bytes(TranscriptVersion::V1),
+        ballot.to_bytes(TranscriptVersion::V2)
+    );
+}
+
+#[allow(non_snake_case)]
+fn check_election_binding<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    // Two elections sharing `unique_bytes`, and so their generators, but not their ids.
+    let unique_bytes: &[&[u8]] = &[b"Reused bytes"];
+    let election_a = Election::<G>::new_bound(unique_bytes, &mut rng);
+    let election_b = Election::<G>::new(unique_bytes, &mut rng).bound([7; 32]);
+    assert_eq!(
+        (&election_a.g1, &election_a.g2),
+        (&election_b.g1, &election_b.g2)
+    );
+    let id_a = Election::<G>::derive_election_id(unique_bytes);
+    assert_eq!(election_a.election_id(), Some(id_a));
+    assert_eq!(election_b.election_id(), Some([7; 32]));
+    assert_ne!(id_a, Election::<G>::derive_election_id(&[b"Other bytes"]));
+    assert_eq!(
+        Election::<G>::new(unique_bytes, &mut rng).election_id(),
+        None
+    );
+    let (g1, g2) = (election_a.g1.clone(), election_a.g2.clone());
+    let (bound_a, bound_b) = (election_a.transcript, election_b.transcript);
+
+    // Ballots created by a bound election verify only under its transcript.
+    let candidates = ["Alice", "Bob"];
+    let ballots = election_a
+        .create_ballots(&mut rng, &candidates, [("1", "Alice")])
+        .unwrap();
+    let ballot = &ballots["1"];
+    assert_eq!(ballot.verify(g1.clone(), g2.clone(), "1", bound_a), Ok(()));
+    for transcript in [bound_b, TranscriptVersion::CURRENT] {
+        let error = ballot
+            .verify(g1.clone(), g2.clone(), "1", transcript)
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BallotError::Vote(VoteError {
+                reason: VoteFailure::ProofInvalid,
+                ..
+            })
+        ));
+    }
+    // Unbound ballots do not verify in a bound election either.
+    let unbound =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", ["Bob"]).unwrap();
+    assert!(unbound
+        .verify(g1.clone(), g2.clone(), "1", bound_a)
+        .is_err());
+    let rebound = Ballot::<_, G, _>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Alice",
+        ["Bob"],
+        bound_b,
+    )
+    .unwrap();
+    assert_eq!(rebound.verify(g1.clone(), g2.clone(), "1", bound_b), Ok(()));
+
+    // The proofs themselves are bound, with identical generators and ids.
+    let r = G::Scalar::random(&mut rng);
+    let R = g2.clone() * r.clone();
+    let Z = g1.clone() * r.clone();
+    let vote_proof = VoteProof::<G>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        &SecretsPresent {
+            r: r.clone(),
+            v: G::Scalar::zero(),
+        },
+        &VoteStatement {
+            Z: Z.clone(),
+            R: R.clone(),
+        },
+        "1",
+        "Alice",
+        bound_a,
+    );
+    let verify_vote = |transcript| {
+        vote_proof.verify(
+            g1.clone(),
+            g2.clone(),
+            Z.clone(),
+            R.clone(),
+            "1",
+            "Alice",
+            transcript,
+        )
+    };
+    assert_eq!(verify_vote(bound_a), Ok(()));
+    assert_eq!(verify_vote(bound_b), Err(ProofError::ChallengeMismatch));
+    assert_eq!(
+        verify_vote(TranscriptVersion::V2),
+        Err(ProofError::ChallengeMismatch)
+    );
+    let ballot_proof =
+        BallotProof::<G>::new_with_transcript(&mut rng, g1.clone(), g2.clone(), r, "1", bound_a);
+    // As for a ballot of this vote and one other, a yes with a zero `r`.
+    let Z_sum = Z.clone() + g1.clone();
+    let verify_ballot = |transcript| {
+        ballot_proof.verify(
+            g1.clone(),
+            g2.clone(),
+            Z_sum.clone(),
+            R.clone(),
+            "1",
+            transcript,
+        )
+    };
+    assert_eq!(verify_ballot(bound_a), Ok(()));
+    assert_eq!(
+        verify_ballot(bound_b),
+        Err(BallotProofFailure::ChallengeMismatch)
+    );
+
+    // Sessions of a bound election give results that verify under its transcript only.
+    let mut session = ElectionSession::new(election_a.clone(), candidates);
+    let handle = session.cast(&mut rng, "1", "Bob").unwrap();
+    session.confirm(handle).unwrap();
+    let mut results = session.into_results();
+    assert_eq!(results.transcript, bound_a);
+    assert_eq!(results.verify(), Ok(()));
+
+    // The binding survives serialization, and unbound elections still load as before.
+    let json = serde_json::to_string(&election_a).unwrap();
+    let deserialized: Election<G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.transcript, bound_a);
+    assert!(json.contains(&base64::encode_config(id_a, base64::URL_SAFE_NO_PAD)));
+    let json = serde_json::to_string(&results).unwrap();
+    let deserialized: ElectionResults<String, String, G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.verify(), Ok(()));
+    results.transcript = bound_b;
+    assert!(results.verify().is_err());
+    let unbound = Election::<G>::new(unique_bytes, &mut rng);
+    let json = serde_json::to_string(&unbound).unwrap();
+    let deserialized: Election<G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.transcript, TranscriptVersion::CURRENT);
+}
+
+#[allow(non_snake_case)]
+fn check_bound_ballots<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new_bound(&[b"Bound ballots"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let bound = election.transcript;
+    let round_trip = |verify: &dyn Fn(TranscriptVersion) -> bool| {
+        assert!(verify(bound));
+        assert!(!verify(TranscriptVersion::CURRENT));
+    };
+
+    // Votes, created directly or with tables, and assembled into a ballot.
+    let tables = election.precompute();
+    let yes =
+        Vote::<G, _>::new_precomputed_with_transcript(&mut rng, &tables, "1", "Alice", true, bound);
+    round_trip(&|t| yes.verify(g1.clone(), g2.clone(), "1", "Alice", t).is_ok());
+    let no = Vote::<G, _>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Bob",
+        false,
+        bound,
+    );
+    round_trip(&|t| no.verify(g1.clone(), g2.clone(), "1", "Bob", t).is_ok());
+    let votes = HashMap::from([("Alice", yes), ("Bob", no)]);
+    assert!(matches!(
+        Ballot::from_votes(&mut rng, g1.clone(), g2.clone(), "1", votes.clone()),
+        Err(BallotAssemblyError::InvalidVote {
+            candidate: "Alice" | "Bob",
+            ..
+        })
+    ));
+    let ballot =
+        Ballot::from_votes_with_transcript(&mut rng, g1.clone(), g2.clone(), "1", votes, bound)
+            .unwrap();
+    round_trip(&|t| ballot.verify(g1.clone(), g2.clone(), "1", t).is_ok());
+
+    // Each other kind of ballot, checked as it must be verified.
+    let ballot = Ballot::<_, G, _>::new_precomputed_with_transcript(
+        &mut rng,
+        &tables,
+        "2",
+        "Alice",
+        ["Bob"],
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| ballot.verify(g1.clone(), g2.clone(), "2", t).is_ok());
+    let ballot = Ballot::<_, G, _>::new_multi_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "3",
+        ["Alice", "Bob"],
+        ["Eve"],
+        2,
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| {
+        ballot
+            .verify_multi(g1.clone(), g2.clone(), "3", 2, t)
+            .is_ok()
+    });
+    let ballot = Ballot::<_, G, _>::new_weighted_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "4",
+        "Alice",
+        ["Bob"],
+        5,
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| {
+        ballot
+            .verify_weighted(g1.clone(), g2.clone(), "4", 5, t)
+            .is_ok()
+    });
+    let ballot = Ballot::<_, G, _>::new_abstention_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "5",
+        ["Alice", "Bob"],
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| {
+        ballot
+            .verify_multi(g1.clone(), g2.clone(), "5", 0, t)
+            .is_ok()
+    });
+    let ballot = Ballot::<_, G, _>::new_referendum_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "6",
+        "Question",
+        true,
+        bound,
+    );
+    round_trip(&|t| {
+        ballot
+            .verify_referendum(g1.clone(), g2.clone(), "6", t)
+            .is_ok()
+    });
+    let ballot = SmallBallot::<_, G, _>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "7",
+        "Alice",
+        ["Bob"],
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| ballot.verify(g1.clone(), g2.clone(), "7", t).is_ok());
+    let ballot = SmallBallot::<_, G, _>::new_multi_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "8",
+        ["Alice", "Bob"],
+        ["Eve"],
+        2,
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| {
+        ballot
+            .verify_multi(g1.clone(), g2.clone(), "8", 2, t)
+            .is_ok()
+    });
+    let ballot = MultiBallot::<_, _, G, _>::new_with_transcript(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "9",
+        [
+            ("Mayor", "Alice", ["Bob"]),
+            ("Treasurer", "Carol", ["Dave"]),
+        ],
+        bound,
+    )
+    .unwrap();
+    round_trip(&|t| ballot.verify(g1.clone(), g2.clone(), "9", t).is_ok());
+
+    // Proofs made directly for a ballot's votes, with and without tables.
+    let ballot =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "10", "Alice", ["Bob"]).unwrap();
+    let vote = &ballot.votes()["Alice"];
+    let (Z, R) = (vote.Z().clone(), vote.R().clone());
+    let proof = VoteProof::new_precomputed_with_transcript(
+        &mut rng,
+        &tables,
+        true,
+        vote.secrets.r.clone(),
+        Z.clone(),
+        R.clone(),
+        "10",
+        "Alice",
+        bound,
+    );
+    round_trip(&|t| {
+        let (Z, R) = (Z.clone(), R.clone());
+        proof
+            .verify(g1.clone(), g2.clone(), Z, R, "10", "Alice", t)
+            .is_ok()
+    });
+    let (Z_sum, R_sum) = ballot.zr_sums();
+    let r_sum = ballot
+        .votes()
+        .values()
+        .map(|vote| vote.secrets.r.clone())
+        .fold(G::Scalar::zero(), |a, b| a + b);
+    let proofs = [
+        BallotProof::for_votes_with_transcript(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            ballot.votes().values(),
+            "10",
+            bound,
+        ),
+        BallotProof::new_precomputed_with_transcript(&mut rng, &tables, r_sum, "10", bound),
+    ];
+    for proof in &proofs {
+        round_trip(&|t| {
+            let (Z_sum, R_sum) = (Z_sum.clone(), R_sum.clone());
+            proof
+                .verify(g1.clone(), g2.clone(), Z_sum, R_sum, "10", t)
+                .is_ok()
+        });
+    }
+}
+
+#[allow(non_snake_case)]
+fn check_proof_api<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    // Nothing here needs the election's private key.
+    let (params, transcript) = {
+        let election = Election::<G>::new(&[b"Proof API"], &mut rng);
+        (election.params(), election.transcript)
+    };
+    let (g1, g2) = (params.g1, params.g2);
+    let tables = ElectionPrecomputed::new(g1.clone(), g2.clone());
+
+    // Vote proofs from each constructor verify with each verifier.
+    let vote = Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", true);
+    let statement = vote.statement();
+    let (Z, R) = (vote.Z().clone(), vote.R().clone());
+    let r = vote.secrets.r.clone();
+    #[allow(deprecated)]
+    let positional = VoteProof::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        true,
+        r.clone(),
+        Z.clone(),
+        R.clone(),
+        "1",
+        "Alice",
+    );
+    let vote_proofs = [
+        vote.pwf().clone(),
+        positional,
+        VoteProof::for_vote(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            &vote.secrets,
+            &statement,
+            "1",
+            "Alice",
+        ),
+        VoteProof::new_precomputed(
+            &mut rng,
+            &tables,
+            true,
+            r,
+            Z.clone(),
+            R.clone(),
+            "1",
+            "Alice",
+        ),
+        VoteProof::new_with_transcript(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            &vote.secrets,
+            &statement,
+            "1",
+            "Alice",
+            transcript,
+        ),
+    ];
+    for proof in &vote_proofs {
+        let verify = |candidate| {
+            proof.verify(
+                g1.clone(),
+                g2.clone(),
+                Z.clone(),
+                R.clone(),
+                "1",
+                candidate,
+                transcript,
+            )
+        };
+        assert_eq!(verify("Alice"), Ok(()));
+        assert_eq!(verify("Bob"), Err(ProofError::ChallengeMismatch));
+        assert_eq!(
+            proof.verify_weighted(
+                g1.clone(),
+                g2.clone(),
+                Z.clone(),
+                R.clone(),
+                "1",
+                "Alice",
+                1,
+                transcript
+            ),
+            Ok(())
+        );
+    }
+
+    // As do ballot proofs, one by one and in batches.
+    let ballot =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", ["Bob"]).unwrap();
+    let (Z_sum, R_sum) = ballot.zr_sums();
+    let r_sum = ballot
+        .votes()
+        .values()
+        .map(|vote| vote.secrets.r.clone())
+        .fold(G::Scalar::zero(), |a, b| a + b);
+    let ballot_proofs = [
+        ballot.pwf().clone(),
+        BallotProof::new(&mut rng, g1.clone(), g2.clone(), r_sum.clone(), "1"),
+        BallotProof::for_votes(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            ballot.votes().values(),
+            "1",
+        ),
+        BallotProof::new_precomputed(&mut rng, &tables, r_sum.clone(), "1"),
+        BallotProof::new_with_transcript(&mut rng, g1.clone(), g2.clone(), r_sum, "1", transcript),
+    ];
+    for proof in &ballot_proofs {
+        let verify = |ballot_id| {
+            proof.verify(
+                g1.clone(),
+                g2.clone(),
+                Z_sum.clone(),
+                R_sum.clone(),
+                ballot_id,
+                transcript,
+            )
+        };
+        assert_eq!(verify("1"), Ok(()));
+        assert!(verify("2").is_err());
+        let (z, r) = (Z_sum.clone(), R_sum.clone());
+        let multi = proof.verify_multi(g1.clone(), g2.clone(), z, r, "1", 1, transcript);
+        assert_eq!(multi, Ok(()));
+        let (z, r) = (Z_sum.clone(), R_sum.clone());
+        let weighted = proof.verify_weighted(g1.clone(), g2.clone(), z, r, "1", 1, transcript);
+        assert_eq!(weighted, Ok(()));
+    }
+    let items = || {
+        ballot_proofs
+            .iter()
+            .map(|proof| (proof, Z_sum.clone(), R_sum.clone(), &b"1"[..]))
+    };
+    assert_eq!(
+        BallotProof::batch_verify(g1.clone(), g2.clone(), items(), transcript, &mut rng),
+        Ok(())
+    );
+    assert_eq!(
+        BallotProof::batch_verify_multi(g1.clone(), g2.clone(), items(), 1, transcript, &mut rng),
+        Ok(())
+    );
+
+    // Referendum ballot proofs have their own verifier.
+    let referendum =
+        Ballot::<_, G, _>::new_referendum(&mut rng, g1.clone(), g2.clone(), "2", "Prop", true);
+    let (_, R_sum) = referendum.zr_sums();
+    assert_eq!(
+        referendum
+            .pwf()
+            .verify_referendum(g1, g2, R_sum, "2", transcript),
+        Ok(())
+    );
+}
+
+fn check_ballot<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Woah some random bytes"], &mut rng);
+
+    let mut ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    assert!(ballot
+        .verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            "1",
+            election.transcript
+        )
+        .is_ok());
+    match ballot.verify(
+        election.g1.clone(),
+        election.g2.clone(),
+        "2",
+        election.transcript,
+    ) {
+        Err(BallotError::Vote(_)) => {}
+        _ => panic!("Assertion failed!"),
+    }
+
+    // Modify pwf and check it fails.
+    ballot.pwf_mut().r = DreipScalar::random(&mut rng);
+    assert_eq!(
+        ballot.verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            "1",
+            election.transcript
+        ),
+        Err(BallotError::BallotProof {
+            ballot_id: "1",
+            reason: BallotProofFailure::ChallengeMismatch,
+        })
+    );
+
+    // Ballots with duplicate or no candidates cannot be created, and say why.
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let duplicate = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Alice"],
+    )
+    .err()
+    .unwrap();
+    assert_eq!(duplicate, BallotCreationError::YesAndNoCandidate("Alice"));
+    assert_eq!(
+        duplicate.to_string(),
+        "candidate id \"Alice\" is both a yes and a no candidate"
+    );
+    assert_eq!(
+        Ballot::<_, G, _>::new(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            "1",
+            "Alice",
+            vec!["Bob", "Eve", "Bob"],
+        )
+        .err(),
+        Some(BallotCreationError::DuplicateCandidate("Bob"))
+    );
+
+    // Distinct candidate ids with the same bytes would share proof transcripts.
+    #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    struct Tagged(&'static str, u8);
+    impl IdBytes for Tagged {
+        fn id_bytes(&self) -> Cow<'_, [u8]> {
+            self.0.id_bytes()
+        }
+    }
+    let ambiguous = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        Tagged("Alice", 1),
+        [Tagged("Bob", 2), Tagged("Alice", 3)],
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        ambiguous,
+        BallotCreationError::AmbiguousCandidateEncoding(Tagged("Alice", 1), Tagged("Alice", 3))
+    );
+    assert_eq!(
+        ambiguous.to_string(),
+        "candidate ids Tagged(\"Alice\", 1) and Tagged(\"Alice\", 3) have the same bytes"
+    );
+
+    let empty = Ballot::<&str, G, _>::new_abstention(&mut rng, g1.clone(), g2.clone(), "1", vec![])
+        .err()
+        .unwrap();
+    assert_eq!(empty, BallotCreationError::NoCandidates);
+    let error: Box<dyn std::error::Error> = Box::new(empty);
+    assert_eq!(error.to_string(), "no candidates");
+    assert_eq!(
+        Ballot::<&str, G, _>::new_multi(&mut rng, g1.clone(), g2.clone(), "1", [], [], 0).err(),
+        Some(BallotCreationError::NoCandidates)
+    );
+
+    // A ballot with no votes, as a malicious dump might contain, fails verification on its
+    // own and within an election.
+    let ballot = Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", ["Bob"])
+        .unwrap()
+        .confirm(None)
+        .unwrap();
+    let mut json = serde_json::to_value(&ballot).unwrap();
+    json["votes"] = serde_json::json!({});
+    let empty: Ballot<String, G, NoSecrets> = serde_json::from_value(json).unwrap();
+    assert!(empty.is_empty());
+    for k in [0, 1] {
+        assert_eq!(
+            empty.verify_multi(g1.clone(), g2.clone(), "1", k, election.transcript),
+            Err(BallotError::Empty { ballot_id: "1" })
+        );
+    }
+    let ballots = HashMap::from([("1", empty)]);
+    let totals = HashMap::<String, CandidateTotals<G>>::new();
+    assert_eq!(
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            &totals,
+            election.transcript
+        ),
+        Err(VerificationError::Ballot(BallotError::Empty {
+            ballot_id: "1"
+        }))
+    );
+    assert_eq!(
+        verify_election_batched(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            &totals,
+            election.transcript,
+            &mut rng
+        ),
+        Err(VerificationError::Ballot(BallotError::Empty {
+            ballot_id: "1"
+        }))
+    );
+
+    // Confirming with incomplete totals fails, leaving all of them untouched.
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    let mut alice = CandidateTotals::<G>::default();
+    let mut bob = CandidateTotals::<G>::default();
+    let mut totals = HashMap::from([("Alice", &mut alice), ("Bob", &mut bob)]);
+    let error = ballot.confirm(Some(&mut totals)).err().unwrap();
+    assert_eq!(error, ConfirmError::MissingCandidate("Eve"));
+    assert_eq!(error.to_string(), "no totals for candidate id \"Eve\"");
+    assert_eq!(alice, CandidateTotals::default());
+    assert_eq!(bob, CandidateTotals::default());
+
+    // The deprecated shim still returns an option.
+    #[allow(deprecated)]
+    let shim = Ballot::<_, G, _>::new_opt(&mut rng, g1.clone(), g2.clone(), "1", "Bob", ["Bob"]);
+    assert!(shim.is_none());
+}
+
+fn check_inspection<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Inspection"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Eve",
+        vec!["Bob", "Alice"],
+    )
+    .unwrap();
+
+    // Candidates are listed in order, whatever order they were given in.
+    assert_eq!(
+        ballot.candidates().copied().collect::<Vec<_>>(),
+        ["Alice", "Bob", "Eve"]
+    );
+    assert_eq!(ballot.len(), 3);
+    assert!(!ballot.is_empty());
+    assert!(ballot.get_vote(&"Eve").unwrap().voted_yes());
+    assert!(!ballot.get_vote(&"Bob").unwrap().voted_yes());
+    assert!(ballot.get_vote(&"Mallory").is_none());
+
+    // The sums are of every vote, and are what the ballot proof is verified against.
+    let (z_sum, r_sum) = ballot.zr_sums();
+    let r = ballot
+        .votes()
+        .values()
+        .map(|vote| vote.secrets.r.clone())
+        .fold(G::Scalar::zero(), |a, b| a + b);
+    assert_eq!(r_sum, g2.clone() * r.clone());
+    assert_eq!(z_sum, g1.clone() * r + g1.clone());
+    assert!(ballot
+        .pwf()
+        .verify(
+            g1.clone(),
+            g2.clone(),
+            z_sum.clone(),
+            r_sum,
+            "1",
+            election.transcript
+        )
+        .is_ok());
+
+    // They are kept on confirmation, and are the identity for an empty ballot.
+    let confirmed = ballot.confirm(None).unwrap();
+    assert_eq!(confirmed.zr_sums().0, z_sum);
+    let (votes, pwf) = confirmed.into_parts();
+    assert_eq!(votes.len(), 3);
+    let empty = Ballot::<&str, G, NoSecrets>::from_parts(BTreeMap::new(), pwf);
+    assert!(empty.is_empty());
+    assert_eq!(empty.len(), 0);
+    assert_eq!(empty.candidates().count(), 0);
+    assert_eq!(
+        empty.zr_sums(),
+        (G::Point::identity(), G::Point::identity())
+    );
+}
+
+fn check_confirm_delta<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Confirm Delta"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["Alice", "Bob", "Eve"];
+    let mut in_place = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut merged = in_place.clone();
+
+    let mut ballots = HashMap::new();
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Bob"), ("3", "Alice"), ("4", "Eve")] {
+        let no = candidates.iter().copied().filter(|c| *c != yes);
+        let ballot =
+            Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), ballot_id, yes, no).unwrap();
+
+        let mut refs = in_place.iter_mut().map(|(c, t)| (*c, t)).collect();
+        let confirmed = ballot.clone().confirm(Some(&mut refs)).unwrap();
+        let (from_delta, delta) = ballot.confirm_with_delta();
+        assert_eq!(from_delta, confirmed);
+
+        // The delta holds exactly this ballot's secrets.
+        assert_eq!(delta.len(), candidates.len());
+        assert_eq!(delta[yes].tally_u64(), Some(1));
+        for (candidate, contribution) in delta.iter() {
+            merged.get_mut(candidate).unwrap().merge(contribution);
+        }
+        ballots.insert(ballot_id, confirmed);
+    }
+    assert_eq!(merged, in_place);
+    assert_eq!(merged["Alice"].tally_u64(), Some(2));
+    assert_eq!(
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            &merged,
+            election.transcript
+        ),
+        Ok(())
+    );
+
+    // Pending ballots give the same delta.
+    let ballot =
+        PendingBallot::<_, G>::new(&mut rng, g1, g2, "5", "Bob", ["Alice", "Eve"]).unwrap();
+    let (confirmed, delta) = ballot.confirm_with_delta();
+    assert_eq!(delta["Bob"].tally_u64(), Some(1));
+    assert_eq!(delta["Eve"].tally_u64(), Some(0));
+    for (candidate, contribution) in delta.iter() {
+        merged.get_mut(candidate).unwrap().merge(contribution);
+    }
+    ballots.insert("5", confirmed.ballot);
+    assert_eq!(
+        verify_election(
+            election.g1,
+            election.g2,
+            &ballots,
+            &merged,
+            election.transcript
+        ),
+        Ok(())
+    );
+}
+
+fn check_small_ballot<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Small Ballot"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["Alice", "Bob", "Eve"];
+    let mut totals = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut small_totals = totals.clone();
+
+    let mut ballots = HashMap::new();
+    let mut small_ballots = HashMap::new();
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Bob"), ("3", "Alice")] {
+        // Both representations of a ballot created from the same randomness are equal.
+        let seed = rng.next_u64();
+        let no = candidates.iter().copied().filter(|c| *c != yes);
+        let ballot = Ballot::<_, G, _>::new(
+            ChaCha20Rng::seed_from_u64(seed),
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            yes,
+            no.clone(),
+        )
+        .unwrap();
+        let small = SmallBallot::<_, G, _>::new(
+            ChaCha20Rng::seed_from_u64(seed),
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            yes,
+            no,
+        )
+        .unwrap();
+        assert_eq!(small, SmallBallot::from(ballot.clone()));
+        assert_eq!(Ballot::from(small.clone()), ballot);
+        assert_eq!(small.len(), ballot.len());
+        assert!(small.candidates().eq(&candidates));
+        assert_eq!(small.zr_sums(), ballot.zr_sums());
+        for candidate in candidates {
+            assert_eq!(small.get_vote(&candidate), ballot.get_vote(&candidate));
+        }
+        assert_eq!(small.get_vote(&"Mallory"), None);
+
+        // They serialize identically, so each loads from the other's JSON.
+        let json = serde_json::to_string(&ballot).unwrap();
+        assert_eq!(serde_json::to_string(&small).unwrap(), json);
+        let loaded: SmallBallot<&str, G, SecretsPresent<G>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, small);
+
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        let confirmed = ballot.confirm(Some(&mut refs)).unwrap();
+        let mut refs = small_totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        let small_confirmed = small.confirm(Some(&mut refs)).unwrap();
+        assert_eq!(Ballot::from(small_confirmed.clone()), confirmed);
+        assert_eq!(
+            small_confirmed.verify(g1.clone(), g2.clone(), ballot_id, election.transcript),
+            Ok(())
+        );
+        ballots.insert(ballot_id, confirmed);
+        small_ballots.insert(ballot_id, small_confirmed);
+    }
+    assert_eq!(small_totals, totals);
+
+    // Confirming needs every candidate's totals.
+    let small =
+        SmallBallot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "4", "Eve", ["Bob"]).unwrap();
+    let mut refs = HashMap::new();
+    assert_eq!(
+        small.confirm(Some(&mut refs)),
+        Err(ConfirmError::MissingCandidate("Bob"))
+    );
+
+    // Elections verify over either, with the same results.
+    let verify = |ballots: &HashMap<_, Ballot<_, G, NoSecrets>>,
+                  small_ballots: &HashMap<_, SmallBallot<_, G, NoSecrets>>| {
+        let result = verify_election(
+            g1.clone(),
+            g2.clone(),
+            ballots,
+            &totals,
+            election.transcript,
+        );
+        let small_result = verify_election(
+            g1.clone(),
+            g2.clone(),
+            small_ballots,
+            &totals,
+            election.transcript,
+        );
+        assert_eq!(small_result, result);
+        result
+    };
+    assert_eq!(verify(&ballots, &small_ballots), Ok(()));
+    let (_, vote) = &mut small_ballots.get_mut("1").unwrap().votes_mut()[0];
+    *vote.R_mut() = DreipPoint::generator();
+    *ballots
+        .get_mut("1")
+        .unwrap()
+        .votes_mut()
+        .get_mut("Alice")
+        .unwrap()
+        .R_mut() = DreipPoint::generator();
+    assert_eq!(
+        verify(&ballots, &small_ballots),
+        Err(VerificationError::Ballot(BallotError::Vote(VoteError {
+            ballot_id: "1",
+            candidate_id: "Alice",
+            reason: VoteFailure::ProofInvalid,
+        })))
+    );
+}
+
+fn check_compute_totals<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Compute Totals"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["Alice", "Bob", "Eve"];
+    let mut ballots = HashMap::new();
+    for i in 0..50 {
+        let yes = candidates[i % 3];
+        let no = candidates.iter().copied().filter(|c| *c != yes);
+        let ballot = Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), i, yes, no).unwrap();
+        ballots.insert(i, ballot);
+    }
+
+    // The totals match those of confirming every ballot, and verify.
+    let totals = compute_totals(&ballots);
+    assert_eq!(totals["Alice"].tally_u64(), Some(17));
+    assert_eq!(totals["Bob"].tally_u64(), Some(17));
+    assert_eq!(totals["Eve"].tally_u64(), Some(16));
+    let mut confirmed_totals = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    for ballot in ballots.values() {
+        let mut refs = confirmed_totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        ballot.clone().confirm(Some(&mut refs)).unwrap();
+    }
+    assert_eq!(totals, confirmed_totals);
+    assert_eq!(
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            &totals,
+            election.transcript
+        ),
+        Ok(())
+    );
+    assert_eq!(compute_totals_checked(&candidates, &ballots), Ok(totals));
+
+    // Every candidate has an entry, even without ballots.
+    let empty = HashMap::<usize, Ballot<_, G, _>>::new();
+    assert!(compute_totals(&empty).is_empty());
+    let totals = compute_totals_checked(&candidates, &empty).unwrap();
+    assert_eq!(totals.len(), 3);
+    assert_eq!(totals["Bob"], CandidateTotals::default());
+
+    // A ballot with an extra or a missing candidate is named.
+    let extra = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        50usize,
+        "Alice",
+        ["Bob", "Eve", "Mallory"],
+    )
+    .unwrap();
+    let mut with_extra = ballots.clone();
+    with_extra.insert(50, extra);
+    assert_eq!(
+        compute_totals_checked(&candidates, &with_extra),
+        Err(CandidateMismatch::Extra {
+            ballot_id: 50,
+            candidate: "Mallory"
+        })
+    );
+    assert_eq!(compute_totals(&with_extra)["Mallory"].tally_u64(), Some(0));
+    let missing = Ballot::<_, G, _>::new(&mut rng, g1, g2, 50usize, "Alice", ["Bob"]).unwrap();
+    let mut with_missing = ballots;
+    with_missing.insert(50, missing);
+    assert_eq!(
+        compute_totals_checked(&candidates, &with_missing),
+        Err(CandidateMismatch::Missing {
+            ballot_id: 50,
+            candidate: "Eve"
+        })
+    );
+}
+
+fn check_sharding<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Sharding"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let candidates = ["Alice", "Bob", "Eve"];
+    let new_totals = || {
+        candidates
+            .iter()
+            .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    // Each of three shards confirms its own ballots into its own totals, and all of them
+    // into the whole election's.
+    let mut whole_totals = new_totals();
+    let mut whole = HashMap::new();
+    let mut shards = vec![HashMap::new(), HashMap::new(), HashMap::new()];
+    let mut partials = vec![new_totals(), new_totals(), new_totals()];
+    for i in 0..12usize {
+        let yes = candidates[i % 2];
+        let no = candidates.iter().copied().filter(|c| *c != yes);
+        let ballot = Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), i, yes, no).unwrap();
+        let mut refs = whole_totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        whole.insert(i, ballot.clone().confirm(Some(&mut refs)).unwrap());
+        let mut refs = partials[i % 3].iter_mut().map(|(c, t)| (*c, t)).collect();
+        shards[i % 3].insert(i, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+
+    // The merged totals are the whole election's, and verify as they do.
+    let merged = merge_totals(partials.clone()).unwrap();
+    assert_eq!(merged, whole_totals);
+    assert_eq!(merged["Alice"].tally_u64(), Some(6));
+    assert_eq!(merged["Eve"].tally_u64(), Some(0));
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), &whole, &merged, transcript),
+        Ok(())
+    );
+    assert_eq!(
+        verify_election_sharded(g1.clone(), g2.clone(), &shards, &merged, transcript),
+        Ok(())
+    );
+    assert!(
+        merge_totals(Vec::<HashMap<&str, CandidateTotals<G>>>::new())
+            .unwrap()
+            .is_empty()
+    );
+
+    // One shard's totals verify only for its own ballots.
+    assert!(
+        verify_election_sharded(g1.clone(), g2.clone(), &shards, &partials[0], transcript).is_err()
+    );
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), &shards[0], &partials[0], transcript),
+        Ok(())
+    );
+
+    // Too few shards fail as the same ballots in one map do.
+    let fewer = verify_election_sharded(g1.clone(), g2.clone(), &shards[1..], &merged, transcript);
+    assert!(fewer.is_err());
+    assert_eq!(
+        fewer,
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &shards[1..]
+                .iter()
+                .flatten()
+                .map(|(id, ballot)| (*id, ballot.clone()))
+                .collect::<HashMap<_, _>>(),
+            &merged,
+            transcript
+        )
+    );
+
+    // A ballot counted by two shards is rejected.
+    let mut duplicated = shards.clone();
+    let (ballot_id, ballot) = shards[0].iter().next().unwrap();
+    duplicated[1].insert(*ballot_id, ballot.clone());
+    assert_eq!(
+        verify_election_sharded(g1.clone(), g2.clone(), &duplicated, &merged, transcript),
+        Err(VerificationError::DuplicateBallot {
+            ballot_id: *ballot_id
+        })
+    );
+
+    // Shards must have totals for the same candidates as the first.
+    let mut missing = partials.clone();
+    missing[2].remove("Bob");
+    assert_eq!(
+        merge_totals(missing),
+        Err(MergeError::MissingCandidate {
+            part: 2,
+            candidate: "Bob"
+        })
+    );
+    let mut extra = partials;
+    extra[1].insert("Mallory", CandidateTotals::default());
+    assert_eq!(
+        merge_totals(extra),
+        Err(MergeError::ExtraCandidate {
+            part: 1,
+            candidate: "Mallory"
+        })
+    );
+}
+
+fn check_subset<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Subset"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let candidates = (0..16)
+        .map(|i| format!("Candidate {}", i))
+        .collect::<Vec<_>>();
+    let new_totals = || {
+        candidates
+            .iter()
+            .map(|candidate| (candidate.clone(), CandidateTotals::<G>::default()))
+            .collect::<HashMap<_, _>>()
+    };
+
+    // As in the benchmark, each ballot votes for the next candidate in turn. The first twelve
+    // are one station's and the rest another's, each confirmed into the station's subtotals
+    // and the whole election's totals.
+    let mut full_totals = new_totals();
+    let mut parts = [HashMap::new(), HashMap::new()];
+    let mut subtotals = vec![new_totals(), new_totals()];
+    for i in 0..20usize {
+        let yes = &candidates[i % candidates.len()];
+        let no = candidates.iter().filter(|c| *c != yes).cloned();
+        let ballot =
+            Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), i, yes.clone(), no).unwrap();
+        let part = usize::from(i >= 12);
+        let mut refs = full_totals
+            .iter_mut()
+            .map(|(c, t)| (c.clone(), t))
+            .collect();
+        ballot.clone().confirm(Some(&mut refs)).unwrap();
+        let mut refs = subtotals[part]
+            .iter_mut()
+            .map(|(c, t)| (c.clone(), t))
+            .collect();
+        let ballot = ballot.confirm(Some(&mut refs)).unwrap();
+        parts[part].insert(i, ballot);
+    }
+
+    // Each part verifies against its own subtotals, and they add up to the whole.
+    for (ballots, totals) in parts.iter().zip(&subtotals) {
+        assert_eq!(
+            verify_subset(g1.clone(), g2.clone(), ballots, totals, transcript),
+            Ok(())
+        );
+    }
+    assert_eq!(
+        verify_totals_decomposition(&full_totals, &subtotals),
+        Ok(())
+    );
+
+    // One part's subtotals do not verify for the other's ballots, nor add up alone.
+    assert!(matches!(
+        verify_subset(g1.clone(), g2.clone(), &parts[1], &subtotals[0], transcript),
+        Err(VerificationError::Tally { .. })
+    ));
+    assert_eq!(
+        verify_totals_decomposition(&full_totals, &subtotals[..1]),
+        Err(DecompositionError::Mismatch {
+            candidate: "Candidate 0".to_string()
+        })
+    );
+
+    // A station with no ballots verifies with zero subtotals for the roster, or none at all,
+    // and adds nothing to the whole.
+    let empty = HashMap::<usize, Ballot<String, G, NoSecrets>>::new();
+    for totals in [new_totals(), HashMap::new()] {
+        assert_eq!(
+            verify_subset(g1.clone(), g2.clone(), &empty, &totals, transcript),
+            Ok(())
+        );
+        let mut with_empty = subtotals.clone();
+        with_empty.push(totals);
+        assert_eq!(
+            verify_totals_decomposition(&full_totals, &with_empty),
+            Ok(())
+        );
+    }
+
+    // But not with votes for a candidate no ballot votes for.
+    let mut claimed = new_totals();
+    claimed.get_mut("Candidate 3").unwrap().tally = G::Scalar::one();
+    assert_eq!(
+        verify_subset(g1.clone(), g2.clone(), &empty, &claimed, transcript),
+        Err(VerificationError::Tally {
+            candidate_id: "Candidate 3".to_string()
+        })
+    );
+
+    // A part's ballots must only vote for candidates in its subtotals.
+    let mut missing = subtotals[0].clone();
+    missing.remove("Candidate 7");
+    assert_eq!(
+        verify_subset(g1.clone(), g2.clone(), &parts[0], &missing, transcript),
+        Err(VerificationError::WrongCandidates)
+    );
+
+    // And a part's subtotals only have candidates of the whole election.
+    let mut extra = subtotals.clone();
+    extra[1].insert("Mallory".to_string(), CandidateTotals::default());
+    assert_eq!(
+        verify_totals_decomposition(&full_totals, &extra),
+        Err(DecompositionError::ExtraCandidate {
+            part: 1,
+            candidate: "Mallory".to_string()
+        })
+    );
+
+    // Tampered subtotals fail both their own part's verification and the decomposition.
+    let mut tampered = subtotals;
+    tampered[1].get_mut("Candidate 14").unwrap().r_sum = G::Scalar::random(&mut rng);
+    assert_eq!(
+        verify_subset(g1.clone(), g2.clone(), &parts[1], &tampered[1], transcript),
+        Err(VerificationError::Tally {
+            candidate_id: "Candidate 14".to_string()
+        })
+    );
+    assert_eq!(
+        verify_totals_decomposition(&full_totals, &tampered),
+        Err(DecompositionError::Mismatch {
+            candidate: "Candidate 14".to_string()
+        })
+    );
+}
+
+fn check_totals_arithmetic<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let mut secrets = |v| SecretsPresent::<G> {
+        r: G::Scalar::random(&mut rng),
+        v: scalar_from_u128::<G>(v),
+    };
+    let (yes, no, other) = (secrets(1), secrets(0), secrets(1));
+    let start = CandidateTotals::<G>::from((scalar_from_u128::<G>(7), secrets(0).r.clone()));
+
+    // Accumulating then retracting votes, in any order, gives the original totals.
+    let mut totals = start.clone();
+    totals.accumulate(&yes);
+    totals.accumulate(&no);
+    assert_eq!(totals.tally_u64(), Some(8));
+    assert_eq!(
+        totals.r_sum,
+        start.r_sum.clone() + yes.r.clone() + no.r.clone()
+    );
+    totals.retract(&yes);
+    assert_eq!(totals.tally_u64(), Some(7));
+    totals.retract(&no);
+    assert_eq!(totals, start);
+
+    // The operators combine totals as `merge` does, and subtract undoes addition.
+    let mut one = CandidateTotals::<G>::default();
+    one.accumulate(&yes);
+    let mut two = CandidateTotals::<G>::default();
+    two.accumulate(&no);
+    two.accumulate(&other);
+    let mut merged = one.clone();
+    merged.merge(&two);
+    assert_eq!(one.clone() + two.clone(), merged);
+    assert_eq!(two.clone() + one.clone(), merged);
+    let mut sum = one.clone();
+    sum += two.clone();
+    assert_eq!(sum, merged);
+    assert_eq!(merged.tally_u64(), Some(2));
+    assert_eq!(merged.clone() - two.clone(), one);
+    let mut difference = merged;
+    difference -= one;
+    assert_eq!(difference, two);
+    assert_eq!(
+        start.clone() + CandidateTotals::default() - CandidateTotals::default(),
+        start
+    );
+}
+
+fn check_tally_counts<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let totals = |tally: G::Scalar| CandidateTotals::<G>::from((tally, G::Scalar::zero()));
+    let counted = |tally: u128| totals(scalar_from_u128::<G>(tally)).tally_u64();
+
+    // Small and boundary values convert exactly.
+    for tally in [0, 1, 2, 1000, u64::MAX as u128 - 1, u64::MAX as u128] {
+        assert_eq!(counted(tally), Some(tally as u64));
+    }
+
+    // Larger tallies, including negative ones, are not counts at all.
+    assert_eq!(counted(u64::MAX as u128 + 1), None);
+    assert_eq!(counted(u128::MAX), None);
+    assert_eq!(totals(-G::Scalar::one()).tally_u64(), None);
+
+    // A real election's counts are its tallies.
+    let election = Election::<G>::new(&[b"Tally Counts"], &mut rng);
+    let mut ballots = HashMap::new();
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Bob"), ("3", "Alice")] {
+        let no = ["Alice", "Bob", "Eve"].into_iter().filter(|c| *c != yes);
+        let ballot = Ballot::<_, G, _>::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            ballot_id,
+            yes,
+            no,
+        )
+        .unwrap();
+        ballots.insert(ballot_id, ballot);
+    }
+    let mut totals = compute_totals(&ballots);
+    assert_eq!(
+        results(&totals),
+        Some(HashMap::from([("Alice", 2), ("Bob", 1), ("Eve", 0)]))
+    );
+
+    let published = election.results(HashMap::<&str, _>::new(), totals.clone());
+    assert_eq!(
+        published.tallies(),
+        Ok(HashMap::from([("Alice", 2), ("Bob", 1), ("Eve", 0)]))
+    );
+    assert_eq!(published.total_ballots(), 0);
+
+    // One tally too large to count makes all of them unavailable.
+    totals.get_mut("Eve").unwrap().tally = scalar_from_u128::<G>(u64::MAX as u128 + 1);
+    assert_eq!(results(&totals), None);
+
+    // Results name the least candidate whose tally is too large.
+    totals.get_mut("Bob").unwrap().tally = -G::Scalar::one();
+    let published = election.results(HashMap::<&str, _>::new(), totals.clone());
+    assert_eq!(
+        published.tallies(),
+        Err(TallyOverflow {
+            candidate_id: "Bob"
+        })
+    );
+    assert_eq!(
+        results(&HashMap::<&str, CandidateTotals<G>>::new()),
+        Some(HashMap::new())
+    );
+}
+
+fn check_assembly<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Assembly"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    // The votes a terminal would create, each for a ballot id and candidate.
+    let mut votes = [
+        ("1", "Alice", true),
+        ("1", "Bob", false),
+        ("1", "Eve", false),
+        ("2", "Bob", false),
+        ("1", "Bob", true),
+    ]
+    .map(|(ballot_id, candidate, yes)| {
+        Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), ballot_id, candidate, yes)
+    })
+    .into_iter();
+    let (alice_yes, bob_no, eve_no, bob_other_ballot, bob_yes) = (
+        votes.next().unwrap(),
+        votes.next().unwrap(),
+        votes.next().unwrap(),
+        votes.next().unwrap(),
+        votes.next().unwrap(),
+    );
+
+    // Assembled ballots verify, and are confirmed and tallied like any other.
+    let ballot = Ballot::from_votes(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        HashMap::from([
+            ("Alice", alice_yes.clone()),
+            ("Bob", bob_no.clone()),
+            ("Eve", eve_no.clone()),
+        ]),
+    )
+    .unwrap();
+    assert_eq!(
+        ballot.verify(g1.clone(), g2.clone(), "1", transcript),
+        Ok(())
+    );
+    assert!(ballot
+        .verify(g1.clone(), g2.clone(), "2", transcript)
+        .is_err());
+    assert_eq!(ballot.claimed_choice(), Ok("Alice"));
+    let mut totals = HashMap::from([
+        ("Alice", CandidateTotals::<G>::default()),
+        ("Bob", CandidateTotals::default()),
+        ("Eve", CandidateTotals::default()),
+    ]);
+    let mut totals_mut = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+    let confirmed = ballot.confirm(Some(&mut totals_mut)).unwrap();
+    assert_eq!(totals["Alice"].tally_u64(), Some(1));
+    let ballots = HashMap::from([("1", confirmed)]);
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), &ballots, &totals, transcript),
+        Ok(())
+    );
+
+    // Ballot proofs made for the votes verify, as do those from their `r` values summed by
+    // hand, but not from any other sum.
+    let votes = [&alice_yes, &bob_no, &eve_no];
+    let (z_sum, r_sum) = votes.iter().fold(
+        (G::Point::identity(), G::Point::identity()),
+        |(z_sum, r_sum), vote| (z_sum + vote.Z().clone(), r_sum + vote.R().clone()),
+    );
+    let verify_proof = |proof: &BallotProof<G>| {
+        proof.verify(
+            g1.clone(),
+            g2.clone(),
+            z_sum.clone(),
+            r_sum.clone(),
+            "1",
+            transcript,
+        )
+    };
+    let proof = BallotProof::for_votes(&mut rng, g1.clone(), g2.clone(), votes, "1");
+    assert_eq!(verify_proof(&proof), Ok(()));
+    let r = votes
+        .iter()
+        .map(|vote| vote.secrets.r.clone())
+        .fold(G::Scalar::zero(), |a, b| a + b);
+    let proof = BallotProof::new(&mut rng, g1.clone(), g2.clone(), r.clone(), "1");
+    assert_eq!(verify_proof(&proof), Ok(()));
+    let wrong_r = r - alice_yes.secrets.r.clone();
+    let proof = BallotProof::new(&mut rng, g1.clone(), g2.clone(), wrong_r, "1");
+    assert_eq!(
+        verify_proof(&proof),
+        Err(BallotProofFailure::ChallengeMismatch)
+    );
+
+    // Votes created for another ballot, or another candidate, are rejected.
+    let assemble = |rng: &mut _, votes| Ballot::from_votes(rng, g1.clone(), g2.clone(), "1", votes);
+    assert_eq!(
+        assemble(
+            &mut rng,
+            HashMap::from([("Alice", alice_yes.clone()), ("Bob", bob_other_ballot)])
+        ),
+        Err(BallotAssemblyError::InvalidVote {
+            candidate: "Bob",
+            reason: VoteFailure::ProofInvalid,
+        })
+    );
+    assert_eq!(
+        assemble(
+            &mut rng,
+            HashMap::from([("Alice", alice_yes.clone()), ("Eve", bob_no.clone())])
+        ),
+        Err(BallotAssemblyError::InvalidVote {
+            candidate: "Eve",
+            reason: VoteFailure::ProofInvalid,
+        })
+    );
+
+    // Exactly one vote must be a yes.
+    assert_eq!(
+        assemble(
+            &mut rng,
+            HashMap::from([("Alice", alice_yes.clone()), ("Bob", bob_yes)])
+        ),
+        Err(BallotAssemblyError::WrongYesCount {
+            expected: 1,
+            actual: 2
+        })
+    );
+    assert_eq!(
+        assemble(&mut rng, HashMap::from([("Bob", bob_no), ("Eve", eve_no)])),
+        Err(BallotAssemblyError::WrongYesCount {
+            expected: 1,
+            actual: 0
+        })
+    );
+    assert_eq!(
+        assemble(&mut rng, HashMap::new()),
+        Err(BallotAssemblyError::NoVotes)
+    );
+}
+
+fn check_outcome<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    /// Run an election in which each ballot votes for the given candidate.
+    fn run<G: DreipGroup>(
+        rng: &mut (impl RngCore + CryptoRng),
+        votes: &[&'static str],
+    ) -> ElectionResults<String, &'static str, G> {
+        let election = Election::<G>::new(&[b"Outcome"], &mut *rng);
+        let mut session = ElectionSession::new(election, ["Alice", "Bob", "Eve"]);
+        for (i, candidate) in votes.iter().enumerate() {
+            let handle = session.cast(&mut *rng, i.to_string(), *candidate).unwrap();
+            session.confirm(handle).unwrap();
+        }
+        session.into_results()
+    }
+
+    // A clear winner, with the others ranked below.
+    let results = run::<G>(&mut rng, &["Bob", "Alice", "Bob", "Eve", "Bob", "Alice"]);
+    let verified = results.verified().unwrap();
+    assert_eq!(verified.ranking(), [("Bob", 3), ("Alice", 2), ("Eve", 1)]);
+    assert_eq!(verified.outcome(), Outcome::Winner("Bob"));
+    assert_eq!(
+        verified.tallies(),
+        HashMap::from([("Alice", 2), ("Bob", 3), ("Eve", 1)])
+    );
+
+    // A two-way tie, ranked by candidate id bytes whatever the order of the votes.
+    let results = run::<G>(&mut rng, &["Eve", "Bob", "Bob", "Eve"]);
+    let verified = results.verified().unwrap();
+    assert_eq!(verified.ranking(), [("Bob", 2), ("Eve", 2), ("Alice", 0)]);
+    assert_eq!(verified.outcome(), Outcome::Tie(vec!["Bob", "Eve"]));
+
+    // Everyone tied on one vote each is a tie between all of them.
+    let results = run::<G>(&mut rng, &["Eve", "Alice", "Bob"]);
+    assert_eq!(
+        results.verified().unwrap().outcome(),
+        Outcome::Tie(vec!["Alice", "Bob", "Eve"])
+    );
+
+    // An election with no ballots has no outcome.
+    let election = Election::<G>::new(&[b"Outcome"], &mut rng);
+    let results = election.results(HashMap::<&str, _>::new(), HashMap::<&str, _>::new());
+    let verified = results.verified().unwrap();
+    assert_eq!(verified.ranking(), []);
+    assert_eq!(verified.outcome(), Outcome::NoBallots);
+
+    // Results that fail verification give no ranking at all.
+    let mut tampered = run::<G>(&mut rng, &["Alice", "Bob", "Alice"]);
+    tampered.totals.get_mut("Bob").unwrap().tally = scalar_from_u128::<G>(3);
+    assert_eq!(
+        tampered.verified().unwrap_err(),
+        VerificationError::Tally {
+            candidate_id: "Bob"
+        }
+    );
+}
+
+fn check_election<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"foobaraboof"], &mut rng);
+    let mut ballots: HashMap<_, Ballot<_, G, _>> = HashMap::new();
+
+    ballots.insert(
+        "1",
+        Ballot::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            "1",
+            "Alice",
+            vec!["Bob", "Eve"],
+        )
+        .unwrap(),
+    );
+    ballots.insert(
+        "2",
+        Ballot::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            "2",
+            "Bob",
+            vec!["Alice", "Eve"],
+        )
+        .unwrap(),
+    );
+    ballots.insert(
+        "3",
+        Ballot::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            "3",
+            "Alice",
+            vec!["Bob", "Eve"],
+        )
+        .unwrap(),
+    );
+
+    let alice_r_sum = ballots
+        .values()
+        .map(|b| b.votes().iter().find(|(c, _)| **c == "Alice").unwrap())
+        .fold(G::Scalar::zero(), |a, (_, b)| a + b.secrets.r.clone());
+    let bob_r_sum = ballots
+        .values()
+        .map(|b| b.votes().iter().find(|(c, _)| **c == "Bob").unwrap())
+        .fold(G::Scalar::zero(), |a, (_, b)| a + b.secrets.r.clone());
+    let eve_r_sum = ballots
+        .values()
+        .map(|b| b.votes().iter().find(|(c, _)| **c == "Eve").unwrap())
+        .fold(G::Scalar::zero(), |a, (_, b)| a + b.secrets.r.clone());
+
+    let one = G::Scalar::one();
+    let two = one.clone() + one.clone();
+    let mut totals = HashMap::new();
+    totals.insert("Alice", (two.clone(), alice_r_sum.clone()).into());
+    totals.insert("Bob", (one.clone(), bob_r_sum.clone()).into());
+    totals.insert("Eve", (G::Scalar::zero(), eve_r_sum).into());
+
+    assert!(verify_both(&election, &ballots, &totals, &mut rng).is_ok());
+
+    // Ballot ids that cannot be cloned verify by reference, and name a failing ballot.
+    #[derive(Debug, Eq, PartialEq, Hash)]
+    struct RowId(&'static str);
+    impl IdBytes for RowId {
+        fn id_bytes(&self) -> Cow<'_, [u8]> {
+            self.0.id_bytes()
+        }
+    }
+    let rows = ["1", "2", "3"].map(RowId);
+    let mut by_row = rows
+        .iter()
+        .map(|row| (row, ballots[row.0].clone()))
+        .collect::<HashMap<_, _>>();
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    assert_eq!(
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &by_row,
+            &totals,
+            election.transcript
+        ),
+        Ok(())
+    );
+    by_row.get_mut(&rows[1]).unwrap().pwf_mut().r = G::Scalar::random(&mut rng);
+    let expected = Err(VerificationError::Ballot(BallotError::BallotProof {
+        ballot_id: &rows[1],
+        reason: BallotProofFailure::ChallengeMismatch,
+    }));
+    assert_eq!(
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &by_row,
+            &totals,
+            election.transcript
+        ),
+        expected
+    );
+
+    // Streamed ballots may be owned, and are dropped as they are verified.
+    let stream = by_row.iter().map(|(row, ballot)| (*row, ballot.clone()));
+    assert_eq!(
+        verify_election_iter(g1, g2, stream, &totals, election.transcript),
+        expected
+    );
+
+    // Now change the tally and check it fails.
+    totals.get_mut("Eve").unwrap().tally = two.clone() + two + one.clone();
+    assert_eq!(
+        verify_both(&election, &ballots, &totals, &mut rng),
+        Err(VerificationError::Tally {
+            candidate_id: "Eve"
+        })
+    );
+
+    // Change the random sum and check it fails.
+    totals.get_mut("Eve").unwrap().tally = G::Scalar::zero();
+    totals.get_mut("Alice").unwrap().r_sum = G::Scalar::random(&mut rng);
+    assert_eq!(
+        verify_both(&election, &ballots, &totals, &mut rng),
+        Err(VerificationError::Tally {
+            candidate_id: "Alice"
+        })
+    );
+
+    // Change the candidates and check it fails.
+    totals.get_mut("Alice").unwrap().r_sum = alice_r_sum;
+    totals.remove("Bob").unwrap();
+    assert_eq!(
+        verify_both(&election, &ballots, &totals, &mut rng),
+        Err(VerificationError::WrongCandidates)
+    );
+
+    // Change a ballot proof and check it fails, naming the ballot.
+    totals.insert("Bob", (one, bob_r_sum).into());
+    let r = ballots["2"].pwf().r.clone();
+    ballots.get_mut("2").unwrap().pwf_mut().r = G::Scalar::random(&mut rng);
+    assert_eq!(
+        verify_both(&election, &ballots, &totals, &mut rng),
+        Err(VerificationError::Ballot(BallotError::BallotProof {
+            ballot_id: "2",
+            reason: BallotProofFailure::ChallengeMismatch,
+        }))
+    );
+    ballots.get_mut("2").unwrap().pwf_mut().r = r;
+
+    // Change a vote and check it fails.
+    *ballots
+        .get_mut("1")
+        .unwrap()
+        .votes_mut()
+        .get_mut("Alice")
+        .unwrap()
+        .R_mut() = DreipPoint::generator();
+    assert_eq!(
+        verify_both(&election, &ballots, &totals, &mut rng),
+        Err(VerificationError::Ballot(BallotError::Vote(VoteError {
+            ballot_id: "1",
+            candidate_id: "Alice",
+            reason: VoteFailure::SecretsMismatch,
+        })))
+    );
+}
+
+fn check_incremental<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    type Results<G> = ElectionResults<&'static str, &'static str, G>;
+
+    /// Feed the audited and then the confirmed ballots to a verifier, as
+    /// `verify_full_election` verifies them, and then check the totals.
+    fn verify_incrementally<G: DreipGroup>(
+        results: &Results<G>,
+    ) -> Result<VerificationReport, VerificationError<&'static str, &'static str>> {
+        let mut verifier =
+            IncrementalVerifier::new(results.g1.clone(), results.g2.clone(), results.transcript)
+                .map_err(VerificationError::InvalidParameters)?;
+        for (ballot_id, ballot) in results.audited.iter() {
+            verifier
+                .feed_audited_ballot(*ballot_id, ballot)
+                .map_err(VerificationError::Audited)?;
+        }
+        for (ballot_id, ballot) in results.confirmed.iter() {
+            verifier
+                .feed_ballot(*ballot_id, ballot)
+                .map_err(VerificationError::Ballot)?;
+        }
+        verifier.finalize(&results.totals)
+    }
+
+    /// Verify the results both ways, checking that they agree.
+    fn verify_both<G: DreipGroup + Debug + Eq>(
+        results: &Results<G>,
+    ) -> Result<VerificationReport, VerificationError<&'static str, &'static str>> {
+        let incremental = verify_incrementally(results);
+        let batch = verify_full_election(
+            results.g1.clone(),
+            results.g2.clone(),
+            &results.confirmed,
+            &results.audited,
+            &HashMap::new(),
+            &HashMap::new(),
+            &results.totals,
+            results.transcript,
+        );
+        assert_eq!(incremental.as_ref().map(|_| ()), batch.as_ref().copied());
+        incremental
+    }
+
+    let election = Election::<G>::new(&[b"Incremental"], &mut rng);
+    let mut session = ElectionSession::new(election, ["Alice", "Bob", "Eve"]);
+    for (ballot_id, candidate) in [("1", "Alice"), ("2", "Bob"), ("3", "Alice"), ("4", "Eve")] {
+        let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+        session.confirm(handle).unwrap();
+    }
+    let handle = session.cast(&mut rng, "5", "Bob").unwrap();
+    session.audit(handle).unwrap();
+    let results = session.into_results();
+
+    assert_eq!(
+        verify_both(&results),
+        Ok(VerificationReport {
+            confirmed: 4,
+            audited: 1,
+            candidates: 3,
+        })
+    );
+
+    // A changed tally or random sum fails at the end.
+    let mut tampered = results.clone();
+    tampered.totals.get_mut("Eve").unwrap().tally = G::Scalar::zero();
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::Tally {
+            candidate_id: "Eve"
+        })
+    );
+    let mut tampered = results.clone();
+    tampered.totals.get_mut("Alice").unwrap().r_sum = G::Scalar::random(&mut rng);
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::Tally {
+            candidate_id: "Alice"
+        })
+    );
+
+    // So do missing and extra candidates.
+    let mut tampered = results.clone();
+    tampered.totals.remove("Bob");
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::WrongCandidates)
+    );
+    let mut tampered = results.clone();
+    tampered
+        .totals
+        .insert("Mallory", CandidateTotals::default());
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::WrongCandidates)
+    );
+
+    // A tampered ballot proof or vote fails as it is fed, naming the ballot.
+    let mut tampered = results.clone();
+    tampered.confirmed.get_mut("2").unwrap().pwf_mut().r = G::Scalar::random(&mut rng);
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::Ballot(BallotError::BallotProof {
+            ballot_id: "2",
+            reason: BallotProofFailure::ChallengeMismatch,
+        }))
+    );
+    let mut tampered = results.clone();
+    *tampered
+        .confirmed
+        .get_mut("1")
+        .unwrap()
+        .votes_mut()
+        .get_mut("Alice")
+        .unwrap()
+        .R_mut() = DreipPoint::generator();
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::Ballot(BallotError::Vote(VoteError {
+            ballot_id: "1",
+            candidate_id: "Alice",
+            reason: VoteFailure::ProofInvalid,
+        })))
+    );
+
+    // An audited ballot must still match its secrets.
+    let mut tampered = results.clone();
+    *tampered
+        .audited
+        .get_mut("5")
+        .unwrap()
+        .votes_mut()
+        .get_mut("Bob")
+        .unwrap()
+        .Z_mut() = DreipPoint::generator();
+    assert_eq!(
+        verify_both(&tampered),
+        Err(VerificationError::Audited(BallotError::Vote(VoteError {
+            ballot_id: "5",
+            candidate_id: "Bob",
+            reason: VoteFailure::SecretsMismatch,
+        })))
+    );
+
+    // A failed ballot is not counted, and the rest can still be fed, but the election fails
+    // even though the totals match.
+    let mut verifier =
+        IncrementalVerifier::new(results.g1.clone(), results.g2.clone(), results.transcript)
+            .unwrap();
+    assert!(verifier.feed_ballot("1", &results.confirmed["2"]).is_err());
+    assert!(verifier
+        .feed_audited_ballot("1", &results.audited["5"])
+        .is_err());
+    for (ballot_id, ballot) in results.confirmed.iter() {
+        verifier.feed_ballot(*ballot_id, ballot).unwrap();
+    }
+    assert_eq!((verifier.confirmed(), verifier.audited()), (4, 0));
+    assert_eq!(verifier.failed(), 2);
+    assert_eq!(
+        verifier.finalize::<&str, _>(&results.totals),
+        Err(VerificationError::FailedBallots { count: 2 })
+    );
+}
+
+fn check_multi_select<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Three of eight"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["A", "B", "C", "D", "E", "F", "G", "H"];
+    let mut new_ballot = |ballot_id, yes: usize, k| {
+        Ballot::<_, G, _>::new_multi(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            candidates[..yes].to_vec(),
+            candidates[yes..].to_vec(),
+            k,
+        )
+    };
+
+    // A 3-of-8 ballot verifies for exactly three yes votes.
+    let ballot = new_ballot("1", 3, 3).unwrap();
+    assert_eq!(ballot.votes().len(), 8);
+    let verify = |ballot: &Ballot<_, G, _>, k| {
+        ballot.verify_multi(g1.clone(), g2.clone(), "1", k, election.transcript)
+    };
+    assert!(verify(&ballot, 3).is_ok());
+    for k in [0, 1, 2, 4, 8] {
+        assert_eq!(
+            verify(&ballot, k),
+            Err(BallotError::BallotProof {
+                ballot_id: "1",
+                reason: BallotProofFailure::ZEquation,
+            })
+        );
+    }
+
+    // Ballots with two or four yes votes are rejected as 3-of-8 ballots.
+    for yes in [2, 4] {
+        let crafted = new_ballot("1", yes, yes).unwrap();
+        assert!(verify(&crafted, yes).is_ok());
+        assert_eq!(
+            verify(&crafted, 3),
+            Err(BallotError::BallotProof {
+                ballot_id: "1",
+                reason: BallotProofFailure::ZEquation,
+            })
+        );
+    }
+
+    // The number of yes candidates must match, and duplicates are still rejected.
+    assert_eq!(
+        new_ballot("2", 2, 3).err(),
+        Some(BallotCreationError::WrongYesCount {
+            expected: 3,
+            actual: 2
+        })
+    );
+    assert_eq!(
+        Ballot::<_, G, _>::new_multi(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            "2",
+            vec!["A", "B", "C"],
+            vec!["C", "D"],
+            3,
+        )
+        .err(),
+        Some(BallotCreationError::YesAndNoCandidate("C"))
+    );
+
+    // Single-selection ballots are the special case `k = 1`.
+    let single =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "A", vec!["B"]).unwrap();
+    assert!(verify(&single, 1).is_ok());
+    assert!(verify(&single, 3).is_err());
+
+    // Whole elections verify with the tallies of the selected candidates.
+    let mut ballots = HashMap::new();
+    let mut totals = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    for (ballot_id, yes) in [("1", ["A", "B", "C"]), ("2", ["A", "D", "H"])] {
+        let no = candidates.iter().filter(|c| !yes.contains(c)).copied();
+        let ballot =
+            Ballot::<_, G, _>::new_multi(&mut rng, g1.clone(), g2.clone(), ballot_id, yes, no, 3)
+                .unwrap();
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        ballots.insert(ballot_id, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+    assert_eq!(totals["A"].tally_u64(), Some(2));
+    assert_eq!(totals["E"].tally_u64(), Some(0));
+    let mut verify_all = |k| {
+        let result = verify_election_multi(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            &totals,
+            k,
+            election.transcript,
+        );
+        let batched = verify_election_batched_multi(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            &totals,
+            k,
+            election.transcript,
+            &mut rng,
+        );
+        assert_eq!(result, batched);
+        result
+    };
+    assert_eq!(verify_all(3), Ok(()));
+    assert!(matches!(
+        verify_all(1),
+        Err(VerificationError::Ballot(BallotError::BallotProof { .. }))
+    ));
+}
+
+fn check_weighted<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Weighted"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let mut new_ballot = |ballot_id, yes, weight| {
+        let no = ["Alice", "Bob", "Eve"]
+            .into_iter()
+            .filter(move |c| *c != yes);
+        Ballot::<_, G, _>::new_weighted(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            yes,
+            no,
+            weight,
+        )
+    };
+    let verify = |ballot: &Ballot<_, G, _>, weight| {
+        ballot.verify_weighted(g1.clone(), g2.clone(), "1", weight, election.transcript)
+    };
+
+    // A weight-5 ballot verifies for weight 5, and no other.
+    let ballot = new_ballot("1", "Alice", 5).unwrap();
+    assert_eq!(
+        ballot.votes()["Alice"].secrets.v,
+        G::Scalar::one()
+            + G::Scalar::one()
+            + G::Scalar::one()
+            + G::Scalar::one()
+            + G::Scalar::one()
+    );
+    assert!(verify(&ballot, 5).is_ok());
+    assert!(ballot
+        .verify(g1.clone(), g2.clone(), "1", election.transcript)
+        .is_err());
+    for weight in [1, 4, 6] {
+        assert!(verify(&ballot, weight).is_err());
+    }
+
+    // A tampered weight fails the vote proof.
+    let mut tampered = new_ballot("1", "Alice", 5).unwrap();
+    let vote = tampered.votes_mut().get_mut("Alice").unwrap();
+    *vote.Z_mut() = vote.Z().clone() + g1.clone();
+    vote.secrets.v = vote.secrets.v.clone() + G::Scalar::one();
+    assert!(matches!(
+        verify(&tampered, 5),
+        Err(BallotError::Vote(VoteError {
+            candidate_id: "Alice",
+            ..
+        }))
+    ));
+
+    // Proofs for weight 1 do not verify for weight 5, whether the ballot's own or moved
+    // onto a weight-5 ballot.
+    let single = new_ballot("1", "Alice", 1).unwrap();
+    assert!(verify(&single, 1).is_ok());
+    assert!(verify(&single, 5).is_err());
+    let mut reused = new_ballot("1", "Alice", 5).unwrap();
+    for (candidate, vote) in reused.votes_mut().iter_mut() {
+        *vote.pwf_mut() = single.votes()[candidate].pwf().clone();
+    }
+    *reused.pwf_mut() = single.pwf().clone();
+    assert!(verify(&reused, 5).is_err());
+    assert!(verify(&reused, 1).is_err());
+
+    // A weight of 1 is an ordinary ballot, and a weight of 0 is rejected.
+    assert!(single
+        .verify(g1.clone(), g2.clone(), "1", election.transcript)
+        .is_ok());
+    assert_eq!(
+        new_ballot("2", "Alice", 0).err(),
+        Some(BallotCreationError::ZeroWeight)
+    );
+
+    // Confirming adds the weight to the tally, and the election verifies with each weight.
+    let mut ballots = HashMap::new();
+    let mut totals = ["Alice", "Bob", "Eve"]
+        .into_iter()
+        .map(|candidate| (candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let weights = HashMap::from([("1", 5), ("2", 3), ("3", 1)]);
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Alice"), ("3", "Bob")] {
+        let ballot = new_ballot(ballot_id, yes, weights[ballot_id]).unwrap();
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        ballots.insert(ballot_id, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+    assert_eq!(totals["Alice"].tally_u64(), Some(8));
+    assert_eq!(totals["Bob"].tally_u64(), Some(1));
+    assert_eq!(totals["Eve"].tally_u64(), Some(0));
+    let verify_all = |weights: &HashMap<_, _>| {
+        verify_election_weighted(
+            g1.clone(),
+            g2.clone(),
+            &ballots,
+            weights,
+            &totals,
+            election.transcript,
+        )
+    };
+    assert_eq!(verify_all(&weights), Ok(()));
+    let mut wrong = weights.clone();
+    wrong.insert("2", 5);
+    assert!(matches!(
+        verify_all(&wrong),
+        Err(VerificationError::Ballot(BallotError::Vote(VoteError {
+            ballot_id: "2",
+            candidate_id: "Alice",
+            reason: VoteFailure::ProofInvalid,
+        })))
+    ));
+    wrong.remove("2");
+    assert_eq!(
+        verify_all(&wrong),
+        Err(VerificationError::MissingWeight { ballot_id: "2" })
+    );
+}
+
+fn check_abstention<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Abstention"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = vec!["Alice", "Bob", "Eve"];
+
+    // An abstention verifies as a ballot with no yes votes, and not with one.
+    let abstention = Ballot::<_, G, _>::new_abstention(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        candidates.clone(),
+    )
+    .unwrap();
+    assert_eq!(abstention.votes().len(), 3);
+    assert!(abstention
+        .votes()
+        .values()
+        .all(|vote| vote.secrets.v == G::Scalar::zero()));
+    assert!(abstention
+        .verify_multi(g1.clone(), g2.clone(), "1", 0, election.transcript)
+        .is_ok());
+    assert_eq!(
+        abstention.verify(g1.clone(), g2.clone(), "1", election.transcript),
+        Err(BallotError::BallotProof {
+            ballot_id: "1",
+            reason: BallotProofFailure::ZEquation,
+        })
+    );
+    assert_eq!(
+        Ballot::<_, G, _>::new_abstention(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            "1",
+            vec!["Alice", "Alice"],
+        )
+        .err(),
+        Some(BallotCreationError::DuplicateCandidate("Alice"))
+    );
+
+    // It contributes nothing to any tally.
+    let mut totals = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut ballots = HashMap::new();
+    let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+    ballots.insert("1", abstention.clone().confirm(Some(&mut refs)).unwrap());
+    let ballot = Ballot::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "2",
+        "Bob",
+        vec!["Alice", "Eve"],
+    )
+    .unwrap();
+    let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+    ballots.insert("2", ballot.confirm(Some(&mut refs)).unwrap());
+    for candidate in candidates.iter() {
+        let expected = if *candidate == "Bob" { 1 } else { 0 };
+        assert_eq!(totals[candidate].tally_u64(), Some(expected));
+    }
+
+    let abstentions = HashSet::from(["1"]);
+    let verify = |ballots: &HashMap<_, _>, abstentions: &HashSet<_>| {
+        verify_election_with_abstentions(
+            g1.clone(),
+            g2.clone(),
+            ballots,
+            &totals,
+            abstentions,
+            election.transcript,
+        )
+    };
+    assert_eq!(verify(&ballots, &abstentions), Ok(()));
+    // Abstentions must be declared, and other ballots cannot pass as abstentions.
+    assert_eq!(
+        verify(&ballots, &HashSet::new()),
+        Err(VerificationError::Ballot(BallotError::BallotProof {
+            ballot_id: "1",
+            reason: BallotProofFailure::ZEquation,
+        }))
+    );
+    assert_eq!(
+        verify(&ballots, &HashSet::from(["1", "2"])),
+        Err(VerificationError::Ballot(BallotError::BallotProof {
+            ballot_id: "2",
+            reason: BallotProofFailure::ZEquation,
+        }))
+    );
+
+    // A doctored abstention with a hidden yes vote is rejected, though every vote verifies.
+    let mut doctored = abstention;
+    let yes = Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Eve", true);
+    doctored.votes_mut().insert("Eve", yes);
+    assert!(verify_votes(
+        &doctored,
+        g1.clone(),
+        g2.clone(),
+        &"1",
+        1,
+        election.transcript
+    )
+    .is_ok());
+    assert_eq!(
+        doctored.verify_multi(g1.clone(), g2.clone(), "1", 0, election.transcript),
+        Err(BallotError::BallotProof {
+            ballot_id: "1",
+            reason: BallotProofFailure::ChallengeMismatch,
+        })
+    );
+    ballots.insert("1", doctored.confirm(None).unwrap());
+    assert_eq!(
+        verify(&ballots, &abstentions),
+        Err(VerificationError::Ballot(BallotError::BallotProof {
+            ballot_id: "1",
+            reason: BallotProofFailure::ChallengeMismatch,
+        }))
+    );
+}
+
+fn check_referendum<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Referendum"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+
+    // Run a 100-ballot referendum, with every third ballot voting yes.
+    let ids = (0..100).map(|i| i.to_string()).collect::<Vec<_>>();
+    let mut totals = HashMap::from([("Yes", CandidateTotals::<G>::default())]);
+    let mut ballots = HashMap::new();
+    for (i, ballot_id) in ids.iter().enumerate() {
+        let ballot = Ballot::<_, G, _>::new_referendum(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            "Yes",
+            i % 3 == 0,
+        );
+        assert_eq!(ballot.votes().len(), 1);
+        // The ballot proof does not reveal the vote, as it verifies for neither count.
+        if i < 2 {
+            for k in [0, 1] {
+                assert!(ballot
+                    .verify_multi(g1.clone(), g2.clone(), ballot_id, k, election.transcript)
+                    .is_err());
+            }
+        }
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        ballots.insert(ballot_id.as_str(), ballot.confirm(Some(&mut refs)).unwrap());
+    }
+    assert_eq!(totals["Yes"].tally_u64(), Some(34));
+    let verify = |ballots: &HashMap<_, _>, totals: &HashMap<_, _>| {
+        verify_election_referendum(g1.clone(), g2.clone(), ballots, totals, election.transcript)
+    };
+    assert_eq!(verify(&ballots, &totals), Ok(()));
+
+    // A wrong tally is rejected.
+    let mut wrong = totals.clone();
+    wrong.get_mut("Yes").unwrap().tally = G::Scalar::one();
+    assert_eq!(
+        verify(&ballots, &wrong),
+        Err(VerificationError::Tally {
+            candidate_id: "Yes"
+        })
+    );
+
+    // So are a tampered ballot proof, and a ballot with more than one vote.
+    let mut ballot = ballots["7"].clone();
+    ballot.pwf_mut().r = G::Scalar::random(&mut rng);
+    assert_eq!(
+        ballot.verify_referendum(g1.clone(), g2.clone(), "7", election.transcript),
+        Err(BallotError::BallotProof {
+            ballot_id: "7",
+            reason: BallotProofFailure::REquation,
+        })
+    );
+    let mut ballot = ballots["7"].clone();
+    let extra = Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), "7", "No", false);
+    ballot.votes_mut().insert("No", extra.confirm());
+    assert_eq!(
+        ballot.verify_referendum(g1.clone(), g2.clone(), "7", election.transcript),
+        Err(BallotError::BallotProof {
+            ballot_id: "7",
+            reason: BallotProofFailure::WrongVoteCount,
+        })
+    );
+}
+
+fn check_questions<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Questions"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let chair = ["Alice", "Bob", "Eve"];
+    let treasurer = ["Alice", "Bob"];
+    let mut new_ballot = |ballot_id, chair_yes, treasurer_yes| {
+        let others = |candidates: &[&'static str], yes| {
+            candidates
+                .iter()
+                .copied()
+                .filter(move |c| *c != yes)
+                .collect::<Vec<_>>()
+        };
+        MultiBallot::<_, _, G, _>::new(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            ballot_id,
+            [
+                ("Chair", chair_yes, others(&chair, chair_yes)),
+                (
+                    "Treasurer",
+                    treasurer_yes,
+                    others(&treasurer, treasurer_yes),
+                ),
+            ],
+        )
+    };
+
+    // Question ids are bound unambiguously, and apart from the plain ballot id.
+    assert_ne!(
+        question_ballot_id("1", "2Chair"),
+        question_ballot_id("12", "Chair")
+    );
+    assert_ne!(question_ballot_id("1", "Chair"), b"1".to_vec());
+
+    // A two-question ballot verifies, and each question's votes are made with its own id.
+    let ballot = new_ballot("1", "Alice", "Bob").unwrap();
+    assert_eq!(ballot.questions.len(), 2);
+    assert!(ballot
+        .verify(g1.clone(), g2.clone(), "1", election.transcript)
+        .is_ok());
+    assert_eq!(
+        ballot.verify(g1.clone(), g2.clone(), "2", election.transcript),
+        Err((
+            "Chair",
+            BallotError::Vote(VoteError {
+                ballot_id: "2",
+                candidate_id: "Alice",
+                reason: VoteFailure::ProofInvalid,
+            })
+        ))
+    );
+    assert!(ballot.questions["Chair"]
+        .verify(g1.clone(), g2.clone(), "1", election.transcript)
+        .is_err());
+    assert!(ballot.questions["Chair"]
+        .verify(
+            g1.clone(),
+            g2.clone(),
+            question_ballot_id("1", "Chair"),
+            election.transcript
+        )
+        .is_ok());
+
+    // A vote moved to the same candidate of another question fails, as does a whole
+    // question's ballot moved to another question.
+    let mut moved = new_ballot("1", "Alice", "Bob").unwrap();
+    let vote = moved.questions["Chair"].votes()["Bob"].clone();
+    moved
+        .questions
+        .get_mut("Treasurer")
+        .unwrap()
+        .votes_mut()
+        .insert("Bob", vote);
+    assert_eq!(
+        moved.verify(g1.clone(), g2.clone(), "1", election.transcript),
+        Err((
+            "Treasurer",
+            BallotError::Vote(VoteError {
+                ballot_id: "1",
+                candidate_id: "Bob",
+                reason: VoteFailure::ProofInvalid,
+            })
+        ))
+    );
+    let mut moved = new_ballot("1", "Alice", "Bob").unwrap();
+    let mut chair_ballot = new_ballot("1", "Bob", "Alice").unwrap();
+    let mut treasurer_votes = chair_ballot.questions.remove("Chair").unwrap();
+    treasurer_votes.votes_mut().remove("Eve");
+    moved.questions.insert("Treasurer", treasurer_votes);
+    assert!(matches!(
+        moved.verify(g1.clone(), g2.clone(), "1", election.transcript),
+        Err(("Treasurer", BallotError::Vote(_)))
+    ));
+
+    // Confirming updates each question's totals, and fails without changing them if any
+    // candidate of any question is missing.
+    let mut totals = HashMap::from([
+        (
+            "Chair",
+            chair
+                .iter()
+                .map(|c| (*c, CandidateTotals::<G>::default()))
+                .collect::<HashMap<_, _>>(),
+        ),
+        (
+            "Treasurer",
+            treasurer
+                .iter()
+                .map(|c| (*c, CandidateTotals::<G>::default()))
+                .collect::<HashMap<_, _>>(),
+        ),
+    ]);
+    let mut ballots = HashMap::new();
+    for (ballot_id, chair_yes, treasurer_yes) in [
+        ("1", "Alice", "Bob"),
+        ("2", "Eve", "Bob"),
+        ("3", "Alice", "Alice"),
+    ] {
+        let ballot = new_ballot(ballot_id, chair_yes, treasurer_yes).unwrap();
+        let mut refs = totals
+            .iter_mut()
+            .map(|(q, t)| (*q, t.iter_mut().map(|(c, t)| (*c, t)).collect()))
+            .collect::<HashMap<_, HashMap<_, _>>>();
+        ballots.insert(ballot_id, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+    let mut missing = totals.clone();
+    missing.get_mut("Treasurer").unwrap().remove("Bob");
+    let mut refs = missing
+        .iter_mut()
+        .map(|(q, t)| (*q, t.iter_mut().map(|(c, t)| (*c, t)).collect()))
+        .collect::<HashMap<_, HashMap<_, _>>>();
+    assert_eq!(
+        new_ballot("4", "Bob", "Alice")
+            .unwrap()
+            .confirm(Some(&mut refs))
+            .err(),
+        Some(("Treasurer", ConfirmError::MissingCandidate("Bob")))
+    );
+    assert_eq!(missing["Chair"], totals["Chair"]);
+    assert_eq!(totals["Chair"]["Alice"].tally_u64(), Some(2));
+    assert_eq!(totals["Chair"]["Bob"].tally_u64(), Some(0));
+    assert_eq!(totals["Chair"]["Eve"].tally_u64(), Some(1));
+    assert_eq!(totals["Treasurer"]["Alice"].tally_u64(), Some(1));
+    assert_eq!(totals["Treasurer"]["Bob"].tally_u64(), Some(2));
+
+    // The whole election verifies against the totals of each question.
+    let verify_all = |ballots: &HashMap<_, _>, totals: &HashMap<_, _>| {
+        verify_election_questions(g1.clone(), g2.clone(), ballots, totals, election.transcript)
+    };
+    assert_eq!(verify_all(&ballots, &totals), Ok(()));
+    let mut swapped = totals.clone();
+    let chair_alice = swapped["Chair"]["Alice"].clone();
+    let treasurer_alice = swapped["Treasurer"]["Alice"].clone();
+    swapped
+        .get_mut("Chair")
+        .unwrap()
+        .insert("Alice", treasurer_alice);
+    swapped
+        .get_mut("Treasurer")
+        .unwrap()
+        .insert("Alice", chair_alice);
+    assert!(matches!(
+        verify_all(&ballots, &swapped),
+        Err(QuestionVerificationError::Question {
+            error: VerificationError::Tally {
+                candidate_id: "Alice"
+            },
+            ..
+        })
+    ));
+    let mut one_question = totals.clone();
+    one_question.remove("Treasurer");
+    assert!(matches!(
+        verify_all(&ballots, &one_question),
+        Err(QuestionVerificationError::WrongQuestions { .. })
+    ));
+
+    // Moving a confirmed vote between questions fails the whole election.
+    let json = serde_json::to_string(&ballots).unwrap();
+    let mut tampered: HashMap<&str, MultiBallot<&str, &str, G, NoSecrets>> =
+        serde_json::from_str(&json).unwrap();
+    assert_eq!(verify_all(&tampered, &totals), Ok(()));
+    let ballot = tampered.get_mut("3").unwrap();
+    let vote = ballot.questions["Chair"].votes()["Alice"].clone();
+    ballot
+        .questions
+        .get_mut("Treasurer")
+        .unwrap()
+        .votes_mut()
+        .insert("Alice", vote);
+    assert_eq!(
+        verify_all(&tampered, &totals),
+        Err(QuestionVerificationError::Question {
+            question_id: "Treasurer",
+            error: VerificationError::Ballot(BallotError::Vote(VoteError {
+                ballot_id: "3",
+                candidate_id: "Alice",
+                reason: VoteFailure::ProofInvalid,
+            }))
+        })
+    );
+
+    // Questions cannot be given twice, and errors name their question.
+    assert_eq!(
+        MultiBallot::<_, _, G, _>::new(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            "2",
+            [
+                ("Chair", "Alice", vec!["Bob"]),
+                ("Chair", "Bob", vec!["Alice"])
+            ],
+        )
+        .err(),
+        Some(("Chair", BallotCreationError::DuplicateQuestion))
+    );
+    assert_eq!(
+        MultiBallot::<_, _, G, _>::new(
+            &mut rng,
+            g1.clone(),
+            g2.clone(),
+            "2",
+            [
+                ("Chair", "Alice", vec!["Bob"]),
+                ("Treasurer", "Bob", vec!["Bob"])
+            ],
+        )
+        .err(),
+        Some(("Treasurer", BallotCreationError::YesAndNoCandidate("Bob")))
+    );
+}
+
+fn check_cancellation<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Cancellation"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["Alice", "Bob", "Eve"];
+    let mut totals = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut new_ballot = |ballot_id, yes| {
+        let no: Vec<_> = candidates.iter().copied().filter(|c| *c != yes).collect();
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), ballot_id, yes, no).unwrap()
+    };
+
+    // Confirm two ballots, audit one, and cancel one for Eve.
+    let mut confirmed = HashMap::new();
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Bob")] {
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        let ballot = new_ballot(ballot_id, yes).confirm(Some(&mut refs)).unwrap();
+        confirmed.insert(ballot_id, ballot);
+    }
+    let audited = HashMap::from([("3", new_ballot("3", "Eve"))]);
+    let cancelled = HashMap::from([("4", new_ballot("4", "Eve").cancel())]);
+    assert_eq!(totals["Eve"].tally_u64(), Some(0));
+
+    let mut results = election.results(confirmed, totals);
+    results.audited = audited;
+    results.cancelled = cancelled;
+    assert_eq!(results.verify(), Ok(()));
+    let json = serde_json::to_string(&results).unwrap();
+    let deserialized: ElectionResults<&str, &str, G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.cancelled, results.cancelled);
+    assert_eq!(deserialized.verify(), Ok(()));
+
+    // Counting the cancelled ballot would give the wrong totals.
+    let mut wrong = results.clone();
+    let vote = &wrong.cancelled["4"].ballot.votes()["Eve"];
+    let eve = wrong.totals.get_mut("Eve").unwrap();
+    eve.tally = eve.tally.clone() + vote.secrets.v.clone();
+    eve.r_sum = eve.r_sum.clone() + vote.secrets.r.clone();
+    assert_eq!(
+        wrong.verify(),
+        Err(VerificationError::Tally {
+            candidate_id: "Eve"
+        })
+    );
+
+    // A tampered cancelled ballot is rejected.
+    let mut tampered = results.clone();
+    let ballot = &mut tampered.cancelled.get_mut("4").unwrap().ballot;
+    let vote = ballot.votes_mut().get_mut("Eve").unwrap();
+    vote.secrets.v = G::Scalar::zero();
+    assert_eq!(
+        tampered.verify(),
+        Err(VerificationError::Ballot(BallotError::Vote(VoteError {
+            ballot_id: "4",
+            candidate_id: "Eve",
+            reason: VoteFailure::SecretsMismatch,
+        })))
+    );
+
+    // As is a tampered audited ballot, as such.
+    let mut tampered = results.clone();
+    let vote = tampered
+        .audited
+        .get_mut("3")
+        .unwrap()
+        .votes_mut()
+        .get_mut("Alice")
+        .unwrap();
+    vote.secrets.v = G::Scalar::one();
+    assert_eq!(
+        tampered.verify(),
+        Err(VerificationError::Audited(BallotError::Vote(VoteError {
+            ballot_id: "3",
+            candidate_id: "Alice",
+            reason: VoteFailure::SecretsMismatch,
+        })))
+    );
+
+    // Results from before audited ballots were published still verify.
+    let mut legacy = serde_json::to_value(&results).unwrap();
+    let fields = legacy.as_object_mut().unwrap();
+    fields.remove("audited");
+    fields.remove("cancelled");
+    let legacy = serde_json::to_string(&legacy).unwrap();
+    let legacy: ElectionResults<&str, &str, G> = serde_json::from_str(&legacy).unwrap();
+    assert!(legacy.audited.is_empty());
+    assert_eq!(legacy.verify(), Ok(()));
+
+    // Ballot ids must not be both confirmed and cancelled, or audited and cancelled.
+    let mut overlap = results.clone();
+    let cancelled = overlap.cancelled.remove("4").unwrap();
+    overlap.cancelled.insert("1", cancelled.clone());
+    assert_eq!(
+        overlap.verify(),
+        Err(VerificationError::DuplicateBallot { ballot_id: "1" })
+    );
+    let mut overlap = results.clone();
+    overlap.cancelled.insert("3", cancelled);
+    assert_eq!(
+        verify_full_election(
+            g1.clone(),
+            g2.clone(),
+            &overlap.confirmed,
+            &overlap.audited,
+            &overlap.cancelled,
+            &overlap.replaced,
+            &overlap.totals,
+            election.transcript,
+        ),
+        Err(VerificationError::DuplicateBallot { ballot_id: "3" })
+    );
+}
+
+fn check_revote<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Revote"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["Alice", "Bob", "Eve"];
+    let mut totals = candidates
+        .iter()
+        .map(|candidate| (*candidate, CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut new_ballot = |ballot_id, yes| {
+        let no: Vec<_> = candidates.iter().copied().filter(|c| *c != yes).collect();
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), ballot_id, yes, no).unwrap()
+    };
+
+    // Confirm two ballots, retaining the secrets of the first.
+    let mut confirmed = HashMap::new();
+    let first = new_ballot("1", "Alice");
+    for (ballot_id, ballot) in [("1", first.clone()), ("2", new_ballot("2", "Bob"))] {
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        confirmed.insert(ballot_id, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+
+    // A replacement must have totals for every candidate of both ballots.
+    let recast = new_ballot("3", "Bob");
+    let before = totals.clone();
+    let mut refs = totals
+        .iter_mut()
+        .filter(|(c, _)| **c != "Alice")
+        .map(|(c, t)| (*c, t))
+        .collect();
+    assert_eq!(
+        replace_ballot(first.clone(), recast.clone(), "3", &mut refs).err(),
+        Some(ConfirmError::MissingCandidate("Alice"))
+    );
+    assert_eq!(totals, before);
+
+    // The first voter votes again, for Bob.
+    let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+    let (record, recast) = replace_ballot(first, recast, "3", &mut refs).unwrap();
+    assert_eq!(record.replaced_by, "3");
+    assert_eq!(totals["Alice"].tally_u64(), Some(0));
+    assert_eq!(totals["Bob"].tally_u64(), Some(2));
+    // The record publishes the old ballot only as it was confirmed, without its secrets.
+    let old = confirmed.remove("1").unwrap();
+    assert_eq!(record.ballot, old);
+    confirmed.insert("3", recast);
+    let mut results = election.results(confirmed, totals);
+    results.replaced.insert("1", record);
+    assert_eq!(results.verify(), Ok(()));
+    let json = serde_json::to_string(&results).unwrap();
+    let deserialized: ElectionResults<&str, &str, G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.replaced, results.replaced);
+
+    // Keeping both the old and new ballots in the confirmed set double counts.
+    let mut both = results.clone();
+    both.confirmed.insert("1", old);
+    assert_eq!(
+        both.verify(),
+        Err(VerificationError::DuplicateBallot { ballot_id: "1" })
+    );
+    both.replaced.clear();
+    assert!(matches!(
+        both.verify(),
+        Err(VerificationError::Tally { .. })
+    ));
+
+    // The replacement must be confirmed, and replace only one ballot.
+    let mut missing = results.clone();
+    missing.replaced.get_mut("1").unwrap().replaced_by = "4";
+    assert_eq!(
+        missing.verify(),
+        Err(VerificationError::Replacement { ballot_id: "1" })
+    );
+    let mut shared = results.clone();
+    let record = shared.replaced["1"].clone();
+    shared.replaced.insert("5", record);
+    assert!(matches!(
+        shared.verify(),
+        Err(VerificationError::Replacement { .. })
+    ));
+
+    // A replaced ballot with an invalid proof is rejected.
+    let mut tampered = results.clone();
+    let ballot = &mut tampered.replaced.get_mut("1").unwrap().ballot;
+    *ballot.votes_mut().get_mut("Alice").unwrap().R_mut() = DreipPoint::generator();
+    assert_eq!(
+        tampered.verify(),
+        Err(VerificationError::Ballot(BallotError::Vote(VoteError {
+            ballot_id: "1",
+            candidate_id: "Alice",
+            reason: VoteFailure::ProofInvalid,
+        })))
+    );
+}
+
+fn check_audit<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Audit"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    let verify = |ballot: &Ballot<_, G, _>, expected| {
+        ballot.verify_audited(g1.clone(), g2.clone(), "1", expected, election.transcript)
+    };
+
+    // The ballot votes for Alice, and no one else.
+    assert_eq!(ballot.claimed_choice(), Ok("Alice"));
+    assert_eq!(verify(&ballot, &"Alice"), Ok(()));
+    assert_eq!(
+        verify(&ballot, &"Bob"),
+        Err(AuditError::WrongChoice {
+            expected: "Bob",
+            actual: "Alice"
+        })
+    );
+    // It is bound to its ballot id, like any other ballot.
+    assert!(matches!(
+        ballot.verify_audited(g1.clone(), g2.clone(), "2", &"Alice", election.transcript),
+        Err(AuditError::Vote(_))
+    ));
+
+    // A malformed ballot with two yes votes claims no single choice, and fails its proof.
+    let mut doctored = ballot.clone();
+    let yes = Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Eve", true);
+    doctored.votes_mut().insert("Eve", yes);
+    assert_eq!(
+        doctored.claimed_choice(),
+        Err(AuditError::MultipleChoices(vec!["Alice", "Eve"]))
+    );
+    assert_eq!(verify(&doctored, &"Alice"), Err(AuditError::BallotProof));
+
+    // A ballot with no yes votes, such as an abstention, claims no choice.
+    let abstention =
+        Ballot::<_, G, _>::new_abstention(&mut rng, g1.clone(), g2.clone(), "1", vec!["Alice"])
+            .unwrap();
+    assert_eq!(abstention.claimed_choice(), Err(AuditError::NoChoice));
+
+    // A secret that is not a vote is rejected, even unverified.
+    let mut doctored = ballot;
+    doctored.votes_mut().get_mut("Bob").unwrap().secrets.v = G::Scalar::one() + G::Scalar::one();
+    assert_eq!(doctored.claimed_choice(), Err(AuditError::Vote("Bob")));
+}
+
+fn check_lifecycle<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Lifecycle"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let mut seed = [0; 32];
+    rng.fill_bytes(&mut seed);
+    let new_pair = || {
+        let new_raw = Ballot::<_, G, _>::new(
+            ChaCha20Rng::from_seed(seed),
+            g1.clone(),
+            g2.clone(),
+            "1",
+            "Alice",
+            vec!["Bob", "Eve"],
+        );
+        let new_pending = PendingBallot::<_, G>::new(
+            ChaCha20Rng::from_seed(seed),
+            g1.clone(),
+            g2.clone(),
+            "1",
+            "Alice",
+            vec!["Bob", "Eve"],
+        );
+        (new_raw.unwrap(), new_pending.unwrap())
+    };
+    // An audited ballot is the raw ballot, and verifies and serializes identically.
+    let (raw, pending) = new_pair();
+    let audited = pending.audit();
+    assert_eq!(audited.ballot, raw);
+    for ballot_id in ["1", "2"] {
+        assert_eq!(
+            audited.verify(g1.clone(), g2.clone(), ballot_id, election.transcript),
+            raw.verify(g1.clone(), g2.clone(), ballot_id, election.transcript)
+        );
+    }
+    assert_eq!(
+        audited.verify_audited(g1.clone(), g2.clone(), "1", &"Alice", election.transcript),
+        Ok(())
+    );
+    let json = serde_json::to_string(&audited).unwrap();
+    assert_eq!(json, serde_json::to_string(&raw).unwrap());
+    let deserialized: AuditedBallot<&str, G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, audited);
+
+    // A confirmed ballot is the raw confirmed ballot, with the same effect on the totals.
+    let (raw, pending) = new_pair();
+    let new_totals = || {
+        ["Alice", "Bob", "Eve"]
+            .into_iter()
+            .map(|candidate| (candidate, CandidateTotals::<G>::default()))
+            .collect::<HashMap<_, _>>()
+    };
+    let (mut raw_totals, mut totals) = (new_totals(), new_totals());
+    let mut refs = raw_totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+    let raw = raw.confirm(Some(&mut refs)).unwrap();
+    let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+    let confirmed = pending.confirm(Some(&mut refs)).unwrap();
+    assert_eq!(confirmed.ballot, raw);
+    assert_eq!(totals, raw_totals);
+    for ballot_id in ["1", "2"] {
+        assert_eq!(
+            confirmed.verify(g1.clone(), g2.clone(), ballot_id, election.transcript),
+            raw.verify(g1.clone(), g2.clone(), ballot_id, election.transcript)
+        );
+    }
+    let json = serde_json::to_string(&confirmed).unwrap();
+    assert_eq!(json, serde_json::to_string(&raw).unwrap());
+    let deserialized: ConfirmedBallot<&str, G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, confirmed);
+
+    // Confirming fails as for the raw ballot, without changing the totals.
+    let (_, pending) = new_pair();
+    let mut refs = totals
+        .iter_mut()
+        .filter(|(c, _)| **c != "Eve")
+        .map(|(c, t)| (*c, t))
+        .collect();
+    assert_eq!(
+        pending.confirm(Some(&mut refs)).err(),
+        Some(ConfirmError::MissingCandidate("Eve"))
+    );
+    assert_eq!(totals, raw_totals);
+}
+
+fn check_election_roster<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::with_candidates(
+        &[b"Election Roster"],
+        vec!["Alice", "Bob", "Eve"],
+        &mut rng,
+    );
+    assert_eq!(
+        election.candidates::<String>(),
+        Some(vec![
+            "Alice".to_string(),
+            "Bob".to_string(),
+            "Eve".to_string()
+        ])
+    );
+
+    // Ballots vote no for the rest of the roster, and confirm into the convenience totals,
+    // which verify.
+    let mut totals = election.create_totals::<String>().unwrap();
+    assert_eq!(totals.len(), 3);
+    assert!(totals.values().all(|t| t.tally_u64() == Some(0)));
+    let mut ballots = HashMap::new();
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Eve"), ("3", "Alice")] {
+        let ballot = election
+            .create_ballot(&mut rng, ballot_id, yes.to_string())
+            .unwrap();
+        assert_eq!(
+            ballot.candidates().collect::<Vec<_>>(),
+            ["Alice", "Bob", "Eve"]
+        );
+        let mut refs = totals.iter_mut().map(|(c, t)| (c.clone(), t)).collect();
+        ballots.insert(ballot_id, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+    assert_eq!(
+        verify_election(
+            election.g1.clone(),
+            election.g2.clone(),
+            &ballots,
+            &totals,
+            election.transcript
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        results(&totals),
+        Some(HashMap::from([
+            ("Alice".to_string(), 2),
+            ("Bob".to_string(), 0),
+            ("Eve".to_string(), 1)
+        ]))
+    );
+
+    // A candidate not on the roster, such as a misspelled one, cannot be voted for.
+    assert_eq!(
+        election
+            .create_ballot(&mut rng, "4", "Alcie".to_string())
+            .unwrap_err(),
+        BallotCreationError::UnknownCandidate("Alcie".to_string())
+    );
+
+    // Nor can a candidate of a type the roster's ids are not the bytes of.
+    assert_eq!(election.candidates::<u32>(), None);
+    assert_eq!(election.create_totals::<u32>(), None);
+    assert_eq!(
+        election.create_ballot(&mut rng, "4", 7u32).unwrap_err(),
+        BallotCreationError::NoRoster
+    );
+
+    // The roster survives serialization, and is not part of the public parameters.
+    let json = serde_json::to_string(&election).unwrap();
+    let deserialized: Election<G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.roster, election.roster);
+    assert_eq!(
+        deserialized.create_totals::<String>().map(|t| t.len()),
+        Some(3)
+    );
+    let plain = Election {
+        roster: None,
+        ..election.clone()
+    };
+    assert_eq!(plain.to_bytes(), election.to_bytes());
+
+    // Elections without a roster serialize as before, and still create ballots from a
+    // candidate list, but not from a roster.
+    let json = serde_json::to_value(&plain).unwrap();
+    assert!(json.get("roster").is_none());
+    let deserialized: Election<G> = serde_json::from_value(json).unwrap();
+    assert_eq!(deserialized.roster, None);
+    assert_eq!(
+        plain
+            .create_ballot(&mut rng, "4", "Alice".to_string())
+            .unwrap_err(),
+        BallotCreationError::NoRoster
+    );
+    assert_eq!(plain.create_totals::<String>(), None);
+    let created = plain
+        .create_ballots(&mut rng, &["Alice", "Bob"], [("4", "Bob")])
+        .unwrap();
+    assert_eq!(created["4"].len(), 2);
+
+    // A corrupted roster fails to load.
+    let mut json = serde_json::to_value(&election).unwrap();
+    json["roster"][0] = "not base64!".into();
+    assert!(serde_json::from_value::<Election<G>>(json).is_err());
+}
+
+fn check_create_ballots<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Create Ballots"], &mut rng);
+    let candidates = ["Alice", "Bob", "Eve"];
+    let requests = ["1", "2", "3", "4", "5"]
+        .into_iter()
+        .zip(candidates.into_iter().cycle())
+        .collect::<Vec<_>>();
+
+    // Bulk creation gives the same ballots as a loop, from the same random stream.
+    let seed = rng.next_u64();
+    let ballots = election
+        .create_ballots(
+            ChaCha20Rng::seed_from_u64(seed),
+            &candidates,
+            requests.clone(),
+        )
+        .unwrap();
+    let mut loop_rng = ChaCha20Rng::seed_from_u64(seed);
+    for (ballot_id, yes) in requests.iter() {
+        let no = candidates.iter().copied().filter(|c| c != yes);
+        let ballot = Ballot::new(
+            &mut loop_rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            *ballot_id,
+            *yes,
+            no,
+        )
+        .unwrap();
+        assert_eq!(ballots[ballot_id], ballot);
+    }
+    assert_eq!(ballots.len(), requests.len());
+
+    // Errors name the ballot that failed.
+    let create = |requests: Vec<(&'static str, &'static str)>| {
+        election
+            .create_ballots(ChaCha20Rng::seed_from_u64(seed), &candidates, requests)
+            .err()
+    };
+    assert_eq!(
+        create(vec![("1", "Alice"), ("2", "Mallory")]),
+        Some(("2", BallotCreationError::UnknownCandidate("Mallory")))
+    );
+    assert_eq!(
+        create(vec![("1", "Alice"), ("2", "Bob"), ("1", "Eve")]),
+        Some(("1", BallotCreationError::DuplicateBallot))
+    );
+    assert_eq!(create(vec![]), None);
+    let repeated = ["Alice", "Bob", "Alice"];
+    assert_eq!(
+        election
+            .create_ballots(&mut rng, &repeated, [("1", "Alice")])
+            .err(),
+        Some(("1", BallotCreationError::YesAndNoCandidate("Alice")))
+    );
+}
+
+fn check_session<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Session"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let mut session = ElectionSession::new(election, ["Alice", "Bob", "Eve"]);
+
+    // Confirmed ballots count towards the totals; audited ones do not.
+    for (ballot_id, candidate) in [("1", "Alice"), ("2", "Bob"), ("3", "Alice")] {
+        let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+        assert_eq!(*handle.ballot_id(), ballot_id);
+        let confirmed = session.confirm(handle).unwrap();
+        assert!(confirmed
+            .verify(g1.clone(), g2.clone(), ballot_id, transcript)
+            .is_ok());
+    }
+    for (ballot_id, candidate) in [("4", "Eve"), ("5", "Bob")] {
+        let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+        let audited = session.audit(handle).unwrap();
+        let result =
+            audited.verify_audited(g1.clone(), g2.clone(), ballot_id, &candidate, transcript);
+        assert_eq!(result, Ok(()));
+    }
+    let tallies = |session: &ElectionSession<&str, &str, G>| {
+        ["Alice", "Bob", "Eve"].map(|c| session.totals()[c].tally_u64().unwrap())
+    };
+    assert_eq!(tallies(&session), [2, 1, 0]);
+
+    // Ballot ids can be cast only once, whether confirmed, audited, or still pending, but
+    // a failed cast does not use up its id.
+    let pending = session.cast(&mut rng, "6", "Eve").unwrap();
+    for ballot_id in ["1", "4", "6"] {
+        assert_eq!(
+            session.cast(&mut rng, ballot_id, "Alice").err(),
+            Some(BallotCreationError::DuplicateBallot)
+        );
+    }
+    assert_eq!(
+        session.cast(&mut rng, "7", "Mallory").err(),
+        Some(BallotCreationError::UnknownCandidate("Mallory"))
+    );
+    let handle = session.cast(&mut rng, "7", "Eve").unwrap();
+    session.confirm(handle).unwrap();
+    session.confirm(pending).unwrap();
+    assert_eq!(tallies(&session), [2, 1, 2]);
+
+    // A handle cast in another session cannot reuse a ballot id this session already
+    // confirmed or audited, and changes nothing.
+    let other = session.election().clone();
+    let mut foreign = |ballot_id| {
+        ElectionSession::new(other.clone(), ["Alice", "Bob", "Eve"])
+            .cast(&mut rng, ballot_id, "Eve")
+            .unwrap()
+    };
+    for ballot_id in ["1", "4"] {
+        let duplicate = Some(ConfirmError::DuplicateBallot);
+        assert_eq!(session.confirm(foreign(ballot_id)).err(), duplicate);
+        assert_eq!(session.audit(foreign(ballot_id)).err(), duplicate);
+    }
+    assert_eq!(tallies(&session), [2, 1, 2]);
+
+    // The results hold every ballot, and verify.
+    let results = session.into_results();
+    assert_eq!(results.confirmed.len(), 5);
+    assert_eq!(results.audited.len(), 2);
+    assert_eq!(results.totals["Eve"].tally_u64(), Some(2));
+    assert_eq!(results.verify(), Ok(()));
+}
+
+fn check_policy<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Policy"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let roster = ["Alice", "Bob", "Eve"];
+    let mut totals = HashMap::from(roster.map(|c| (c, CandidateTotals::<G>::default())));
+    let mut confirmed = HashMap::new();
+    for (ballot_id, yes, no) in [
+        ("1", "Alice", vec!["Bob", "Eve"]),
+        ("2", "Eve", vec!["Alice", "Bob"]),
+        ("3", "Bob", vec!["Alice"]),
+    ] {
+        let ballot =
+            Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), ballot_id, yes, no).unwrap();
+        let mut ballot_totals = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        confirmed.insert(ballot_id, ballot.confirm(Some(&mut ballot_totals)).unwrap());
+    }
+
+    // The short ballot passes the default path, but not a policy requiring the roster.
+    let verify = |policy: &VerificationPolicy<&'static str>| {
+        verify_election_with_policy(
+            g1.clone(),
+            g2.clone(),
+            &confirmed,
+            &totals,
+            election.transcript,
+            policy,
+        )
+    };
+    assert_eq!(
+        verify_election(
+            g1.clone(),
+            g2.clone(),
+            &confirmed,
+            &totals,
+            election.transcript
+        ),
+        Ok(())
+    );
+    assert_eq!(verify(&VerificationPolicy::default()), Ok(()));
+    let short = |reason| {
+        Err(VerificationError::Policy {
+            ballot_id: "3",
+            reason,
+        })
+    };
+    assert_eq!(
+        verify(&VerificationPolicy::roster(roster)),
+        short(PolicyViolation::TooFewCandidates { count: 2, min: 3 })
+    );
+    let required = VerificationPolicy {
+        required_candidates: Some(["Eve"].into()),
+        ..Default::default()
+    };
+    assert_eq!(
+        verify(&required),
+        short(PolicyViolation::MissingCandidate("Eve"))
+    );
+    let at_most_two = VerificationPolicy {
+        max_candidates: Some(2),
+        ..Default::default()
+    };
+    assert!(matches!(
+        verify(&at_most_two),
+        Err(VerificationError::Policy {
+            reason: PolicyViolation::TooManyCandidates { count: 3, max: 2 },
+            ..
+        })
+    ));
+
+    // A policy does not excuse failing proofs, which are checked first.
+    let mut tampered = confirmed.clone();
+    tampered.get_mut("1").unwrap().pwf_mut().r = G::Scalar::random(&mut rng);
+    assert!(matches!(
+        verify_election_with_policy(
+            g1.clone(),
+            g2.clone(),
+            &tampered,
+            &totals,
+            election.transcript,
+            &VerificationPolicy::roster(roster),
+        ),
+        Err(VerificationError::Ballot(_))
+    ));
+
+    // Published results apply the policy to revealed ballots too.
+    let mut session = ElectionSession::new(election, roster);
+    let handle = session.cast(&mut rng, "1", "Alice").unwrap();
+    session.confirm(handle).unwrap();
+    let mut results = session.into_results();
+    let audited = Ballot::<_, G, _>::new(&mut rng, g1, g2, "4", "Bob", ["Eve"]).unwrap();
+    results.audited.insert("4", audited);
+    assert_eq!(results.verify(), Ok(()));
+    assert_eq!(
+        results.verify_with_policy(&VerificationPolicy::roster(roster)),
+        Err(VerificationError::Policy {
+            ballot_id: "4",
+            reason: PolicyViolation::TooFewCandidates { count: 2, min: 3 },
+        })
+    );
+    assert_eq!(
+        results.verify_with_policy(&VerificationPolicy::consistent()),
+        Err(VerificationError::InconsistentRoster {
+            ballot_id: "4",
+            missing: vec!["Alice"],
+            extra: vec![],
+        })
+    );
+}
+
+fn check_roster<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Roster"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let candidates = (0..16)
+        .map(|i| format!("Candidate {}", i))
+        .collect::<Vec<_>>();
+
+    // Sixteen ballots for all sixteen candidates, and one that quietly omits a candidate.
+    let mut secret = HashMap::new();
+    for i in 0..17usize {
+        let yes = &candidates[i % candidates.len()];
+        let no = candidates
+            .iter()
+            .filter(|c| *c != yes && (i < 16 || *c != "Candidate 5"))
+            .cloned();
+        let ballot =
+            Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), i, yes.clone(), no).unwrap();
+        secret.insert(i, ballot);
+    }
+    let publish = |secret: &HashMap<usize, Ballot<String, G, SecretsPresent<G>>>| {
+        secret
+            .iter()
+            .map(|(ballot_id, ballot)| (*ballot_id, ballot.clone().confirm(None).unwrap()))
+            .collect::<HashMap<_, _>>()
+    };
+    let ballots = publish(&secret);
+    assert_eq!(ballots[&16].len(), 15);
+
+    // Totals computed from the ballots as they are add up, but a consistent roster policy
+    // names the ballot and the candidate it is missing.
+    let totals = compute_totals(&secret);
+    let verify = |ballots: &HashMap<_, _>, totals: &HashMap<_, _>| {
+        verify_election_with_policy(
+            g1.clone(),
+            g2.clone(),
+            ballots,
+            totals,
+            transcript,
+            &VerificationPolicy::consistent(),
+        )
+    };
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), &ballots, &totals, transcript),
+        Ok(())
+    );
+    assert_eq!(
+        verify(&ballots, &totals),
+        Err(VerificationError::InconsistentRoster {
+            ballot_id: 16,
+            missing: vec!["Candidate 5".to_string()],
+            extra: vec![],
+        })
+    );
+
+    // The totals are verified first.
+    let mut tampered = totals.clone();
+    tampered.remove("Candidate 5");
+    assert_eq!(
+        verify(&ballots, &tampered),
+        Err(VerificationError::WrongCandidates)
+    );
+
+    // Without the odd ballot, the rest verify.
+    secret.remove(&16);
+    let (ballots, totals) = (publish(&secret), compute_totals(&secret));
+    assert_eq!(verify(&ballots, &totals), Ok(()));
+
+    // Published results check their revealed ballots against the totals too, naming any
+    // candidates they have that the totals do not.
+    let mut results = election.results(ballots, totals);
+    assert_eq!(
+        results.verify_with_policy(&VerificationPolicy::consistent()),
+        Ok(())
+    );
+    let mut roster = candidates.clone();
+    roster[3] = "Mallory".to_string();
+    let audited = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1,
+        g2,
+        100usize,
+        roster[0].clone(),
+        roster[1..].iter().cloned(),
+    )
+    .unwrap();
+    results.audited.insert(100, audited);
+    assert_eq!(results.verify(), Ok(()));
+    assert_eq!(
+        results.verify_with_policy(&VerificationPolicy::consistent()),
+        Err(VerificationError::InconsistentRoster {
+            ballot_id: 100,
+            missing: vec!["Candidate 3".to_string()],
+            extra: vec!["Mallory".to_string()],
+        })
+    );
+}
+
+fn check_receipt<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Receipt"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    let context = SigningContext::new("Receipt", "Q1", "7KQ2");
+    let issue = || election.issue_receipt(ballot.clone(), "1", Some(&context));
+    let verify = |receipt: &Receipt<_, _, G, _>| {
+        receipt.verify(&election.public_key, g1.clone(), g2.clone())
+    };
+
+    // Receipts verify, with or without context, and survive a JSON round trip.
+    let receipt = issue();
+    assert_eq!(verify(&receipt), Ok(()));
+    let json = serde_json::to_string(&receipt).unwrap();
+    let deserialized: Receipt<&str, &str, G, _> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, receipt);
+    assert_eq!(verify(&deserialized), Ok(()));
+    let plain = election.issue_receipt(ballot.clone().confirm(None).unwrap(), "1", None);
+    assert_eq!(
+        plain.verify(&election.public_key, g1.clone(), g2.clone()),
+        Ok(())
+    );
+    let json = serde_json::to_string(&plain).unwrap();
+    assert!(!json.contains("context"));
+    let deserialized: Receipt<&str, &str, G, NoSecrets> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, plain);
+
+    // A signature from another receipt is rejected.
+    let mut tampered = issue();
+    tampered.signature = G::Signature::from_bytes(&plain.signature.to_bytes()).unwrap();
+    assert_eq!(verify(&tampered), Err(ReceiptError::Signature));
+
+    // So is a different ballot, even one that verifies on its own.
+    let mut tampered = issue();
+    tampered.ballot = Ballot::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Bob",
+        vec!["Alice", "Eve"],
+    )
+    .unwrap();
+    assert_eq!(verify(&tampered), Err(ReceiptError::Signature));
+
+    // So are a different ballot id and context.
+    let mut tampered = issue();
+    tampered.ballot_id = "2";
+    assert_eq!(verify(&tampered), Err(ReceiptError::Signature));
+    let mut tampered = issue();
+    tampered.context = None;
+    assert_eq!(verify(&tampered), Err(ReceiptError::Signature));
+    let mut tampered = issue();
+    tampered.context.as_mut().unwrap().confirmation_code = "7KQ3".to_string();
+    assert_eq!(verify(&tampered), Err(ReceiptError::Signature));
+
+    // Any signer produces the same signed bytes.
+    let signed = Receipt::sign(
+        &election.private_key,
+        ballot.clone(),
+        "1",
+        Some(&context),
+        election.transcript,
+    )
+    .unwrap();
+    assert_eq!(verify(&signed), Ok(()));
+
+    // A correctly signed receipt for an invalid ballot fails on the ballot proof.
+    let mut invalid = ballot.clone();
+    invalid.pwf_mut().r = G::Scalar::random(&mut rng);
+    let receipt = election.issue_receipt(invalid, "1", None);
+    assert_eq!(
+        verify(&receipt),
+        Err(ReceiptError::Ballot(BallotError::BallotProof {
+            ballot_id: "1",
+            reason: BallotProofFailure::ChallengeMismatch,
+        }))
+    );
+}
+
+/// Verify the election both sequentially and batched, checking that they agree.
+fn verify_both<G: DreipGroup, S: VoteSecrets<G>>(
+    election: &Election<G>,
+    ballots: &HashMap<&'static str, Ballot<&'static str, G, S>>,
+    totals: &HashMap<&'static str, CandidateTotals<G>>,
+    rng: impl RngCore + CryptoRng,
+) -> Result<(), VerificationError<&'static str, &'static str>> {
+    let result = verify_election(
+        election.g1.clone(),
+        election.g2.clone(),
+        ballots,
+        totals,
+        election.transcript,
+    );
+    let batched = verify_election_batched(
+        election.g1.clone(),
+        election.g2.clone(),
+        ballots,
+        totals,
+        election.transcript,
+        rng,
+    );
+    assert_eq!(result, batched);
+    // A stream of borrowed ballots in the map's order fails at the same ballot.
+    let streamed = verify_election_iter(
+        election.g1.clone(),
+        election.g2.clone(),
+        ballots
+            .iter()
+            .map(|(ballot_id, ballot)| (*ballot_id, ballot))
+            .collect::<Vec<_>>(),
+        totals,
+        election.transcript,
+    );
+    assert_eq!(result, streamed);
+    result
+}
+
+/// A ballot proof with its `Z` sum, `R` sum, and ballot id.
+pub(crate) type ProofValues<G> = (
+    BallotProof<G>,
+    <G as DreipGroup>::Point,
+    <G as DreipGroup>::Point,
+    String,
+);
+
+/// Create `n` valid ballot proofs, with the values they are verified against.
+pub(crate) fn ballot_proofs<G: DreipGroup>(
+    mut rng: impl RngCore + CryptoRng,
+    g1: &G::Point,
+    g2: &G::Point,
+    n: usize,
+) -> Vec<ProofValues<G>> {
+    (0..n)
+        .map(|i| {
+            let r_sum = G::Scalar::random(&mut rng);
+            let ballot_id = i.to_string();
+            let proof =
+                BallotProof::new(&mut rng, g1.clone(), g2.clone(), r_sum.clone(), &ballot_id);
+            let z_sum = g1.clone() * (r_sum.clone() + G::Scalar::one());
+            let r_sum = g2.clone() * r_sum;
+            (proof, z_sum, r_sum, ballot_id)
+        })
+        .collect()
+}
+
+fn check_batch_verify<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Batch verification"], &mut rng);
+    let (g1, g2) = (election.g1, election.g2);
+    let mut batch_rng = ChaCha20Rng::from_rng(&mut rng).expect("Seeding cannot fail");
+    let mut verify = |proofs: &[ProofValues<G>]| {
+        BallotProof::batch_verify(
+            g1.clone(),
+            g2.clone(),
+            proofs.iter().map(|(proof, z_sum, r_sum, id)| {
+                (proof, z_sum.clone(), r_sum.clone(), id.as_bytes())
+            }),
+            TranscriptVersion::CURRENT,
+            &mut batch_rng,
+        )
+    };
+
+    let proofs = ballot_proofs::<G>(&mut rng, &g1, &g2, 10);
+    assert_eq!(verify(&[]), Ok(()));
+    assert_eq!(verify(&proofs), Ok(()));
+
+    // A single proof is verified on its own.
+    assert_eq!(verify(&proofs[..1]), Ok(()));
+    let mut single = proofs[..1].to_vec();
+    single[0].0.r = G::Scalar::random(&mut rng);
+    assert_eq!(
+        verify(&single),
+        Err((0, BallotProofFailure::ChallengeMismatch))
+    );
+
+    // Tampering with any part of a single item is pinpointed. Changing `a`, `b`, `r`, or the
+    // ballot id changes the challenge, failing both equations, while changing a sum fails
+    // only its own equation.
+    let random = G::Scalar::random(&mut rng);
+    for field in 0..6 {
+        let mut tampered = proofs.clone();
+        let (proof, z_sum, r_sum, ballot_id) = &mut tampered[7];
+        let reason = match field {
+            0 => {
+                proof.a = proof.a.clone() + g1.clone();
+                BallotProofFailure::ChallengeMismatch
+            }
+            1 => {
+                proof.b = proof.b.clone() + g2.clone();
+                BallotProofFailure::ChallengeMismatch
+            }
+            2 => {
+                proof.r = random.clone();
+                BallotProofFailure::ChallengeMismatch
+            }
+            3 => {
+                *z_sum = z_sum.clone() + g1.clone();
+                BallotProofFailure::ZEquation
+            }
+            4 => {
+                *r_sum = r_sum.clone() + g2.clone();
+                BallotProofFailure::REquation
+            }
+            _ => {
+                *ballot_id = "8".to_string();
+                BallotProofFailure::ChallengeMismatch
+            }
+        };
+        assert_eq!(verify(&tampered), Err((7, reason)));
+    }
+
+    // Errors in two items which cancel out in a fixed combination are still caught.
+    let mut tampered = proofs.clone();
+    tampered[2].0.a = tampered[2].0.a.clone() + g1.clone();
+    tampered[5].0.a = tampered[5].0.a.clone() - g1.clone();
+    assert_eq!(
+        verify(&tampered),
+        Err((2, BallotProofFailure::ChallengeMismatch))
+    );
+
+    // So are errors which cancel out across the two equations of one item.
+    let mut tampered = proofs;
+    tampered[4].0.a = tampered[4].0.a.clone() + g1.clone();
+    tampered[4].0.b = tampered[4].0.b.clone() - g1.clone();
+    assert_eq!(
+        verify(&tampered),
+        Err((4, BallotProofFailure::ChallengeMismatch))
+    );
+}
+
+fn check_signature_batch<G: DreipGroup>(mut rng: impl RngCore + CryptoRng)
+where
+    G::PublicKey: Sync,
+    G::Signature: Sync,
+{
+    let election = Election::<G>::new(&[b"Signature batch"], &mut rng);
+    let (other_key, _) = G::new_keys(&mut rng);
+    let mut msgs = (0..40u32)
+        .map(|i| format!("Receipt {}", i).into_bytes())
+        .collect::<Vec<_>>();
+    let mut sigs = msgs
+        .iter()
+        .map(|msg| election.private_key.sign(msg))
+        .collect::<Vec<_>>();
+    let verify = |msgs: &[Vec<u8>], sigs: &[G::Signature]| {
+        let items = msgs
+            .iter()
+            .zip(sigs)
+            .map(|(msg, sig)| (&msg[..], sig))
+            .collect::<Vec<_>>();
+        let result = election.public_key.batch_verify(&items);
+        let sequential = items
+            .iter()
+            .position(|(msg, sig)| !election.public_key.verify(msg, sig))
+            .map_or(Ok(()), Err);
+        assert_eq!(result, sequential);
+        #[cfg(feature = "parallel")]
+        assert_eq!(election.public_key.par_batch_verify(&items), result);
+        result
+    };
+
+    assert_eq!(verify(&[], &[]), Ok(()));
+    assert_eq!(verify(&msgs, &sigs), Ok(()));
+    assert_eq!(verify(&msgs[..3], &sigs[..3]), Ok(()));
+
+    // A flipped message byte, or a signature by another key, is pinpointed.
+    msgs[23][0] ^= 1;
+    assert_eq!(verify(&msgs, &sigs), Err(23));
+    sigs[5] = other_key.sign(&msgs[5]);
+    assert_eq!(verify(&msgs, &sigs), Err(5));
+    assert_eq!(verify(&msgs[..7], &sigs[..7]), Err(5));
+    assert_eq!(verify(&msgs[6..], &sigs[6..]), Err(17));
+}
+
+fn check_params_encoding<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Parameters"], &mut rng);
+    let bytes = election.to_bytes();
+    let params = Election::<G>::params_from_bytes(&bytes).unwrap();
+    assert_eq!(params.g1, election.g1);
+    assert_eq!(params.g2, election.g2);
+    assert_eq!(params.public_key.to_bytes(), election.public_key.to_bytes());
+    assert_eq!(params.to_bytes(), bytes);
+    assert_strict(&params);
+
+    // The private key is not included.
+    let private_key = election.private_key.to_bytes();
+    assert!(!bytes
+        .windows(private_key.len())
+        .any(|window| window == private_key));
+
+    // Identity generators are rejected.
+    let mut params = election.params();
+    params.g2 = G::Point::identity();
+    assert!(ElectionParams::<G>::from_bytes(&params.to_bytes()).is_none());
+}
+
+fn check_with_keys<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let (private_key, public_key) = G::new_keys(&mut rng);
+    let (_, other_public_key) = G::new_keys(&mut rng);
+
+    let election =
+        Election::<G>::with_keys(&[b"Keys"], private_key.clone(), public_key.clone()).unwrap();
+    let generated = Election::<G>::new(&[b"Keys"], &mut rng);
+    assert_eq!(election.g1, generated.g1);
+    assert_eq!(election.g2, generated.g2);
+    assert_eq!(election.private_key.to_bytes(), private_key.to_bytes());
+    assert_eq!(election.public_key.to_bytes(), public_key.to_bytes());
+
+    // Keys that do not correspond are rejected.
+    assert_eq!(
+        Election::<G>::with_keys(&[b"Keys"], private_key, other_public_key).unwrap_err(),
+        KeyMismatch
+    );
+
+    // Verifiers can build the public parameters without a private key.
+    let params = Election::<G>::from_public(election.g1.clone(), election.g2.clone(), public_key);
+    assert_eq!(params.to_bytes(), election.to_bytes());
+}
+
+fn check_from_seed<G: DreipGroup + Debug + Eq>(_rng: impl RngCore + CryptoRng) {
+    let seed = [7; 32];
+    let election = Election::<G>::from_seed(&[b"Seeded"], &seed);
+
+    // The same inputs give byte-identical elections, private key included.
+    let again = Election::<G>::from_seed(&[b"Seeded"], &seed);
+    assert_eq!(
+        serde_json::to_string(&election).unwrap(),
+        serde_json::to_string(&again).unwrap()
+    );
+
+    // A different seed, or the same seed for a different election, gives different keys.
+    for other in [
+        Election::<G>::from_seed(&[b"Seeded"], &[8; 32]),
+        Election::<G>::from_seed(&[b"Reseeded"], &seed),
+    ] {
+        assert_ne!(
+            other.private_key.to_bytes(),
+            election.private_key.to_bytes()
+        );
+        assert_ne!(other.public_key.to_bytes(), election.public_key.to_bytes());
+    }
+}
+
+fn check_fingerprint<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Fingerprint"], &mut rng);
+    let fingerprint = election.fingerprint();
+    assert_eq!(election.params().fingerprint(), fingerprint);
+    assert_eq!(election.fingerprint_hex().len(), 64);
+
+    // Serialization round trips preserve the fingerprint.
+    let params = Election::<G>::params_from_bytes(&election.to_bytes()).unwrap();
+    assert_eq!(params.fingerprint(), fingerprint);
+    let json = serde_json::to_string(&election).unwrap();
+    let deserialized: Election<G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.fingerprint(), fingerprint);
+
+    // Changing any one parameter changes the fingerprint.
+    let other = Election::<G>::new(&[b"Other"], &mut rng);
+    let mut params = election.params();
+    params.g1 = other.g1.clone() + other.g2.clone();
+    assert_ne!(params.fingerprint(), fingerprint);
+    let mut params = election.params();
+    params.g2 = other.g2.clone();
+    assert_ne!(params.fingerprint(), fingerprint);
+    let mut params = election.params();
+    params.public_key = other.public_key;
+    assert_ne!(params.fingerprint(), fingerprint);
+}
+
+fn check_parameters<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    assert_ne!(G::Point::generator(), G::Point::identity());
+    let election = Election::<G>::new(&[b"Parameters"], &mut rng);
+    assert_eq!(election.validate_parameters(), Ok(()));
+    assert_eq!(election.params().validate_parameters(), Ok(()));
+
+    let mut params = election.params();
+    params.g2 = G::Point::identity();
+    assert_eq!(params.validate_parameters(), Err(ParameterError::InvalidG2));
+    params.g2 = params.g1.clone();
+    assert_eq!(params.validate_parameters(), Err(ParameterError::InvalidG2));
+
+    // Only groups that use the standard generator are held to it.
+    let mut params = election.params();
+    params.g1 = params.g1.clone() + params.g1.clone();
+    let expected = if election.g1 == G::Point::generator() {
+        Err(ParameterError::NonStandardG1)
+    } else {
+        Ok(())
+    };
+    assert_eq!(params.validate_parameters(), expected);
+
+    let mut dump = serde_json::to_value(&election).unwrap();
+    dump["g2"] = dump["g1"].clone();
+    let loaded: Election<G> = serde_json::from_value(dump).unwrap();
+    assert_eq!(loaded.validate_parameters(), Err(ParameterError::InvalidG2));
+
+    // Honest results, for tampering with below.
+    let mut session = ElectionSession::new(election.clone(), ["Alice", "Bob"]);
+    let handle = session.cast(&mut rng, "1", "Alice").unwrap();
+    session.confirm(handle).unwrap();
+    let results = session.into_results();
+    assert_eq!(results.verify(), Ok(()));
+
+    // Verification refuses to start with bad generators.
+    let ballot = Ballot::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob"],
+    )
+    .unwrap();
+    let ballots = HashMap::from([("1", ballot)]);
+    let bad = Election {
+        g2: G::Point::identity(),
+        ..election
+    };
+    assert_eq!(
+        verify_both(&bad, &ballots, &HashMap::new(), &mut rng),
+        Err(VerificationError::InvalidParameters(
+            ParameterError::InvalidG2
+        ))
+    );
+    assert_eq!(bad.validate_parameters(), Err(ParameterError::InvalidG2));
+
+    // Dumps load whatever generators they hold, but their results are rejected up front,
+    // before any ballot is checked.
+    let mut dump = serde_json::to_value(results).unwrap();
+    dump["g2"] = G::Point::identity().to_bytestring().into();
+    let loaded: ElectionResults<String, String, G> = serde_json::from_value(dump).unwrap();
+    assert_eq!(
+        loaded.verify(),
+        Err(VerificationError::InvalidParameters(
+            ParameterError::InvalidG2
+        ))
+    );
+}
+
+fn check_group_name<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Group name"], &mut rng);
+    let mut json = serde_json::to_value(&election).unwrap();
+    assert_eq!(json["group"], G::NAME);
+    let deserialized: Election<G> = serde_json::from_value(json.clone()).unwrap();
+    assert!(!deserialized.group.is_legacy());
+    assert_eq!(deserialized.to_bytes(), election.to_bytes());
+
+    // Another group's name is reported, before any point fails to decode.
+    let mut other = json.clone();
+    other["group"] = "DREIP-OTHER".into();
+    let error = serde_json::from_value::<Election<G>>(other)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains(&format!(
+            "dump was created with DREIP-OTHER, verifier built for {}",
+            G::NAME
+        )),
+        "{}",
+        error
+    );
+
+    // Elections from before group names are accepted, but marked.
+    json.as_object_mut().unwrap().remove("group");
+    let legacy: Election<G> = serde_json::from_value(json).unwrap();
+    assert!(legacy.group.is_legacy());
+    assert_eq!(legacy.to_bytes(), election.to_bytes());
+    let reserialized = serde_json::to_value(&legacy).unwrap();
+    assert_eq!(reserialized["group"], G::NAME);
+}
+
+/// Check that the canonical encoding decodes, but padded or truncated versions do not.
+fn assert_strict<T: Serializable>(value: &T) {
+    let bytes = value.to_bytes();
+    assert!(T::from_bytes(&bytes).is_some());
+    assert!(T::from_bytes(&[&bytes[..], &[0]].concat()).is_none());
+    assert!(T::from_bytes(&[&[0], &bytes[..]].concat()).is_none());
+    assert!(T::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+    assert!(T::from_bytes(&bytes[1..]).is_none());
+    assert!(T::from_bytes(&[]).is_none());
+}
+
+fn check_strict_decoding<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let point = G::Point::from_hash(&[b"Strict decoding"]);
+    let scalar = G::Scalar::random(&mut rng);
+    let (private_key, public_key) = G::new_keys(&mut rng);
+    let signature = private_key.sign(b"Strict decoding");
+    assert_strict(&point);
+    assert_strict(&scalar);
+    assert_strict(&private_key);
+    assert_strict(&public_key);
+    assert_strict(&signature);
+
+    // All ones is out of range for every backend's scalars, and not a valid point.
+    let scalar_len = scalar.to_bytes().len();
+    assert!(G::Scalar::from_bytes(&vec![0xff; scalar_len]).is_none());
+    let point_len = point.to_bytes().len();
+    assert!(G::Point::from_bytes(&vec![0xff; point_len]).is_none());
+
+    // Bad values are reported with the type and reason.
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(bound = "")]
+    #[allow(dead_code)]
+    struct Wrapper<G: DreipGroup> {
+        #[serde(with = "crate::group::serde_bytestring")]
+        scalar: G::Scalar,
+    }
+    let padded = [scalar.to_bytes(), vec![0]].concat();
+    let json = format!(
+        r#"{{"scalar": "{}"}}"#,
+        base64::encode_config(padded, base64::URL_SAFE_NO_PAD)
+    );
+    let err = serde_json::from_str::<Wrapper<G>>(&json)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains(std::any::type_name::<G::Scalar>()), "{}", err);
+    assert!(
+        err.contains(&format!("of {} bytes", scalar_len + 1)),
+        "{}",
+        err
+    );
+    let err = serde_json::from_str::<Wrapper<G>>(r#"{"scalar": "!"}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid bytestring"), "{}", err);
+}
+
+/// Build the scalar `n` from the group operations alone, by double-and-add.
+fn scalar_from_u128<G: DreipGroup>(n: u128) -> G::Scalar {
+    (0..u128::BITS).rev().fold(G::Scalar::zero(), |acc, bit| {
+        let acc = acc.clone() + acc;
+        if n >> bit & 1 == 1 {
+            acc + G::Scalar::one()
+        } else {
+            acc
+        }
+    })
+}
+
+/// Assert that the value's encoding has its declared length, if any.
+fn assert_byte_size<T: Serializable>(value: &T) {
+    if let Some(size) = T::BYTE_SIZE {
+        assert_eq!(value.to_bytes().len(), size);
+    }
+}
+
+/// A vote's encoding as originally built, from separately allocated fields.
+fn nested_vote_bytes<G: DreipGroup>(
+    vote: &Vote<G, SecretsPresent<G>>,
+    transcript: TranscriptVersion,
+) -> Vec<u8> {
+    let secrets = [vote.secrets.r.to_bytes(), vote.secrets.v.to_bytes()].concat();
+    let pwf = transcript.encode(&[
+        &vote.pwf().c1.to_bytes(),
+        &vote.pwf().c2.to_bytes(),
+        &vote.pwf().r1.to_bytes(),
+        &vote.pwf().r2.to_bytes(),
+    ]);
+    transcript.encode(&[&secrets, &vote.R().to_bytes(), &vote.Z().to_bytes(), &pwf])
+}
+
+/// A ballot's encoding as originally built, from separately allocated fields.
+fn nested_ballot_bytes<G: DreipGroup>(
+    ballot: &Ballot<&str, G, SecretsPresent<G>>,
+    transcript: TranscriptVersion,
+) -> Vec<u8> {
+    let mut votes = ballot.votes().iter().collect::<Vec<_>>();
+    votes.sort_by_key(|(c, _)| *c);
+    let votes = votes
+        .into_iter()
+        .map(|(candidate, vote)| (candidate, nested_vote_bytes(vote, transcript)))
+        .collect::<Vec<_>>();
+    let pwf = transcript.encode(&[
+        &ballot.pwf().a.to_bytes(),
+        &ballot.pwf().b.to_bytes(),
+        &ballot.pwf().r.to_bytes(),
+    ]);
+    let mut fields: Vec<&[u8]> = Vec::new();
+    for (candidate, vote) in votes.iter() {
+        fields.push(candidate.as_bytes());
+        fields.push(vote);
+    }
+    fields.push(&pwf);
+    transcript.encode(&fields)
+}
+
+fn check_identity_points<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Identity Points"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let mut session = ElectionSession::new(election, ["Alice", "Bob"]);
+    for (ballot_id, candidate) in [("1", "Alice"), ("2", "Bob")] {
+        let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+        session.confirm(handle).unwrap();
+    }
+    let handle = session.cast(&mut rng, "3", "Bob").unwrap();
+    session.audit(handle).unwrap();
+    let dump = serde_json::to_value(session.into_results()).unwrap();
+    let load = |dump| serde_json::from_value::<ElectionResults<String, String, G>>(dump).unwrap();
+
+    // An honest dump is unaffected.
+    assert_eq!(load(dump.clone()).verify(), Ok(()));
+
+    // Identity points decode, but each is rejected by name, not as a failing equation.
+    let identity = serde_json::Value::String(G::Point::identity().to_bytestring());
+    let vote_error = |ballot_id: &str, candidate_id: &str| {
+        BallotError::Vote(VoteError {
+            ballot_id: ballot_id.to_string(),
+            candidate_id: candidate_id.to_string(),
+            reason: VoteFailure::IdentityPoint,
+        })
+    };
+    let proof_error = |ballot_id: &str| BallotError::BallotProof {
+        ballot_id: ballot_id.to_string(),
+        reason: BallotProofFailure::IdentityPoint,
+    };
+    let cases = [
+        (
+            ["confirmed", "1", "votes", "Alice", "Z"],
+            VerificationError::Ballot(vote_error("1", "Alice")),
+        ),
+        (
+            ["confirmed", "2", "votes", "Bob", "R"],
+            VerificationError::Ballot(vote_error("2", "Bob")),
+        ),
+        (
+            ["audited", "3", "votes", "Alice", "R"],
+            VerificationError::Audited(vote_error("3", "Alice")),
+        ),
+        (
+            ["confirmed", "1", "pwf", "a", ""],
+            VerificationError::Ballot(proof_error("1")),
+        ),
+        (
+            ["confirmed", "2", "pwf", "b", ""],
+            VerificationError::Ballot(proof_error("2")),
+        ),
+    ];
+    for (path, expected) in cases {
+        let mut dump = dump.clone();
+        let field = path
+            .iter()
+            .filter(|key| !key.is_empty())
+            .fold(&mut dump, |value, key| &mut value[*key]);
+        assert!(field.is_string());
+        *field = identity.clone();
+        let results = load(dump);
+        assert_eq!(results.verify(), Err(expected.clone()));
+
+        // Batching the ballot proofs does not let a degenerate one through.
+        if let VerificationError::Ballot(expected) = expected {
+            assert_eq!(
+                verify_election_batched(
+                    g1.clone(),
+                    g2.clone(),
+                    &results.confirmed,
+                    &results.totals,
+                    transcript,
+                    &mut rng,
+                ),
+                Err(VerificationError::Ballot(expected))
+            );
+        }
+    }
+
+    // A vote with identity commitments fails even where its secrets would match them.
+    let mut ballot =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "4", "A", ["B"]).unwrap();
+    let vote = ballot.votes_mut().get_mut("B").unwrap();
+    *vote.Z_mut() = G::Point::identity();
+    *vote.R_mut() = G::Point::identity();
+    vote.secrets.r = G::Scalar::zero();
+    match ballot.verify(g1, g2, "4", transcript) {
+        Err(BallotError::Vote(error)) => assert_eq!(
+            error.to_string(),
+            "vote for candidate id \"B\" on ballot id \"4\": Z or R is the identity point"
+        ),
+        result => panic!("expected an identity point, got {:?}", result),
+    }
+}
+
+fn check_proof_bytes<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Proof bytes"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let ballot =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", ["Bob"]).unwrap();
+    let vote = ballot.get_vote(&"Alice").unwrap();
+    let (z_sum, r_sum) = ballot.zr_sums();
+
+    // Both proofs survive the round trip, and still verify.
+    let vote_bytes = vote.pwf().to_bytes(TranscriptVersion::V0);
+    let vote_proof = VoteProof::<G>::from_bytes(&vote_bytes).unwrap();
+    assert_eq!(&vote_proof, vote.pwf());
+    if let Some(len) = VoteProof::<G>::BYTES {
+        assert_eq!(vote_bytes.len(), len);
+    }
+    let (z, r) = (vote.Z().clone(), vote.R().clone());
+    assert_eq!(
+        vote_proof.verify(g1.clone(), g2.clone(), z, r, "1", "Alice", transcript),
+        Ok(())
+    );
+    let ballot_bytes = ballot.pwf().to_bytes(TranscriptVersion::V0);
+    let ballot_proof = BallotProof::<G>::from_bytes(&ballot_bytes).unwrap();
+    assert_eq!(&ballot_proof, ballot.pwf());
+    if let Some(len) = BallotProof::<G>::BYTES {
+        assert_eq!(ballot_bytes.len(), len);
+    }
+    assert_eq!(
+        ballot_proof.verify(g1, g2, z_sum, r_sum, "1", transcript),
+        Ok(())
+    );
+
+    // Any other length fails without panicking, including the framed signing encodings.
+    for len in 0..vote_bytes.len() {
+        assert!(VoteProof::<G>::from_bytes(&vote_bytes[..len]).is_none());
+    }
+    for len in 0..ballot_bytes.len() {
+        assert!(BallotProof::<G>::from_bytes(&ballot_bytes[..len]).is_none());
+    }
+    assert!(VoteProof::<G>::from_bytes(&[&vote_bytes[..], &[0]].concat()).is_none());
+    assert!(BallotProof::<G>::from_bytes(&[&ballot_bytes[..], &[0]].concat()).is_none());
+    assert!(VoteProof::<G>::from_bytes(&vote.pwf().to_bytes(transcript)).is_none());
+    assert!(BallotProof::<G>::from_bytes(&ballot.pwf().to_bytes(transcript)).is_none());
+
+    // Corrupted bytes of the right length decode or not, but never panic, and a decoded
+    // proof differs from the original.
+    for _ in 0..64 {
+        let mut corrupted = vote_bytes.clone();
+        corrupted[rng.next_u32() as usize % vote_bytes.len()] ^= 1 << (rng.next_u32() % 8);
+        assert_ne!(
+            VoteProof::<G>::from_bytes(&corrupted).as_ref(),
+            Some(vote.pwf())
+        );
+        let mut corrupted = ballot_bytes.clone();
+        corrupted[rng.next_u32() as usize % ballot_bytes.len()] ^= 1 << (rng.next_u32() % 8);
+        assert_ne!(
+            BallotProof::<G>::from_bytes(&corrupted).as_ref(),
+            Some(ballot.pwf())
+        );
+        let mut random = vec![0; ballot_bytes.len()];
+        rng.fill_bytes(&mut random);
+        let _ = BallotProof::<G>::from_bytes(&random);
+    }
+
+    // Non-canonical scalars are rejected.
+    let scalar_len = vote_bytes.len() / 4;
+    let unreduced = vec![0xFF; scalar_len];
+    if G::Scalar::from_bytes(&unreduced).is_none() {
+        let mut bytes = vote_bytes.clone();
+        bytes[..scalar_len].copy_from_slice(&unreduced);
+        assert!(VoteProof::<G>::from_bytes(&bytes).is_none());
+        let mut bytes = ballot_bytes.clone();
+        let r_start = bytes.len() - scalar_len;
+        bytes[r_start..].copy_from_slice(&unreduced);
+        assert!(BallotProof::<G>::from_bytes(&bytes).is_none());
+    }
+}
+
+fn check_write_bytes<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Write Bytes"], &mut rng);
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    let confirmed = ballot.clone().confirm(None).unwrap();
+    for transcript in [
+        TranscriptVersion::V0,
+        TranscriptVersion::V1,
+        TranscriptVersion::V2,
+    ] {
+        // Every value encodes as it did before, in exactly `byte_len` bytes.
+        let expected = nested_ballot_bytes(&ballot, transcript);
+        assert_eq!(ballot.to_bytes(transcript), expected);
+        assert_eq!(ballot.byte_len(transcript), expected.len());
+        for vote in ballot.votes().values() {
+            let expected = nested_vote_bytes(vote, transcript);
+            assert_eq!(vote.to_bytes(transcript), expected);
+            assert_eq!(vote.byte_len(transcript), expected.len());
+            assert_eq!(
+                vote.pwf().byte_len(transcript),
+                vote.pwf().to_bytes(transcript).len()
+            );
+            assert_eq!(
+                vote.secrets.byte_len(transcript),
+                Vec::from(&vote.secrets).len()
+            );
+        }
+        assert_eq!(
+            ballot.pwf().byte_len(transcript),
+            ballot.pwf().to_bytes(transcript).len()
+        );
+        let bytes = confirmed.to_bytes(transcript);
+        assert_eq!(confirmed.byte_len(transcript), bytes.len());
+
+        // Writing appends to what is already in the buffer.
+        let mut out = b"Prefix".to_vec();
+        ballot.write_bytes(&mut out, transcript);
+        assert_eq!(out, [&b"Prefix"[..], &expected].concat());
+    }
+}
+
+fn check_canonical<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Canonical"], &mut rng);
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Bob",
+        vec!["Alice", "Eve"],
+    )
+    .unwrap();
+
+    // The encoding is versioned, and does not depend on the order the votes were added in.
+    let bytes = ballot.canonical_bytes();
+    assert_eq!(bytes[..2], [CANONICAL_MAGIC, CANONICAL_VERSION]);
+    let mut reordered = Ballot::from_parts(BTreeMap::new(), ballot.pwf().clone());
+    for candidate in ["Eve", "Bob", "Alice"] {
+        reordered
+            .votes_mut()
+            .insert(candidate, ballot.votes()[candidate].clone());
+    }
+    assert_eq!(reordered.canonical_bytes(), bytes);
+    for vote in ballot.votes().values() {
+        assert_eq!(
+            vote.canonical_bytes()[..2],
+            [CANONICAL_MAGIC, CANONICAL_VERSION]
+        );
+    }
+
+    // It differs from the legacy encoding, and changes without the secrets.
+    for transcript in [
+        TranscriptVersion::V0,
+        TranscriptVersion::V1,
+        TranscriptVersion::V2,
+    ] {
+        assert_ne!(ballot.to_bytes(transcript), bytes);
+    }
+    let confirmed = ballot.clone().confirm(None).unwrap();
+    assert_ne!(confirmed.canonical_bytes(), bytes);
+
+    // The legacy receipt signs the canonical encoding alone.
+    #[allow(deprecated)]
+    let signature = ballot.sign_receipt(&election.private_key).unwrap();
+    assert!(election.public_key.verify(&bytes, &signature));
+
+    // Ballots with the same votes but different ids have different signed bytes.
+    let with_id = ballot.to_bytes_with_id("1");
+    assert_eq!(with_id[..2], [CANONICAL_MAGIC, CANONICAL_VERSION]);
+    assert_eq!(reordered.to_bytes_with_id("1"), with_id);
+    assert_ne!(reordered.to_bytes_with_id("2"), with_id);
+    assert_ne!(ballot.to_bytes_with_id(""), bytes);
+    assert_eq!(with_id, Receipt::to_bytes(&ballot, &"1", None));
+
+    // Receipts sign the ballot with its id, and verify as a `Receipt` without a context.
+    let signature = ballot
+        .sign_receipt_with_id("1", &election.private_key)
+        .unwrap();
+    assert!(election.public_key.verify(&with_id, &signature));
+    assert!(!election
+        .public_key
+        .verify(&reordered.to_bytes_with_id("2"), &signature));
+    let receipt = Receipt {
+        ballot_id: "1",
+        ballot,
+        context: None,
+        transcript: TranscriptVersion::CURRENT,
+        signature,
+    };
+    assert_eq!(
+        receipt.verify(&election.public_key, election.g1, election.g2),
+        Ok(())
+    );
+}
+
+#[allow(non_snake_case)]
+fn check_codec<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Codec"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let ballot = Ballot::<String, G, _>::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "1",
+        "Bob".to_string(),
+        ["Alice".to_string(), "Eve".to_string()],
+    )
+    .unwrap();
+    let confirmed = ballot.clone().confirm(None).unwrap();
+
+    // Ballots and votes survive the round trip, with or without secrets, and still verify.
+    let bytes = ballot.encode();
+    assert_eq!(bytes, ballot.canonical_bytes());
+    let decoded = Ballot::<String, G, SecretsPresent<G>>::decode(&bytes).unwrap();
+    assert_eq!(decoded, ballot);
+    assert_eq!(
+        decoded.verify(g1.clone(), g2.clone(), "1", election.transcript),
+        Ok(())
+    );
+    let confirmed_bytes = confirmed.encode();
+    assert_eq!(
+        Ballot::<String, G, NoSecrets>::decode(&confirmed_bytes).as_ref(),
+        Ok(&confirmed)
+    );
+    assert_eq!(
+        Ballot::<String, G, Secrets<G>>::decode(&bytes),
+        Ok(ballot.clone().into())
+    );
+    assert_eq!(
+        Ballot::<String, G, Secrets<G>>::decode(&confirmed_bytes),
+        Ok(confirmed.clone().into())
+    );
+    for (vote, confirmed_vote) in ballot.votes().values().zip(confirmed.votes().values()) {
+        assert_eq!(Vote::decode(&vote.encode()).as_ref(), Ok(vote));
+        assert_eq!(
+            Vote::decode(&confirmed_vote.encode()).as_ref(),
+            Ok(confirmed_vote)
+        );
+    }
+    let numbered =
+        Ballot::<u32, G, _>::new(&mut rng, g1.clone(), g2.clone(), 2u32, 1, [2, 3]).unwrap();
+    assert_eq!(Ballot::decode(&numbered.encode()).as_ref(), Ok(&numbered));
+
+    // The secrets and candidate ids must be of the expected types.
+    assert_eq!(
+        Ballot::<String, G, NoSecrets>::decode(&bytes),
+        Err(DecodeError::InvalidField("secrets"))
+    );
+    assert_eq!(
+        Ballot::<String, G, SecretsPresent<G>>::decode(&confirmed_bytes),
+        Err(DecodeError::InvalidField("secrets"))
+    );
+    assert_eq!(
+        Ballot::<u64, G, SecretsPresent<G>>::decode(&numbered.encode()),
+        Err(DecodeError::InvalidField("candidate id"))
+    );
+    assert_eq!(
+        Ballot::<u32, G, SecretsPresent<G>>::decode(&bytes),
+        Err(DecodeError::InvalidField("candidate id"))
+    );
+
+    // The header is checked, and no bytes may be missing or left over.
+    let decode = |bytes: &[u8]| Ballot::<String, G, SecretsPresent<G>>::decode(bytes);
+    assert_eq!(decode(&[]), Err(DecodeError::Truncated));
+    assert_eq!(decode(&[CANONICAL_MAGIC]), Err(DecodeError::Truncated));
+    assert_eq!(decode(&[0]), Err(DecodeError::Magic(0)));
+    let mut versioned = bytes.clone();
+    versioned[1] = CANONICAL_VERSION + 1;
+    assert_eq!(
+        decode(&versioned),
+        Err(DecodeError::Version(CANONICAL_VERSION + 1))
+    );
+    // Decoding large groups' points is slow, so only some of the truncations are tried.
+    let truncations = |len: usize| (0..len).step_by(len / 32 + 1).chain([len - 1]);
+    for len in truncations(bytes.len()) {
+        assert!(decode(&bytes[..len]).is_err());
+    }
+    assert_eq!(
+        decode(&[&bytes[..], &[0]].concat()),
+        Err(DecodeError::TrailingBytes)
+    );
+    let vote = ballot.get_vote(&"Bob".to_string()).unwrap();
+    let vote_bytes = vote.encode();
+    for len in truncations(vote_bytes.len()) {
+        assert!(Vote::<G, SecretsPresent<G>>::decode(&vote_bytes[..len]).is_err());
+    }
+    assert_eq!(
+        Vote::<G, SecretsPresent<G>>::decode(&[&vote_bytes[..], &[0]].concat()),
+        Err(DecodeError::TrailingBytes)
+    );
+
+    // Ballots are reassembled from their fields, to change one at a time.
+    let field = |bytes: &[u8]| TranscriptVersion::V1.encode(&[bytes]);
+    let assemble = |count: &[u8], votes: &[(&str, Vec<u8>)]| {
+        let mut out = vec![CANONICAL_MAGIC, CANONICAL_VERSION];
+        out.extend(field(count));
+        for (candidate, body) in votes {
+            out.extend(field(candidate.as_bytes()));
+            out.extend(field(body));
+        }
+        out.extend(field(&ballot.pwf().to_bytes(TranscriptVersion::V1)));
+        out
+    };
+    let body = |candidate: &str| ballot.votes()[candidate].encode()[2..].to_vec();
+    let votes = ["Alice", "Bob", "Eve"].map(|candidate| (candidate, body(candidate)));
+    assert_eq!(assemble(&3u64.to_be_bytes(), &votes), bytes);
+
+    // The votes must be in strictly increasing order of candidate id.
+    let [alice, bob, eve] = votes.clone();
+    let swapped = [bob.clone(), alice.clone(), eve.clone()];
+    assert_eq!(
+        decode(&assemble(&3u64.to_be_bytes(), &swapped)),
+        Err(DecodeError::UnsortedVotes)
+    );
+    let repeated = [alice.clone(), bob.clone(), bob.clone(), eve.clone()];
+    assert_eq!(
+        decode(&assemble(&4u64.to_be_bytes(), &repeated)),
+        Err(DecodeError::UnsortedVotes)
+    );
+
+    // The vote count must be exact, and of the right width.
+    assert!(decode(&assemble(&2u64.to_be_bytes(), &votes)).is_err());
+    assert!(decode(&assemble(&4u64.to_be_bytes(), &votes)).is_err());
+    assert_eq!(
+        decode(&assemble(&3u32.to_be_bytes(), &votes)),
+        Err(DecodeError::InvalidField("vote count"))
+    );
+
+    // Non-canonical points and scalars are rejected.
+    let secrets =
+        TranscriptVersion::V1.encode(&[&vote.secrets.r.to_bytes(), &vote.secrets.v.to_bytes()]);
+    let (R, Z) = (vote.R().to_bytes(), vote.Z().to_bytes());
+    let proof = |c1: &[u8]| {
+        let pwf = vote.pwf();
+        TranscriptVersion::V1.encode(&[
+            c1,
+            &pwf.c2.to_bytes(),
+            &pwf.r1.to_bytes(),
+            &pwf.r2.to_bytes(),
+        ])
+    };
+    let vote_body =
+        |R: &[u8], c1: &[u8]| TranscriptVersion::V1.encode(&[&secrets, R, &Z, &proof(c1)]);
+    let with_bob = |body: Vec<u8>| {
+        assemble(
+            &3u64.to_be_bytes(),
+            &[alice.clone(), ("Bob", body), eve.clone()],
+        )
+    };
+    let c1 = vote.pwf().c1.to_bytes();
+    assert_eq!(with_bob(vote_body(&R, &c1)), bytes);
+    let invalid_point = vec![0xFF; R.len()];
+    if G::Point::from_bytes(&invalid_point).is_none() {
+        assert_eq!(
+            decode(&with_bob(vote_body(&invalid_point, &c1))),
+            Err(DecodeError::InvalidField("R"))
+        );
+    }
+    let unreduced = vec![0xFF; c1.len()];
+    if G::Scalar::from_bytes(&unreduced).is_none() {
+        assert_eq!(
+            decode(&with_bob(vote_body(&R, &unreduced))),
+            Err(DecodeError::InvalidField("c1"))
+        );
+    }
+
+    // Fields within a vote must be exactly as long as their contents.
+    let padded = [&vote_body(&R, &c1)[..], &[0]].concat();
+    assert_eq!(decode(&with_bob(padded)), Err(DecodeError::TrailingBytes));
+}
+
+fn check_byte_size<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Byte Sizes"], &mut rng);
+    assert_byte_size(&election.g1);
+    assert_byte_size(&election.g2);
+    assert_byte_size(&G::Scalar::zero());
+    assert_byte_size(&G::Scalar::random(&mut rng));
+    assert_byte_size(&election.private_key);
+    assert_byte_size(&election.public_key);
+    assert_byte_size(&election.private_key.sign(b"Message"));
+
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob"],
+    )
+    .unwrap();
+    for transcript in [
+        TranscriptVersion::V0,
+        TranscriptVersion::V1,
+        TranscriptVersion::V2,
+    ] {
+        if let Some(size) = VoteProof::<G>::BYTES {
+            for vote in ballot.votes().values() {
+                assert_eq!(
+                    vote.pwf().to_bytes(transcript).len(),
+                    transcript.encoded_len(size, 4)
+                );
+            }
+        }
+        if let Some(size) = BallotProof::<G>::BYTES {
+            assert_eq!(
+                ballot.pwf().to_bytes(transcript).len(),
+                transcript.encoded_len(size, 3)
+            );
+        }
+    }
+}
+
+fn check_to_u64<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    assert_eq!(G::Scalar::zero().to_u64(), Some(0));
+    assert_eq!(G::Scalar::one().to_u64(), Some(1));
+    assert_eq!(scalar_from_u128::<G>(1234).to_u64(), Some(1234));
+    assert_eq!(
+        scalar_from_u128::<G>(u64::MAX as u128).to_u64(),
+        Some(u64::MAX)
+    );
+    assert_eq!(scalar_from_u128::<G>(u64::MAX as u128 + 1).to_u64(), None);
+    assert_eq!((G::Scalar::zero() - G::Scalar::one()).to_u64(), None);
+
+    let totals =
+        CandidateTotals::<G>::from((scalar_from_u128::<G>(42), G::Scalar::random(&mut rng)));
+    assert_eq!(totals.tally_u64(), Some(42));
+}
+
+fn check_invert<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    assert_eq!(G::Scalar::zero().invert(), None);
+    assert_eq!(G::Scalar::one().invert(), Some(G::Scalar::one()));
+    let x = G::Scalar::random(&mut rng);
+    let inverse = x.invert().expect("A random scalar is almost never zero");
+    assert_eq!(x.clone() * inverse.clone(), G::Scalar::one());
+    assert_eq!(inverse.invert(), Some(x));
+}
+
+#[cfg(feature = "bigint")]
+fn check_bigint<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    use num_bigint::BigUint;
+
+    // Scalars convert to their integer values and back.
+    assert_eq!(G::Scalar::zero().to_bigint(), BigUint::from(0u32));
+    assert_eq!(G::Scalar::one().to_bigint(), BigUint::from(1u32));
+    let big = u64::MAX as u128 + 1;
+    assert_eq!(scalar_from_u128::<G>(big).to_bigint(), BigUint::from(big));
+    assert_eq!(
+        G::Scalar::from_bigint(&BigUint::from(big)),
+        Some(scalar_from_u128::<G>(big))
+    );
+    let scalar = G::Scalar::random(&mut rng);
+    assert_eq!(G::Scalar::from_bigint(&scalar.to_bigint()), Some(scalar));
+
+    // The group order is out of range, and is one more than minus one.
+    let order = (G::Scalar::zero() - G::Scalar::one()).to_bigint() + 1u32;
+    assert!(G::Scalar::from_bigint(&order).is_none());
+    assert!(G::Scalar::from_bigint(&(order << 1024)).is_none());
+
+    // Points convert to their canonical encoding, read as big-endian.
+    let point = G::Point::from_hash(&[b"Big integers"]);
+    let bytes = point.to_bytes();
+    let value = point.to_bigint();
+    assert_eq!(value, BigUint::from_bytes_be(&bytes));
+    let mut round_trip = value.to_bytes_be();
+    round_trip.splice(0..0, vec![0; bytes.len() - round_trip.len()]);
+    assert_eq!(G::Point::from_bytes(&round_trip), Some(point));
+}
+
+fn check_precomputed<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let election = Election::<G>::new(&[b"Precomputation"], &mut rng);
+    let tables = election.precompute();
+
+    // Table multiplication agrees with plain multiplication.
+    let minus_one = G::Scalar::zero() - G::Scalar::one();
+    for scalar in [
+        G::Scalar::zero(),
+        G::Scalar::one(),
+        minus_one,
+        G::Scalar::random(&mut rng),
+    ] {
+        assert_eq!(tables.g1.mul(&scalar), election.g1.clone() * scalar.clone());
+        assert_eq!(tables.g2.mul(&scalar), election.g2.clone() * scalar);
+    }
+
+    // Ballots are identical given the same random number stream.
+    let plain = Ballot::<_, G, _>::new(
+        StdRng::seed_from_u64(7),
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    let precomputed = Ballot::<_, G, _>::new_precomputed(
+        StdRng::seed_from_u64(7),
+        &tables,
+        "1",
+        "Alice",
+        vec!["Bob", "Eve"],
+    )
+    .unwrap();
+    assert_eq!(plain, precomputed);
+    assert!(precomputed
+        .verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            "1",
+            election.transcript
+        )
+        .is_ok());
+
+    // As are the individual votes and proofs.
+    let plain = Vote::<G, _>::new(
+        StdRng::seed_from_u64(8),
+        election.g1.clone(),
+        election.g2.clone(),
+        "1",
+        "Alice",
+        true,
+    );
+    let precomputed =
+        Vote::<G, _>::new_precomputed(StdRng::seed_from_u64(8), &tables, "1", "Alice", true);
+    assert_eq!(plain, precomputed);
+    let plain = BallotProof::<G>::new(
+        StdRng::seed_from_u64(9),
+        election.g1.clone(),
+        election.g2.clone(),
+        G::Scalar::one(),
+        "1",
+    );
+    let precomputed =
+        BallotProof::<G>::new_precomputed(StdRng::seed_from_u64(9), &tables, G::Scalar::one(), "1");
+    assert_eq!(plain, precomputed);
+}
+
+/// Verify a vote proof with separate multiplications, as before `DreipGroup::msm`.
+#[allow(non_snake_case)]
+fn reference_vote_verify<G: DreipGroup>(
+    proof: &VoteProof<G>,
+    (g1, g2): (G::Point, G::Point),
+    (Z, R): (G::Point, G::Point),
+    ids: (&str, &str),
+) -> bool {
+    let a1 = g1.clone() * proof.r1.clone() + Z.clone() * proof.c1.clone();
+    let b1 = g2.clone() * proof.r1.clone() + R.clone() * proof.c1.clone();
+    let a2 = g1.clone() * proof.r2.clone() + (Z.clone() - g1.clone()) * proof.c2.clone();
+    let b2 = g2.clone() * proof.r2.clone() + R.clone() * proof.c2.clone();
+    let challenge: G::Scalar = TranscriptVersion::CURRENT.challenge(
+        VOTE_PROOF_LABEL,
+        VOTE_PROOF_TAG,
+        &[
+            &g1.to_bytes(),
+            &g2.to_bytes(),
+            &Z.to_bytes(),
+            &R.to_bytes(),
+            &a1.to_bytes(),
+            &b1.to_bytes(),
+            &a2.to_bytes(),
+            &b2.to_bytes(),
+            ids.0.as_bytes(),
+            ids.1.as_bytes(),
+        ],
+    );
+    proof.c1.clone() + proof.c2.clone() == challenge
+}
+
+/// Verify a ballot proof with separate multiplications, as before `DreipGroup::msm`.
+#[allow(non_snake_case)]
+fn reference_ballot_verify<G: DreipGroup>(
+    proof: &BallotProof<G>,
+    (g1, g2): (G::Point, G::Point),
+    (Z_sum, R_sum): (G::Point, G::Point),
+    ballot_id: &str,
+) -> bool {
+    let challenge: G::Scalar = TranscriptVersion::CURRENT.challenge(
+        BALLOT_PROOF_LABEL,
+        BALLOT_PROOF_TAG,
+        &[
+            &g1.to_bytes(),
+            &g2.to_bytes(),
+            &proof.a.to_bytes(),
+            &proof.b.to_bytes(),
+            ballot_id.as_bytes(),
+        ],
+    );
+    let X = Z_sum - g1.clone();
+    g1 * proof.r.clone() == proof.a.clone() + X * challenge.clone()
+        && g2 * proof.r.clone() == proof.b.clone() + R_sum * challenge
+}
+
+fn check_msm<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Multi-scalar multiplication"], &mut rng);
+    let gens = (election.g1.clone(), election.g2.clone());
+
+    // MSM agrees with the naive sum, including for edge cases.
+    assert_eq!(G::msm(&[], &[]), G::Point::identity());
+    let points = (0..3)
+        .map(|i| G::Point::from_hash(&[&[i]]))
+        .collect::<Vec<_>>();
+    let minus_one = G::Scalar::zero() - G::Scalar::one();
+    for scalars in [
+        vec![G::Scalar::zero(), G::Scalar::one(), minus_one],
+        (0..3).map(|_| G::Scalar::random(&mut rng)).collect(),
+    ] {
+        let naive = points
+            .iter()
+            .zip(&scalars)
+            .fold(G::Point::identity(), |acc, (p, s)| {
+                acc + p.clone() * s.clone()
+            });
+        assert_eq!(G::msm(&points, &scalars), naive);
+        assert_eq!(
+            G::msm(&points[..1], &scalars[..1]),
+            points[0].clone() * scalars[0].clone()
+        );
+    }
+
+    // Verification accepts and rejects exactly the same proofs as before.
+    let ballot = Ballot::<_, G, _>::new(
+        &mut rng,
+        gens.0.clone(),
+        gens.1.clone(),
+        "1",
+        "Alice",
+        vec!["Bob"],
+    )
+    .unwrap();
+    let vote = &ballot.votes()["Alice"];
+    let other = &ballot.votes()["Bob"];
+    let random = G::Scalar::random(&mut rng);
+    let mut proofs = vec![vote.pwf().clone(), other.pwf().clone()];
+    for i in 0..4 {
+        let mut proof = vote.pwf().clone();
+        let field = [&mut proof.c1, &mut proof.c2, &mut proof.r1, &mut proof.r2];
+        *field.into_iter().nth(i).unwrap() = random.clone();
+        proofs.push(proof);
+    }
+    for proof in proofs.iter() {
+        for (values, ids) in [
+            ((vote.Z().clone(), vote.R().clone()), ("1", "Alice")),
+            ((vote.Z().clone(), vote.R().clone()), ("1", "Bob")),
+            ((other.Z().clone(), other.R().clone()), ("1", "Bob")),
+            ((vote.Z().clone(), other.R().clone()), ("1", "Alice")),
+        ] {
+            let result = proof
+                .verify(
+                    gens.0.clone(),
+                    gens.1.clone(),
+                    values.0.clone(),
+                    values.1.clone(),
+                    ids.0,
+                    ids.1,
+                    TranscriptVersion::CURRENT,
+                )
+                .is_ok();
+            assert_eq!(
+                result,
+                reference_vote_verify(proof, gens.clone(), values, ids)
+            );
+        }
+    }
+
+    let sums = (
+        vote.Z().clone() + other.Z().clone(),
+        vote.R().clone() + other.R().clone(),
+    );
+    let mut tampered = ballot.pwf().clone();
+    tampered.r = random;
+    for proof in [ballot.pwf(), &tampered] {
+        for (values, id) in [
+            (sums.clone(), "1"),
+            (sums.clone(), "2"),
+            ((vote.Z().clone(), vote.R().clone()), "1"),
+        ] {
+            let result = proof
+                .verify(
+                    gens.0.clone(),
+                    gens.1.clone(),
+                    values.0.clone(),
+                    values.1.clone(),
+                    id,
+                    TranscriptVersion::CURRENT,
+                )
+                .is_ok();
+            assert_eq!(
+                result,
+                reference_ballot_verify(proof, gens.clone(), values, id)
+            );
+        }
+    }
+    assert!(reference_ballot_verify(ballot.pwf(), gens, sums, "1"));
+}
+
+#[allow(non_snake_case)]
+fn check_ct_eq<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    // Constant-time equality agrees with `==`, including for identity and zero.
+    let points = [
+        G::Point::identity(),
+        G::Point::from_hash(&[b"1"]),
+        G::Point::from_hash(&[b"2"]),
+        G::Point::from_hash(&[b"1"]) + G::Point::identity(),
+    ];
+    for x in points.iter() {
+        for y in points.iter() {
+            assert_eq!(x.ct_eq(y), x == y);
+        }
+    }
+    let scalars = [
+        G::Scalar::zero(),
+        G::Scalar::one(),
+        G::Scalar::random(&mut rng),
+        G::Scalar::one() + G::Scalar::zero(),
+    ];
+    for x in scalars.iter() {
+        for y in scalars.iter() {
+            assert_eq!(x.ct_eq(y), x == y);
+        }
+    }
+
+    // Checking secrets accepts and rejects exactly the same values as `==`.
+    let election = Election::<G>::new(&[b"Constant-time equality"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let ballot =
+        Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), "1", "Alice", vec!["Bob"])
+            .unwrap();
+    let vote = &ballot.votes()["Alice"];
+    let other = &ballot.votes()["Bob"];
+    for (Z, R) in [
+        (vote.Z().clone(), vote.R().clone()),
+        (other.Z().clone(), vote.R().clone()),
+        (vote.Z().clone(), other.R().clone()),
+        (other.Z().clone(), other.R().clone()),
+    ] {
+        let expected = g1.clone() * (vote.secrets.r.clone() + vote.secrets.v.clone()) == Z
+            && g2.clone() * vote.secrets.r.clone() == R;
+        assert_eq!(
+            vote.secrets.verify(g1.clone(), g2.clone(), R, Z).is_some(),
+            expected
+        );
+    }
+}
+
+fn check_round_trip<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    const CANDIDATES: &[&str] = &["Alice", "Bob", "Eve"];
+    let election = Election::<G>::new(&[b"Round trip"], &mut rng);
+
+    // Create and confirm some ballots, two for each candidate.
+    let mut totals = CANDIDATES
+        .iter()
+        .map(|c| (c.to_string(), CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut ballots = HashMap::new();
+    for i in 0..6 {
+        let ballot_id = i.to_string();
+        let yes_candidate = CANDIDATES[i % CANDIDATES.len()];
+        let no_candidates = CANDIDATES
+            .iter()
+            .filter(|c| **c != yes_candidate)
+            .map(|c| c.to_string());
+        let ballot = Ballot::<_, G, _>::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            &ballot_id,
+            yes_candidate.to_string(),
+            no_candidates,
+        )
+        .unwrap();
+        let mut totals_mut = totals
+            .iter_mut()
+            .map(|(id, t)| (id.clone(), t))
+            .collect::<HashMap<_, _>>();
+        ballots.insert(ballot_id, ballot.confirm(Some(&mut totals_mut)).unwrap());
+    }
+
+    // Check the tallies and verify.
+    assert!(totals.values().all(|t| t.tally_u64() == Some(2)));
+    assert!(verify_election(
+        election.g1.clone(),
+        election.g2.clone(),
+        &ballots,
+        &totals,
+        election.transcript
+    )
+    .is_ok());
+
+    // Serialize, deserialize, and verify again.
+    let election_json = serde_json::to_string(&election).unwrap();
+    let ballots_json = serde_json::to_string(&ballots).unwrap();
+    let totals_json = serde_json::to_string(&totals).unwrap();
+    let new_election: Election<G> = serde_json::from_str(&election_json).unwrap();
+    let new_ballots: HashMap<String, Ballot<String, G, NoSecrets>> =
+        serde_json::from_str(&ballots_json).unwrap();
+    let new_totals: HashMap<String, CandidateTotals<G>> =
+        serde_json::from_str(&totals_json).unwrap();
+    assert_eq!(new_election.transcript, election.transcript);
+    assert_eq!(new_election.g1, election.g1);
+    assert_eq!(new_election.g2, election.g2);
+    assert_eq!(new_ballots, ballots);
+    assert_eq!(new_totals, totals);
+    assert!(verify_election(
+        new_election.g1.clone(),
+        new_election.g2.clone(),
+        &new_ballots,
+        &new_totals,
+        new_election.transcript
+    )
+    .is_ok());
+
+    // Elections serialized before transcript versions existed use the legacy one.
+    let mut legacy_json: serde_json::Value = serde_json::from_str(&election_json).unwrap();
+    legacy_json.as_object_mut().unwrap().remove("transcript");
+    let legacy_election: Election<G> = serde_json::from_value(legacy_json).unwrap();
+    assert_eq!(legacy_election.transcript, TranscriptVersion::V0);
+
+    // Ballots serialize identically whatever order their votes were added in, listing the
+    // votes in order of candidate ID.
+    let ballot_json = serde_json::to_string(&ballots["0"]).unwrap();
+    let shuffled = |seed| {
+        let mut ballot: Ballot<String, G, NoSecrets> = serde_json::from_str(&ballot_json).unwrap();
+        let mut votes = std::mem::take(ballot.votes_mut())
+            .into_iter()
+            .collect::<Vec<_>>();
+        votes.shuffle(&mut ChaCha20Rng::seed_from_u64(seed));
+        ballot.votes_mut().extend(votes);
+        serde_json::to_string(&ballot).unwrap()
+    };
+    assert_eq!(shuffled(1), ballot_json);
+    assert_eq!(shuffled(2), ballot_json);
+    let position = |candidate| ballot_json.find(&format!("\"{}\"", candidate)).unwrap();
+    assert!(position("Alice") < position("Bob"));
+    assert!(position("Bob") < position("Eve"));
+}
+
+fn check_mixed_secrets<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    const CANDIDATES: &[&str] = &["Alice", "Bob", "Eve"];
+    let election = Election::<G>::new(&[b"Mixed secrets"], &mut rng);
+
+    // Confirm three ballots and audit a fourth.
+    let mut totals = CANDIDATES
+        .iter()
+        .map(|c| (c.to_string(), CandidateTotals::<G>::default()))
+        .collect::<HashMap<_, _>>();
+    let mut confirmed = HashMap::new();
+    let mut audited = HashMap::new();
+    for i in 0..4 {
+        let ballot_id = i.to_string();
+        let yes_candidate = CANDIDATES[i % CANDIDATES.len()];
+        let no_candidates = CANDIDATES
+            .iter()
+            .filter(|c| **c != yes_candidate)
+            .map(|c| c.to_string());
+        let ballot = Ballot::<_, G, _>::new(
+            &mut rng,
+            election.g1.clone(),
+            election.g2.clone(),
+            &ballot_id,
+            yes_candidate.to_string(),
+            no_candidates,
+        )
+        .unwrap();
+        if i == 3 {
+            audited.insert(ballot_id, ballot);
+        } else {
+            let mut totals_mut = totals.iter_mut().map(|(id, t)| (id.clone(), t)).collect();
+            confirmed.insert(ballot_id, ballot.confirm(Some(&mut totals_mut)).unwrap());
+        }
+    }
+
+    // Both kinds serialize exactly as their static types do, so one map holds them all.
+    let mut json = serde_json::to_value(&confirmed).unwrap();
+    let audited_json = serde_json::to_value(&audited).unwrap();
+    json.as_object_mut()
+        .unwrap()
+        .extend(audited_json.as_object().unwrap().clone());
+    let load = || -> HashMap<String, Ballot<String, G, Secrets<G>>> {
+        serde_json::from_value(json.clone()).unwrap()
+    };
+    let mixed = load();
+    assert_eq!(mixed.len(), 4);
+    assert_eq!(serde_json::to_value(&mixed).unwrap(), json);
+    assert!(mixed["3"].secrets_present());
+    assert!(mixed["0"].secrets_absent());
+    let vote = &mixed["3"].votes()["Alice"];
+    assert_eq!(
+        vote.secrets.present(),
+        Some(&audited["3"].votes()["Alice"].secrets)
+    );
+    assert_eq!(mixed["0"].votes()["Alice"].secrets, Secrets::Absent);
+
+    // Every ballot verifies, with its secrets if present, and the confirmed ones tally.
+    for (ballot_id, ballot) in mixed.iter() {
+        assert_eq!(
+            ballot.verify(
+                election.g1.clone(),
+                election.g2.clone(),
+                ballot_id.clone(),
+                election.transcript
+            ),
+            Ok(())
+        );
+    }
+    let (present, absent): (HashMap<_, _>, HashMap<_, _>) = load()
+        .into_iter()
+        .partition(|(_, ballot)| ballot.secrets_present());
+    assert_eq!(
+        verify_election(
+            election.g1.clone(),
+            election.g2.clone(),
+            &absent,
+            &totals,
+            election.transcript
+        ),
+        Ok(())
+    );
+    // The audited ballot is not counted, so the tallies fail with it included.
+    assert!(matches!(
+        verify_election(
+            election.g1.clone(),
+            election.g2.clone(),
+            &mixed,
+            &totals,
+            election.transcript
+        ),
+        Err(VerificationError::Tally { .. })
+    ));
+
+    // The ballots convert back to their static types, but not to the other one.
+    let present = present
+        .into_iter()
+        .map(|(id, ballot)| (id, ballot.into_present().unwrap()))
+        .collect::<HashMap<_, _>>();
+    assert_eq!(present, audited);
+    let absent = absent
+        .into_iter()
+        .map(|(id, ballot)| (id, ballot.into_absent().unwrap()))
+        .collect::<HashMap<_, _>>();
+    assert_eq!(absent, confirmed);
+    let mut reloaded = load();
+    let ballot = reloaded.remove("0").unwrap();
+    assert_eq!(ballot.into_present(), Err(load().remove("0").unwrap()));
+    let ballot = reloaded.remove("3").unwrap();
+    assert_eq!(ballot.into_absent(), Err(load().remove("3").unwrap()));
+    let mut static_ballots = present;
+    let converted: Ballot<String, G, Secrets<G>> = static_ballots.remove("3").unwrap().into();
+    assert_eq!(converted, mixed["3"]);
+    let mut static_ballots = absent;
+    let converted: Ballot<String, G, Secrets<G>> = static_ballots.remove("0").unwrap().into();
+    assert_eq!(converted, mixed["0"]);
+
+    // The encodings are those of the static types.
+    assert_eq!(
+        mixed["3"].to_bytes(election.transcript),
+        audited["3"].to_bytes(election.transcript)
+    );
+    assert_eq!(
+        mixed["0"].canonical_bytes(),
+        confirmed["0"].canonical_bytes()
+    );
+    assert_eq!(
+        Vec::from(&mixed["3"].votes()["Bob"].secrets),
+        Vec::from(&audited["3"].votes()["Bob"].secrets)
+    );
+
+    // Wrong secrets are caught.
+    let mut tampered = load().remove("3").unwrap();
+    let vote = tampered.votes_mut().get_mut("Bob").unwrap();
+    if let Secrets::Present(secrets) = &mut vote.secrets {
+        secrets.v = G::Scalar::one();
+    }
+    assert!(tampered
+        .verify(
+            election.g1.clone(),
+            election.g2.clone(),
+            "3".to_string(),
+            election.transcript
+        )
+        .is_err());
+
+    // A vote with only one of the secrets is rejected.
+    let mut partial = json["3"].clone();
+    partial["votes"]["Alice"]
+        .as_object_mut()
+        .unwrap()
+        .remove("v");
+    let result = serde_json::from_value::<Ballot<String, G, Secrets<G>>>(partial);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("missing field `v`"));
+}
+
+/// A deterministic FNV-1a hasher, standing in for a caller's choice of hasher.
+#[derive(Default)]
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+type FnvMap<K, V> = HashMap<K, V, BuildHasherDefault<Fnv>>;
+
+fn check_integer_ids<G: DreipGroup + Debug + Eq + Clone>(mut rng: impl RngCore + CryptoRng) {
+    // Integers are encoded big-endian at their full width.
+    assert_eq!(*7u32.id_bytes(), [0, 0, 0, 7]);
+    assert_eq!(7u64.id_bytes(), 7usize.id_bytes());
+    assert_eq!(*258u16.id_bytes(), [1, 2]);
+    assert_eq!(*"Alice".id_bytes(), *b"Alice");
+
+    let election = Election::<G>::new(&[b"Integer ids"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let transcript = election.transcript;
+    let candidates: [u32; 3] = [100, 200, 300];
+
+    // Run a whole election with `u64` ballot ids and `u32` candidate ids.
+    let mut session = ElectionSession::new(election, candidates);
+    for ballot_id in 1..=6u64 {
+        let candidate = candidates[ballot_id as usize % 3];
+        let handle = session.cast(&mut rng, ballot_id, candidate).unwrap();
+        if ballot_id == 6 {
+            let audited = session.audit(handle).unwrap();
+            let result = audited.verify_audited(g1.clone(), g2.clone(), 6u64, &100, transcript);
+            assert_eq!(result, Ok(()));
+        } else {
+            session.confirm(handle).unwrap();
+        }
+    }
+    let results = session.into_results();
+    assert_eq!(results.verify(), Ok(()));
+    assert_eq!(results.totals[&200].tally_u64(), Some(2));
+    let (confirmed, totals) = (&results.confirmed, &results.totals);
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), confirmed, totals, transcript),
+        Ok(())
+    );
+    let batched = verify_election_batched(
+        g1.clone(),
+        g2.clone(),
+        confirmed,
+        totals,
+        transcript,
+        &mut rng,
+    );
+    assert_eq!(batched, Ok(()));
+
+    // The results survive a JSON round trip, and name bad ballots by their integer id.
+    let json = serde_json::to_string(&results).unwrap();
+    let mut deserialized: ElectionResults<u64, u32, G> = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized.confirmed, results.confirmed);
+    deserialized.confirmed.get_mut(&4).unwrap().pwf_mut().r = G::Scalar::random(&mut rng);
+    assert!(matches!(
+        deserialized.verify(),
+        Err(VerificationError::Ballot(BallotError::BallotProof {
+            ballot_id: 4,
+            ..
+        }))
+    ));
+
+    // Proofs are bound to the bytes: a vote for candidate `7u32` is one for the bytes
+    // `[0, 0, 0, 7]`, but ballot id `1u64` is not ballot id `1u32`.
+    let vote = Vote::<G, _>::new(&mut rng, g1.clone(), g2.clone(), 1u64, 7u32, true);
+    let verify = |ballot_id: &dyn IdBytes, candidate_id: &dyn IdBytes| {
+        vote.verify(g1.clone(), g2.clone(), ballot_id, candidate_id, transcript)
+            .is_ok()
+    };
+    assert!(verify(&1u64, &[0u8, 0, 0, 7]));
+    assert!(!verify(&1u32, &7u32));
+    assert!(!verify(&1u64, &7u64));
+}
+
+fn check_hashers<G: DreipGroup + Debug + Eq>(mut rng: impl RngCore + CryptoRng) {
+    let election = Election::<G>::new(&[b"Hashers"], &mut rng);
+    let (g1, g2) = (election.g1.clone(), election.g2.clone());
+    let candidates = ["Alice", "Bob", "Eve"];
+
+    // Confirm a ballot for each candidate, and audit one, keeping them in FNV maps.
+    let mut totals = candidates
+        .iter()
+        .map(|c| (*c, CandidateTotals::<G>::default()))
+        .collect::<FnvMap<_, _>>();
+    let mut confirmed = FnvMap::default();
+    for (ballot_id, yes) in [("1", "Alice"), ("2", "Bob"), ("3", "Eve")] {
+        let no = candidates.iter().copied().filter(|c| *c != yes);
+        let ballot =
+            Ballot::<_, G, _>::new(&mut rng, g1.clone(), g2.clone(), ballot_id, yes, no).unwrap();
+        let mut refs = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+        confirmed.insert(ballot_id, ballot.confirm(Some(&mut refs)).unwrap());
+    }
+    let mut audited = FnvMap::default();
+    let ballot = Ballot::new(
+        &mut rng,
+        g1.clone(),
+        g2.clone(),
+        "4",
+        "Bob",
+        ["Alice", "Eve"],
+    );
+    audited.insert("4", ballot.unwrap());
+
+    // Every verifier accepts them.
+    let transcript = election.transcript;
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), &confirmed, &totals, transcript),
+        Ok(())
+    );
+    assert_eq!(
+        verify_election_batched(
+            g1.clone(),
+            g2.clone(),
+            &confirmed,
+            &totals,
+            transcript,
+            &mut rng
+        ),
+        Ok(())
+    );
+    let none = HashSet::<&str, BuildHasherDefault<Fnv>>::default();
+    assert_eq!(
+        verify_election_with_abstentions(
+            g1.clone(),
+            g2.clone(),
+            &confirmed,
+            &totals,
+            &none,
+            transcript
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        verify_full_election(
+            g1.clone(),
+            g2.clone(),
+            &confirmed,
+            &audited,
+            &FnvMap::default(),
+            &FnvMap::default(),
+            &totals,
+            transcript,
+        ),
+        Ok(())
+    );
+
+    // The maps deserialize with their hasher, and still reject wrong totals.
+    let json = serde_json::to_string(&confirmed).unwrap();
+    let loaded: FnvMap<&str, Ballot<&str, G, NoSecrets>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, confirmed);
+    let json = serde_json::to_string(&totals).unwrap();
+    let mut loaded_totals: FnvMap<&str, CandidateTotals<G>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        verify_election(g1.clone(), g2.clone(), &loaded, &loaded_totals, transcript),
+        Ok(())
+    );
+    loaded_totals.get_mut("Eve").unwrap().tally = G::Scalar::zero();
+    assert_eq!(
+        verify_election(g1, g2, &loaded, &loaded_totals, transcript),
+        Err(VerificationError::Tally {
+            candidate_id: "Eve"
+        })
+    );
+}
@@ -0,0 +1,750 @@
+//! Threshold signatures, so that receipts and published results can be signed jointly
+//! by `t` of `n` trustees rather than by a single key.
+//!
+//! ECDSA does not lend itself to this, so [`Threshold`] wraps any group with Schnorr
+//! signatures instead: its points, scalars, and generators are those of the underlying
+//! group, so elections run exactly as before, but its keys and signatures are
+//! [`ThresholdPrivateKey`], [`ThresholdPublicKey`], and [`ThresholdSignature`]. Elections,
+//! results, and dumps over `Threshold<G>` therefore work unchanged with threshold keys.
+//!
+//! A trusted dealer splits the private key with [`split_key`], hands each trustee its
+//! [`KeyShare`], and then destroys the key. Signing follows FROST, in two rounds:
+//!
+//! 1. Each participating trustee calls [`KeyShare::commit`], keeps the `SigningNonces`
+//!    secret, and publishes the `NonceCommitment`.
+//! 2. Given the message and all commitments, each calls [`KeyShare::sign`] to produce a
+//!    `PartialSignature`, and anyone can then [`combine_signatures`] into a signature
+//!    that `DreipPublicKey::verify` accepts, exactly as if made by the full key.
+//!
+//! Fewer than `t` trustees reconstruct the wrong key, so cannot produce a valid signature.
+//! This module requires the `threshold` feature.
+
+use rand::{CryptoRng, RngCore};
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+
+use crate::group::{
+    scalar_from_u64, total_byte_size, wipe, DreipGroup, DreipPoint, DreipPrivateKey,
+    DreipPublicKey, DreipScalar, Serializable,
+};
+use crate::pwf::TranscriptVersion;
+
+/// The domain separation tag for threshold signature challenges.
+const CHALLENGE_TAG: &[u8] = b"THRESHOLD-CHALLENGE";
+
+/// The domain separation tag for deriving deterministic nonces when signing with the full key.
+const NONCE_TAG: &[u8] = b"THRESHOLD-NONCE";
+
+/// The domain separation tag for FROST binding factors.
+const BINDING_TAG: &[u8] = b"THRESHOLD-BINDING";
+
+/// Hash the given inputs, encoded as by `TranscriptVersion::V2`, with the given tag.
+fn hash_to_scalar<S: DreipScalar>(tag: &[u8], inputs: &[&[u8]]) -> S {
+    S::from_hash_with_tag(tag, &[&TranscriptVersion::V2.encode(inputs)])
+}
+
+/// The group `G`, with Schnorr keys and signatures that support threshold signing.
+///
+/// Its `NAME` is the same for every underlying group, as there is no way to include
+/// the underlying group's name in a constant.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Threshold<G>(PhantomData<G>);
+
+/// A Schnorr signature `(r, s)`, checked by `g * s == r + public_key * c`, where `g` is
+/// the group's standard generator and `c` hashes `r`, the public key, and the message.
+pub struct ThresholdSignature<G: DreipGroup> {
+    /// The commitment.
+    pub r: G::Point,
+    /// The response.
+    pub s: G::Scalar,
+}
+
+/// A full Schnorr signing key, which may be split among trustees with `split_key`.
+pub struct ThresholdPrivateKey<G: DreipGroup>(G::Scalar);
+
+/// A Schnorr verification key: the point `g * x` for the private key `x`.
+pub struct ThresholdPublicKey<G: DreipGroup>(pub G::Point);
+
+/// One trustee's share of a `ThresholdPrivateKey`.
+pub struct KeyShare<G: DreipGroup> {
+    index: u32,
+    secret: G::Scalar,
+    public_key: ThresholdPublicKey<G>,
+}
+
+/// A trustee's secret nonces for one signing session. These must be used at most once,
+/// so are consumed by `KeyShare::sign`, and cannot be cloned.
+pub struct SigningNonces<G: DreipGroup> {
+    index: u32,
+    hiding: G::Scalar,
+    binding: G::Scalar,
+}
+
+/// The public commitment to a trustee's `SigningNonces`, shared with the other signers.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NonceCommitment<G: DreipGroup> {
+    /// The index of the trustee.
+    pub index: u32,
+    /// The commitment to the hiding nonce.
+    pub hiding: G::Point,
+    /// The commitment to the binding nonce.
+    pub binding: G::Point,
+}
+
+/// A trustee's contribution to a threshold signature.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PartialSignature<G: DreipGroup> {
+    /// The index of the trustee.
+    pub index: u32,
+    /// The trustee's share of the response.
+    pub z: G::Scalar,
+}
+
+/// An error in splitting a key or producing a threshold signature.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum ThresholdError {
+    /// The threshold is zero or greater than the number of trustees.
+    InvalidThreshold,
+    /// More than one commitment was given for this trustee.
+    DuplicateSigner(u32),
+    /// The trustee's own commitment was missing or did not match its nonces.
+    UnknownSigner(u32),
+    /// The trustee committed, but gave no partial signature.
+    MissingPartial(u32),
+    /// The partial signatures did not combine into a valid signature, because there were
+    /// fewer than the threshold, or one of them was wrong.
+    InvalidSignature,
+}
+
+/// The Schnorr challenge for the given commitment, public key, and message.
+fn challenge<G: DreipGroup>(r: &G::Point, public_key: &G::Point, msg: &[u8]) -> G::Scalar {
+    hash_to_scalar(CHALLENGE_TAG, &[&r.to_bytes(), &public_key.to_bytes(), msg])
+}
+
+// The key and signature types are implemented by hand rather than derived, as deriving
+// would require `G` itself, not just its points and scalars, to implement each trait.
+
+impl<G: DreipGroup> Debug for ThresholdSignature<G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThresholdSignature")
+            .field("r", &self.r)
+            .field("s", &self.s)
+            .finish()
+    }
+}
+
+impl<G: DreipGroup> Clone for ThresholdSignature<G> {
+    fn clone(&self) -> Self {
+        Self {
+            r: self.r.clone(),
+            s: self.s.clone(),
+        }
+    }
+}
+
+impl<G: DreipGroup> PartialEq for ThresholdSignature<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.r == other.r && self.s == other.s
+    }
+}
+
+impl<G: DreipGroup> Eq for ThresholdSignature<G> {}
+
+impl<G: DreipGroup> Debug for ThresholdPublicKey<G> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ThresholdPublicKey").field(&self.0).finish()
+    }
+}
+
+impl<G: DreipGroup> Clone for ThresholdPublicKey<G> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<G: DreipGroup> PartialEq for ThresholdPublicKey<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<G: DreipGroup> Eq for ThresholdPublicKey<G> {}
+
+impl<G: DreipGroup> Clone for ThresholdPrivateKey<G> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<G: DreipGroup> Clone for KeyShare<G> {
+    fn clone(&self) -> Self {
+        Self {
+            index: self.index,
+            secret: self.secret.clone(),
+            public_key: self.public_key.clone(),
+        }
+    }
+}
+
+impl<G: DreipGroup> Serializable for ThresholdSignature<G> {
+    const BYTE_SIZE: Option<usize> = total_byte_size(&[
+        <G::Point as Serializable>::BYTE_SIZE,
+        <G::Scalar as Serializable>::BYTE_SIZE,
+    ]);
+
+    /// Encode as `r` followed by `s`.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.r.to_bytes();
+        bytes.extend(self.s.to_bytes());
+        bytes
+    }
+
+    /// Decode from `r` followed by `s`, using the length of the generator's encoding for `r`.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let point_len = G::Point::generator().to_bytes().len();
+        if bytes.len() < point_len {
+            return None;
+        }
+        let (r, s) = bytes.split_at(point_len);
+        Some(Self {
+            r: G::Point::from_bytes(r)?,
+            s: G::Scalar::from_bytes(s)?,
+        })
+    }
+}
+
+impl<G: DreipGroup> ThresholdPrivateKey<G> {
+    /// Generate a new random private key.
+    pub fn new(mut rng: impl RngCore + CryptoRng) -> Self {
+        loop {
+            let x = G::Scalar::random(&mut rng);
+            if x != G::Scalar::zero() {
+                return Self(x);
+            }
+        }
+    }
+
+    /// Get the public key corresponding to this private key.
+    pub fn public_key(&self) -> ThresholdPublicKey<G> {
+        ThresholdPublicKey(G::Point::generator() * self.0.clone())
+    }
+}
+
+impl<G: DreipGroup> Debug for ThresholdPrivateKey<G> {
+    /// Only print the corresponding public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ThresholdPrivateKey")
+            .field(&self.public_key())
+            .finish()
+    }
+}
+
+impl<G: DreipGroup> Drop for ThresholdPrivateKey<G> {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
+impl<G: DreipGroup> Serializable for ThresholdPrivateKey<G> {
+    const BYTE_SIZE: Option<usize> = <G::Scalar as Serializable>::BYTE_SIZE;
+
+    /// Encode as the scalar.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    /// Decode from the scalar, which must not be zero.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        G::Scalar::from_bytes(bytes)
+            .filter(|x| *x != G::Scalar::zero())
+            .map(Self)
+    }
+}
+
+impl<G: DreipGroup> DreipPrivateKey for ThresholdPrivateKey<G> {
+    type Signature = ThresholdSignature<G>;
+
+    /// Sign with a nonce derived from the key and the message, so no randomness is needed.
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        let mut k: G::Scalar = hash_to_scalar(NONCE_TAG, &[&self.0.to_bytes(), msg]);
+        let r = G::Point::generator() * k.clone();
+        let c = challenge::<G>(&r, &self.public_key().0, msg);
+        let s = k.clone() + c * self.0.clone();
+        wipe(&mut k);
+        ThresholdSignature { r, s }
+    }
+}
+
+impl<G: DreipGroup> Serializable for ThresholdPublicKey<G> {
+    const BYTE_SIZE: Option<usize> = <G::Point as Serializable>::BYTE_SIZE;
+
+    /// Encode as the point.
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    /// Decode from the point, which must not be the identity.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        G::Point::from_bytes(bytes)
+            .filter(|y| *y != G::Point::identity())
+            .map(Self)
+    }
+}
+
+impl<G: DreipGroup> DreipPublicKey for ThresholdPublicKey<G> {
+    type Signature = ThresholdSignature<G>;
+
+    fn verify(&self, msg: &[u8], signature: &Self::Signature) -> bool {
+        let c = challenge::<G>(&signature.r, &self.0, msg);
+        G::msm(
+            &[G::Point::generator(), self.0.clone()],
+            &[signature.s.clone(), -c],
+        ) == signature.r
+    }
+}
+
+impl<G: DreipGroup> DreipGroup for Threshold<G> {
+    const NAME: &'static str = "DREIP-THRESHOLD";
+    type Signature = ThresholdSignature<G>;
+    type Point = G::Point;
+    type Scalar = G::Scalar;
+    type PrivateKey = ThresholdPrivateKey<G>;
+    type PublicKey = ThresholdPublicKey<G>;
+
+    fn new_generators(unique_bytes: &[&[u8]]) -> (Self::Point, Self::Point) {
+        G::new_generators(unique_bytes)
+    }
+
+    fn new_keys(rng: impl RngCore + CryptoRng) -> (Self::PrivateKey, Self::PublicKey) {
+        let private_key = ThresholdPrivateKey::new(rng);
+        let public_key = private_key.public_key();
+        (private_key, public_key)
+    }
+
+    fn msm(points: &[Self::Point], scalars: &[Self::Scalar]) -> Self::Point {
+        G::msm(points, scalars)
+    }
+}
+
+/// Split a private key into `n` shares, any `t` of which can sign, with Shamir secret
+/// sharing. Shares are numbered from 1 to `n`. The caller is the trusted dealer, and
+/// should destroy the private key afterwards.
+pub fn split_key<G: DreipGroup>(
+    private_key: &ThresholdPrivateKey<G>,
+    t: u32,
+    n: u32,
+    mut rng: impl RngCore + CryptoRng,
+) -> Result<Vec<KeyShare<G>>, ThresholdError> {
+    if t == 0 || t > n {
+        return Err(ThresholdError::InvalidThreshold);
+    }
+    // A random polynomial of degree t - 1 whose constant term is the key.
+    let mut coefficients = vec![private_key.0.clone()];
+    coefficients.extend((1..t).map(|_| G::Scalar::random(&mut rng)));
+    let public_key = private_key.public_key();
+    let shares = (1..=n)
+        .map(|index| {
+            let x = scalar_from_u64::<G::Scalar>(index.into());
+            let secret = coefficients
+                .iter()
+                .rev()
+                .fold(G::Scalar::zero(), |acc, a| acc * x.clone() + a.clone());
+            KeyShare {
+                index,
+                secret,
+                public_key: public_key.clone(),
+            }
+        })
+        .collect();
+    coefficients.iter_mut().for_each(wipe);
+    Ok(shares)
+}
+
+/// Check the signers' commitments, and sort them by index.
+fn sorted_commitments<G: DreipGroup>(
+    commitments: &[NonceCommitment<G>],
+) -> Result<Vec<&NonceCommitment<G>>, ThresholdError> {
+    let mut sorted = commitments.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|commitment| commitment.index);
+    match sorted
+        .windows(2)
+        .find(|pair| pair[0].index == pair[1].index)
+    {
+        Some(pair) => Err(ThresholdError::DuplicateSigner(pair[0].index)),
+        None => Ok(sorted),
+    }
+}
+
+/// Compute each signer's binding factor, and the combined commitment `r`.
+fn group_commitment<G: DreipGroup>(
+    public_key: &ThresholdPublicKey<G>,
+    msg: &[u8],
+    commitments: &[&NonceCommitment<G>],
+) -> (Vec<G::Scalar>, G::Point) {
+    let encoded = commitments
+        .iter()
+        .map(|commitment| {
+            TranscriptVersion::V2.encode(&[
+                &commitment.index.to_be_bytes(),
+                &commitment.hiding.to_bytes(),
+                &commitment.binding.to_bytes(),
+            ])
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    let public_key = public_key.to_bytes();
+    let factors = commitments
+        .iter()
+        .map(|commitment| {
+            hash_to_scalar(
+                BINDING_TAG,
+                &[&public_key, msg, &encoded, &commitment.index.to_be_bytes()],
+            )
+        })
+        .collect::<Vec<G::Scalar>>();
+    let r = commitments
+        .iter()
+        .zip(&factors)
+        .map(|(commitment, factor)| {
+            commitment.hiding.clone() + commitment.binding.clone() * factor.clone()
+        })
+        .sum();
+    (factors, r)
+}
+
+/// The Lagrange coefficient at zero for the given signer among all of the signers.
+fn lagrange_coefficient<G: DreipGroup>(
+    index: u32,
+    commitments: &[&NonceCommitment<G>],
+) -> G::Scalar {
+    let x = scalar_from_u64::<G::Scalar>(index.into());
+    let (numerator, denominator) = commitments
+        .iter()
+        .filter(|commitment| commitment.index != index)
+        .map(|commitment| scalar_from_u64::<G::Scalar>(commitment.index.into()))
+        .fold((G::Scalar::one(), G::Scalar::one()), |(num, den), x_j| {
+            (num * x_j.clone(), den * (x_j - x.clone()))
+        });
+    numerator
+        * denominator
+            .invert()
+            .expect("Signer indices are distinct, so no difference is zero")
+}
+
+impl<G: DreipGroup> KeyShare<G> {
+    /// The index of this share, from 1 to `n`.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// The public key of the split private key.
+    pub fn public_key(&self) -> &ThresholdPublicKey<G> {
+        &self.public_key
+    }
+
+    /// Round one: generate fresh nonces for a signing session, and their commitment.
+    pub fn commit(
+        &self,
+        mut rng: impl RngCore + CryptoRng,
+    ) -> (SigningNonces<G>, NonceCommitment<G>) {
+        let nonces = SigningNonces::<G> {
+            index: self.index,
+            hiding: G::Scalar::random(&mut rng),
+            binding: G::Scalar::random(&mut rng),
+        };
+        let commitment = NonceCommitment {
+            index: self.index,
+            hiding: G::Point::generator() * nonces.hiding.clone(),
+            binding: G::Point::generator() * nonces.binding.clone(),
+        };
+        (nonces, commitment)
+    }
+
+    /// Round two: sign the message, given the commitments of every participating trustee,
+    /// including this one.
+    pub fn sign(
+        &self,
+        nonces: SigningNonces<G>,
+        msg: &[u8],
+        commitments: &[NonceCommitment<G>],
+    ) -> Result<PartialSignature<G>, ThresholdError> {
+        let commitments = sorted_commitments(commitments)?;
+        let position = commitments
+            .iter()
+            .position(|commitment| commitment.index == self.index)
+            .filter(|_| nonces.index == self.index)
+            .ok_or(ThresholdError::UnknownSigner(self.index))?;
+        let own = commitments[position];
+        if own.hiding != G::Point::generator() * nonces.hiding.clone()
+            || own.binding != G::Point::generator() * nonces.binding.clone()
+        {
+            return Err(ThresholdError::UnknownSigner(self.index));
+        }
+
+        let (factors, r) = group_commitment(&self.public_key, msg, &commitments);
+        let c = challenge::<G>(&r, &self.public_key.0, msg);
+        let lambda = lagrange_coefficient(self.index, &commitments);
+        let z = nonces.hiding.clone()
+            + nonces.binding.clone() * factors[position].clone()
+            + lambda * self.secret.clone() * c;
+        Ok(PartialSignature {
+            index: self.index,
+            z,
+        })
+    }
+}
+
+impl<G: DreipGroup> Debug for KeyShare<G> {
+    /// Only print the index and public key, never the secret.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyShare")
+            .field("index", &self.index)
+            .field("public_key", &self.public_key)
+            .finish()
+    }
+}
+
+impl<G: DreipGroup> Drop for KeyShare<G> {
+    fn drop(&mut self) {
+        wipe(&mut self.secret);
+    }
+}
+
+impl<G: DreipGroup> Serializable for KeyShare<G> {
+    const BYTE_SIZE: Option<usize> = total_byte_size(&[
+        Some(4),
+        <G::Scalar as Serializable>::BYTE_SIZE,
+        <G::Point as Serializable>::BYTE_SIZE,
+    ]);
+
+    /// Encode as the index as 4 big-endian bytes, the secret, and the public key.
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.index.to_be_bytes().to_vec();
+        bytes.extend(self.secret.to_bytes());
+        bytes.extend(self.public_key.to_bytes());
+        bytes
+    }
+
+    /// Decode from the encoding above, using the length of the zero scalar's encoding
+    /// for the secret. The index must not be zero.
+    fn from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        let (index, rest) = bytes.split_first_chunk::<4>()?;
+        let scalar_len = G::Scalar::zero().to_bytes().len();
+        if rest.len() < scalar_len {
+            return None;
+        }
+        let (secret, public_key) = rest.split_at(scalar_len);
+        Some(Self {
+            index: Some(u32::from_be_bytes(*index)).filter(|index| *index != 0)?,
+            secret: G::Scalar::from_bytes(secret)?,
+            public_key: ThresholdPublicKey::from_bytes(public_key)?,
+        })
+    }
+}
+
+impl<G: DreipGroup> Drop for SigningNonces<G> {
+    fn drop(&mut self) {
+        wipe(&mut self.hiding);
+        wipe(&mut self.binding);
+    }
+}
+
+/// Combine the partial signatures of every trustee that committed into a full signature,
+/// which is checked before being returned.
+pub fn combine_signatures<G: DreipGroup>(
+    public_key: &ThresholdPublicKey<G>,
+    msg: &[u8],
+    commitments: &[NonceCommitment<G>],
+    partials: &[PartialSignature<G>],
+) -> Result<ThresholdSignature<G>, ThresholdError> {
+    let commitments = sorted_commitments(commitments)?;
+    let s = commitments
+        .iter()
+        .map(|commitment| {
+            partials
+                .iter()
+                .find(|partial| partial.index == commitment.index)
+                .map(|partial| partial.z.clone())
+                .ok_or(ThresholdError::MissingPartial(commitment.index))
+        })
+        .sum::<Result<G::Scalar, _>>()?;
+    let (_, r) = group_commitment(public_key, msg, &commitments);
+    let signature = ThresholdSignature { r, s };
+    if public_key.verify(msg, &signature) {
+        Ok(signature)
+    } else {
+        Err(ThresholdError::InvalidSignature)
+    }
+}
+
+#[cfg(all(test, feature = "p256_impl"))]
+mod tests {
+    use super::*;
+
+    use p256::NistP256;
+    use std::collections::HashMap;
+
+    use crate::ballots::Ballot;
+    use crate::election::{CandidateTotals, Election, ElectionResults};
+    use crate::testing::check_group;
+
+    /// Run both signing rounds with the given trustees.
+    fn sign_with_shares(
+        shares: &[&KeyShare<NistP256>],
+        msg: &[u8],
+    ) -> Result<ThresholdSignature<NistP256>, ThresholdError> {
+        let mut rng = rand::thread_rng();
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            shares.iter().map(|share| share.commit(&mut rng)).unzip();
+        let partials = shares
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| share.sign(nonces, msg, &commitments))
+            .collect::<Result<Vec<_>, _>>()?;
+        combine_signatures(shares[0].public_key(), msg, &commitments, &partials)
+    }
+
+    #[test]
+    fn test_threshold_signing() {
+        let mut rng = rand::thread_rng();
+        let private_key = ThresholdPrivateKey::<NistP256>::new(&mut rng);
+        let public_key = private_key.public_key();
+        let shares = split_key(&private_key, 3, 5, &mut rng).unwrap();
+        let msg = b"Published results";
+
+        // Any three trustees can sign, and the signature verifies unchanged.
+        for signers in [[0, 1, 2], [4, 2, 0], [1, 3, 4]] {
+            let signers = signers.map(|i| &shares[i]);
+            let signature = sign_with_shares(&signers, msg).unwrap();
+            assert!(public_key.verify(msg, &signature));
+            assert!(!public_key.verify(b"Other results", &signature));
+            let decoded = ThresholdSignature::from_bytes(&signature.to_bytes()).unwrap();
+            assert!(public_key.verify(msg, &decoded));
+        }
+        assert!(sign_with_shares(&shares.iter().collect::<Vec<_>>(), msg).is_ok());
+
+        // Two cannot, even when their partial signatures are summed directly.
+        let signers = [&shares[0], &shares[3]];
+        assert_eq!(
+            sign_with_shares(&signers, msg),
+            Err(ThresholdError::InvalidSignature)
+        );
+        let (nonces, commitments): (Vec<_>, Vec<_>) =
+            signers.iter().map(|share| share.commit(&mut rng)).unzip();
+        let s = signers
+            .iter()
+            .zip(nonces)
+            .map(|(share, nonces)| share.sign(nonces, msg, &commitments).unwrap().z)
+            .sum();
+        let sorted = commitments.iter().collect::<Vec<_>>();
+        let (_, r) = group_commitment(&public_key, msg, &sorted);
+        assert!(!public_key.verify(msg, &ThresholdSignature { r, s }));
+
+        // The full key signs compatibly.
+        assert!(public_key.verify(msg, &private_key.sign(msg)));
+    }
+
+    #[test]
+    fn test_threshold_errors() {
+        let mut rng = rand::thread_rng();
+        let private_key = ThresholdPrivateKey::<NistP256>::new(&mut rng);
+        assert_eq!(
+            split_key(&private_key, 0, 3, &mut rng).unwrap_err(),
+            ThresholdError::InvalidThreshold
+        );
+        assert_eq!(
+            split_key(&private_key, 4, 3, &mut rng).unwrap_err(),
+            ThresholdError::InvalidThreshold
+        );
+        let shares = split_key(&private_key, 2, 3, &mut rng).unwrap();
+        let msg = b"Message";
+
+        // Signing needs the trustee's own commitment, matching its nonces, once.
+        let (nonces, own) = shares[0].commit(&mut rng);
+        let (_, other) = shares[1].commit(&mut rng);
+        assert_eq!(
+            shares[0].sign(nonces, msg, std::slice::from_ref(&other)),
+            Err(ThresholdError::UnknownSigner(1))
+        );
+        let (nonces, _) = shares[0].commit(&mut rng);
+        assert_eq!(
+            shares[0].sign(nonces, msg, &[own.clone(), other.clone()]),
+            Err(ThresholdError::UnknownSigner(1))
+        );
+        let (nonces, own) = shares[0].commit(&mut rng);
+        assert_eq!(
+            shares[0].sign(nonces, msg, &[own.clone(), own.clone(), other.clone()]),
+            Err(ThresholdError::DuplicateSigner(1))
+        );
+
+        // Every committed trustee must contribute.
+        let (nonces, own) = shares[0].commit(&mut rng);
+        let partial = shares[0].sign(nonces, msg, &[own.clone(), other.clone()]);
+        assert_eq!(
+            combine_signatures(
+                &private_key.public_key(),
+                msg,
+                &[own, other],
+                &[partial.unwrap()]
+            ),
+            Err(ThresholdError::MissingPartial(2))
+        );
+
+        // Shares survive serialization.
+        let decoded = KeyShare::<NistP256>::from_bytes(&shares[2].to_bytes()).unwrap();
+        assert_eq!(decoded.index(), 3);
+        assert_eq!(decoded.to_bytes(), shares[2].to_bytes());
+    }
+
+    #[test]
+    fn test_threshold_election() {
+        let mut rng = rand::thread_rng();
+        let election = Election::<Threshold<NistP256>>::new(&[b"Threshold"], &mut rng);
+        let shares = split_key(&election.private_key, 2, 3, &mut rng).unwrap();
+        let ballot = Ballot::new(
+            &mut rng,
+            election.g1,
+            election.g2,
+            "1",
+            "Alice",
+            vec!["Bob"],
+        )
+        .unwrap();
+
+        // Trustees sign the receipt in place of the election key.
+        let receipt = ballot.to_bytes(election.transcript);
+        let signature = sign_with_shares(&[&shares[0], &shares[2]], &receipt).unwrap();
+        assert!(election.public_key.verify(&receipt, &signature));
+
+        // Results over the threshold group serialize and verify as usual.
+        let mut totals = HashMap::from([("Alice", CandidateTotals::default())]);
+        totals.insert("Bob", CandidateTotals::default());
+        let confirmed = {
+            let mut totals = totals.iter_mut().map(|(c, t)| (*c, t)).collect();
+            HashMap::from([("1", ballot.confirm(Some(&mut totals)).unwrap())])
+        };
+        let results = election.results(confirmed, totals);
+        let json = serde_json::to_string(&results).unwrap();
+        let results: ElectionResults<&str, &str, Threshold<NistP256>> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(results.verify(), Ok(()));
+    }
+
+    #[test]
+    fn test_check_group() {
+        let report = check_group::<Threshold<NistP256>>(rand::thread_rng());
+        assert!(report.is_ok(), "{:?}", report.failed);
+    }
+}